@@ -0,0 +1,91 @@
+//! Benchmarks of the hot database paths against a seeded database.
+//!
+//! Run with `cargo bench --features bench`. Seeding the database takes a
+//! while; it's done once and shared by all benchmarks.
+
+use std::hint::black_box;
+use std::time::Duration;
+
+use criterion::{criterion_group, criterion_main, BatchSize, Criterion};
+use ntfier_lib::bench_support::{self, Database, SEARCH_TERM};
+
+const SUBSCRIPTIONS: usize = 50;
+const NOTIFICATIONS: usize = 100_000;
+/// Notifications per insert batch, about a page of messages from a sync.
+const INSERT_BATCH: usize = 100;
+
+fn bench_reads(c: &mut Criterion, db: &Database, subscription_id: &str) {
+    c.bench_function("get_all_subscriptions", |b| {
+        b.iter(|| black_box(db.get_all_subscriptions()))
+    });
+    c.bench_function("get_unread_count", |b| {
+        b.iter(|| black_box(db.get_unread_count(black_box(subscription_id))))
+    });
+    c.bench_function("get_total_unread_count", |b| {
+        b.iter(|| black_box(db.get_total_unread_count()))
+    });
+    c.bench_function("search_notifications", |b| {
+        b.iter(|| black_box(db.search_notifications(black_box(SEARCH_TERM), 50)))
+    });
+}
+
+fn bench_inserts(c: &mut Criterion, db: &Database, subscription_id: &str) {
+    let mut next = NOTIFICATIONS;
+    c.bench_function("insert_notification_batch", |b| {
+        b.iter_batched(
+            || {
+                let batch: Vec<_> = (next..next + INSERT_BATCH)
+                    .map(|n| {
+                        (
+                            bench_support::notification(subscription_id, n),
+                            format!("bench-{n}"),
+                        )
+                    })
+                    .collect();
+                next += INSERT_BATCH;
+                batch
+            },
+            |batch| {
+                for (notification, ntfy_id) in &batch {
+                    black_box(db.insert_notification_with_ntfy_id(notification, ntfy_id)).ok();
+                }
+            },
+            BatchSize::PerIteration,
+        );
+    });
+}
+
+fn benches(c: &mut Criterion) {
+    let path = std::env::temp_dir().join(format!("ntfier-bench-{}.db", uuid::Uuid::new_v4()));
+    let db = match bench_support::seed(&path, SUBSCRIPTIONS, NOTIFICATIONS) {
+        Ok(db) => db,
+        Err(e) => {
+            eprintln!("Failed to seed the benchmark database: {e}");
+            std::fs::remove_file(&path).ok();
+            return;
+        }
+    };
+    let subscription_id = match db.get_all_subscriptions() {
+        Ok(subs) => subs.into_iter().next().map(|s| s.id).unwrap_or_default(),
+        Err(e) => {
+            eprintln!("Failed to read the benchmark subscriptions: {e}");
+            String::new()
+        }
+    };
+
+    bench_reads(c, &db, &subscription_id);
+    // Last, as it grows the database the other benchmarks read
+    bench_inserts(c, &db, &subscription_id);
+
+    drop(db);
+    std::fs::remove_file(&path).ok();
+}
+
+criterion_group! {
+    name = db;
+    config = Criterion::default()
+        .sample_size(20)
+        .measurement_time(Duration::from_secs(10));
+    targets = benches
+}
+criterion_main!(db);
@@ -0,0 +1,4 @@
+DROP INDEX IF EXISTS idx_subscriptions_deleted_at;
+DROP INDEX IF EXISTS idx_notifications_deleted_at;
+ALTER TABLE subscriptions DROP COLUMN deleted_at;
+ALTER TABLE notifications DROP COLUMN deleted_at;
@@ -0,0 +1,6 @@
+-- Soft-delete timestamps (milliseconds); NULL means not deleted
+ALTER TABLE notifications ADD COLUMN deleted_at BIGINT;
+ALTER TABLE subscriptions ADD COLUMN deleted_at BIGINT;
+
+CREATE INDEX idx_notifications_deleted_at ON notifications(deleted_at);
+CREATE INDEX idx_subscriptions_deleted_at ON subscriptions(deleted_at);
@@ -0,0 +1,2 @@
+-- Detached servers are removed from settings but keep their subscriptions' history
+ALTER TABLE servers ADD COLUMN detached INTEGER NOT NULL DEFAULT 0;
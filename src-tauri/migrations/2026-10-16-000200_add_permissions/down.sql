@@ -0,0 +1,2 @@
+DROP TABLE IF EXISTS permissions;
+ALTER TABLE subscriptions DROP COLUMN trusted;
@@ -0,0 +1,14 @@
+-- Trusted subscriptions may run actions without prompting
+ALTER TABLE subscriptions ADD COLUMN trusted INTEGER NOT NULL DEFAULT 0;
+
+-- Remembered permission decisions per subscription and capability
+CREATE TABLE permissions (
+    id TEXT PRIMARY KEY NOT NULL,
+    subscription_id TEXT NOT NULL REFERENCES subscriptions(id) ON DELETE CASCADE,
+    capability TEXT NOT NULL,
+    allowed INTEGER NOT NULL,
+    created_at BIGINT NOT NULL,
+    UNIQUE(subscription_id, capability)
+);
+
+CREATE INDEX idx_permissions_subscription ON permissions(subscription_id);
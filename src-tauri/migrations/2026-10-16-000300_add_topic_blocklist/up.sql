@@ -0,0 +1,7 @@
+-- Topics matching a blocklist entry are never auto-created by account sync
+CREATE TABLE blocked_topics (
+    id TEXT PRIMARY KEY NOT NULL,
+    server_pattern TEXT NOT NULL,
+    topic_pattern TEXT NOT NULL,
+    created_at BIGINT NOT NULL
+);
@@ -0,0 +1,2 @@
+ALTER TABLE subscriptions DROP COLUMN pattern_id;
+DROP TABLE IF EXISTS subscription_patterns;
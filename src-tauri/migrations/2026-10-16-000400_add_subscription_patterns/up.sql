@@ -0,0 +1,12 @@
+-- Pattern subscriptions auto-create child subscriptions for matching topics
+CREATE TABLE subscription_patterns (
+    id TEXT PRIMARY KEY NOT NULL,
+    server_url TEXT NOT NULL,
+    pattern TEXT NOT NULL,
+    display_name TEXT,
+    created_at BIGINT NOT NULL,
+    UNIQUE(server_url, pattern)
+);
+
+-- Children keep working as plain subscriptions if their pattern is removed
+ALTER TABLE subscriptions ADD COLUMN pattern_id TEXT REFERENCES subscription_patterns(id) ON DELETE SET NULL;
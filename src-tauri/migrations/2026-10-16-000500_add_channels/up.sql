@@ -0,0 +1,17 @@
+-- Channels group notifications by tag across topics with their own display settings
+CREATE TABLE channels (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL UNIQUE,
+    muted INTEGER NOT NULL DEFAULT 0,
+    sound INTEGER,
+    priority INTEGER,
+    created_at BIGINT NOT NULL
+);
+
+-- Each tag maps to at most one channel
+CREATE TABLE channel_tags (
+    tag TEXT PRIMARY KEY NOT NULL,
+    channel_id TEXT NOT NULL REFERENCES channels(id) ON DELETE CASCADE
+);
+
+CREATE INDEX idx_channel_tags_channel_id ON channel_tags(channel_id);
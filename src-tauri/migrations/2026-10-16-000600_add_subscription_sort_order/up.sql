@@ -0,0 +1,9 @@
+-- Position of a subscription when the list is sorted manually
+ALTER TABLE subscriptions ADD COLUMN sort_order INTEGER NOT NULL DEFAULT 0;
+
+-- Seed the manual order from the current alphabetical order
+UPDATE subscriptions SET sort_order = (
+    SELECT COUNT(*) FROM subscriptions s2
+    WHERE COALESCE(s2.display_name, s2.topic) COLLATE NOCASE
+        < COALESCE(subscriptions.display_name, subscriptions.topic) COLLATE NOCASE
+);
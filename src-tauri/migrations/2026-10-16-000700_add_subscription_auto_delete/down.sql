@@ -0,0 +1 @@
+ALTER TABLE subscriptions DROP COLUMN auto_delete_after_hours;
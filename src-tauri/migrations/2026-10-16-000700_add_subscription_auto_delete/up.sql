@@ -0,0 +1,2 @@
+-- Hours after which a subscription's notifications are deleted (NULL keeps them)
+ALTER TABLE subscriptions ADD COLUMN auto_delete_after_hours INTEGER;
@@ -0,0 +1,3 @@
+-- Local subscriptions never touch the network; they belong to a hidden
+-- placeholder server and are filled with generated test notifications
+ALTER TABLE subscriptions ADD COLUMN local INTEGER NOT NULL DEFAULT 0;
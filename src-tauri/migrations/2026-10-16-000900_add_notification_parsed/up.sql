@@ -0,0 +1,2 @@
+-- Fields extracted from structured payloads of known producers (JSON, NULL if unrecognized)
+ALTER TABLE notifications ADD COLUMN parsed TEXT;
@@ -0,0 +1,2 @@
+-- OpenGraph preview of the first bare URL in the message (JSON, NULL if none)
+ALTER TABLE notifications ADD COLUMN link_preview TEXT;
@@ -0,0 +1,6 @@
+-- Links to hosts matching a blocklist entry are never opened from notifications
+CREATE TABLE blocked_urls (
+    id TEXT PRIMARY KEY NOT NULL,
+    host_pattern TEXT NOT NULL,
+    created_at BIGINT NOT NULL
+);
@@ -0,0 +1 @@
+ALTER TABLE subscriptions DROP COLUMN min_toast_priority;
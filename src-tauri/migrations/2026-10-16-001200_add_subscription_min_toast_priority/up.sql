@@ -0,0 +1,2 @@
+-- Per-subscription override of the global min_toast_priority setting (NULL = use the global floor)
+ALTER TABLE subscriptions ADD COLUMN min_toast_priority INTEGER;
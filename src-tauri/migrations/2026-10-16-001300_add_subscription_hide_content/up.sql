@@ -0,0 +1,2 @@
+-- Toasts of subscriptions with hide_content show only the topic and unread count
+ALTER TABLE subscriptions ADD COLUMN hide_content INTEGER NOT NULL DEFAULT 0;
@@ -0,0 +1,5 @@
+-- Global keyboard shortcuts, one accelerator per action
+CREATE TABLE shortcuts (
+    action TEXT PRIMARY KEY NOT NULL,
+    accelerator TEXT NOT NULL
+);
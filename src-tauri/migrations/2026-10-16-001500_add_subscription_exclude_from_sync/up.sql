@@ -0,0 +1,2 @@
+-- Subscriptions excluded from history sync only receive live messages
+ALTER TABLE subscriptions ADD COLUMN exclude_from_sync INTEGER NOT NULL DEFAULT 0;
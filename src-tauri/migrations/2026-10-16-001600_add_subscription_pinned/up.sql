@@ -0,0 +1,2 @@
+-- Pinned subscriptions are listed and synced first
+ALTER TABLE subscriptions ADD COLUMN pinned INTEGER NOT NULL DEFAULT 0;
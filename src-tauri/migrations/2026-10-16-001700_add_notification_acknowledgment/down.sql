@@ -0,0 +1,2 @@
+ALTER TABLE notifications DROP COLUMN acknowledged_at;
+ALTER TABLE notifications DROP COLUMN acknowledged_by;
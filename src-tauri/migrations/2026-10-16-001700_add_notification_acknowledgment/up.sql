@@ -0,0 +1,3 @@
+-- Who acknowledged a notification and when (milliseconds)
+ALTER TABLE notifications ADD COLUMN acknowledged_by TEXT;
+ALTER TABLE notifications ADD COLUMN acknowledged_at BIGINT;
@@ -0,0 +1,2 @@
+-- Local note about what was done about a notification
+ALTER TABLE notifications ADD COLUMN note TEXT;
@@ -0,0 +1,5 @@
+-- Message format from ntfy's content_type ('plain' or 'markdown')
+ALTER TABLE notifications ADD COLUMN content_kind TEXT NOT NULL DEFAULT 'plain';
+
+-- Messages stored before the format was known were always rendered as markdown
+UPDATE notifications SET content_kind = 'markdown';
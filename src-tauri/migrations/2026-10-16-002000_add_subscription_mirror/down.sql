@@ -0,0 +1,2 @@
+ALTER TABLE subscriptions DROP COLUMN mirror_format;
+ALTER TABLE subscriptions DROP COLUMN mirror_path;
@@ -0,0 +1,3 @@
+-- Received notifications are appended to mirror_path, if set
+ALTER TABLE subscriptions ADD COLUMN mirror_path TEXT;
+ALTER TABLE subscriptions ADD COLUMN mirror_format TEXT NOT NULL DEFAULT 'jsonl';
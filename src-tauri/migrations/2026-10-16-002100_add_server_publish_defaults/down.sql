@@ -0,0 +1,4 @@
+ALTER TABLE servers DROP COLUMN default_delay;
+ALTER TABLE servers DROP COLUMN default_markdown;
+ALTER TABLE servers DROP COLUMN default_tags;
+ALTER TABLE servers DROP COLUMN default_priority;
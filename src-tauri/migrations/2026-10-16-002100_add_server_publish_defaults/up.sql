@@ -0,0 +1,5 @@
+-- Defaults merged into messages published to a server
+ALTER TABLE servers ADD COLUMN default_priority INTEGER;
+ALTER TABLE servers ADD COLUMN default_tags TEXT NOT NULL DEFAULT '[]';
+ALTER TABLE servers ADD COLUMN default_markdown INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE servers ADD COLUMN default_delay TEXT;
@@ -0,0 +1,9 @@
+-- Local reminders shown on a cron-like schedule
+CREATE TABLE reminders (
+    id TEXT PRIMARY KEY NOT NULL,
+    title TEXT,
+    message TEXT NOT NULL,
+    schedule TEXT NOT NULL,
+    next_run BIGINT,
+    created_at BIGINT NOT NULL
+);
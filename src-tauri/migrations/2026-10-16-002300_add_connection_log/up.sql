@@ -0,0 +1,10 @@
+-- Recent connection errors per subscription, for diagnosing flaky servers
+CREATE TABLE connection_log (
+    id TEXT PRIMARY KEY NOT NULL,
+    subscription_id TEXT NOT NULL REFERENCES subscriptions(id) ON DELETE CASCADE,
+    kind TEXT NOT NULL,
+    message TEXT NOT NULL,
+    created_at BIGINT NOT NULL
+);
+
+CREATE INDEX idx_connection_log_subscription ON connection_log(subscription_id, created_at);
@@ -0,0 +1,2 @@
+-- How a server's host name is resolved (JSON), empty object for the system default
+ALTER TABLE servers ADD COLUMN resolution TEXT NOT NULL DEFAULT '{}';
@@ -0,0 +1,7 @@
+-- Per-subscription overrides of the global notification settings
+CREATE TABLE subscription_settings (
+    subscription_id TEXT PRIMARY KEY NOT NULL REFERENCES subscriptions(id) ON DELETE CASCADE,
+    sound TEXT,
+    display_method TEXT,
+    auto_mark_read INTEGER NOT NULL DEFAULT 0
+);
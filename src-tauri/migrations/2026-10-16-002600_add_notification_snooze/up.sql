@@ -0,0 +1,2 @@
+-- When a snoozed notification is shown again (milliseconds)
+ALTER TABLE notifications ADD COLUMN snoozed_until BIGINT;
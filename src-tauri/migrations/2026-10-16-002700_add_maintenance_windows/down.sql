@@ -0,0 +1 @@
+ALTER TABLE subscription_settings DROP COLUMN maintenance_windows;
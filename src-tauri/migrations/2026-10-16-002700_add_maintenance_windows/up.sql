@@ -0,0 +1,3 @@
+-- Recurring windows (JSON) during which a subscription's notifications are
+-- stored as read and not shown
+ALTER TABLE subscription_settings ADD COLUMN maintenance_windows TEXT NOT NULL DEFAULT '[]';
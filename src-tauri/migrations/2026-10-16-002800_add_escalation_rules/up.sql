@@ -0,0 +1,17 @@
+-- Rules re-notifying about unread, unacknowledged high priority notifications
+CREATE TABLE escalation_rules (
+    id TEXT PRIMARY KEY NOT NULL,
+    subscription_id TEXT REFERENCES subscriptions(id) ON DELETE CASCADE,
+    min_priority INTEGER NOT NULL,
+    delay_minutes INTEGER NOT NULL,
+    max_repeats INTEGER NOT NULL,
+    publish_to TEXT,
+    created_at BIGINT NOT NULL
+);
+
+-- How often and when each notification was escalated
+CREATE TABLE escalations (
+    notification_id TEXT PRIMARY KEY NOT NULL REFERENCES notifications(id) ON DELETE CASCADE,
+    count INTEGER NOT NULL,
+    last_at BIGINT NOT NULL
+);
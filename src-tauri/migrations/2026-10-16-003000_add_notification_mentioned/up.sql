@@ -0,0 +1,2 @@
+-- Whether the message mentions the user (one of the mention strings)
+ALTER TABLE notifications ADD COLUMN mentioned INTEGER NOT NULL DEFAULT 0;
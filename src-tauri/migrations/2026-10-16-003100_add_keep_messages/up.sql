@@ -0,0 +1,2 @@
+-- Only this many newest notifications of a subscription are kept
+ALTER TABLE subscriptions ADD COLUMN keep_messages INTEGER;
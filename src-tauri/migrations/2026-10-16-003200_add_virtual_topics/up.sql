@@ -0,0 +1,11 @@
+-- Merged views over several subscriptions, with their own mute and sound
+CREATE TABLE virtual_topics (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    subscription_ids TEXT NOT NULL DEFAULT '[]',
+    tags TEXT NOT NULL DEFAULT '[]',
+    min_priority INTEGER,
+    muted INTEGER NOT NULL DEFAULT 0,
+    sound TEXT,
+    created_at BIGINT NOT NULL
+);
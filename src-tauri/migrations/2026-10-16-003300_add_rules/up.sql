@@ -0,0 +1,9 @@
+-- Rules filtering and routing incoming notifications
+CREATE TABLE rules (
+    id TEXT PRIMARY KEY NOT NULL,
+    name TEXT NOT NULL,
+    enabled INTEGER NOT NULL DEFAULT 1,
+    conditions TEXT NOT NULL DEFAULT '{}',
+    actions TEXT NOT NULL DEFAULT '[]',
+    created_at BIGINT NOT NULL
+);
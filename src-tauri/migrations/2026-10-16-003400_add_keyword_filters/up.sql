@@ -0,0 +1,9 @@
+-- Keywords muting matching messages, globally or in one subscription
+CREATE TABLE keyword_filters (
+    id TEXT PRIMARY KEY NOT NULL,
+    subscription_id TEXT REFERENCES subscriptions(id) ON DELETE CASCADE,
+    pattern TEXT NOT NULL,
+    is_regex INTEGER NOT NULL DEFAULT 0,
+    discard INTEGER NOT NULL DEFAULT 0,
+    created_at BIGINT NOT NULL
+);
@@ -0,0 +1 @@
+ALTER TABLE subscriptions DROP COLUMN sync_window_hours;
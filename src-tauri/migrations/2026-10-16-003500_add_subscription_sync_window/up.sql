@@ -0,0 +1,2 @@
+-- History sync of a subscription only fetches messages from this many hours back
+ALTER TABLE subscriptions ADD COLUMN sync_window_hours INTEGER;
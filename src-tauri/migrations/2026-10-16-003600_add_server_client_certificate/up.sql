@@ -0,0 +1,2 @@
+-- PKCS#12 file presented to the server as a client certificate (mTLS)
+ALTER TABLE servers ADD COLUMN client_certificate TEXT;
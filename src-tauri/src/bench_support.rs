@@ -0,0 +1,93 @@
+//! Seeded databases for the benchmarks in `benches/`.
+//!
+//! Only built with the `bench` feature, which exposes the database to the
+//! benchmark targets without making it part of the library's API.
+
+use std::path::Path;
+
+use diesel::connection::SimpleConnection;
+
+pub use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{ContentKind, CreateSubscription, Notification, Priority};
+
+/// Word contained in one in every hundred seeded messages, for searches.
+pub const SEARCH_TERM: &str = "overheated";
+
+/// Server the seeded subscriptions belong to.
+const SERVER_URL: &str = "https://ntfy.sh";
+
+/// Creates a database at `path` with `subscriptions` subscriptions sharing
+/// `notifications` notifications evenly. A third of the notifications are
+/// unread.
+pub fn seed(path: &Path, subscriptions: usize, notifications: usize) -> Result<Database, AppError> {
+    let db = Database::new(path)?;
+
+    let subs: Vec<CreateSubscription> = (0..subscriptions)
+        .map(|i| CreateSubscription {
+            topic: format!("bench-topic-{i}"),
+            server_url: SERVER_URL.to_string(),
+            display_name: None,
+        })
+        .collect();
+    let ids: Vec<String> = db
+        .create_subscriptions(&subs)?
+        .into_iter()
+        .flatten()
+        .collect();
+    if ids.is_empty() {
+        return Ok(db);
+    }
+
+    // One transaction, so seeding doesn't wait for a sync to disk per row
+    db.conn()?.batch_execute("BEGIN")?;
+    let inserted = (0..notifications).try_for_each(|i| {
+        let topic_id = &ids[i % ids.len()];
+        let mut notification = notification(topic_id, i);
+        notification.read = i % 3 != 0;
+        db.insert_notification_with_ntfy_id(&notification, &format!("bench-{i}"))
+    });
+    match inserted {
+        Ok(()) => db.conn()?.batch_execute("COMMIT")?,
+        Err(e) => {
+            db.conn()?.batch_execute("ROLLBACK")?;
+            return Err(e);
+        }
+    }
+
+    Ok(db)
+}
+
+/// An unread notification for the subscription `topic_id`; `n` makes its
+/// content and timestamp differ from the others.
+pub fn notification(topic_id: &str, n: usize) -> Notification {
+    let message = if n % 100 == 0 {
+        format!("Server {n} {SEARCH_TERM}, shutting down")
+    } else {
+        format!("Backup {n} finished in {} seconds", n % 600)
+    };
+
+    Notification {
+        id: uuid::Uuid::new_v4().to_string(),
+        topic_id: topic_id.to_string(),
+        title: format!("Notification {n}"),
+        message,
+        priority: Priority::Default,
+        tags: vec!["bench".to_string()],
+        timestamp: 1_700_000_000_000 + n as i64 * 1000,
+        actions: Vec::new(),
+        attachments: Vec::new(),
+        read: false,
+        is_expanded: false,
+        is_favorite: false,
+        deleted_at: None,
+        parsed: None,
+        link_preview: None,
+        acknowledged_by: None,
+        acknowledged_at: None,
+        note: None,
+        content_kind: ContentKind::Plain,
+        snoozed_until: None,
+        mentioned: false,
+    }
+}
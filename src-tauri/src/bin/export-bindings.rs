@@ -0,0 +1,12 @@
+//! Exports the TypeScript bindings without starting the app.
+
+fn main() {
+    if let Err(e) = ntfier_lib::export_bindings() {
+        eprintln!("{e}");
+        std::process::exit(1);
+    }
+    println!(
+        "TypeScript bindings exported to {}",
+        ntfier_lib::BINDINGS_PATH
+    );
+}
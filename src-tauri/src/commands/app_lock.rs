@@ -0,0 +1,54 @@
+//! Commands for the app lock.
+
+use tauri::{AppHandle, State};
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::AppLockMethod;
+use crate::services::{CommandTimer, LockService};
+
+/// Whether the app is locked, i.e. the lock screen should be shown.
+#[tauri::command]
+#[specta::specta]
+pub fn is_app_locked(lock: State<'_, LockService>) -> bool {
+    lock.is_locked()
+}
+
+/// Lock the app now. Does nothing if no lock method is set.
+#[tauri::command]
+#[specta::specta]
+pub fn lock_app(app_handle: AppHandle, lock: State<'_, LockService>) {
+    lock.lock(&app_handle);
+}
+
+/// Unlock the app with the PIN, or by asking the OS to authenticate the user
+/// when the `system` method is set (`pin` is ignored then).
+#[tauri::command]
+#[specta::specta]
+pub async fn unlock_app(
+    app_handle: AppHandle,
+    pin: Option<String>,
+    _timer: CommandTimer,
+) -> Result<(), AppError> {
+    LockService::unlock(&app_handle, pin).await
+}
+
+/// Set how the app is unlocked. `pin` is required for the `pin` method;
+/// `off` disables the app lock and removes the stored PIN.
+#[tauri::command]
+#[specta::specta]
+pub fn set_app_lock(
+    app_handle: AppHandle,
+    method: AppLockMethod,
+    pin: Option<String>,
+) -> Result<(), AppError> {
+    LockService::configure(&app_handle, method, pin)
+}
+
+/// Set how many minutes the main window may be out of focus before the app
+/// locks (0 = only on start).
+#[tauri::command]
+#[specta::specta]
+pub fn set_app_lock_minutes(db: State<'_, Database>, minutes: u32) -> Result<(), AppError> {
+    db.set_setting("app_lock_minutes", &minutes.to_string())
+}
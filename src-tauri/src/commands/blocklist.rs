@@ -0,0 +1,85 @@
+//! Commands for managing the topic, URL and keyword blocklists.
+
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{
+    BlockedTopic, BlockedUrl, CreateBlockedTopic, CreateBlockedUrl, CreateKeywordFilter,
+    KeywordFilter,
+};
+
+/// Get all topic blocklist entries.
+#[tauri::command]
+#[specta::specta]
+pub fn get_blocked_topics(db: State<'_, Database>) -> Result<Vec<BlockedTopic>, AppError> {
+    db.get_blocked_topics()
+}
+
+/// Block topics matching a pattern from being auto-created by account sync.
+///
+/// Existing subscriptions are left untouched.
+#[tauri::command]
+#[specta::specta]
+pub fn add_blocked_topic(
+    db: State<'_, Database>,
+    entry: CreateBlockedTopic,
+) -> Result<BlockedTopic, AppError> {
+    db.add_blocked_topic(entry)
+}
+
+/// Remove a topic blocklist entry.
+#[tauri::command]
+#[specta::specta]
+pub fn remove_blocked_topic(db: State<'_, Database>, id: String) -> Result<(), AppError> {
+    db.remove_blocked_topic(&id)
+}
+
+/// Get all URL blocklist entries.
+#[tauri::command]
+#[specta::specta]
+pub fn get_blocked_urls(db: State<'_, Database>) -> Result<Vec<BlockedUrl>, AppError> {
+    db.get_blocked_urls()
+}
+
+/// Block links to hosts matching a pattern from being opened from notifications.
+#[tauri::command]
+#[specta::specta]
+pub fn add_blocked_url(
+    db: State<'_, Database>,
+    entry: CreateBlockedUrl,
+) -> Result<BlockedUrl, AppError> {
+    db.add_blocked_url(entry)
+}
+
+/// Remove a URL blocklist entry.
+#[tauri::command]
+#[specta::specta]
+pub fn remove_blocked_url(db: State<'_, Database>, id: String) -> Result<(), AppError> {
+    db.remove_blocked_url(&id)
+}
+
+/// Get all keyword filters.
+#[tauri::command]
+#[specta::specta]
+pub fn get_keyword_filters(db: State<'_, Database>) -> Result<Vec<KeywordFilter>, AppError> {
+    db.get_keyword_filters()
+}
+
+/// Mute messages containing a keyword or matching a regular expression, in
+/// one subscription or all of them. Applies to messages received from now on.
+#[tauri::command]
+#[specta::specta]
+pub fn add_keyword_filter(
+    db: State<'_, Database>,
+    filter: CreateKeywordFilter,
+) -> Result<KeywordFilter, AppError> {
+    db.add_keyword_filter(filter)
+}
+
+/// Remove a keyword filter.
+#[tauri::command]
+#[specta::specta]
+pub fn remove_keyword_filter(db: State<'_, Database>, id: String) -> Result<(), AppError> {
+    db.remove_keyword_filter(&id)
+}
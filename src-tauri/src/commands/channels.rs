@@ -0,0 +1,41 @@
+//! Commands for managing notification channels.
+
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{Channel, ChannelInput};
+
+/// Get all notification channels with their mapped tags.
+#[tauri::command]
+#[specta::specta]
+pub fn get_channels(db: State<'_, Database>) -> Result<Vec<Channel>, AppError> {
+    db.get_channels()
+}
+
+/// Create a channel. Tags already mapped to another channel move to the new one.
+#[tauri::command]
+#[specta::specta]
+pub fn create_channel(db: State<'_, Database>, channel: ChannelInput) -> Result<Channel, AppError> {
+    db.create_channel(channel)
+}
+
+/// Update a channel's name, tags and display settings.
+///
+/// Only affects notifications received afterwards.
+#[tauri::command]
+#[specta::specta]
+pub fn update_channel(
+    db: State<'_, Database>,
+    id: String,
+    channel: ChannelInput,
+) -> Result<Channel, AppError> {
+    db.update_channel(&id, channel)
+}
+
+/// Delete a channel and its tag mappings.
+#[tauri::command]
+#[specta::specta]
+pub fn delete_channel(db: State<'_, Database>, id: String) -> Result<(), AppError> {
+    db.delete_channel(&id)
+}
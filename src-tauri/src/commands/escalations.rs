@@ -0,0 +1,33 @@
+//! Commands for managing escalation rules.
+
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{CreateEscalationRule, EscalationRule};
+
+/// Get all escalation rules.
+#[tauri::command]
+#[specta::specta]
+pub fn get_escalation_rules(db: State<'_, Database>) -> Result<Vec<EscalationRule>, AppError> {
+    db.get_escalation_rules()
+}
+
+/// Add a rule re-notifying about high priority notifications nobody read or
+/// acknowledged: every `delayMinutes` they're shown again with an alarm
+/// sound and optionally published to another topic, up to `maxRepeats` times.
+#[tauri::command]
+#[specta::specta]
+pub fn create_escalation_rule(
+    db: State<'_, Database>,
+    rule: CreateEscalationRule,
+) -> Result<EscalationRule, AppError> {
+    db.create_escalation_rule(&rule)
+}
+
+/// Remove an escalation rule.
+#[tauri::command]
+#[specta::specta]
+pub fn delete_escalation_rule(db: State<'_, Database>, id: String) -> Result<(), AppError> {
+    db.delete_escalation_rule(&id)
+}
@@ -1,11 +1,35 @@
+pub mod app_lock;
+pub mod blocklist;
+pub mod channels;
+pub mod escalations;
 pub mod notifications;
+pub mod permissions;
+pub mod publish;
+pub mod reminders;
+pub mod rules;
 pub mod settings;
+pub mod shortcuts;
+pub mod statistics;
+pub mod status;
 pub mod subscriptions;
 pub mod sync;
 pub mod update;
+pub mod virtual_topics;
 
+pub use app_lock::*;
+pub use blocklist::*;
+pub use channels::*;
+pub use escalations::*;
 pub use notifications::*;
+pub use permissions::*;
+pub use publish::*;
+pub use reminders::*;
+pub use rules::*;
 pub use settings::*;
+pub use shortcuts::*;
+pub use statistics::*;
+pub use status::*;
 pub use subscriptions::*;
 pub use sync::*;
 pub use update::*;
+pub use virtual_topics::*;
@@ -1,27 +1,50 @@
 use tauri::{AppHandle, Manager, State};
 
+use crate::config::feed::MAX_PAGE_SIZE;
+use crate::config::search::MAX_RESULTS;
 use crate::db::Database;
 use crate::error::AppError;
-use crate::models::Notification;
-use crate::services::{NtfyClient, TrayManager};
+use crate::models::{FeedCursor, FeedFilter, FeedPage, Notification};
+use crate::services::{
+    credential_manager, AcknowledgmentService, ActionService, CommandTimer, LockService,
+    ReceiptService, ReceiptState, ToastHistory, TrashService, TrayManager, UrlSafety,
+};
 
 /// Helper to refresh tray icon after unread count changes
-fn refresh_tray(app_handle: AppHandle) {
-    tauri::async_runtime::spawn(async move {
-        let tray_manager: tauri::State<TrayManager> = app_handle.state();
-        tray_manager.refresh_from_db(&app_handle).await;
-    });
+fn refresh_tray(app_handle: &AppHandle) {
+    app_handle
+        .state::<TrayManager>()
+        .refresh_from_db(app_handle);
 }
 
 #[tauri::command]
 #[specta::specta]
 pub fn get_notifications(
     db: State<'_, Database>,
+    lock: State<'_, LockService>,
     subscription_id: String,
 ) -> Result<Vec<Notification>, AppError> {
+    lock.ensure_unlocked()?;
     db.get_notifications_by_subscription(&subscription_id)
 }
 
+/// Get a page of the unified feed: notifications from all subscriptions
+/// matching `filter`, newest first. Pass the previous page's `nextCursor` as
+/// `cursor` for the next page; `limit` is capped at 200.
+#[tauri::command]
+#[specta::specta]
+pub fn get_all_notifications(
+    db: State<'_, Database>,
+    lock: State<'_, LockService>,
+    limit: u32,
+    cursor: Option<FeedCursor>,
+    filter: Option<FeedFilter>,
+) -> Result<FeedPage, AppError> {
+    lock.ensure_unlocked()?;
+    let limit = i64::from(limit).clamp(1, MAX_PAGE_SIZE);
+    db.get_all_notifications(&filter.unwrap_or_default(), cursor.as_ref(), limit)
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn mark_as_read(
@@ -30,7 +53,11 @@ pub fn mark_as_read(
     id: String,
 ) -> Result<(), AppError> {
     db.mark_notification_read(&id)?;
-    refresh_tray(app_handle);
+    app_handle.state::<ToastHistory>().dismiss(&app_handle, &id);
+    if let Some((subscription_id, ntfy_id)) = db.get_notification_origin(&id)? {
+        ReceiptService::send(&app_handle, &subscription_id, &ntfy_id, ReceiptState::Read);
+    }
+    refresh_tray(&app_handle);
     Ok(())
 }
 
@@ -42,62 +69,171 @@ pub fn mark_all_as_read(
     subscription_id: String,
 ) -> Result<(), AppError> {
     db.mark_all_notifications_read(&subscription_id)?;
-    refresh_tray(app_handle);
+    app_handle
+        .state::<ToastHistory>()
+        .dismiss_subscription(&app_handle, &subscription_id);
+    refresh_tray(&app_handle);
     Ok(())
 }
 
+/// Move a notification to the trash.
+///
+/// Remote deletion (when `delete_local_only` is off) is deferred until the
+/// trash is emptied, so the deletion can be undone with `restore_notification`.
 #[tauri::command]
 #[specta::specta]
-pub async fn delete_notification(
+pub fn delete_notification(
     app_handle: AppHandle,
     db: State<'_, Database>,
     id: String,
 ) -> Result<(), AppError> {
-    // Check if we should also delete remotely
-    let delete_local_only = db.get_delete_local_only()?;
-    if !delete_local_only {
-        if let Some((Some(ntfy_id), subscription_id)) = db.get_notification_meta(&id)? {
-            // Look up subscription to get server_url and topic
-            if let Some(subscription) = db.get_subscription_by_id(&subscription_id)? {
-                // Find credentials for this server
-                let servers = db.get_servers_with_credentials()?;
-                let server = servers
-                    .iter()
-                    .find(|s| s.url_matches(&subscription.server_url));
-
-                let (username, password) = server
-                    .and_then(|s| s.credentials())
-                    .map_or((None, None), |(u, p)| (Some(u), Some(p)));
-
-                match NtfyClient::new() {
-                    Ok(client) => {
-                        if let Err(e) = client
-                            .delete_message(
-                                &subscription.server_url,
-                                &subscription.topic,
-                                &ntfy_id,
-                                username,
-                                password,
-                            )
-                            .await
-                        {
-                            log::warn!("Failed to delete message remotely: {e}");
-                        }
-                    }
-                    Err(e) => {
-                        log::warn!("Failed to create HTTP client for remote delete: {e}");
-                    }
-                }
-            }
-        }
-    }
+    db.soft_delete_notification(&id)?;
+    refresh_tray(&app_handle);
+    Ok(())
+}
 
-    // Always delete locally
-    db.delete_notification(&id)?;
-    refresh_tray(app_handle);
+/// Restore a notification from the trash.
+#[tauri::command]
+#[specta::specta]
+pub fn restore_notification(
+    app_handle: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+) -> Result<(), AppError> {
+    db.restore_notification(&id)?;
+    refresh_tray(&app_handle);
     Ok(())
 }
 
+/// Get all notifications in the trash, most recently deleted first.
+#[tauri::command]
+#[specta::specta]
+pub fn get_trash(
+    db: State<'_, Database>,
+    lock: State<'_, LockService>,
+) -> Result<Vec<Notification>, AppError> {
+    lock.ensure_unlocked()?;
+    db.get_deleted_notifications()
+}
+
+/// Permanently delete all trashed notifications and subscriptions.
+#[tauri::command]
+#[specta::specta]
+pub async fn empty_trash(app_handle: AppHandle, _timer: CommandTimer) -> Result<(), AppError> {
+    credential_manager::record_user_access()?;
+    TrashService::empty(&app_handle).await?;
+    refresh_tray(&app_handle);
+    Ok(())
+}
+
+/// Set a note about what was done about a notification. An empty note
+/// removes it. Notes stay local and are matched by `search_notifications`.
+#[tauri::command]
+#[specta::specta]
+pub fn set_notification_note(
+    db: State<'_, Database>,
+    id: String,
+    text: String,
+) -> Result<(), AppError> {
+    let text = text.trim();
+    db.set_notification_note(&id, Some(text).filter(|t| !t.is_empty()))
+}
+
+/// Snooze a notification until `until_timestamp` (milliseconds): its toast
+/// is removed, and it's shown again and marked unread once the time comes.
+#[tauri::command]
+#[specta::specta]
+pub fn snooze_notification(
+    app_handle: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+    until_timestamp: i64,
+) -> Result<(), AppError> {
+    if until_timestamp <= chrono::Utc::now().timestamp_millis() {
+        return Err(AppError::Validation(
+            "Snooze time must be in the future".to_string(),
+        ));
+    }
+    db.set_notification_snooze(&id, Some(until_timestamp))?;
+    app_handle.state::<ToastHistory>().dismiss(&app_handle, &id);
+    Ok(())
+}
+
+/// Cancel the snooze of a notification without showing it again.
+#[tauri::command]
+#[specta::specta]
+pub fn unsnooze_notification(db: State<'_, Database>, id: String) -> Result<(), AppError> {
+    db.set_notification_snooze(&id, None)
+}
+
+/// Search notifications by title, message and note, newest first.
+#[tauri::command]
+#[specta::specta]
+pub fn search_notifications(
+    db: State<'_, Database>,
+    lock: State<'_, LockService>,
+    query: String,
+) -> Result<Vec<Notification>, AppError> {
+    lock.ensure_unlocked()?;
+    let query = query.trim();
+    if query.is_empty() {
+        return Ok(Vec::new());
+    }
+    db.search_notifications(query, MAX_RESULTS)
+}
+
+/// Acknowledge a notification, recording who took care of it.
+///
+/// With `publish`, an ack message including `note` is also sent to the topic
+/// so others subscribed to it see who took the alert.
+#[tauri::command]
+#[specta::specta]
+pub async fn acknowledge_notification(
+    db: State<'_, Database>,
+    id: String,
+    note: Option<String>,
+    publish: bool,
+    _timer: CommandTimer,
+) -> Result<Notification, AppError> {
+    credential_manager::record_user_access()?;
+    AcknowledgmentService::acknowledge(&db, &id, note.as_deref(), publish).await
+}
+
+/// Open the click or view action link of a notification in the browser.
+///
+/// Links with unsafe schemes or blocked hosts are refused. Links from
+/// untrusted subscriptions are opened only after the user confirms via
+/// `permission:request`; resolves to `false` if they decline.
+#[tauri::command]
+#[specta::specta]
+pub async fn open_notification_url(
+    app_handle: AppHandle,
+    subscription_id: String,
+    url: String,
+    _timer: CommandTimer,
+) -> Result<bool, AppError> {
+    UrlSafety::open(&app_handle, &subscription_id, &url).await
+}
+
+/// Run an action button of a notification: open a `view` action's link,
+/// send an `http` action's request or pass a `broadcast` action to the
+/// frontend as `action:broadcast`.
+///
+/// Links and requests to blocked hosts are refused. Those from untrusted
+/// subscriptions are opened or sent only after the user confirms via
+/// `permission:request`; resolves to `false` if they decline. The outcome of
+/// a request is also reported with `action:executed`.
+#[tauri::command]
+#[specta::specta]
+pub async fn execute_notification_action(
+    app_handle: AppHandle,
+    notification_id: String,
+    action_id: String,
+    _timer: CommandTimer,
+) -> Result<bool, AppError> {
+    ActionService::execute(&app_handle, &notification_id, &action_id).await
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn set_notification_favorite(
@@ -110,7 +246,11 @@ pub fn set_notification_favorite(
 
 #[tauri::command]
 #[specta::specta]
-pub fn get_favorite_notifications(db: State<'_, Database>) -> Result<Vec<Notification>, AppError> {
+pub fn get_favorite_notifications(
+    db: State<'_, Database>,
+    lock: State<'_, LockService>,
+) -> Result<Vec<Notification>, AppError> {
+    lock.ensure_unlocked()?;
     db.get_favorite_notifications()
 }
 
@@ -0,0 +1,57 @@
+//! Commands for managing action permissions.
+
+use tauri::{AppHandle, State};
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{Capability, Permission};
+use crate::services::{CommandTimer, PermissionService};
+
+/// Check whether a subscription may use a capability before running an action.
+///
+/// Prompts the user via `permission:request` unless the subscription is trusted
+/// or a decision was remembered. Resolves to `false` if denied or unanswered.
+#[tauri::command]
+#[specta::specta]
+pub async fn request_permission(
+    handle: AppHandle,
+    subscription_id: String,
+    capability: Capability,
+    detail: String,
+    _timer: CommandTimer,
+) -> Result<bool, AppError> {
+    Ok(PermissionService::request(&handle, &subscription_id, capability, &detail).await)
+}
+
+/// Answer a `permission:request` prompt.
+///
+/// With `remember`, the decision is stored and reused for future actions of
+/// the same capability from that subscription.
+#[tauri::command]
+#[specta::specta]
+pub fn respond_permission(
+    db: State<'_, Database>,
+    permissions: State<'_, PermissionService>,
+    request_id: String,
+    allow: bool,
+    remember: bool,
+) -> Result<(), AppError> {
+    permissions.respond(&db, &request_id, allow, remember)
+}
+
+/// Get remembered permission decisions, optionally for a single subscription.
+#[tauri::command]
+#[specta::specta]
+pub fn get_permissions(
+    db: State<'_, Database>,
+    subscription_id: Option<String>,
+) -> Result<Vec<Permission>, AppError> {
+    db.get_permissions(subscription_id.as_deref())
+}
+
+/// Forget a remembered decision so the user is asked again next time.
+#[tauri::command]
+#[specta::specta]
+pub fn revoke_permission(db: State<'_, Database>, id: String) -> Result<(), AppError> {
+    db.revoke_permission(&id)
+}
@@ -0,0 +1,88 @@
+//! Commands for publishing to topics.
+
+use tauri::State;
+
+use crate::config::publish::MAX_ATTACHMENT_BYTES;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{CaptureMode, OutboxEntry, PublishMessage, PublishOutcome};
+use crate::services::{
+    credential_manager, policy, CommandTimer, OutboxService, OutgoingAttachment, OutgoingMessage,
+    PublishService, ScreenCapture,
+};
+
+/// Publish a message to a subscription's topic. Fields the message leaves
+/// unset fall back to the server's publishing defaults.
+///
+/// If the server is unreachable, the message is queued in the outbox and sent
+/// once it's reachable again (`outbox:sent`), or given up on
+/// (`outbox:failed`).
+#[tauri::command]
+#[specta::specta]
+pub async fn publish_message(
+    db: State<'_, Database>,
+    subscription_id: String,
+    message: PublishMessage,
+    _timer: CommandTimer,
+) -> Result<PublishOutcome, AppError> {
+    if message.message.trim().is_empty() {
+        return Err(AppError::InvalidUrl("Message cannot be empty".to_string()));
+    }
+    credential_manager::record_user_access()?;
+    OutboxService::publish(&db, &subscription_id, &message).await
+}
+
+/// Get the messages waiting in the outbox, oldest first.
+#[tauri::command]
+#[specta::specta]
+pub fn get_outbox(db: State<'_, Database>) -> Result<Vec<OutboxEntry>, AppError> {
+    db.get_outbox()
+}
+
+/// Remove a message from the outbox without sending it.
+#[tauri::command]
+#[specta::specta]
+pub fn discard_outbox_entry(db: State<'_, Database>, id: String) -> Result<(), AppError> {
+    db.delete_outbox_entry(&id)
+}
+
+/// Tags of published screenshots (shown as a camera by ntfy clients).
+const SCREENSHOT_TAGS: [&str; 1] = ["camera"];
+
+/// Take a screenshot and publish it as an attachment to a subscription's topic.
+///
+/// Returns `AppError::Cancelled` if the user dismissed an interactive capture,
+/// or `AppError::PolicyLocked` if attachments are disabled.
+#[tauri::command]
+#[specta::specta]
+pub async fn capture_and_publish(
+    db: State<'_, Database>,
+    mode: CaptureMode,
+    subscription_id: String,
+    _timer: CommandTimer,
+) -> Result<(), AppError> {
+    policy::current().check_attachments()?;
+    credential_manager::record_user_access()?;
+    let data = ScreenCapture::capture(mode).await?;
+    if data.len() as u64 > MAX_ATTACHMENT_BYTES {
+        return Err(AppError::Validation(
+            "Screenshot is too large to publish".to_string(),
+        ));
+    }
+
+    let filename = format!(
+        "screenshot-{}.png",
+        chrono::Local::now().format("%Y%m%d-%H%M%S")
+    );
+    let message = OutgoingMessage {
+        title: "Screenshot",
+        message: &filename,
+        tags: &SCREENSHOT_TAGS,
+        ..Default::default()
+    };
+    let attachment = OutgoingAttachment {
+        filename: &filename,
+        data,
+    };
+    PublishService::publish(&db, &subscription_id, &message, Some(attachment)).await
+}
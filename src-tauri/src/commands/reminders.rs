@@ -0,0 +1,34 @@
+//! Commands for managing local reminders.
+
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::Reminder;
+
+/// Get all reminders.
+#[tauri::command]
+#[specta::specta]
+pub fn get_reminders(db: State<'_, Database>) -> Result<Vec<Reminder>, AppError> {
+    db.get_reminders()
+}
+
+/// Add a reminder shown locally whenever the cron expression `schedule`
+/// matches, e.g. `0 9 * * 1-5` for 9:00 on weekdays.
+#[tauri::command]
+#[specta::specta]
+pub fn create_reminder(
+    db: State<'_, Database>,
+    title: Option<String>,
+    message: String,
+    schedule: String,
+) -> Result<Reminder, AppError> {
+    db.create_reminder(title.as_deref(), &message, &schedule)
+}
+
+/// Remove a reminder.
+#[tauri::command]
+#[specta::specta]
+pub fn delete_reminder(db: State<'_, Database>, id: String) -> Result<(), AppError> {
+    db.delete_reminder(&id)
+}
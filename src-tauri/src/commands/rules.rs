@@ -0,0 +1,82 @@
+//! Commands for managing notification rules.
+
+use tauri::State;
+
+use crate::config::rules::{MAX_PREVIEW_NOTIFICATIONS, PREVIEW_NOTIFICATIONS};
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{Rule, RuleFile, RuleInput, RulePreview};
+use crate::services::RuleService;
+
+/// Get all rules in the order they're evaluated.
+#[tauri::command]
+#[specta::specta]
+pub fn get_rules(db: State<'_, Database>) -> Result<Vec<Rule>, AppError> {
+    db.get_rules()
+}
+
+/// Create a rule applied to notifications received from now on.
+#[tauri::command]
+#[specta::specta]
+pub fn create_rule(db: State<'_, Database>, rule: RuleInput) -> Result<Rule, AppError> {
+    let rule = db.create_rule(rule)?;
+    RuleService::invalidate();
+    Ok(rule)
+}
+
+/// Update a rule's name, conditions and actions, or enable or disable it.
+#[tauri::command]
+#[specta::specta]
+pub fn update_rule(db: State<'_, Database>, id: String, rule: RuleInput) -> Result<Rule, AppError> {
+    let rule = db.update_rule(&id, rule)?;
+    RuleService::invalidate();
+    Ok(rule)
+}
+
+/// Delete a rule.
+#[tauri::command]
+#[specta::specta]
+pub fn delete_rule(db: State<'_, Database>, id: String) -> Result<(), AppError> {
+    db.delete_rule(&id)?;
+    RuleService::invalidate();
+    Ok(())
+}
+
+/// Export all rules to a JSON file at `path`, to share them with other
+/// installations.
+#[tauri::command]
+#[specta::specta]
+pub fn export_rules(db: State<'_, Database>, path: String) -> Result<(), AppError> {
+    let json = serde_json::to_string_pretty(&RuleFile::new(db.get_rules()?))?;
+    std::fs::write(&path, json)
+        .map_err(|e| AppError::Validation(format!("Failed to write the file: {}", e.kind())))
+}
+
+/// Import the rules of a file written by `export_rules`, after the existing
+/// ones. Nothing is imported if any rule in the file is invalid. Commands of
+/// imported rules still ask for the `run_command` permission.
+#[tauri::command]
+#[specta::specta]
+pub fn import_rules(db: State<'_, Database>, path: String) -> Result<Vec<Rule>, AppError> {
+    let json = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::Validation(format!("Failed to read the file: {}", e.kind())))?;
+    let rules = db.create_rules(RuleFile::parse(&json)?)?;
+    RuleService::invalidate();
+    Ok(rules)
+}
+
+/// Preview what a candidate rule would have done to the newest `limit`
+/// stored notifications (500 by default), without saving it.
+#[tauri::command]
+#[specta::specta]
+pub fn preview_rule(
+    db: State<'_, Database>,
+    rule: RuleInput,
+    limit: Option<u32>,
+) -> Result<RulePreview, AppError> {
+    let limit = limit
+        .unwrap_or(PREVIEW_NOTIFICATIONS)
+        .min(MAX_PREVIEW_NOTIFICATIONS);
+    let notifications = db.get_recent_notifications_with_topics(i64::from(limit))?;
+    RulePreview::run(rule, notifications)
+}
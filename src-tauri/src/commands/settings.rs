@@ -1,14 +1,46 @@
-use tauri::State;
+use std::path::Path;
+
+use tauri::{AppHandle, State};
+use zeroize::Zeroizing;
 
 use crate::db::Database;
 use crate::error::AppError;
-use crate::models::{AppSettings, NotificationDisplayMethod, ServerConfig, ThemeMode};
-use crate::services::ConnectionManager;
+use crate::models::{
+    normalize_url, AppSettings, ConfigFileStatus, DeviceInfo, DndSchedule, DoNotDisturb,
+    HttpTimeouts, LogRedaction, MissingCredential, NotificationDisplayMethod, Policy, Priority,
+    ProxySettings, PublishDefaults, ReceiptTopic, ServerConfig, ServerRemovalImpact,
+    ServerResolution, SubscriptionSort, TagPriority, ThemeMode, TrayIconStyle, WatchFolder,
+};
+use crate::services::client_certificate::{self, ClientCertificate};
+use crate::services::{
+    credential_manager, log_redaction, policy, proxy, resolver, CommandTimer, ConfigFileService,
+    ConnectionManager, DndScheduler, KioskMode, MetricsEndpoint, NtfyClient, TrayManager,
+};
 
 #[tauri::command]
 #[specta::specta]
-pub fn get_settings(db: State<'_, Database>) -> Result<AppSettings, AppError> {
-    db.get_settings()
+pub fn get_settings(
+    db: State<'_, Database>,
+    kiosk: State<'_, KioskMode>,
+) -> Result<AppSettings, AppError> {
+    let mut settings = db.get_settings()?;
+    settings.kiosk_mode = kiosk.is_enabled();
+    Ok(settings)
+}
+
+/// Get the outcome of the last load of the `ntfier.toml` configuration file,
+/// including which settings it manages.
+#[tauri::command]
+#[specta::specta]
+pub fn get_config_file_status(config_file: State<'_, ConfigFileService>) -> ConfigFileStatus {
+    config_file.status()
+}
+
+/// Get the policy locking settings in managed deployments.
+#[tauri::command]
+#[specta::specta]
+pub fn get_policy() -> Policy {
+    policy::current()
 }
 
 #[tauri::command]
@@ -25,18 +57,68 @@ pub fn set_theme(db: State<'_, Database>, theme: ThemeMode) -> Result<(), AppErr
 #[tauri::command]
 #[specta::specta]
 pub fn add_server(db: State<'_, Database>, server: ServerConfig) -> Result<(), AppError> {
-    db.add_server(server)
+    policy::current().check_server(&server.url)?;
+    db.add_server(server)?;
+    resolver::reload(&db);
+    Ok(())
 }
 
+/// Edit a server, migrating its keychain entry and reconnecting its subscriptions.
+#[tauri::command]
+#[specta::specta]
+pub async fn update_server(
+    db: State<'_, Database>,
+    conn_manager: State<'_, ConnectionManager>,
+    original_url: String,
+    server: ServerConfig,
+    _timer: CommandTimer,
+) -> Result<(), AppError> {
+    if !server.url_matches(&original_url) {
+        policy::current().check_server(&server.url)?;
+    }
+    conn_manager.disconnect_server(&original_url).await;
+    let new_url = server.url.clone();
+    let result = db.update_server(&original_url, server);
+    resolver::reload(&db);
+    client_certificate::reload(&db);
+
+    // Reconnect under whichever URL is current, even if the update failed
+    let url = if result.is_ok() {
+        &new_url
+    } else {
+        &original_url
+    };
+    conn_manager.connect_server(url).await;
+    result
+}
+
+/// Report how many subscriptions and notifications removing a server would delete.
+///
+/// Meant to be shown in a confirmation dialog before calling `remove_server`.
+#[tauri::command]
+#[specta::specta]
+pub fn get_server_removal_impact(
+    db: State<'_, Database>,
+    url: String,
+) -> Result<ServerRemovalImpact, AppError> {
+    db.get_server_removal_impact(&url)
+}
+
+/// Remove a server, optionally keeping its subscriptions' local history.
 #[tauri::command]
 #[specta::specta]
 pub async fn remove_server(
     db: State<'_, Database>,
     conn_manager: State<'_, ConnectionManager>,
     url: String,
+    keep_history: bool,
+    _timer: CommandTimer,
 ) -> Result<(), AppError> {
     conn_manager.disconnect_server(&url).await;
-    db.remove_server(&url)
+    let result = db.remove_server(&url, keep_history);
+    resolver::reload(&db);
+    client_certificate::reload(&db);
+    result
 }
 
 #[tauri::command]
@@ -70,6 +152,22 @@ pub fn set_notification_method(
     db.set_setting("notification_method", method_str)
 }
 
+/// Set how the subscription list is ordered.
+#[tauri::command]
+#[specta::specta]
+pub fn set_subscription_sort(
+    db: State<'_, Database>,
+    sort: SubscriptionSort,
+) -> Result<(), AppError> {
+    let sort_str = match sort {
+        SubscriptionSort::Recent => "recent",
+        SubscriptionSort::Alphabetical => "alphabetical",
+        SubscriptionSort::UnreadFirst => "unread_first",
+        SubscriptionSort::Manual => "manual",
+    };
+    db.set_setting("subscription_sort", sort_str)
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn set_notification_force_display(
@@ -112,6 +210,18 @@ pub fn set_notification_sound(db: State<'_, Database>, enabled: bool) -> Result<
     db.set_setting("notification_sound", if enabled { "true" } else { "false" })
 }
 
+#[tauri::command]
+#[specta::specta]
+pub fn set_notification_group_by_topic(
+    db: State<'_, Database>,
+    enabled: bool,
+) -> Result<(), AppError> {
+    db.set_setting(
+        "notification_group_by_topic",
+        if enabled { "true" } else { "false" },
+    )
+}
+
 #[tauri::command]
 #[specta::specta]
 pub fn set_compact_view(db: State<'_, Database>, enabled: bool) -> Result<(), AppError> {
@@ -138,3 +248,548 @@ pub fn set_delete_local_only(db: State<'_, Database>, enabled: bool) -> Result<(
 pub fn set_favorites_enabled(db: State<'_, Database>, enabled: bool) -> Result<(), AppError> {
     db.set_setting("favorites_enabled", if enabled { "true" } else { "false" })
 }
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_startup_delay(db: State<'_, Database>, secs: u32) -> Result<(), AppError> {
+    db.set_setting("startup_delay_secs", &secs.to_string())
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_wait_for_network(db: State<'_, Database>, enabled: bool) -> Result<(), AppError> {
+    db.set_setting("wait_for_network", if enabled { "true" } else { "false" })
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_credential_lock_minutes(db: State<'_, Database>, minutes: u32) -> Result<(), AppError> {
+    db.set_setting("credential_lock_minutes", &minutes.to_string())?;
+    credential_manager::set_idle_timeout(credential_manager::idle_timeout_from_minutes(minutes))
+}
+
+/// Clear cached credentials so the next use re-reads them from the OS keychain.
+#[tauri::command]
+#[specta::specta]
+pub fn lock_credentials() -> Result<(), AppError> {
+    credential_manager::clear_cache()
+}
+
+#[tauri::command]
+#[specta::specta]
+pub fn set_daily_summary_enabled(db: State<'_, Database>, enabled: bool) -> Result<(), AppError> {
+    db.set_setting(
+        "daily_summary_enabled",
+        if enabled { "true" } else { "false" },
+    )
+}
+
+/// Set the local time of day (`HH:MM`) the daily digest is shown at.
+#[tauri::command]
+#[specta::specta]
+pub fn set_daily_summary_time(db: State<'_, Database>, time: String) -> Result<(), AppError> {
+    let time = chrono::NaiveTime::parse_from_str(time.trim(), "%H:%M")
+        .map_err(|_| AppError::InvalidUrl(format!("Invalid time of day: {time}")))?;
+    db.set_setting("daily_summary_time", &time.format("%H:%M").to_string())
+}
+
+/// Enable fetching link previews. Off by default, since fetching a preview
+/// tells the linked site that the message was received.
+#[tauri::command]
+#[specta::specta]
+pub fn set_link_previews_enabled(db: State<'_, Database>, enabled: bool) -> Result<(), AppError> {
+    db.set_setting(
+        "link_previews_enabled",
+        if enabled { "true" } else { "false" },
+    )
+}
+
+/// Set the lowest priority shown as a toast. Notifications below it are still
+/// stored; subscriptions with their own floor ignore this setting.
+#[tauri::command]
+#[specta::specta]
+pub fn set_min_toast_priority(db: State<'_, Database>, priority: Priority) -> Result<(), AppError> {
+    db.set_setting("min_toast_priority", &(priority as u8).to_string())
+}
+
+/// Get the defaults merged into messages published to a server.
+#[tauri::command]
+#[specta::specta]
+pub fn get_server_publish_defaults(
+    db: State<'_, Database>,
+    url: String,
+) -> Result<PublishDefaults, AppError> {
+    db.get_server_publish_defaults(&url)
+}
+
+/// Set the defaults merged into messages published to a server: its tags are
+/// added to every message, and its priority, markdown flag and delay apply
+/// where a message sets none.
+#[tauri::command]
+#[specta::specta]
+pub fn set_server_publish_defaults(
+    db: State<'_, Database>,
+    url: String,
+    defaults: PublishDefaults,
+) -> Result<(), AppError> {
+    defaults.validate()?;
+    let defaults = PublishDefaults {
+        tags: defaults.tags.iter().map(|t| t.trim().to_string()).collect(),
+        delay: defaults.delay.map(|d| d.trim().to_string()),
+        ..defaults
+    };
+    db.set_server_publish_defaults(&url, &defaults)
+}
+
+/// Get how a server's host name is resolved.
+#[tauri::command]
+#[specta::specta]
+pub fn get_server_resolution(
+    db: State<'_, Database>,
+    url: String,
+) -> Result<ServerResolution, AppError> {
+    Ok(policy::current().resolution(db.get_server_resolution(&url)?))
+}
+
+/// Set how a server's host name is resolved: which address family to try
+/// first, static addresses to use instead of DNS, or a SOCKS5 proxy it is
+/// only reached through. Applies to HTTP requests and WebSocket connections,
+/// though with a SOCKS proxy only WebSocket connections work; the server's
+/// subscriptions reconnect. A proxy forced by the policy can't be changed.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_server_resolution(
+    db: State<'_, Database>,
+    conn_manager: State<'_, ConnectionManager>,
+    url: String,
+    resolution: ServerResolution,
+    _timer: CommandTimer,
+) -> Result<(), AppError> {
+    resolution.validate()?;
+    policy::current().check_resolution(&resolution)?;
+    let resolution = ServerResolution {
+        static_addresses: resolution
+            .static_addresses
+            .iter()
+            .map(|a| a.trim().to_string())
+            .collect(),
+        socks_proxy: resolution.socks_proxy.map(|p| p.trim().to_string()),
+        ..resolution
+    };
+    db.set_server_resolution(&url, &resolution)?;
+    resolver::reload(&db);
+
+    conn_manager.disconnect_server(&url).await;
+    conn_manager.connect_server(&url).await;
+    Ok(())
+}
+
+/// Get the path of the PKCS#12 file a server presents as its client
+/// certificate, if it has one.
+#[tauri::command]
+#[specta::specta]
+pub fn get_server_client_certificate(
+    db: State<'_, Database>,
+    url: String,
+) -> Result<Option<String>, AppError> {
+    db.get_server_client_certificate(&url)
+}
+
+/// Set the PKCS#12 file a server presents as its client certificate, for
+/// servers behind a reverse proxy requiring one (mTLS); no `path` removes it.
+/// The file must open with `passphrase`, which is stored in the OS keychain.
+/// Applies to HTTP requests and WebSocket connections; the server's
+/// subscriptions reconnect.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_server_client_certificate(
+    db: State<'_, Database>,
+    conn_manager: State<'_, ConnectionManager>,
+    url: String,
+    path: Option<String>,
+    passphrase: Option<String>,
+    _timer: CommandTimer,
+) -> Result<(), AppError> {
+    let path = path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+    let passphrase = Zeroizing::new(passphrase.unwrap_or_default());
+    if let Some(ref path) = path {
+        ClientCertificate::load(Path::new(path), &passphrase)?;
+    }
+
+    // Store the passphrase first, and put the previous one back if the path
+    // can't be saved, so the two never go out of step
+    let previous = credential_manager::get_certificate_passphrase(&url)?.map(Zeroizing::new);
+    if path.is_some() && !passphrase.is_empty() {
+        credential_manager::store_certificate_passphrase(&url, &passphrase)?;
+    } else {
+        credential_manager::delete_certificate_passphrase(&url)?;
+    }
+    if let Err(e) = db.set_server_client_certificate(&url, path.as_deref()) {
+        let restored = match previous {
+            Some(previous) => credential_manager::store_certificate_passphrase(&url, &previous),
+            None => credential_manager::delete_certificate_passphrase(&url),
+        };
+        if let Err(e) = restored {
+            log::warn!("Failed to restore certificate passphrase: {e}");
+        }
+        return Err(e);
+    }
+    client_certificate::reload(&db);
+
+    conn_manager.disconnect_server(&url).await;
+    conn_manager.connect_server(&url).await;
+    Ok(())
+}
+
+/// Set the proxy HTTP requests and WebSocket connections go through. For a
+/// manual proxy with a username, `password` replaces the stored one; `None`
+/// keeps it. All subscriptions reconnect.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_proxy_settings(
+    db: State<'_, Database>,
+    conn_manager: State<'_, ConnectionManager>,
+    settings: ProxySettings,
+    password: Option<String>,
+    _timer: CommandTimer,
+) -> Result<(), AppError> {
+    settings.validate()?;
+    let settings = ProxySettings {
+        host: settings
+            .host
+            .map(|h| h.trim().to_string())
+            .filter(|h| !h.is_empty()),
+        username: settings
+            .username
+            .map(|u| u.trim().to_string())
+            .filter(|u| !u.is_empty()),
+        ..settings
+    };
+
+    match password.filter(|_| settings.username.is_some()) {
+        Some(password) if !password.is_empty() => {
+            credential_manager::store_proxy_password(&password)?;
+        }
+        Some(_) => credential_manager::delete_proxy_password()?,
+        None if settings.username.is_none() => credential_manager::delete_proxy_password()?,
+        None => {}
+    }
+    db.set_setting("proxy", &serde_json::to_string(&settings)?)?;
+    proxy::reload(&db);
+
+    conn_manager.disconnect_all().await;
+    conn_manager.connect_all().await;
+    Ok(())
+}
+
+/// Set the timeouts of requests to ntfy servers. Applies to requests made
+/// from now on.
+#[tauri::command]
+#[specta::specta]
+pub fn set_http_timeouts(db: State<'_, Database>, timeouts: HttpTimeouts) -> Result<(), AppError> {
+    timeouts.validate()?;
+    db.set_setting("http_timeouts", &serde_json::to_string(&timeouts)?)?;
+    NtfyClient::reload_timeouts(&db);
+    Ok(())
+}
+
+/// Set the priorities given to notifications by tag. Applies to notifications
+/// received from now on; a channel's own priority takes precedence.
+#[tauri::command]
+#[specta::specta]
+pub fn set_tag_priorities(
+    db: State<'_, Database>,
+    mappings: Vec<TagPriority>,
+) -> Result<(), AppError> {
+    let mut normalized = Vec::with_capacity(mappings.len());
+    for mapping in mappings {
+        mapping.validate()?;
+        normalized.push(TagPriority {
+            tag: mapping.tag.trim().to_string(),
+            priority: mapping.priority,
+        });
+    }
+    db.set_setting("tag_priorities", &serde_json::to_string(&normalized)?)
+}
+
+/// Set the words marking messages as mentioning the user, e.g. their name
+/// and handle. Applies to messages received from now on.
+#[tauri::command]
+#[specta::specta]
+pub fn set_mentions(db: State<'_, Database>, mentions: Vec<String>) -> Result<(), AppError> {
+    let mut normalized: Vec<String> = Vec::with_capacity(mentions.len());
+    for mention in mentions {
+        let mention = mention.trim();
+        if !mention.is_empty() && !normalized.iter().any(|m| m.eq_ignore_ascii_case(mention)) {
+            normalized.push(mention.to_string());
+        }
+    }
+    db.set_setting("mentions", &serde_json::to_string(&normalized)?)
+}
+
+/// Set when toasts are held back, and which notifications are shown anyway.
+#[tauri::command]
+#[specta::specta]
+pub fn set_do_not_disturb(
+    db: State<'_, Database>,
+    mut settings: DoNotDisturb,
+) -> Result<(), AppError> {
+    settings.validate()?;
+    for keyword in &mut settings.exceptions.keywords {
+        *keyword = keyword.trim().to_string();
+    }
+    db.set_setting("do_not_disturb", &serde_json::to_string(&settings)?)
+}
+
+/// Set the weekly quiet hours during which only max priority notifications
+/// are shown as toasts.
+#[tauri::command]
+#[specta::specta]
+pub fn set_dnd_schedule(
+    app_handle: AppHandle,
+    db: State<'_, Database>,
+    schedule: DndSchedule,
+) -> Result<(), AppError> {
+    schedule.validate()?;
+    db.set_setting("dnd_schedule", &serde_json::to_string(&schedule)?)?;
+    DndScheduler::check(&app_handle);
+    Ok(())
+}
+
+/// Set the tray icon style and switch the tray icon to it.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_tray_icon_style(
+    app_handle: AppHandle,
+    db: State<'_, Database>,
+    tray_manager: State<'_, TrayManager>,
+    style: TrayIconStyle,
+    _timer: CommandTimer,
+) -> Result<(), AppError> {
+    let style_str = match style {
+        TrayIconStyle::Color => "color",
+        TrayIconStyle::MonochromeLight => "monochrome_light",
+        TrayIconStyle::MonochromeDark => "monochrome_dark",
+        TrayIconStyle::Auto => "auto",
+    };
+    db.set_setting("tray_icon_style", style_str)?;
+    tray_manager.reload_icons(&app_handle).await;
+    Ok(())
+}
+
+/// Show only the topic in toasts while the session is locked. Redacted
+/// toasts are shown again in full once the session is unlocked.
+#[tauri::command]
+#[specta::specta]
+pub fn set_hide_content_when_locked(
+    db: State<'_, Database>,
+    enabled: bool,
+) -> Result<(), AppError> {
+    db.set_setting(
+        "hide_content_when_locked",
+        if enabled { "true" } else { "false" },
+    )
+}
+
+/// Set how topic names and message content are written to the log.
+#[tauri::command]
+#[specta::specta]
+pub fn set_log_redaction(db: State<'_, Database>, redaction: LogRedaction) -> Result<(), AppError> {
+    db.set_setting("log_redaction", redaction.as_str())?;
+    log_redaction::set_mode(redaction);
+    Ok(())
+}
+
+/// Set the local folders whose new and changed files are published to a
+/// subscription's topic. Files already in a folder aren't published.
+#[tauri::command]
+#[specta::specta]
+pub fn set_watch_folders(
+    db: State<'_, Database>,
+    folders: Vec<WatchFolder>,
+) -> Result<(), AppError> {
+    if folders.iter().any(|f| f.attach_file) {
+        policy::current().check_attachments()?;
+    }
+    let mut normalized = Vec::with_capacity(folders.len());
+    for folder in folders {
+        folder.validate()?;
+        if db
+            .get_subscription_by_id(&folder.subscription_id)?
+            .is_none()
+        {
+            return Err(AppError::NotFound(format!(
+                "Subscription {} not found",
+                folder.subscription_id
+            )));
+        }
+        normalized.push(WatchFolder {
+            path: folder.path.trim().to_string(),
+            ..folder
+        });
+    }
+    db.set_setting("watch_folders", &serde_json::to_string(&normalized)?)
+}
+
+/// Set the subscription the clipboard quick-share shortcut publishes to
+/// (`None` disables quick-share) and whether to ask before publishing.
+#[tauri::command]
+#[specta::specta]
+pub fn set_clipboard_share(
+    db: State<'_, Database>,
+    subscription_id: Option<String>,
+    confirm: bool,
+) -> Result<(), AppError> {
+    if let Some(ref id) = subscription_id {
+        let sub = db
+            .get_subscription_by_id(id)?
+            .ok_or_else(|| AppError::NotFound(format!("Subscription {id} not found")))?;
+        if sub.local || sub.detached {
+            return Err(AppError::InvalidUrl(format!(
+                "Subscription {id} can't be published to"
+            )));
+        }
+    }
+    db.set_setting(
+        "clipboard_share_subscription_id",
+        subscription_id.as_deref().unwrap_or_default(),
+    )?;
+    db.set_setting(
+        "clipboard_share_confirm",
+        if confirm { "true" } else { "false" },
+    )
+}
+
+/// List servers whose password is missing from or unreadable in the OS keychain.
+#[tauri::command]
+#[specta::specta]
+pub fn verify_credentials_consistency(
+    db: State<'_, Database>,
+) -> Result<Vec<MissingCredential>, AppError> {
+    db.verify_credentials_consistency()
+}
+
+/// Get this installation's device ID and name.
+#[tauri::command]
+#[specta::specta]
+pub fn get_device_info(db: State<'_, Database>) -> Result<DeviceInfo, AppError> {
+    db.get_device_info()
+}
+
+/// Rename this device. `None` restores the computer's host name.
+#[tauri::command]
+#[specta::specta]
+pub fn set_device_name(db: State<'_, Database>, name: Option<String>) -> Result<(), AppError> {
+    let name = name.map(|n| n.trim().to_string()).unwrap_or_default();
+    db.set_setting("device_name", &name)
+}
+
+/// Publish a receipt to `topic` whenever a message is shown or read, or stop
+/// publishing receipts with `None`.
+#[tauri::command]
+#[specta::specta]
+pub fn set_receipt_topic(
+    db: State<'_, Database>,
+    topic: Option<ReceiptTopic>,
+) -> Result<(), AppError> {
+    let json = match topic {
+        Some(topic) => {
+            topic.validate()?;
+            serde_json::to_string(&ReceiptTopic {
+                server_url: normalize_url(topic.server_url.trim()).to_string(),
+                topic: topic.topic.trim().to_string(),
+            })?
+        }
+        None => String::new(),
+    };
+    db.set_setting("receipt_topic", &json)
+}
+
+/// Serve Prometheus metrics at `http://127.0.0.1:<port>/metrics`, or stop
+/// serving them with `None`. The previous port is kept if the new one can't
+/// be listened on.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_metrics_endpoint_port(
+    app_handle: AppHandle,
+    db: State<'_, Database>,
+    metrics_endpoint: State<'_, MetricsEndpoint>,
+    port: Option<u16>,
+    _timer: CommandTimer,
+) -> Result<(), AppError> {
+    metrics_endpoint.set_port(&app_handle, port).await?;
+    db.set_setting(
+        "metrics_endpoint_port",
+        &port.map(|p| p.to_string()).unwrap_or_default(),
+    )
+}
+
+/// Announce notifications to screen readers in addition to showing toasts.
+#[tauri::command]
+#[specta::specta]
+pub fn set_screen_reader_announcements(
+    db: State<'_, Database>,
+    enabled: bool,
+) -> Result<(), AppError> {
+    db.set_setting(
+        "screen_reader_announcements",
+        if enabled { "true" } else { "false" },
+    )
+}
+
+/// Use a custom PNG or ICO file as the tray icon. `None` restores the default.
+///
+/// The file is validated before it is saved; if it later fails to load (e.g.
+/// it was moved), the default icon is used.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_tray_icon_path(
+    app_handle: AppHandle,
+    db: State<'_, Database>,
+    tray_manager: State<'_, TrayManager>,
+    path: Option<String>,
+    _timer: CommandTimer,
+) -> Result<(), AppError> {
+    set_tray_icon_setting(&db, "tray_icon_path", path)?;
+    tray_manager.reload_icons(&app_handle).await;
+    Ok(())
+}
+
+/// Use a custom PNG or ICO file as the tray icon while there are unread
+/// notifications. `None` restores the default.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_tray_unread_icon_path(
+    app_handle: AppHandle,
+    db: State<'_, Database>,
+    tray_manager: State<'_, TrayManager>,
+    path: Option<String>,
+    _timer: CommandTimer,
+) -> Result<(), AppError> {
+    set_tray_icon_setting(&db, "tray_unread_icon_path", path)?;
+    tray_manager.reload_icons(&app_handle).await;
+    Ok(())
+}
+
+/// Validates a custom tray icon file and stores its path (empty when unset).
+fn set_tray_icon_setting(db: &Database, key: &str, path: Option<String>) -> Result<(), AppError> {
+    let path = path.map(|p| p.trim().to_string()).filter(|p| !p.is_empty());
+    if let Some(ref path) = path {
+        TrayManager::load_icon_file(std::path::Path::new(path)).map_err(AppError::InvalidUrl)?;
+    }
+    db.set_setting(key, path.as_deref().unwrap_or(""))
+}
+
+/// Turn kiosk mode on or off. While it's on, only commands that read data or
+/// handle notifications are allowed. Fails when turning it off while the app
+/// was started with `--kiosk`.
+#[tauri::command]
+#[specta::specta]
+pub fn set_kiosk_mode(
+    db: State<'_, Database>,
+    kiosk: State<'_, KioskMode>,
+    enabled: bool,
+) -> Result<(), AppError> {
+    kiosk.set_enabled(enabled)?;
+    db.set_setting("kiosk_mode", if enabled { "true" } else { "false" })
+}
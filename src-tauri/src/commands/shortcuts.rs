@@ -0,0 +1,118 @@
+//! Commands for configuring global keyboard shortcuts.
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use tauri::{AppHandle, State};
+
+use crate::config::publish::MAX_ATTACHMENT_BYTES;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{ClipboardContent, ShortcutAction, ShortcutBinding};
+use crate::services::{
+    credential_manager, policy, CommandTimer, HotkeyService, OutgoingAttachment, OutgoingMessage,
+    PublishService,
+};
+
+/// Title of messages published by clipboard quick-share.
+const CLIPBOARD_TITLE: &str = "Clipboard";
+
+/// Tags of messages published by clipboard quick-share.
+const CLIPBOARD_TAGS: [&str; 1] = ["clipboard"];
+
+/// Get all bound global shortcuts.
+#[tauri::command]
+#[specta::specta]
+pub fn get_shortcuts(db: State<'_, Database>) -> Result<Vec<ShortcutBinding>, AppError> {
+    db.get_shortcuts()
+}
+
+/// Bind an action to a global shortcut, or unbind it when `accelerator` is empty.
+///
+/// If the shortcut can't be registered (for example because another
+/// application already uses it), the previous binding is kept.
+#[tauri::command]
+#[specta::specta]
+pub fn set_shortcut(
+    app: AppHandle,
+    db: State<'_, Database>,
+    action: ShortcutAction,
+    accelerator: Option<String>,
+) -> Result<(), AppError> {
+    let accelerator = accelerator
+        .map(|a| a.trim().to_string())
+        .filter(|a| !a.is_empty());
+    if let Some(ref accelerator) = accelerator {
+        HotkeyService::parse(accelerator)?;
+    }
+
+    let previous = db
+        .get_shortcuts()?
+        .into_iter()
+        .find(|binding| binding.action == action)
+        .map(|binding| binding.accelerator);
+    db.set_shortcut(action, accelerator.as_deref())?;
+
+    if HotkeyService::register_all(&app)?.contains(&action) {
+        db.set_shortcut(action, previous.as_deref())?;
+        HotkeyService::register_all(&app)?;
+        return Err(AppError::Validation(format!(
+            "Shortcut {} is already in use",
+            accelerator.unwrap_or_default()
+        )));
+    }
+
+    Ok(())
+}
+
+/// Publish clipboard contents to the quick-share topic.
+///
+/// Called by the frontend when the `share_clipboard` shortcut is pressed,
+/// after reading the clipboard and, with `clipboard_share_confirm`, asking.
+#[tauri::command]
+#[specta::specta]
+pub async fn share_clipboard(
+    db: State<'_, Database>,
+    content: ClipboardContent,
+    _timer: CommandTimer,
+) -> Result<(), AppError> {
+    credential_manager::record_user_access()?;
+    let subscription_id = db
+        .get_clipboard_share_subscription_id()?
+        .ok_or_else(|| AppError::NotFound("No quick-share topic is set".to_string()))?;
+
+    match content {
+        ClipboardContent::Text { text } => {
+            if text.trim().is_empty() {
+                return Err(AppError::Validation("Clipboard is empty".to_string()));
+            }
+            let message = OutgoingMessage {
+                title: CLIPBOARD_TITLE,
+                message: &text,
+                tags: &CLIPBOARD_TAGS,
+                ..Default::default()
+            };
+            PublishService::publish(&db, &subscription_id, &message, None).await
+        }
+        ClipboardContent::Image { data } => {
+            policy::current().check_attachments()?;
+            let data = STANDARD
+                .decode(data.trim())
+                .map_err(|e| AppError::Validation(format!("Invalid clipboard image: {e}")))?;
+            if data.len() as u64 > MAX_ATTACHMENT_BYTES {
+                return Err(AppError::Validation(
+                    "Clipboard image is too large to share".to_string(),
+                ));
+            }
+            let message = OutgoingMessage {
+                title: CLIPBOARD_TITLE,
+                message: "Image from clipboard",
+                tags: &CLIPBOARD_TAGS,
+                ..Default::default()
+            };
+            let attachment = OutgoingAttachment {
+                filename: "clipboard.png",
+                data,
+            };
+            PublishService::publish(&db, &subscription_id, &message, Some(attachment)).await
+        }
+    }
+}
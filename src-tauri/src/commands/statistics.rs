@@ -0,0 +1,88 @@
+//! Commands for notification statistics.
+
+use tauri::State;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{ActivityReport, DailySummary, ReportFormat, ReportRange, ServerStats};
+use crate::services::{credential_manager, CommandTimer, NtfyClient, SummaryService};
+
+/// Get a digest of notifications received in the last day.
+///
+/// The same payload is emitted as the `summary:daily` event when the
+/// scheduled daily digest is shown.
+#[tauri::command]
+#[specta::specta]
+pub fn get_daily_summary(db: State<'_, Database>) -> Result<DailySummary, AppError> {
+    SummaryService::build(&db)
+}
+
+/// Generate a report of notification volume over `range`: per-topic counts,
+/// busiest hours and priority distribution, rendered as JSON or Markdown.
+#[tauri::command]
+#[specta::specta]
+pub fn generate_activity_report(
+    db: State<'_, Database>,
+    range: ReportRange,
+    format: ReportFormat,
+) -> Result<String, AppError> {
+    let since = chrono::Utc::now().timestamp_millis() - range.secs() * 1000;
+    let topics = db.get_topic_activity(since)?;
+
+    let report = ActivityReport {
+        range,
+        since,
+        total_received: topics.iter().map(|t| t.received).sum(),
+        topics,
+        hourly: db.get_hourly_counts(since)?,
+        priorities: db.get_priority_counts(since)?,
+    };
+
+    match format {
+        ReportFormat::Json => Ok(serde_json::to_string_pretty(&report)?),
+        ReportFormat::Markdown => Ok(report.to_markdown()),
+    }
+}
+
+/// Get message totals and (with admin credentials) user counts of a configured server.
+///
+/// Wraps ntfy's `/v1/stats` and admin `/v1/users` endpoints.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_server_stats(
+    db: State<'_, Database>,
+    server_url: String,
+    _timer: CommandTimer,
+) -> Result<ServerStats, AppError> {
+    credential_manager::record_user_access()?;
+    let server = db
+        .get_servers_with_credentials()?
+        .into_iter()
+        .find(|s| s.url_matches(&server_url))
+        .ok_or_else(|| AppError::NotFound(format!("Server {server_url} not found")))?;
+    let credentials = server.credentials();
+
+    let client = NtfyClient::new()?;
+    let stats = client
+        .get_stats(
+            &server.url,
+            credentials.map(|(u, _)| u),
+            credentials.map(|(_, p)| p),
+        )
+        .await?;
+
+    let users = match credentials {
+        Some((username, password)) => client.list_users(&server.url, username, password).await?,
+        None => None,
+    };
+
+    Ok(ServerStats {
+        server_url: server.url.clone(),
+        messages: stats.messages,
+        messages_rate: stats.messages_rate,
+        user_count: users.as_ref().map(|u| u.len() as u32),
+        admin_count: users
+            .as_ref()
+            .map(|u| u.iter().filter(|user| user.role == "admin").count() as u32),
+    })
+}
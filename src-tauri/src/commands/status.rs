@@ -0,0 +1,49 @@
+//! Commands for runtime status reporting.
+
+use tauri::{AppHandle, State};
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{ConnectionLogEntry, InternalMetrics, MaintenanceReport, RuntimeStatus};
+use crate::services::{CommandTimer, MaintenanceService, MetricsService, StatusService};
+
+/// Get a snapshot of connection states, last sync times and unread counts.
+///
+/// The same payload is emitted periodically as the `runtime:status` event.
+#[tauri::command]
+#[specta::specta]
+pub async fn get_runtime_status(
+    handle: AppHandle,
+    _timer: CommandTimer,
+) -> Result<RuntimeStatus, AppError> {
+    StatusService::collect(&handle).await
+}
+
+/// Get internal performance metrics collected since the app started.
+#[tauri::command]
+#[specta::specta]
+pub fn get_internal_metrics() -> InternalMetrics {
+    MetricsService::snapshot()
+}
+
+/// Get the most recent connection errors of a subscription, newest first.
+#[tauri::command]
+#[specta::specta]
+pub fn get_connection_log(
+    db: State<'_, Database>,
+    subscription_id: String,
+) -> Result<Vec<ConnectionLogEntry>, AppError> {
+    db.get_connection_log(&subscription_id)
+}
+
+/// Remove orphaned data: notifications and other rows of deleted
+/// subscriptions, cached images of deleted notifications and keychain
+/// entries of removed servers. Also runs daily in the background.
+#[tauri::command]
+#[specta::specta]
+pub async fn run_maintenance(
+    handle: AppHandle,
+    _timer: CommandTimer,
+) -> Result<MaintenanceReport, AppError> {
+    MaintenanceService::run(&handle).await
+}
@@ -1,9 +1,17 @@
-use tauri::State;
+use tauri::{AppHandle, State};
 
 use crate::db::Database;
 use crate::error::AppError;
-use crate::models::{CreateSubscription, Subscription};
-use crate::services::ConnectionManager;
+use crate::models::{
+    parse_subscription_list, publish_snippet, ApplyTemplateParams, CreateLocalSubscription,
+    CreateSubscription, CreateSubscriptionPattern, FileMirror, Priority, RetentionPolicy,
+    SkippedImportLine, SnippetFlavor, Subscription, SubscriptionImport, SubscriptionPattern,
+    SubscriptionSettings, SubscriptionTemplate,
+};
+use crate::services::{
+    policy, CommandTimer, ConnectionManager, PatternService, RetentionService, TemplateService,
+    TestDataService,
+};
 
 #[tauri::command]
 #[specta::specta]
@@ -17,21 +25,172 @@ pub async fn add_subscription(
     db: State<'_, Database>,
     conn_manager: State<'_, ConnectionManager>,
     subscription: CreateSubscription,
+    _timer: CommandTimer,
 ) -> Result<Subscription, AppError> {
+    policy::current().check_server(&subscription.server_url)?;
     let sub = db.create_subscription(subscription)?;
     conn_manager.connect(&sub).await?;
     Ok(sub)
 }
 
+/// List the built-in subscription templates.
+#[tauri::command]
+#[specta::specta]
+pub fn list_subscription_templates() -> Vec<SubscriptionTemplate> {
+    SubscriptionTemplate::all()
+}
+
+/// Subscribe from a template.
+///
+/// Without a topic, the template's suggested topic with a random suffix is
+/// used. The template's toast floor and tag priorities are applied as well.
+#[tauri::command]
+#[specta::specta]
+pub async fn apply_subscription_template(
+    db: State<'_, Database>,
+    conn_manager: State<'_, ConnectionManager>,
+    template_id: String,
+    params: ApplyTemplateParams,
+    _timer: CommandTimer,
+) -> Result<Subscription, AppError> {
+    policy::current().check_server(&params.server_url)?;
+    TemplateService::apply(&db, &conn_manager, &template_id, params).await
+}
+
+/// Get a ready-to-paste snippet that publishes to a subscription's topic.
+///
+/// Servers with credentials get a basic auth header with a password placeholder.
+#[tauri::command]
+#[specta::specta]
+pub fn get_publish_snippet(
+    db: State<'_, Database>,
+    subscription_id: String,
+    flavor: SnippetFlavor,
+) -> Result<String, AppError> {
+    let sub = db
+        .get_subscription_by_id(&subscription_id)?
+        .ok_or_else(|| AppError::NotFound(format!("Subscription {subscription_id} not found")))?;
+    if sub.local {
+        return Err(AppError::InvalidUrl(
+            "Local topics can't be published to".to_string(),
+        ));
+    }
+    let username = db.get_server_username(&sub.server_url)?;
+    Ok(publish_snippet(
+        flavor,
+        &sub.server_url,
+        &sub.topic,
+        username.as_deref(),
+    ))
+}
+
+/// Import subscriptions from a text or CSV file with one topic URL per line,
+/// optionally followed by a comma and a display name.
+///
+/// Valid topics are created in one transaction and connected; invalid lines,
+/// topics on servers blocked by the policy and topics already subscribed are
+/// reported as skipped.
+#[tauri::command]
+#[specta::specta]
+pub async fn import_subscriptions_from_file(
+    db: State<'_, Database>,
+    conn_manager: State<'_, ConnectionManager>,
+    path: String,
+    _timer: CommandTimer,
+) -> Result<SubscriptionImport, AppError> {
+    let text = std::fs::read_to_string(&path)
+        .map_err(|e| AppError::Validation(format!("Failed to read the file: {}", e.kind())))?;
+    let (entries, mut skipped) = parse_subscription_list(&text, &db.get_default_server_url()?);
+    let policy = policy::current();
+    let (entries, blocked): (Vec<_>, Vec<_>) = entries
+        .into_iter()
+        .partition(|(_, sub)| policy.check_server(&sub.server_url).is_ok());
+    skipped.extend(blocked.into_iter().map(|(line, sub)| SkippedImportLine {
+        line,
+        text: format!("{}/{}", sub.server_url, sub.topic),
+        reason: "Server blocked by policy".to_string(),
+    }));
+
+    let subs: Vec<CreateSubscription> = entries.iter().map(|(_, sub)| sub.clone()).collect();
+    let ids = db.create_subscriptions(&subs)?;
+
+    let mut created = Vec::new();
+    for ((line, sub), id) in entries.into_iter().zip(ids) {
+        let Some(id) = id else {
+            skipped.push(SkippedImportLine {
+                line,
+                text: format!("{}/{}", sub.server_url, sub.topic),
+                reason: "Already subscribed".to_string(),
+            });
+            continue;
+        };
+        if let Some(sub) = db.get_subscription_by_id(&id)? {
+            if let Err(e) = conn_manager.connect(&sub).await {
+                log::warn!("Failed to connect imported subscription {id}: {e}");
+            }
+            created.push(sub);
+        }
+    }
+    skipped.sort_by_key(|s| s.line);
+
+    Ok(SubscriptionImport { created, skipped })
+}
+
+/// Move a subscription to the trash and close its connection.
+///
+/// Its notifications are kept until the trash is emptied, so the removal
+/// can be undone with `restore_subscription`.
 #[tauri::command]
 #[specta::specta]
 pub async fn remove_subscription(
     db: State<'_, Database>,
     conn_manager: State<'_, ConnectionManager>,
     id: String,
+    _timer: CommandTimer,
 ) -> Result<(), AppError> {
     conn_manager.disconnect(&id).await;
-    db.delete_subscription(&id)
+    db.soft_delete_subscription(&id)
+}
+
+/// Restore a subscription from the trash and reconnect it.
+#[tauri::command]
+#[specta::specta]
+pub async fn restore_subscription(
+    db: State<'_, Database>,
+    conn_manager: State<'_, ConnectionManager>,
+    id: String,
+    _timer: CommandTimer,
+) -> Result<Subscription, AppError> {
+    let sub = db.restore_subscription(&id)?;
+    conn_manager.connect(&sub).await?;
+    Ok(sub)
+}
+
+/// Reconnect a subscription now instead of waiting for its next retry.
+///
+/// Without `force`, only a disconnected subscription is reconnected; with it,
+/// a connected one is restarted as well.
+#[tauri::command]
+#[specta::specta]
+pub async fn reconnect_subscription(
+    db: State<'_, Database>,
+    conn_manager: State<'_, ConnectionManager>,
+    id: String,
+    force: bool,
+    _timer: CommandTimer,
+) -> Result<(), AppError> {
+    let sub = db
+        .get_subscription_by_id(&id)?
+        .filter(|sub| sub.deleted_at.is_none())
+        .ok_or_else(|| AppError::NotFound(format!("Subscription {id} not found")))?;
+    conn_manager.reconnect(&sub, force).await
+}
+
+/// Get all subscriptions in the trash, most recently deleted first.
+#[tauri::command]
+#[specta::specta]
+pub fn get_deleted_subscriptions(db: State<'_, Database>) -> Result<Vec<Subscription>, AppError> {
+    db.get_deleted_subscriptions()
 }
 
 #[tauri::command]
@@ -39,3 +198,283 @@ pub async fn remove_subscription(
 pub fn toggle_mute(db: State<'_, Database>, id: String) -> Result<Subscription, AppError> {
     db.toggle_subscription_mute(&id)
 }
+
+/// Set the manual order of the subscription list.
+///
+/// Takes effect when the `subscription_sort` setting is `manual`.
+#[tauri::command]
+#[specta::specta]
+pub fn reorder_subscriptions(db: State<'_, Database>, ids: Vec<String>) -> Result<(), AppError> {
+    db.reorder_subscriptions(&ids)
+}
+
+/// Mark a subscription as trusted so its actions run without permission prompts.
+#[tauri::command]
+#[specta::specta]
+pub fn set_subscription_trusted(
+    db: State<'_, Database>,
+    id: String,
+    trusted: bool,
+) -> Result<Subscription, AppError> {
+    db.set_subscription_trusted(&id, trusted)?;
+    db.get_subscription_by_id(&id)?
+        .ok_or_else(|| AppError::NotFound(format!("Subscription {id} not found")))
+}
+
+/// Set how many hours a subscription's notifications are kept before being
+/// deleted automatically. `None` keeps them until deleted by hand.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_subscription_auto_delete(
+    app_handle: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+    hours: Option<u32>,
+    _timer: CommandTimer,
+) -> Result<Subscription, AppError> {
+    if hours == Some(0) {
+        return Err(AppError::Validation(
+            "Auto-delete window must be at least one hour".to_string(),
+        ));
+    }
+
+    db.set_subscription_auto_delete(&id, hours)?;
+    RetentionService::run(&app_handle);
+    db.get_subscription_by_id(&id)?
+        .ok_or_else(|| AppError::NotFound(format!("Subscription {id} not found")))
+}
+
+/// Set how long a subscription's notifications are kept: forever, for a
+/// number of days or as a number of the newest ones. Notifications past it
+/// are deleted right away.
+#[tauri::command]
+#[specta::specta]
+pub async fn set_retention_policy(
+    app_handle: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+    policy: RetentionPolicy,
+    _timer: CommandTimer,
+) -> Result<Subscription, AppError> {
+    db.set_subscription_retention(&id, policy)?;
+    RetentionService::run(&app_handle);
+    db.get_subscription_by_id(&id)?
+        .ok_or_else(|| AppError::NotFound(format!("Subscription {id} not found")))
+}
+
+/// Set the lowest priority shown as a toast for a subscription, overriding the
+/// global `min_toast_priority`. `None` falls back to the global floor.
+#[tauri::command]
+#[specta::specta]
+pub fn set_subscription_min_toast_priority(
+    db: State<'_, Database>,
+    id: String,
+    priority: Option<Priority>,
+) -> Result<Subscription, AppError> {
+    db.set_subscription_min_toast_priority(&id, priority)?;
+    db.get_subscription_by_id(&id)?
+        .ok_or_else(|| AppError::NotFound(format!("Subscription {id} not found")))
+}
+
+/// Get the notification settings a subscription overrides.
+#[tauri::command]
+#[specta::specta]
+pub fn get_subscription_settings(
+    db: State<'_, Database>,
+    id: String,
+) -> Result<SubscriptionSettings, AppError> {
+    db.get_subscription_settings(&id)
+}
+
+/// Set the notification settings a subscription overrides: its toast sound,
+/// the lowest priority shown as a toast, the display method, whether new
+/// notifications are marked as read, and maintenance windows during which
+/// they're marked as read and not shown. Unset fields use the global settings.
+#[tauri::command]
+#[specta::specta]
+pub fn set_subscription_settings(
+    db: State<'_, Database>,
+    id: String,
+    settings: SubscriptionSettings,
+) -> Result<SubscriptionSettings, AppError> {
+    settings.validate()?;
+    db.set_subscription_settings(&id, &settings)?;
+    db.get_subscription_settings(&id)
+}
+
+/// Hide message content in a subscription's toasts, showing only the topic
+/// and unread count. The full messages remain available in the app.
+#[tauri::command]
+#[specta::specta]
+pub fn set_subscription_hide_content(
+    db: State<'_, Database>,
+    id: String,
+    hidden: bool,
+) -> Result<Subscription, AppError> {
+    db.set_subscription_hide_content(&id, hidden)?;
+    db.get_subscription_by_id(&id)?
+        .ok_or_else(|| AppError::NotFound(format!("Subscription {id} not found")))
+}
+
+/// Pin a subscription to the top of the list. Pinned subscriptions are also
+/// synced first after startup.
+#[tauri::command]
+#[specta::specta]
+pub fn set_subscription_pinned(
+    db: State<'_, Database>,
+    id: String,
+    pinned: bool,
+) -> Result<Subscription, AppError> {
+    db.set_subscription_pinned(&id, pinned)?;
+    db.get_subscription_by_id(&id)?
+        .ok_or_else(|| AppError::NotFound(format!("Subscription {id} not found")))
+}
+
+/// Append every notification received for a subscription to a local file, as
+/// JSON lines or a plain log, so other tools can tail it. `None` stops mirroring.
+#[tauri::command]
+#[specta::specta]
+pub fn set_subscription_mirror(
+    db: State<'_, Database>,
+    id: String,
+    mirror: Option<FileMirror>,
+) -> Result<Subscription, AppError> {
+    let mirror = mirror
+        .map(|m| {
+            m.validate()?;
+            Ok::<_, AppError>(FileMirror {
+                path: m.path.trim().to_string(),
+                ..m
+            })
+        })
+        .transpose()?;
+    db.set_subscription_mirror(&id, mirror.as_ref())?;
+    db.get_subscription_by_id(&id)?
+        .ok_or_else(|| AppError::NotFound(format!("Subscription {id} not found")))
+}
+
+/// Exclude a subscription from notification history sync. Missed messages are
+/// never fetched for it; it only receives messages over its live connection.
+#[tauri::command]
+#[specta::specta]
+pub fn set_subscription_exclude_from_sync(
+    db: State<'_, Database>,
+    id: String,
+    excluded: bool,
+) -> Result<Subscription, AppError> {
+    db.set_subscription_exclude_from_sync(&id, excluded)?;
+    db.get_subscription_by_id(&id)?
+        .ok_or_else(|| AppError::NotFound(format!("Subscription {id} not found")))
+}
+
+/// Limit how far back history sync fetches a subscription's missed messages,
+/// in hours. `None` fetches everything since the last sync.
+#[tauri::command]
+#[specta::specta]
+pub fn set_subscription_sync_window(
+    db: State<'_, Database>,
+    id: String,
+    hours: Option<u32>,
+) -> Result<Subscription, AppError> {
+    if hours == Some(0) {
+        return Err(AppError::Validation(
+            "Sync window must be at least one hour".to_string(),
+        ));
+    }
+    db.set_subscription_sync_window(&id, hours)?;
+    db.get_subscription_by_id(&id)?
+        .ok_or_else(|| AppError::NotFound(format!("Subscription {id} not found")))
+}
+
+/// Add a local test subscription that never touches the network.
+#[tauri::command]
+#[specta::specta]
+pub fn add_local_subscription(
+    db: State<'_, Database>,
+    subscription: CreateLocalSubscription,
+) -> Result<Subscription, AppError> {
+    db.create_local_subscription(subscription)
+}
+
+/// Fill a local subscription with synthetic notifications.
+///
+/// Priorities cycle through `priority_mix`. Returns the number generated.
+#[tauri::command]
+#[specta::specta]
+pub async fn generate_test_notifications(
+    app_handle: AppHandle,
+    subscription_id: String,
+    count: u32,
+    priority_mix: Vec<Priority>,
+    _timer: CommandTimer,
+) -> Result<u32, AppError> {
+    TestDataService::generate(&app_handle, &subscription_id, count, &priority_mix)
+}
+
+/// Get all pattern subscriptions.
+#[tauri::command]
+#[specta::specta]
+pub fn get_subscription_patterns(
+    db: State<'_, Database>,
+) -> Result<Vec<SubscriptionPattern>, AppError> {
+    db.get_subscription_patterns()
+}
+
+/// Add a pattern subscription and create children for topics already matching it.
+///
+/// Only servers that expose a topic listing produce children.
+#[tauri::command]
+#[specta::specta]
+pub async fn add_subscription_pattern(
+    handle: AppHandle,
+    db: State<'_, Database>,
+    pattern: CreateSubscriptionPattern,
+    _timer: CommandTimer,
+) -> Result<SubscriptionPattern, AppError> {
+    policy::current().check_server(&pattern.server_url)?;
+    let pattern = db.add_subscription_pattern(pattern)?;
+    if let Err(e) = PatternService::refresh(&handle, &pattern).await {
+        log::warn!(
+            "Initial refresh of pattern {} failed: {}",
+            pattern.pattern,
+            e
+        );
+    }
+    Ok(pattern)
+}
+
+/// Remove a pattern subscription.
+///
+/// With `remove_children`, its child subscriptions are moved to the trash;
+/// otherwise they are kept as plain subscriptions.
+#[tauri::command]
+#[specta::specta]
+pub async fn remove_subscription_pattern(
+    db: State<'_, Database>,
+    conn_manager: State<'_, ConnectionManager>,
+    id: String,
+    remove_children: bool,
+    _timer: CommandTimer,
+) -> Result<(), AppError> {
+    if remove_children {
+        let children = db
+            .get_all_subscriptions()?
+            .into_iter()
+            .filter(|s| s.pattern_id.as_deref() == Some(id.as_str()));
+        for child in children {
+            conn_manager.disconnect(&child.id).await;
+            db.soft_delete_subscription(&child.id)?;
+        }
+    }
+    db.remove_subscription_pattern(&id)
+}
+
+/// Poll topic listings now and create any newly matching child subscriptions.
+#[tauri::command]
+#[specta::specta]
+pub async fn refresh_subscription_patterns(
+    handle: AppHandle,
+    _timer: CommandTimer,
+) -> Result<Vec<Subscription>, AppError> {
+    Ok(PatternService::refresh_all(&handle).await)
+}
@@ -3,7 +3,10 @@ use tauri::{AppHandle, State};
 use crate::db::Database;
 use crate::error::AppError;
 use crate::models::{normalize_url, Subscription};
-use crate::services::{ConnectionManager, NtfyClient, SyncService};
+use crate::services::{
+    log_redaction, CommandTimer, ConnectionManager, NtfyClient, SyncCancellation, SyncService,
+    SyncSummary,
+};
 
 /// Sync subscriptions from a server that has user credentials
 #[tauri::command]
@@ -12,7 +15,9 @@ pub async fn sync_subscriptions(
     app_handle: AppHandle,
     db: State<'_, Database>,
     conn_manager: State<'_, ConnectionManager>,
+    cancellation: State<'_, SyncCancellation>,
     server_url: String,
+    _timer: CommandTimer,
 ) -> Result<Vec<Subscription>, AppError> {
     log::info!("sync_subscriptions called for server: {server_url}");
 
@@ -47,7 +52,8 @@ pub async fn sync_subscriptions(
         .ok_or_else(|| AppError::Connection("Server has no password configured".to_string()))?;
 
     // Fetch account info from ntfy server
-    let client = NtfyClient::new()?;
+    let cancel_token = cancellation.token();
+    let client = NtfyClient::new()?.with_cancellation(cancel_token.clone());
     let account = client.get_account(&server_url, username, password).await?;
 
     log::info!(
@@ -57,6 +63,8 @@ pub async fn sync_subscriptions(
 
     // Load existing subscriptions once (avoid N+1 query)
     let existing = db.get_all_subscriptions()?;
+    let trashed = db.get_deleted_subscriptions()?;
+    let blocklist = db.get_blocked_topics()?;
     let our_base = normalize_url(&server_url);
 
     let mut synced_subscriptions = Vec::new();
@@ -64,7 +72,7 @@ pub async fn sync_subscriptions(
     for ntfy_sub in account.subscriptions {
         log::info!(
             "Processing subscription: {} @ {}",
-            ntfy_sub.topic,
+            log_redaction::redact(&ntfy_sub.topic),
             ntfy_sub.base_url
         );
 
@@ -81,13 +89,42 @@ pub async fn sync_subscriptions(
             .iter()
             .find(|s| s.server_url_matches(our_base) && s.topic == ntfy_sub.topic)
         {
-            log::info!("Subscription already exists: {}", ntfy_sub.topic);
+            log::info!(
+                "Subscription already exists: {}",
+                log_redaction::redact(&ntfy_sub.topic)
+            );
             synced_subscriptions.push(existing_sub.clone());
             continue;
         }
 
+        // Don't resurrect subscriptions the user moved to the trash
+        if trashed
+            .iter()
+            .any(|s| s.server_url_matches(our_base) && s.topic == ntfy_sub.topic)
+        {
+            log::info!(
+                "Subscription is in trash, skipping: {}",
+                log_redaction::redact(&ntfy_sub.topic)
+            );
+            continue;
+        }
+
+        if blocklist
+            .iter()
+            .any(|b| b.matches(&server_url, &ntfy_sub.topic))
+        {
+            log::info!(
+                "Topic is blocklisted, skipping: {}",
+                log_redaction::redact(&ntfy_sub.topic)
+            );
+            continue;
+        }
+
         // Create new subscription
-        log::info!("Creating new subscription: {}", ntfy_sub.topic);
+        log::info!(
+            "Creating new subscription: {}",
+            log_redaction::redact(&ntfy_sub.topic)
+        );
         let new_sub = db.create_subscription(crate::models::CreateSubscription {
             topic: ntfy_sub.topic.clone(),
             server_url: server_url.clone(),
@@ -109,8 +146,17 @@ pub async fn sync_subscriptions(
         "Syncing notifications for {} subscriptions...",
         synced_subscriptions.len()
     );
-    for sub in &synced_subscriptions {
-        SyncService::sync_subscription_notifications(
+    let mut summary = SyncSummary::default();
+    let mut by_importance = synced_subscriptions.clone();
+    SyncService::sort_by_importance(&mut by_importance);
+    for sub in by_importance.iter().filter(|s| !s.exclude_from_sync) {
+        if cancel_token.is_cancelled() {
+            summary.cancelled = true;
+            summary.emit(&app_handle);
+            return Err(AppError::Cancelled("Sync cancelled".to_string()));
+        }
+
+        let result = SyncService::sync_subscription_notifications(
             &app_handle,
             &db,
             &client,
@@ -119,9 +165,18 @@ pub async fn sync_subscriptions(
             Some(password.as_str()),
         )
         .await;
+        summary.record(&result);
     }
+    summary.emit(&app_handle);
 
     log::info!("Notification sync completed for server {server_url}");
 
     Ok(synced_subscriptions)
 }
+
+/// Cancel all in-flight sync operations
+#[tauri::command]
+#[specta::specta]
+pub fn cancel_sync(cancellation: State<'_, SyncCancellation>) {
+    cancellation.cancel();
+}
@@ -3,14 +3,17 @@
 use tauri::AppHandle;
 
 use crate::error::AppError;
-use crate::services::{UpdateInfo, UpdateService};
+use crate::services::{CommandTimer, UpdateInfo, UpdateService};
 
 /// Check for available updates.
 ///
 /// Returns update information if an update is available, null otherwise.
 #[tauri::command]
 #[specta::specta]
-pub async fn check_for_update(handle: AppHandle) -> Result<Option<UpdateInfo>, AppError> {
+pub async fn check_for_update(
+    handle: AppHandle,
+    _timer: CommandTimer,
+) -> Result<Option<UpdateInfo>, AppError> {
     UpdateService::check_for_update(&handle).await
 }
 
@@ -19,7 +22,7 @@ pub async fn check_for_update(handle: AppHandle) -> Result<Option<UpdateInfo>, A
 /// This will download the update and may restart the application.
 #[tauri::command]
 #[specta::specta]
-pub async fn install_update(handle: AppHandle) -> Result<(), AppError> {
+pub async fn install_update(handle: AppHandle, _timer: CommandTimer) -> Result<(), AppError> {
     UpdateService::install_update(&handle).await
 }
 
@@ -0,0 +1,70 @@
+//! Commands for managing virtual topics.
+
+use tauri::{AppHandle, Manager, State};
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{Notification, VirtualTopic, VirtualTopicInput};
+use crate::services::{LockService, TrayManager};
+
+/// Get all virtual topics with their unread counts.
+#[tauri::command]
+#[specta::specta]
+pub fn get_virtual_topics(db: State<'_, Database>) -> Result<Vec<VirtualTopic>, AppError> {
+    db.get_virtual_topics()
+}
+
+/// Get the notifications included in a virtual topic, newest first.
+#[tauri::command]
+#[specta::specta]
+pub fn get_virtual_topic_notifications(
+    db: State<'_, Database>,
+    lock: State<'_, LockService>,
+    id: String,
+) -> Result<Vec<Notification>, AppError> {
+    lock.ensure_unlocked()?;
+    db.get_virtual_topic_notifications(&id)
+}
+
+/// Mark all notifications included in a virtual topic as read.
+#[tauri::command]
+#[specta::specta]
+pub fn mark_virtual_topic_read(
+    app_handle: AppHandle,
+    db: State<'_, Database>,
+    id: String,
+) -> Result<(), AppError> {
+    db.mark_virtual_topic_read(&id)?;
+    app_handle
+        .state::<TrayManager>()
+        .refresh_from_db(&app_handle);
+    Ok(())
+}
+
+/// Create a virtual topic merging several subscriptions into one view.
+#[tauri::command]
+#[specta::specta]
+pub fn create_virtual_topic(
+    db: State<'_, Database>,
+    topic: VirtualTopicInput,
+) -> Result<VirtualTopic, AppError> {
+    db.create_virtual_topic(topic)
+}
+
+/// Update a virtual topic's subscriptions, filter and display settings.
+#[tauri::command]
+#[specta::specta]
+pub fn update_virtual_topic(
+    db: State<'_, Database>,
+    id: String,
+    topic: VirtualTopicInput,
+) -> Result<VirtualTopic, AppError> {
+    db.update_virtual_topic(&id, topic)
+}
+
+/// Delete a virtual topic. The notifications it included are kept.
+#[tauri::command]
+#[specta::specta]
+pub fn delete_virtual_topic(db: State<'_, Database>, id: String) -> Result<(), AppError> {
+    db.delete_virtual_topic(&id)
+}
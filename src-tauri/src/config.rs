@@ -9,4 +9,333 @@ pub mod connection {
     /// Maximum random jitter in seconds added to backoff intervals.
     /// Helps prevent thundering herd when multiple connections retry simultaneously.
     pub const JITTER_MAX_SECS: u64 = 3;
+
+    /// Maximum time in seconds to wait for the WebSocket handshake to complete.
+    pub const HANDSHAKE_TIMEOUT_SECS: u64 = 15;
+
+    /// Messages a connection buffers for storing before it stops reading
+    /// from the socket.
+    pub const INGEST_BUFFER_SIZE: usize = 256;
+
+    /// Maximum number of buffered messages stored before the tray is refreshed.
+    pub const INGEST_BATCH_SIZE: usize = 64;
+
+    /// Maximum number of toasts being shown at once, and shown by a single
+    /// sync. Messages arriving or synced while all slots are busy are stored
+    /// without a toast.
+    pub const MAX_CONCURRENT_TOASTS: usize = 4;
+
+    /// Connection errors kept per subscription in the connection log.
+    pub const LOG_ENTRIES_PER_SUBSCRIPTION: i64 = 20;
+}
+
+/// Retry configuration for notification writes that failed on arrival.
+pub mod write_retry {
+    /// Delays in milliseconds before each retry of a failed write.
+    /// The write is dropped once all retries have failed.
+    pub const RETRY_BACKOFF_MS: [u64; 5] = [250, 500, 1_000, 2_000, 5_000];
+
+    /// Maximum number of writes waiting for a retry. The oldest write is
+    /// dropped when a new one arrives at a full queue.
+    pub const CAPACITY: usize = 500;
+}
+
+/// HTTP client timeout configuration.
+pub mod http {
+    /// Default maximum time in seconds to establish a TCP/TLS connection to a server.
+    pub const CONNECT_TIMEOUT_SECS: u32 = 10;
+
+    /// Default maximum time in seconds to wait between successive reads of a response.
+    pub const READ_TIMEOUT_SECS: u32 = 30;
+
+    /// Default overall deadline in seconds for a single request, including the response body.
+    pub const REQUEST_TIMEOUT_SECS: u32 = 30;
+
+    /// Default overall deadline in seconds for message polling requests.
+    /// Longer than the default because `since=all` may return a large backlog.
+    pub const POLL_TIMEOUT_SECS: u32 = 120;
+
+    /// Longest timeout in seconds that can be set in the settings.
+    pub const MAX_TIMEOUT_SECS: u32 = 3_600;
+
+    /// Overall deadline in seconds for downloading notification images.
+    pub const IMAGE_DOWNLOAD_TIMEOUT_SECS: u64 = 10;
+
+    /// Overall deadline in seconds for fetching a page for its link preview.
+    pub const LINK_PREVIEW_TIMEOUT_SECS: u64 = 10;
+
+    /// Time in seconds an unused pooled connection is kept open for reuse.
+    pub const POOL_IDLE_TIMEOUT_SECS: u64 = 90;
+
+    /// Maximum number of idle connections kept open per server.
+    pub const POOL_MAX_IDLE_PER_HOST: usize = 8;
+
+    /// Interval in seconds between TCP keep-alive probes on idle connections.
+    pub const TCP_KEEPALIVE_SECS: u64 = 60;
+
+    /// Session tokens are renewed this many seconds before they expire.
+    pub const SESSION_TOKEN_MARGIN_SECS: i64 = 300;
+}
+
+/// Tray icon configuration.
+pub mod tray {
+    /// Largest width or height in pixels accepted for a custom tray icon.
+    pub const MAX_CUSTOM_ICON_PX: u32 = 512;
+
+    /// Delay in milliseconds before a requested icon refresh runs. Requests
+    /// arriving in the meantime are served by the same refresh.
+    pub const REFRESH_DEBOUNCE_MS: u64 = 250;
+}
+
+/// Session lock detection configuration.
+pub mod session {
+    /// Interval in seconds between checks whether the session is locked.
+    pub const LOCK_CHECK_INTERVAL_SECS: u64 = 3;
+
+    /// Maximum number of redacted toasts kept to show again in full after
+    /// unlocking; older ones are dropped.
+    pub const MAX_CATCH_UP_TOASTS: usize = 5;
+}
+
+/// App lock configuration.
+pub mod app_lock {
+    /// Interval in seconds between idle checks of the app lock.
+    pub const IDLE_CHECK_INTERVAL_SECS: u64 = 30;
+
+    /// Minimum length of the app lock PIN.
+    pub const MIN_PIN_LENGTH: usize = 4;
+}
+
+/// Runtime status reporting configuration.
+pub mod status {
+    /// Interval in seconds between `runtime:status` events.
+    pub const REPORT_INTERVAL_SECS: u64 = 5;
+}
+
+/// Startup sequencing configuration.
+pub mod startup {
+    /// Interval in seconds between connectivity probes while waiting for the network.
+    pub const NETWORK_CHECK_INTERVAL_SECS: u64 = 2;
+
+    /// Maximum time in seconds to wait for the network before syncing anyway.
+    pub const NETWORK_WAIT_MAX_SECS: u64 = 60;
+
+    /// Timeout in seconds for a single connectivity probe.
+    pub const NETWORK_PROBE_TIMEOUT_SECS: u64 = 3;
+}
+
+/// Wake-up and network change detection.
+pub mod wake {
+    /// Interval in seconds between checks for a wake-up or network change.
+    pub const CHECK_INTERVAL_SECS: u64 = 5;
+
+    /// How much longer than the check interval the wall clock has to have
+    /// advanced between two checks for the system to be considered to have
+    /// slept, in seconds.
+    pub const SLEEP_THRESHOLD_SECS: i64 = 30;
+}
+
+/// Trash (soft-delete) configuration.
+pub mod trash {
+    /// How long deleted notifications and subscriptions stay restorable, in seconds.
+    pub const RETENTION_SECS: i64 = 30 * 24 * 60 * 60;
+}
+
+/// Credential cache configuration.
+pub mod credentials {
+    /// Interval in seconds between idle-lock checks of the credential cache.
+    pub const IDLE_CHECK_INTERVAL_SECS: u64 = 60;
+}
+
+/// Action permission configuration.
+pub mod permissions {
+    /// How long to wait for the user to answer a permission prompt before denying.
+    pub const PROMPT_TIMEOUT_SECS: u64 = 120;
+}
+
+/// Pattern subscription configuration.
+pub mod patterns {
+    /// Interval in seconds between topic listing polls for pattern subscriptions.
+    pub const POLL_INTERVAL_SECS: u64 = 300;
+}
+
+/// Per-subscription retention configuration.
+pub mod retention {
+    /// Interval in seconds between checks for notifications past their subscription's retention.
+    pub const CHECK_INTERVAL_SECS: u64 = 600;
+}
+
+/// Daily summary configuration.
+pub mod summary {
+    /// Interval in seconds between checks whether the daily digest is due.
+    pub const CHECK_INTERVAL_SECS: u64 = 60;
+
+    /// How far back the daily digest looks, in seconds.
+    pub const WINDOW_SECS: i64 = 24 * 60 * 60;
+
+    /// Number of busiest topics named in the digest notification.
+    pub const TOP_TOPICS: usize = 3;
+}
+
+/// Reminder configuration.
+pub mod reminders {
+    /// Interval in seconds between checks for due reminders.
+    pub const CHECK_INTERVAL_SECS: u64 = 30;
+}
+
+/// Notification rule configuration.
+pub mod rules {
+    /// Time a rule's command may run before it is killed (seconds).
+    pub const COMMAND_TIMEOUT_SECS: u64 = 60;
+
+    /// Version of the rule files written by `export_rules`.
+    pub const FILE_VERSION: u32 = 1;
+
+    /// Stored notifications a rule is previewed against by default.
+    pub const PREVIEW_NOTIFICATIONS: u32 = 500;
+
+    /// Most stored notifications a rule can be previewed against.
+    pub const MAX_PREVIEW_NOTIFICATIONS: u32 = 5_000;
+}
+
+/// Escalation configuration.
+pub mod escalation {
+    /// Interval in seconds between checks for notifications to escalate.
+    pub const CHECK_INTERVAL_SECS: u64 = 30;
+
+    /// Most times a rule may escalate a notification.
+    pub const MAX_REPEATS: u32 = 10;
+
+    /// Notifications older than this (seconds) are never escalated, so a new
+    /// rule doesn't go off for old unread messages.
+    pub const MAX_AGE_SECS: i64 = 24 * 60 * 60;
+}
+
+/// Outbox configuration.
+pub mod outbox {
+    /// Interval in seconds between attempts to send queued messages.
+    pub const FLUSH_INTERVAL_SECS: u64 = 30;
+
+    /// Failed attempts to a reachable server after which a queued message is
+    /// given up on.
+    pub const MAX_ATTEMPTS: u32 = 5;
+}
+
+/// Snooze configuration.
+pub mod snooze {
+    /// Interval in seconds between checks for snoozed notifications that
+    /// are due to be shown again.
+    pub const CHECK_INTERVAL_SECS: u64 = 15;
+}
+
+/// Do-not-disturb schedule configuration.
+pub mod dnd {
+    /// Interval in seconds between checks whether scheduled quiet hours
+    /// began or ended.
+    pub const CHECK_INTERVAL_SECS: u64 = 30;
+}
+
+/// Local (offline) test subscription configuration.
+pub mod local_topics {
+    /// Placeholder server URL local subscriptions are attached to.
+    pub const SERVER_URL: &str = "local://ntfier";
+
+    /// Maximum number of test notifications generated per call.
+    pub const MAX_TEST_NOTIFICATIONS: u32 = 100;
+
+    /// Time between generated test notifications, in seconds (newest is now).
+    pub const TEST_NOTIFICATION_SPACING_SECS: i64 = 60 * 60;
+}
+
+/// Link preview configuration.
+pub mod link_previews {
+    /// Maximum number of bytes read from a page when looking for Open Graph tags.
+    pub const MAX_HTML_BYTES: usize = 512 * 1024;
+}
+
+/// Notification search configuration.
+pub mod search {
+    /// Maximum number of notifications returned by a search.
+    pub const MAX_RESULTS: i64 = 200;
+}
+
+/// Unified feed configuration.
+pub mod feed {
+    /// Maximum number of notifications in a page of the feed.
+    pub const MAX_PAGE_SIZE: i64 = 200;
+}
+
+/// Metrics endpoint configuration.
+pub mod metrics_endpoint {
+    /// Largest HTTP request head read from a metrics client (bytes).
+    pub const MAX_REQUEST_BYTES: usize = 8 * 1024;
+}
+
+/// Publishing configuration.
+pub mod publish {
+    /// Largest file attached to a published message (bytes).
+    /// Matches ntfy's default attachment size limit.
+    pub const MAX_ATTACHMENT_BYTES: u64 = 15 * 1024 * 1024;
+}
+
+/// Watch folder configuration.
+pub mod folder_watcher {
+    /// Interval between scans of the watched folders (seconds).
+    pub const POLL_INTERVAL_SECS: u64 = 5;
+}
+
+/// Subscription template configuration.
+pub mod templates {
+    /// Length of the random suffix added to a template's suggested topic.
+    pub const TOPIC_SUFFIX_LEN: usize = 6;
+}
+
+/// Log redaction configuration.
+pub mod log_redaction {
+    /// Characters of a topic or message kept in the log in truncate mode.
+    pub const TRUNCATE_CHARS: usize = 8;
+}
+
+/// Safety checks for links opened from notifications.
+pub mod url_safety {
+    /// URL schemes click and view actions may open. Everything else (`file:`,
+    /// `javascript:`, custom protocol handlers, ...) is refused.
+    pub const ALLOWED_SCHEMES: [&str; 3] = ["http", "https", "mailto"];
+}
+
+/// Message history sync configuration.
+pub mod sync {
+    /// Difference in seconds between the local and server clocks above which
+    /// a warning is logged.
+    pub const CLOCK_SKEW_WARN_SECS: i64 = 60;
+}
+
+/// Configuration file.
+pub mod config_file {
+    /// Name of the configuration file in the app data directory.
+    pub const FILE_NAME: &str = "ntfier.toml";
+    /// Interval between checks of the file for changes (seconds).
+    pub const POLL_INTERVAL_SECS: u64 = 5;
+}
+
+/// Provisioning for managed deployments.
+pub mod provisioning {
+    /// Environment variable holding the path of a provisioning JSON file.
+    pub const FILE_VAR: &str = "NTFIER_PROVISION";
+    /// Environment variable holding the URL of a server to provision.
+    pub const SERVER_VAR: &str = "NTFIER_SERVER";
+    /// Environment variable holding the username for the provisioned server.
+    pub const USERNAME_VAR: &str = "NTFIER_USERNAME";
+    /// Environment variable holding the password for the provisioned server.
+    pub const PASSWORD_VAR: &str = "NTFIER_PASSWORD";
+    /// Environment variable holding comma-separated topics to subscribe to
+    /// on the provisioned server.
+    pub const TOPICS_VAR: &str = "NTFIER_TOPICS";
+}
+
+/// Orphaned data cleanup configuration.
+pub mod maintenance {
+    /// Interval in seconds between cleanups of orphaned rows, cached images
+    /// and keychain entries.
+    pub const INTERVAL_SECS: u64 = 24 * 60 * 60;
 }
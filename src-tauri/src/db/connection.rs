@@ -6,10 +6,12 @@ use diesel::sqlite::SqliteConnection;
 use diesel_migrations::{embed_migrations, EmbeddedMigrations, MigrationHarness};
 use std::path::Path;
 use std::sync::{Mutex, MutexGuard};
+use std::time::Instant;
 
 use super::models::NewServer;
 use super::schema::servers;
 use crate::error::AppError;
+use crate::services::MetricsService;
 
 /// Embedded database migrations.
 pub const MIGRATIONS: EmbeddedMigrations = embed_migrations!("migrations");
@@ -62,9 +64,13 @@ impl Database {
 
     /// Acquires a lock on the database connection.
     pub fn conn(&self) -> Result<MutexGuard<'_, SqliteConnection>, AppError> {
-        self.conn
+        let started = Instant::now();
+        let conn = self
+            .conn
             .lock()
-            .map_err(|e| AppError::Database(format!("Mutex poisoned: {e}")))
+            .map_err(|e| AppError::Database(format!("Mutex poisoned: {e}")));
+        MetricsService::record_db_lock_wait(started.elapsed());
+        conn
     }
 
     /// Inserts the default ntfy.sh server if no servers exist.
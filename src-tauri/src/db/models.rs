@@ -4,9 +4,19 @@
 
 use diesel::prelude::*;
 
-use super::schema::{notifications, servers, settings, subscriptions};
+use super::schema::{
+    blocked_topics, blocked_urls, channel_tags, channels, connection_log, escalation_rules,
+    keyword_filters, notifications, outbox, permissions, reminders, rules, servers, settings,
+    shortcuts, subscription_patterns, subscription_settings, subscriptions, virtual_topics,
+};
 use super::types::{JsonActions, JsonAttachments, JsonTags};
-use crate::models::{Notification, Priority, Subscription};
+use crate::models::{
+    BlockedTopic, BlockedUrl, Capability, Channel, ConnectionErrorKind, ConnectionLogEntry,
+    ContentKind, EscalationRule, FileMirror, KeywordFilter, MirrorFormat, Notification,
+    NotificationDisplayMethod, NotificationSound, OutboxEntry, Permission, Priority, Reminder,
+    Rule, ShortcutAction, ShortcutBinding, Subscription, SubscriptionPattern, SubscriptionSettings,
+    TopicActivity, VirtualTopic,
+};
 
 // ===== Server =====
 
@@ -20,6 +30,8 @@ pub struct ServerRow {
     pub url: String,
     pub username: Option<String>,
     pub is_default: i32,
+    #[allow(dead_code)]
+    pub detached: i32,
 }
 
 /// A new server to insert.
@@ -46,6 +58,20 @@ pub struct SubscriptionRow {
     pub display_name: Option<String>,
     pub muted: i32,
     pub last_sync: Option<i64>,
+    pub deleted_at: Option<i64>,
+    pub trusted: i32,
+    pub pattern_id: Option<String>,
+    pub sort_order: i32,
+    pub auto_delete_after_hours: Option<i32>,
+    pub local: i32,
+    pub min_toast_priority: Option<i32>,
+    pub hide_content: i32,
+    pub exclude_from_sync: i32,
+    pub pinned: i32,
+    pub mirror_path: Option<String>,
+    pub mirror_format: String,
+    pub keep_messages: Option<i32>,
+    pub sync_window_hours: Option<i32>,
 }
 
 /// A new subscription to insert.
@@ -57,6 +83,8 @@ pub struct NewSubscription<'a> {
     pub topic: &'a str,
     pub display_name: Option<&'a str>,
     pub muted: i32,
+    pub sort_order: i32,
+    pub local: i32,
 }
 
 // ===== Notification =====
@@ -80,6 +108,15 @@ pub struct NotificationRow {
     pub attachments: JsonAttachments,
     pub is_expanded: i32,
     pub is_favorite: i32,
+    pub deleted_at: Option<i64>,
+    pub parsed: Option<String>,
+    pub link_preview: Option<String>,
+    pub acknowledged_by: Option<String>,
+    pub acknowledged_at: Option<i64>,
+    pub note: Option<String>,
+    pub content_kind: String,
+    pub snoozed_until: Option<i64>,
+    pub mentioned: i32,
 }
 
 impl NotificationRow {
@@ -98,6 +135,19 @@ impl NotificationRow {
             read: self.read == 1,
             is_expanded: self.is_expanded == 1,
             is_favorite: self.is_favorite == 1,
+            deleted_at: self.deleted_at,
+            parsed: self
+                .parsed
+                .and_then(|json| serde_json::from_str(&json).ok()),
+            link_preview: self
+                .link_preview
+                .and_then(|json| serde_json::from_str(&json).ok()),
+            acknowledged_by: self.acknowledged_by,
+            acknowledged_at: self.acknowledged_at,
+            note: self.note,
+            content_kind: ContentKind::parse(&self.content_kind),
+            snoozed_until: self.snoozed_until,
+            mentioned: self.mentioned == 1,
         }
     }
 }
@@ -119,6 +169,178 @@ pub struct NewNotification<'a> {
     pub attachments: JsonAttachments,
     pub is_expanded: i32,
     pub is_favorite: i32,
+    pub parsed: Option<String>,
+    pub link_preview: Option<String>,
+    pub content_kind: &'a str,
+    pub mentioned: i32,
+}
+
+// ===== Permission =====
+
+/// A permission row from the database.
+#[derive(Debug, Clone, Queryable, Insertable, Selectable)]
+#[diesel(table_name = permissions)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct PermissionRow {
+    pub id: String,
+    pub subscription_id: String,
+    pub capability: String,
+    pub allowed: i32,
+    pub created_at: i64,
+}
+
+impl PermissionRow {
+    /// Converts database row to domain Permission model.
+    ///
+    /// Returns `None` for capabilities this version doesn't know about.
+    pub fn into_permission(self) -> Option<Permission> {
+        Some(Permission {
+            capability: Capability::parse(&self.capability)?,
+            id: self.id,
+            subscription_id: self.subscription_id,
+            allowed: self.allowed == 1,
+            created_at: self.created_at,
+        })
+    }
+}
+
+// ===== Blocked topic =====
+
+/// A topic blocklist row from the database.
+#[derive(Debug, Clone, Queryable, Insertable, Selectable)]
+#[diesel(table_name = blocked_topics)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct BlockedTopicRow {
+    pub id: String,
+    pub server_pattern: String,
+    pub topic_pattern: String,
+    pub created_at: i64,
+}
+
+impl From<BlockedTopicRow> for BlockedTopic {
+    fn from(row: BlockedTopicRow) -> Self {
+        Self {
+            id: row.id,
+            server_pattern: row.server_pattern,
+            topic_pattern: row.topic_pattern,
+            created_at: row.created_at,
+        }
+    }
+}
+
+// ===== Blocked URL =====
+
+/// A URL blocklist row from the database.
+#[derive(Debug, Clone, Queryable, Insertable, Selectable)]
+#[diesel(table_name = blocked_urls)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct BlockedUrlRow {
+    pub id: String,
+    pub host_pattern: String,
+    pub created_at: i64,
+}
+
+impl From<BlockedUrlRow> for BlockedUrl {
+    fn from(row: BlockedUrlRow) -> Self {
+        Self {
+            id: row.id,
+            host_pattern: row.host_pattern,
+            created_at: row.created_at,
+        }
+    }
+}
+
+// ===== Keyword filter =====
+
+/// A keyword filter row from the database.
+#[derive(Debug, Clone, Queryable, Insertable, Selectable)]
+#[diesel(table_name = keyword_filters)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct KeywordFilterRow {
+    pub id: String,
+    pub subscription_id: Option<String>,
+    pub pattern: String,
+    pub is_regex: i32,
+    pub discard: i32,
+    pub created_at: i64,
+}
+
+impl From<KeywordFilterRow> for KeywordFilter {
+    fn from(row: KeywordFilterRow) -> Self {
+        Self {
+            id: row.id,
+            subscription_id: row.subscription_id,
+            pattern: row.pattern,
+            is_regex: row.is_regex == 1,
+            discard: row.discard == 1,
+            created_at: row.created_at,
+        }
+    }
+}
+
+// ===== Subscription pattern =====
+
+/// A pattern subscription row from the database.
+#[derive(Debug, Clone, Queryable, Insertable, Selectable)]
+#[diesel(table_name = subscription_patterns)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct SubscriptionPatternRow {
+    pub id: String,
+    pub server_url: String,
+    pub pattern: String,
+    pub display_name: Option<String>,
+    pub created_at: i64,
+}
+
+impl From<SubscriptionPatternRow> for SubscriptionPattern {
+    fn from(row: SubscriptionPatternRow) -> Self {
+        Self {
+            id: row.id,
+            server_url: row.server_url,
+            pattern: row.pattern,
+            display_name: row.display_name,
+            created_at: row.created_at,
+        }
+    }
+}
+
+// ===== Channel =====
+
+/// A channel row from the database.
+#[derive(Debug, Clone, Queryable, Insertable, Selectable)]
+#[diesel(table_name = channels)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct ChannelRow {
+    pub id: String,
+    pub name: String,
+    pub muted: i32,
+    pub sound: Option<i32>,
+    pub priority: Option<i32>,
+    pub created_at: i64,
+}
+
+impl ChannelRow {
+    /// Converts database row to domain Channel model with its mapped tags.
+    pub fn into_channel(self, tags: Vec<String>) -> Channel {
+        Channel {
+            id: self.id,
+            name: self.name,
+            tags,
+            muted: self.muted == 1,
+            sound: self.sound.map(|s| s == 1),
+            priority: self.priority.map(|p| Priority::from(p as i8)),
+            created_at: self.created_at,
+        }
+    }
+}
+
+/// A tag-to-channel mapping row.
+#[derive(Debug, Clone, Queryable, Insertable, Selectable)]
+#[diesel(table_name = channel_tags)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct ChannelTagRow {
+    pub tag: String,
+    pub channel_id: String,
 }
 
 // ===== Setting =====
@@ -132,6 +354,247 @@ pub struct SettingRow {
     pub value: String,
 }
 
+// ===== Shortcut =====
+
+/// A global shortcut row from the database.
+#[derive(Debug, Clone, Queryable, Insertable, Selectable)]
+#[diesel(table_name = shortcuts)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct ShortcutRow {
+    pub action: String,
+    pub accelerator: String,
+}
+
+impl ShortcutRow {
+    /// Converts to a shortcut binding.
+    ///
+    /// Returns `None` for actions this version doesn't know about.
+    pub fn into_binding(self) -> Option<ShortcutBinding> {
+        Some(ShortcutBinding {
+            action: ShortcutAction::parse(&self.action)?,
+            accelerator: self.accelerator,
+        })
+    }
+}
+
+// ===== Connection log =====
+
+/// A connection log row from the database.
+#[derive(Debug, Clone, Queryable, Insertable, Selectable)]
+#[diesel(table_name = connection_log)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct ConnectionLogRow {
+    pub id: String,
+    pub subscription_id: String,
+    pub kind: String,
+    pub message: String,
+    pub created_at: i64,
+}
+
+impl From<ConnectionLogRow> for ConnectionLogEntry {
+    fn from(row: ConnectionLogRow) -> Self {
+        Self {
+            kind: ConnectionErrorKind::parse(&row.kind),
+            message: row.message,
+            timestamp: row.created_at,
+        }
+    }
+}
+
+// ===== Reminder =====
+
+/// A reminder row from the database.
+#[derive(Debug, Clone, Queryable, Insertable, Selectable)]
+#[diesel(table_name = reminders)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct ReminderRow {
+    pub id: String,
+    pub title: Option<String>,
+    pub message: String,
+    pub schedule: String,
+    pub next_run: Option<i64>,
+    pub created_at: i64,
+}
+
+impl From<ReminderRow> for Reminder {
+    fn from(row: ReminderRow) -> Self {
+        Self {
+            id: row.id,
+            title: row.title,
+            message: row.message,
+            schedule: row.schedule,
+            next_run: row.next_run,
+            created_at: row.created_at,
+        }
+    }
+}
+
+// ===== Escalation rule =====
+
+/// An escalation rule row from the database.
+#[derive(Debug, Clone, Queryable, Insertable, Selectable)]
+#[diesel(table_name = escalation_rules)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct EscalationRuleRow {
+    pub id: String,
+    pub subscription_id: Option<String>,
+    pub min_priority: i32,
+    pub delay_minutes: i32,
+    pub max_repeats: i32,
+    /// JSON of the topic escalation messages are published to.
+    pub publish_to: Option<String>,
+    pub created_at: i64,
+}
+
+impl From<EscalationRuleRow> for EscalationRule {
+    fn from(row: EscalationRuleRow) -> Self {
+        Self {
+            id: row.id,
+            subscription_id: row.subscription_id,
+            min_priority: Priority::from(row.min_priority as i8),
+            delay_minutes: u32::try_from(row.delay_minutes).unwrap_or(1).max(1),
+            max_repeats: u32::try_from(row.max_repeats).unwrap_or(0),
+            publish_to: row
+                .publish_to
+                .and_then(|json| serde_json::from_str(&json).ok()),
+            created_at: row.created_at,
+        }
+    }
+}
+
+// ===== Outbox =====
+
+/// An outbox row from the database.
+#[derive(Debug, Clone, Queryable, Insertable, Selectable)]
+#[diesel(table_name = outbox)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct OutboxRow {
+    pub id: String,
+    pub subscription_id: String,
+    /// JSON of the queued message.
+    pub message: String,
+    pub attempts: i32,
+    pub created_at: i64,
+}
+
+impl OutboxRow {
+    /// Converts the row to an outbox entry; `None` if the message can't be
+    /// read back.
+    pub fn into_entry(self) -> Option<OutboxEntry> {
+        let message = serde_json::from_str(&self.message)
+            .map_err(|e| log::warn!("Ignoring invalid outbox message {}: {e}", self.id))
+            .ok()?;
+        Some(OutboxEntry {
+            id: self.id,
+            subscription_id: self.subscription_id,
+            message,
+            attempts: u32::try_from(self.attempts).unwrap_or(0),
+            created_at: self.created_at,
+        })
+    }
+}
+
+// ===== Virtual topic =====
+
+/// A virtual topic row from the database.
+#[derive(Debug, Clone, Queryable, Insertable, Selectable)]
+#[diesel(table_name = virtual_topics)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct VirtualTopicRow {
+    pub id: String,
+    pub name: String,
+    /// JSON array of the merged subscriptions' IDs.
+    pub subscription_ids: String,
+    /// JSON array of the included tags.
+    pub tags: String,
+    pub min_priority: Option<i32>,
+    pub muted: i32,
+    pub sound: Option<String>,
+    pub created_at: i64,
+}
+
+impl From<VirtualTopicRow> for VirtualTopic {
+    fn from(row: VirtualTopicRow) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            subscription_ids: serde_json::from_str(&row.subscription_ids).unwrap_or_default(),
+            tags: serde_json::from_str(&row.tags).unwrap_or_default(),
+            min_priority: row.min_priority.map(|p| Priority::from(p as i8)),
+            muted: row.muted == 1,
+            sound: row.sound.as_deref().and_then(NotificationSound::parse),
+            unread_count: 0,
+            created_at: row.created_at,
+        }
+    }
+}
+
+// ===== Rule =====
+
+/// A rule row from the database.
+#[derive(Debug, Clone, Queryable, Insertable, Selectable)]
+#[diesel(table_name = rules)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct RuleRow {
+    pub id: String,
+    pub name: String,
+    pub enabled: i32,
+    /// JSON of the rule's conditions.
+    pub conditions: String,
+    /// JSON array of the rule's actions.
+    pub actions: String,
+    pub created_at: i64,
+}
+
+impl From<RuleRow> for Rule {
+    fn from(row: RuleRow) -> Self {
+        Self {
+            id: row.id,
+            name: row.name,
+            enabled: row.enabled == 1,
+            conditions: serde_json::from_str(&row.conditions).unwrap_or_default(),
+            actions: serde_json::from_str(&row.actions).unwrap_or_default(),
+            created_at: row.created_at,
+        }
+    }
+}
+
+// ===== Subscription settings =====
+
+/// A subscription settings row from the database.
+#[derive(Debug, Clone, Queryable, Insertable, Selectable)]
+#[diesel(table_name = subscription_settings)]
+#[diesel(check_for_backend(diesel::sqlite::Sqlite))]
+pub struct SubscriptionSettingsRow {
+    pub subscription_id: String,
+    pub sound: Option<String>,
+    pub display_method: Option<String>,
+    pub auto_mark_read: i32,
+    /// JSON list of maintenance windows.
+    pub maintenance_windows: String,
+}
+
+impl SubscriptionSettingsRow {
+    /// Converts the row to settings with the subscription's minimum toast priority.
+    pub fn into_settings(self, min_toast_priority: Option<i32>) -> SubscriptionSettings {
+        SubscriptionSettings {
+            sound: self.sound.as_deref().and_then(NotificationSound::parse),
+            min_priority: min_toast_priority.map(|p| Priority::from(p as i8)),
+            display_method: self
+                .display_method
+                .as_deref()
+                .map(NotificationDisplayMethod::parse),
+            auto_mark_read: self.auto_mark_read == 1,
+            maintenance_windows: serde_json::from_str(&self.maintenance_windows).unwrap_or_else(
+                |e| {
+                    log::warn!("Ignoring invalid maintenance windows: {e}");
+                    Vec::new()
+                },
+            ),
+        }
+    }
+}
+
 // ===== Helper for raw SQL queries =====
 
 /// Result row for subscription queries with aggregated data.
@@ -143,16 +606,50 @@ pub struct SubscriptionQueryRow {
     pub topic: String,
     #[diesel(sql_type = diesel::sql_types::Text)]
     pub server_url: String,
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    pub server_detached: i32,
     #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
     pub display_name: Option<String>,
     #[diesel(sql_type = diesel::sql_types::Integer)]
     pub muted: i32,
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    pub trusted: i32,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
+    pub pattern_id: Option<String>,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Integer>)]
+    pub auto_delete_after_hours: Option<i32>,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Integer>)]
+    pub keep_messages: Option<i32>,
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    pub local: i32,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Integer>)]
+    pub min_toast_priority: Option<i32>,
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    pub hide_content: i32,
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    pub exclude_from_sync: i32,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Integer>)]
+    pub sync_window_hours: Option<i32>,
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    pub pinned: i32,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Text>)]
+    pub mirror_path: Option<String>,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub mirror_format: String,
     #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::BigInt>)]
     pub last_sync: Option<i64>,
     #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::BigInt>)]
+    pub deleted_at: Option<i64>,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::BigInt>)]
     pub last_notif: Option<i64>,
     #[diesel(sql_type = diesel::sql_types::BigInt)]
     pub unread: i64,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub total: i64,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub recent: i64,
+    #[diesel(sql_type = diesel::sql_types::Nullable<diesel::sql_types::Integer>)]
+    pub max_unread_priority: Option<i32>,
 }
 
 impl From<SubscriptionQueryRow> for Subscription {
@@ -163,8 +660,63 @@ impl From<SubscriptionQueryRow> for Subscription {
             server_url: row.server_url,
             display_name: row.display_name,
             muted: row.muted == 1,
+            // The placeholder server of local subscriptions is hidden, not removed
+            detached: row.server_detached == 1 && row.local == 0,
+            local: row.local == 1,
+            trusted: row.trusted == 1,
+            pattern_id: row.pattern_id,
+            auto_delete_after_hours: row
+                .auto_delete_after_hours
+                .and_then(|h| u32::try_from(h).ok()),
+            keep_messages: row.keep_messages.and_then(|n| u32::try_from(n).ok()),
+            min_toast_priority: row.min_toast_priority.map(|p| Priority::from(p as i8)),
+            hide_content: row.hide_content == 1,
+            exclude_from_sync: row.exclude_from_sync == 1,
+            sync_window_hours: row.sync_window_hours.and_then(|h| u32::try_from(h).ok()),
+            pinned: row.pinned == 1,
+            mirror: row.mirror_path.map(|path| FileMirror {
+                path,
+                format: MirrorFormat::parse(&row.mirror_format),
+            }),
             last_notification: row.last_notif,
             unread_count: row.unread as i32,
+            total_count: row.total as i32,
+            recent_count: row.recent as i32,
+            max_unread_priority: row.max_unread_priority.map(|p| Priority::from(p as i8)),
+            deleted_at: row.deleted_at,
         }
     }
 }
+
+/// Result row for per-subscription activity aggregates.
+#[derive(Debug, QueryableByName)]
+pub struct TopicActivityRow {
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub subscription_id: String,
+    #[diesel(sql_type = diesel::sql_types::Text)]
+    pub name: String,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub received: i64,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub unread: i64,
+}
+
+impl From<TopicActivityRow> for TopicActivity {
+    fn from(row: TopicActivityRow) -> Self {
+        Self {
+            subscription_id: row.subscription_id,
+            name: row.name,
+            received: row.received as i32,
+            unread: row.unread as i32,
+        }
+    }
+}
+
+/// Result row for notification counts grouped by an integer bucket (hour, priority).
+#[derive(Debug, QueryableByName)]
+pub struct BucketCountRow {
+    #[diesel(sql_type = diesel::sql_types::Integer)]
+    pub bucket: i32,
+    #[diesel(sql_type = diesel::sql_types::BigInt)]
+    pub count: i64,
+}
@@ -0,0 +1,165 @@
+//! Topic, URL and keyword blocklist database queries.
+
+use diesel::prelude::*;
+
+use crate::db::connection::Database;
+use crate::db::models::{BlockedTopicRow, BlockedUrlRow, KeywordFilterRow};
+use crate::db::schema::{blocked_topics, blocked_urls, keyword_filters};
+use crate::error::AppError;
+use crate::models::{
+    BlockedTopic, BlockedUrl, CreateBlockedTopic, CreateBlockedUrl, CreateKeywordFilter,
+    KeywordFilter, Notification,
+};
+
+impl Database {
+    /// Gets all blocklist entries, oldest first.
+    pub fn get_blocked_topics(&self) -> Result<Vec<BlockedTopic>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<BlockedTopicRow> = blocked_topics::table
+            .order(blocked_topics::created_at.asc())
+            .load(&mut *conn)?;
+
+        Ok(rows.into_iter().map(BlockedTopic::from).collect())
+    }
+
+    /// Adds a blocklist entry.
+    pub fn add_blocked_topic(&self, entry: CreateBlockedTopic) -> Result<BlockedTopic, AppError> {
+        entry.validate()?;
+
+        let row = BlockedTopicRow {
+            id: uuid::Uuid::new_v4().to_string(),
+            server_pattern: entry
+                .server_pattern
+                .map_or_else(|| "*".to_string(), |p| p.trim().to_string()),
+            topic_pattern: entry.topic_pattern.trim().to_string(),
+            created_at: chrono::Utc::now().timestamp_millis(),
+        };
+
+        let mut conn = self.conn()?;
+        diesel::insert_into(blocked_topics::table)
+            .values(&row)
+            .execute(&mut *conn)?;
+
+        Ok(row.into())
+    }
+
+    /// Removes a blocklist entry.
+    pub fn remove_blocked_topic(&self, id: &str) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::delete(blocked_topics::table.filter(blocked_topics::id.eq(id)))
+            .execute(&mut *conn)?;
+
+        Ok(())
+    }
+
+    /// Gets all URL blocklist entries, oldest first.
+    pub fn get_blocked_urls(&self) -> Result<Vec<BlockedUrl>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<BlockedUrlRow> = blocked_urls::table
+            .order(blocked_urls::created_at.asc())
+            .load(&mut *conn)?;
+
+        Ok(rows.into_iter().map(BlockedUrl::from).collect())
+    }
+
+    /// Adds a URL blocklist entry.
+    pub fn add_blocked_url(&self, entry: CreateBlockedUrl) -> Result<BlockedUrl, AppError> {
+        entry.validate()?;
+
+        let row = BlockedUrlRow {
+            id: uuid::Uuid::new_v4().to_string(),
+            host_pattern: entry.host_pattern.trim().to_ascii_lowercase(),
+            created_at: chrono::Utc::now().timestamp_millis(),
+        };
+
+        let mut conn = self.conn()?;
+        diesel::insert_into(blocked_urls::table)
+            .values(&row)
+            .execute(&mut *conn)?;
+
+        Ok(row.into())
+    }
+
+    /// Removes a URL blocklist entry.
+    pub fn remove_blocked_url(&self, id: &str) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::delete(blocked_urls::table.filter(blocked_urls::id.eq(id))).execute(&mut *conn)?;
+
+        Ok(())
+    }
+
+    /// Gets all keyword filters, oldest first.
+    pub fn get_keyword_filters(&self) -> Result<Vec<KeywordFilter>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<KeywordFilterRow> = keyword_filters::table
+            .order(keyword_filters::created_at.asc())
+            .load(&mut *conn)?;
+
+        Ok(rows.into_iter().map(KeywordFilter::from).collect())
+    }
+
+    /// Gets the keyword filters matching a notification.
+    pub fn get_keyword_filters_for(
+        &self,
+        notification: &Notification,
+    ) -> Result<Vec<KeywordFilter>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<KeywordFilterRow> = keyword_filters::table
+            .filter(
+                keyword_filters::subscription_id
+                    .is_null()
+                    .or(keyword_filters::subscription_id.eq(&notification.topic_id)),
+            )
+            .load(&mut *conn)?;
+
+        Ok(rows
+            .into_iter()
+            .map(KeywordFilter::from)
+            .filter(|filter| filter.matches(notification))
+            .collect())
+    }
+
+    /// Adds a keyword filter.
+    pub fn add_keyword_filter(
+        &self,
+        filter: CreateKeywordFilter,
+    ) -> Result<KeywordFilter, AppError> {
+        filter.validate()?;
+
+        let row = KeywordFilterRow {
+            id: uuid::Uuid::new_v4().to_string(),
+            subscription_id: filter.subscription_id,
+            pattern: if filter.is_regex {
+                filter.pattern
+            } else {
+                filter.pattern.trim().to_string()
+            },
+            is_regex: i32::from(filter.is_regex),
+            discard: i32::from(filter.discard),
+            created_at: chrono::Utc::now().timestamp_millis(),
+        };
+
+        let mut conn = self.conn()?;
+        diesel::insert_into(keyword_filters::table)
+            .values(&row)
+            .execute(&mut *conn)?;
+
+        Ok(row.into())
+    }
+
+    /// Removes a keyword filter.
+    pub fn remove_keyword_filter(&self, id: &str) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::delete(keyword_filters::table.filter(keyword_filters::id.eq(id)))
+            .execute(&mut *conn)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,182 @@
+//! Channel-related database queries.
+
+use diesel::prelude::*;
+use diesel::Connection;
+use std::collections::HashMap;
+
+use crate::db::connection::Database;
+use crate::db::models::{ChannelRow, ChannelTagRow};
+use crate::db::schema::{channel_tags, channels};
+use crate::error::AppError;
+use crate::models::{Channel, ChannelInput, CHANNEL_TAG_PREFIX};
+
+impl Database {
+    /// Gets all channels with their mapped tags, ordered by name.
+    pub fn get_channels(&self) -> Result<Vec<Channel>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<ChannelRow> = channels::table
+            .order(channels::name.asc())
+            .load(&mut *conn)?;
+        let tag_rows: Vec<ChannelTagRow> = channel_tags::table
+            .order(channel_tags::tag.asc())
+            .load(&mut *conn)?;
+
+        let mut tags_by_channel: HashMap<String, Vec<String>> = HashMap::new();
+        for row in tag_rows {
+            tags_by_channel
+                .entry(row.channel_id)
+                .or_default()
+                .push(row.tag);
+        }
+
+        Ok(rows
+            .into_iter()
+            .map(|row| {
+                let tags = tags_by_channel.remove(&row.id).unwrap_or_default();
+                row.into_channel(tags)
+            })
+            .collect())
+    }
+
+    /// Resolves the channel a notification belongs to from its tags.
+    ///
+    /// Tags are checked in order; the first one that is mapped to a channel, or
+    /// that names one as `channel:<name>`, wins.
+    pub fn get_channel_for_tags(&self, tags: &[String]) -> Result<Option<Channel>, AppError> {
+        if tags.is_empty() {
+            return Ok(None);
+        }
+
+        let mut conn = self.conn()?;
+
+        let mapped: HashMap<String, String> = channel_tags::table
+            .filter(channel_tags::tag.eq_any(tags))
+            .select((channel_tags::tag, channel_tags::channel_id))
+            .load::<(String, String)>(&mut *conn)?
+            .into_iter()
+            .collect();
+
+        for tag in tags {
+            let row: Option<ChannelRow> = if let Some(channel_id) = mapped.get(tag) {
+                channels::table
+                    .filter(channels::id.eq(channel_id))
+                    .first(&mut *conn)
+                    .optional()?
+            } else if let Some(name) = tag.strip_prefix(CHANNEL_TAG_PREFIX) {
+                channels::table
+                    .filter(channels::name.eq(name))
+                    .first(&mut *conn)
+                    .optional()?
+            } else {
+                None
+            };
+
+            if let Some(row) = row {
+                let channel_tags: Vec<String> = channel_tags::table
+                    .filter(channel_tags::channel_id.eq(&row.id))
+                    .select(channel_tags::tag)
+                    .order(channel_tags::tag.asc())
+                    .load(&mut *conn)?;
+                return Ok(Some(row.into_channel(channel_tags)));
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Creates a channel. Tags already mapped to another channel are moved to this one.
+    pub fn create_channel(&self, input: ChannelInput) -> Result<Channel, AppError> {
+        let id = uuid::Uuid::new_v4().to_string();
+        self.save_channel(&id, input, true)
+    }
+
+    /// Updates a channel's name, tags and display settings.
+    pub fn update_channel(&self, id: &str, input: ChannelInput) -> Result<Channel, AppError> {
+        self.save_channel(id, input, false)
+    }
+
+    /// Deletes a channel and its tag mappings.
+    pub fn delete_channel(&self, id: &str) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::delete(channels::table.filter(channels::id.eq(id))).execute(&mut *conn)?;
+
+        Ok(())
+    }
+
+    fn save_channel(
+        &self,
+        id: &str,
+        input: ChannelInput,
+        is_new: bool,
+    ) -> Result<Channel, AppError> {
+        input.validate()?;
+        let mut conn = self.conn()?;
+
+        let name = input.name.trim().to_string();
+        let mut tags: Vec<String> = input.tags.iter().map(|t| t.trim().to_string()).collect();
+        tags.sort();
+        tags.dedup();
+
+        let row = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let created_at = if is_new {
+                chrono::Utc::now().timestamp_millis()
+            } else {
+                channels::table
+                    .filter(channels::id.eq(id))
+                    .select(channels::created_at)
+                    .first(conn)?
+            };
+
+            let row = ChannelRow {
+                id: id.to_string(),
+                name,
+                muted: i32::from(input.muted),
+                sound: input.sound.map(i32::from),
+                priority: input.priority.map(|p| p as i32),
+                created_at,
+            };
+
+            if is_new {
+                diesel::insert_into(channels::table)
+                    .values(&row)
+                    .execute(conn)?;
+            } else {
+                diesel::update(channels::table.filter(channels::id.eq(id)))
+                    .set((
+                        channels::name.eq(&row.name),
+                        channels::muted.eq(row.muted),
+                        channels::sound.eq(row.sound),
+                        channels::priority.eq(row.priority),
+                    ))
+                    .execute(conn)?;
+            }
+
+            // Replace mappings, taking over tags from other channels
+            diesel::delete(
+                channel_tags::table.filter(
+                    channel_tags::channel_id
+                        .eq(id)
+                        .or(channel_tags::tag.eq_any(&tags)),
+                ),
+            )
+            .execute(conn)?;
+
+            let new_tags: Vec<ChannelTagRow> = tags
+                .iter()
+                .map(|tag| ChannelTagRow {
+                    tag: tag.clone(),
+                    channel_id: id.to_string(),
+                })
+                .collect();
+            diesel::insert_into(channel_tags::table)
+                .values(&new_tags)
+                .execute(conn)?;
+
+            Ok(row)
+        })?;
+
+        Ok(row.into_channel(tags))
+    }
+}
@@ -0,0 +1,63 @@
+//! Connection log database queries.
+
+use diesel::prelude::*;
+
+use crate::config::connection::LOG_ENTRIES_PER_SUBSCRIPTION;
+use crate::db::connection::Database;
+use crate::db::models::ConnectionLogRow;
+use crate::db::schema::connection_log;
+use crate::error::AppError;
+use crate::models::{ConnectionErrorKind, ConnectionLogEntry};
+
+impl Database {
+    /// Gets the connection errors recorded for a subscription, newest first.
+    pub fn get_connection_log(
+        &self,
+        subscription_id: &str,
+    ) -> Result<Vec<ConnectionLogEntry>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<ConnectionLogRow> = connection_log::table
+            .filter(connection_log::subscription_id.eq(subscription_id))
+            .order(connection_log::created_at.desc())
+            .load(&mut *conn)?;
+
+        Ok(rows.into_iter().map(ConnectionLogEntry::from).collect())
+    }
+
+    /// Records a connection error, keeping only the newest
+    /// `LOG_ENTRIES_PER_SUBSCRIPTION` errors of the subscription.
+    pub fn add_connection_log_entry(
+        &self,
+        subscription_id: &str,
+        kind: ConnectionErrorKind,
+        message: &str,
+    ) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::insert_into(connection_log::table)
+            .values(&ConnectionLogRow {
+                id: uuid::Uuid::new_v4().to_string(),
+                subscription_id: subscription_id.to_string(),
+                kind: kind.as_str().to_string(),
+                message: message.to_string(),
+                created_at: chrono::Utc::now().timestamp_millis(),
+            })
+            .execute(&mut *conn)?;
+
+        let keep: Vec<String> = connection_log::table
+            .filter(connection_log::subscription_id.eq(subscription_id))
+            .order(connection_log::created_at.desc())
+            .limit(LOG_ENTRIES_PER_SUBSCRIPTION)
+            .select(connection_log::id)
+            .load(&mut *conn)?;
+        diesel::delete(
+            connection_log::table
+                .filter(connection_log::subscription_id.eq(subscription_id))
+                .filter(connection_log::id.ne_all(keep)),
+        )
+        .execute(&mut *conn)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,118 @@
+//! Escalation rule database queries.
+
+use diesel::prelude::*;
+
+use crate::db::connection::Database;
+use crate::db::models::{EscalationRuleRow, NotificationRow};
+use crate::db::schema::{escalation_rules, escalations, notifications};
+use crate::error::AppError;
+use crate::models::{CreateEscalationRule, Escalated, EscalationRule, Notification, Priority};
+
+impl Database {
+    /// Gets all escalation rules, oldest first.
+    pub fn get_escalation_rules(&self) -> Result<Vec<EscalationRule>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<EscalationRuleRow> = escalation_rules::table
+            .order(escalation_rules::created_at.asc())
+            .load(&mut *conn)?;
+
+        Ok(rows.into_iter().map(EscalationRule::from).collect())
+    }
+
+    /// Adds an escalation rule.
+    pub fn create_escalation_rule(
+        &self,
+        rule: &CreateEscalationRule,
+    ) -> Result<EscalationRule, AppError> {
+        rule.validate()?;
+
+        let row = EscalationRuleRow {
+            id: uuid::Uuid::new_v4().to_string(),
+            subscription_id: rule.subscription_id.clone(),
+            min_priority: rule.min_priority as i32,
+            delay_minutes: rule.delay_minutes as i32,
+            max_repeats: rule.max_repeats as i32,
+            publish_to: rule
+                .publish_to
+                .as_ref()
+                .map(serde_json::to_string)
+                .transpose()?,
+            created_at: chrono::Utc::now().timestamp_millis(),
+        };
+
+        let mut conn = self.conn()?;
+        diesel::insert_into(escalation_rules::table)
+            .values(&row)
+            .execute(&mut *conn)?;
+
+        Ok(row.into())
+    }
+
+    /// Removes an escalation rule.
+    pub fn delete_escalation_rule(&self, id: &str) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::delete(escalation_rules::table.filter(escalation_rules::id.eq(id)))
+            .execute(&mut *conn)?;
+
+        Ok(())
+    }
+
+    /// Gets notifications that may need escalating: unread, unacknowledged
+    /// and not trashed, of at least `min_priority` and received after `since`
+    /// (milliseconds), with how often they were escalated.
+    pub fn get_escalation_candidates(
+        &self,
+        min_priority: Priority,
+        since: i64,
+    ) -> Result<Vec<(Notification, Escalated)>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<(NotificationRow, Option<(i32, i64)>)> = notifications::table
+            .left_join(escalations::table)
+            .filter(notifications::read.eq(0))
+            .filter(notifications::acknowledged_at.is_null())
+            .filter(notifications::deleted_at.is_null())
+            .filter(notifications::priority.ge(min_priority as i32))
+            .filter(notifications::timestamp.ge(since))
+            .select((
+                NotificationRow::as_select(),
+                (escalations::count, escalations::last_at).nullable(),
+            ))
+            .load(&mut *conn)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(row, escalated)| {
+                let escalated =
+                    escalated.map_or_else(Escalated::default, |(count, last_at)| Escalated {
+                        count: u32::try_from(count).unwrap_or(0),
+                        last_at: Some(last_at),
+                    });
+                (row.into_notification(), escalated)
+            })
+            .collect())
+    }
+
+    /// Records that a notification was escalated `count` times, last at `now`
+    /// (milliseconds).
+    pub fn record_escalation(
+        &self,
+        notification_id: &str,
+        count: u32,
+        now: i64,
+    ) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::replace_into(escalations::table)
+            .values((
+                escalations::notification_id.eq(notification_id),
+                escalations::count.eq(count as i32),
+                escalations::last_at.eq(now),
+            ))
+            .execute(&mut *conn)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,81 @@
+//! Queries finding and removing orphaned rows.
+//!
+//! Foreign keys are enforced on every connection now, but databases created
+//! before that may still hold rows whose parent was deleted.
+
+use diesel::prelude::*;
+
+use crate::db::connection::Database;
+use crate::db::models::NotificationRow;
+use crate::db::schema::notifications;
+use crate::error::AppError;
+use crate::models::ContentKind;
+use crate::services::image_cache;
+
+/// Statements deleting rows whose parent row is gone, other than notifications.
+const ORPHANED_ROW_DELETES: [&str; 7] = [
+    "DELETE FROM connection_log WHERE subscription_id NOT IN (SELECT id FROM subscriptions)",
+    "DELETE FROM outbox WHERE subscription_id NOT IN (SELECT id FROM subscriptions)",
+    "DELETE FROM permissions WHERE subscription_id NOT IN (SELECT id FROM subscriptions)",
+    "DELETE FROM subscription_settings WHERE subscription_id NOT IN (SELECT id FROM subscriptions)",
+    "DELETE FROM escalation_rules WHERE subscription_id IS NOT NULL \
+         AND subscription_id NOT IN (SELECT id FROM subscriptions)",
+    "DELETE FROM escalations WHERE notification_id NOT IN (SELECT id FROM notifications)",
+    "DELETE FROM channel_tags WHERE channel_id NOT IN (SELECT id FROM channels)",
+];
+
+impl Database {
+    /// Permanently deletes notifications whose subscription no longer exists.
+    ///
+    /// Returns the number of deleted notifications.
+    pub fn delete_orphaned_notifications(&self) -> Result<usize, AppError> {
+        let mut conn = self.conn()?;
+
+        let deleted = diesel::sql_query(
+            "DELETE FROM notifications \
+             WHERE subscription_id NOT IN (SELECT id FROM subscriptions)",
+        )
+        .execute(&mut *conn)?;
+
+        Ok(deleted)
+    }
+
+    /// Deletes rows of deleted subscriptions, notifications and channels
+    /// from the other tables. Run after `delete_orphaned_notifications`.
+    ///
+    /// Returns the number of deleted rows.
+    pub fn delete_orphaned_rows(&self) -> Result<usize, AppError> {
+        let mut conn = self.conn()?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            ORPHANED_ROW_DELETES.iter().try_fold(0, |total, sql| {
+                Ok(total + diesel::sql_query(*sql).execute(conn)?)
+            })
+        })
+        .map_err(AppError::from)
+    }
+
+    /// Gets the URLs of all images notifications may show in a toast,
+    /// including those of trashed notifications.
+    pub fn get_notification_image_urls(&self) -> Result<Vec<String>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<NotificationRow> = notifications::table.load(&mut *conn)?;
+
+        Ok(rows
+            .into_iter()
+            .map(NotificationRow::into_notification)
+            .flat_map(|notification| {
+                let markdown_image = (notification.content_kind == ContentKind::Markdown)
+                    .then(|| image_cache::extract_first_image_from_markdown(&notification.message))
+                    .flatten();
+                notification
+                    .attachments
+                    .into_iter()
+                    .filter(|a| a.attachment_type.starts_with("image/"))
+                    .map(|a| a.url)
+                    .chain(markdown_image)
+            })
+            .collect())
+    }
+}
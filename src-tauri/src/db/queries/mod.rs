@@ -2,9 +2,23 @@
 //!
 //! Organized by entity type for maintainability.
 
+mod blocklist;
+mod channels;
+mod connection_log;
+mod escalations;
+mod maintenance;
 mod notifications;
+mod outbox;
+mod patterns;
+mod permissions;
+mod reminders;
+mod rules;
 mod servers;
 mod settings;
+mod shortcuts;
+mod statistics;
+mod subscription_settings;
 mod subscriptions;
+mod virtual_topics;
 
 // Re-export query implementations via Database impl blocks
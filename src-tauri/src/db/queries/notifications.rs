@@ -7,10 +7,11 @@ use crate::db::models::{NewNotification, NotificationRow};
 use crate::db::schema::{notifications, subscriptions};
 use crate::db::types::{JsonActions, JsonAttachments, JsonTags};
 use crate::error::AppError;
-use crate::models::Notification;
+use crate::models::{FeedCursor, FeedFilter, FeedPage, LinkPreview, Notification};
 
 impl Database {
-    /// Gets all notifications for a subscription, ordered by timestamp descending.
+    /// Gets all notifications for a subscription that are not in the trash,
+    /// ordered by timestamp descending.
     pub fn get_notifications_by_subscription(
         &self,
         subscription_id: &str,
@@ -19,6 +20,7 @@ impl Database {
 
         let rows: Vec<NotificationRow> = notifications::table
             .filter(notifications::subscription_id.eq(subscription_id))
+            .filter(notifications::deleted_at.is_null())
             .order(notifications::timestamp.desc())
             .load(&mut *conn)?;
 
@@ -28,7 +30,66 @@ impl Database {
             .collect())
     }
 
+    /// Gets a page of notifications from all subscriptions matching `filter`,
+    /// newest first, starting after `cursor` (from the start without one).
+    ///
+    /// Trashed notifications and those of trashed subscriptions are skipped.
+    pub fn get_all_notifications(
+        &self,
+        filter: &FeedFilter,
+        cursor: Option<&FeedCursor>,
+        limit: i64,
+    ) -> Result<FeedPage, AppError> {
+        let mut conn = self.conn()?;
+
+        let mut query = notifications::table
+            .inner_join(subscriptions::table)
+            .filter(notifications::deleted_at.is_null())
+            .filter(subscriptions::deleted_at.is_null())
+            .select(NotificationRow::as_select())
+            .order((notifications::timestamp.desc(), notifications::id.desc()))
+            .into_boxed();
+        if !filter.subscription_ids.is_empty() {
+            query = query.filter(notifications::subscription_id.eq_any(&filter.subscription_ids));
+        }
+        if filter.unread_only {
+            query = query.filter(notifications::read.eq(0));
+        }
+        if let Some(priority) = filter.min_priority {
+            query = query.filter(notifications::priority.ge(priority as i32));
+        }
+        if let Some(cursor) = cursor {
+            query = query.filter(
+                notifications::timestamp
+                    .lt(cursor.timestamp)
+                    .or(notifications::timestamp
+                        .eq(cursor.timestamp)
+                        .and(notifications::id.lt(&cursor.id))),
+            );
+        }
+
+        // One more than asked for tells whether there is a next page
+        let mut rows: Vec<NotificationRow> = query.limit(limit + 1).load(&mut *conn)?;
+        let has_more = rows.len() > usize::try_from(limit).unwrap_or(0);
+        rows.truncate(usize::try_from(limit).unwrap_or(0));
+
+        let notifications: Vec<Notification> = rows
+            .into_iter()
+            .map(NotificationRow::into_notification)
+            .collect();
+        let next_cursor = notifications
+            .last()
+            .filter(|_| has_more)
+            .map(FeedCursor::from);
+        Ok(FeedPage {
+            notifications,
+            next_cursor,
+        })
+    }
+
     /// Checks if a notification with the given `ntfy_id` exists.
+    ///
+    /// Trashed notifications count as existing so sync doesn't re-import them.
     pub fn notification_exists_by_ntfy_id(&self, ntfy_id: &str) -> Result<bool, AppError> {
         use diesel::dsl::count_star;
 
@@ -67,6 +128,16 @@ impl Database {
             attachments: JsonAttachments::new(notification.attachments.clone()),
             is_expanded: i32::from(notification.is_expanded),
             is_favorite: i32::from(notification.is_favorite),
+            parsed: notification
+                .parsed
+                .as_ref()
+                .and_then(|parsed| serde_json::to_string(parsed).ok()),
+            link_preview: notification
+                .link_preview
+                .as_ref()
+                .and_then(|preview| serde_json::to_string(preview).ok()),
+            content_kind: notification.content_kind.as_str(),
+            mentioned: i32::from(notification.mentioned),
         };
 
         diesel::replace_into(notifications::table)
@@ -104,6 +175,16 @@ impl Database {
             attachments: JsonAttachments::new(notification.attachments.clone()),
             is_expanded: i32::from(notification.is_expanded),
             is_favorite: i32::from(notification.is_favorite),
+            parsed: notification
+                .parsed
+                .as_ref()
+                .and_then(|parsed| serde_json::to_string(parsed).ok()),
+            link_preview: notification
+                .link_preview
+                .as_ref()
+                .and_then(|preview| serde_json::to_string(preview).ok()),
+            content_kind: notification.content_kind.as_str(),
+            mentioned: i32::from(notification.mentioned),
         };
 
         diesel::insert_or_ignore_into(notifications::table)
@@ -137,6 +218,176 @@ impl Database {
         Ok(())
     }
 
+    /// Gets a notification by ID, including trashed ones.
+    pub fn get_notification_by_id(&self, id: &str) -> Result<Option<Notification>, AppError> {
+        let mut conn = self.conn()?;
+
+        let row: Option<NotificationRow> = notifications::table
+            .filter(notifications::id.eq(id))
+            .first(&mut *conn)
+            .optional()?;
+
+        Ok(row.map(NotificationRow::into_notification))
+    }
+
+    /// Records who acknowledged a notification, now.
+    pub fn acknowledge_notification(
+        &self,
+        id: &str,
+        acknowledged_by: &str,
+    ) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::update(notifications::table.filter(notifications::id.eq(id)))
+            .set((
+                notifications::acknowledged_by.eq(acknowledged_by),
+                notifications::acknowledged_at.eq(chrono::Utc::now().timestamp_millis()),
+            ))
+            .execute(&mut *conn)?;
+
+        Ok(())
+    }
+
+    /// Sets the note of a notification (`None` removes it).
+    pub fn set_notification_note(&self, id: &str, note: Option<&str>) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::update(notifications::table.filter(notifications::id.eq(id)))
+            .set(notifications::note.eq(note))
+            .execute(&mut *conn)?;
+
+        Ok(())
+    }
+
+    /// Sets when a notification is shown again (`None` cancels the snooze).
+    pub fn set_notification_snooze(
+        &self,
+        id: &str,
+        snoozed_until: Option<i64>,
+    ) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        let updated = diesel::update(notifications::table.filter(notifications::id.eq(id)))
+            .set(notifications::snoozed_until.eq(snoozed_until))
+            .execute(&mut *conn)?;
+        if updated == 0 {
+            return Err(AppError::NotFound(format!("Notification {id} not found")));
+        }
+
+        Ok(())
+    }
+
+    /// Gets snoozed notifications due to be shown again at `now`
+    /// (milliseconds), skipping trashed ones.
+    pub fn get_due_snoozed_notifications(&self, now: i64) -> Result<Vec<Notification>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<NotificationRow> = notifications::table
+            .filter(notifications::snoozed_until.le(now))
+            .filter(notifications::deleted_at.is_null())
+            .order(notifications::snoozed_until.asc())
+            .load(&mut *conn)?;
+
+        Ok(rows
+            .into_iter()
+            .map(NotificationRow::into_notification)
+            .collect())
+    }
+
+    /// Ends the snooze of a notification and marks it unread again.
+    pub fn end_notification_snooze(&self, id: &str) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::update(notifications::table.filter(notifications::id.eq(id)))
+            .set((
+                notifications::snoozed_until.eq(None::<i64>),
+                notifications::read.eq(0),
+            ))
+            .execute(&mut *conn)?;
+
+        Ok(())
+    }
+
+    /// Finds notifications whose title, message or note contains `query`
+    /// (case-insensitive for ASCII), newest first.
+    ///
+    /// Trashed notifications and those of trashed subscriptions are skipped.
+    pub fn search_notifications(
+        &self,
+        query: &str,
+        limit: i64,
+    ) -> Result<Vec<Notification>, AppError> {
+        let mut conn = self.conn()?;
+
+        let escaped = query
+            .replace('\\', "\\\\")
+            .replace('%', "\\%")
+            .replace('_', "\\_");
+        let pattern = format!("%{escaped}%");
+
+        let rows: Vec<NotificationRow> = notifications::table
+            .inner_join(subscriptions::table)
+            .filter(
+                notifications::message
+                    .like(&pattern)
+                    .escape('\\')
+                    .or(notifications::title.like(&pattern).escape('\\'))
+                    .or(notifications::note.like(&pattern).escape('\\')),
+            )
+            .filter(notifications::deleted_at.is_null())
+            .filter(subscriptions::deleted_at.is_null())
+            .order(notifications::timestamp.desc())
+            .limit(limit)
+            .select(NotificationRow::as_select())
+            .load(&mut *conn)?;
+
+        Ok(rows
+            .into_iter()
+            .map(NotificationRow::into_notification)
+            .collect())
+    }
+
+    /// Gets the newest `limit` notifications from all subscriptions, each with
+    /// its subscription's topic.
+    ///
+    /// Trashed notifications and those of trashed subscriptions are skipped.
+    pub fn get_recent_notifications_with_topics(
+        &self,
+        limit: i64,
+    ) -> Result<Vec<(String, Notification)>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<(String, NotificationRow)> = notifications::table
+            .inner_join(subscriptions::table)
+            .filter(notifications::deleted_at.is_null())
+            .filter(subscriptions::deleted_at.is_null())
+            .order(notifications::timestamp.desc())
+            .limit(limit)
+            .select((subscriptions::topic, NotificationRow::as_select()))
+            .load(&mut *conn)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(topic, row)| (topic, row.into_notification()))
+            .collect())
+    }
+
+    /// Stores the link preview of a notification.
+    pub fn set_notification_link_preview(
+        &self,
+        id: &str,
+        preview: &LinkPreview,
+    ) -> Result<(), AppError> {
+        let json = serde_json::to_string(preview)?;
+        let mut conn = self.conn()?;
+
+        diesel::update(notifications::table.filter(notifications::id.eq(id)))
+            .set(notifications::link_preview.eq(json))
+            .execute(&mut *conn)?;
+
+        Ok(())
+    }
+
     /// Sets the favorite state of a notification.
     pub fn set_notification_favorite(&self, id: &str, favorite: bool) -> Result<(), AppError> {
         let mut conn = self.conn()?;
@@ -153,8 +404,12 @@ impl Database {
         let mut conn = self.conn()?;
 
         let rows: Vec<NotificationRow> = notifications::table
+            .inner_join(subscriptions::table)
             .filter(notifications::is_favorite.eq(1))
+            .filter(notifications::deleted_at.is_null())
+            .filter(subscriptions::deleted_at.is_null())
             .order(notifications::timestamp.desc())
+            .select(NotificationRow::as_select())
             .load(&mut *conn)?;
 
         Ok(rows
@@ -174,32 +429,140 @@ impl Database {
         Ok(())
     }
 
-    /// Gets `ntfy_id` and `subscription_id` for a notification (needed for remote delete).
-    pub fn get_notification_meta(
-        &self,
-        id: &str,
-    ) -> Result<Option<(Option<String>, String)>, AppError> {
+    /// Permanently deletes a notification.
+    pub fn delete_notification(&self, id: &str) -> Result<(), AppError> {
         let mut conn = self.conn()?;
 
-        let result: Option<(Option<String>, String)> = notifications::table
-            .filter(notifications::id.eq(id))
-            .select((notifications::ntfy_id, notifications::subscription_id))
-            .first(&mut *conn)
-            .optional()?;
+        diesel::delete(notifications::table.filter(notifications::id.eq(id)))
+            .execute(&mut *conn)?;
+
+        Ok(())
+    }
+
+    /// Permanently deletes notifications older than their subscription's
+    /// `auto_delete_after_hours`. Favorites and trashed notifications are kept.
+    ///
+    /// Returns the number of deleted notifications.
+    pub fn delete_expired_notifications(&self) -> Result<usize, AppError> {
+        let mut conn = self.conn()?;
+
+        let deleted = diesel::sql_query(
+            "DELETE FROM notifications \
+             WHERE deleted_at IS NULL AND is_favorite = 0 \
+             AND timestamp < ? - (SELECT s.auto_delete_after_hours FROM subscriptions s \
+                 WHERE s.id = notifications.subscription_id) * 3600000",
+        )
+        .bind::<diesel::sql_types::BigInt, _>(chrono::Utc::now().timestamp_millis())
+        .execute(&mut *conn)?;
 
-        Ok(result)
+        Ok(deleted)
     }
 
-    /// Deletes a notification.
-    pub fn delete_notification(&self, id: &str) -> Result<(), AppError> {
+    /// Permanently deletes all but the newest `keep_messages` notifications
+    /// of each subscription that has it set. Favorites and trashed
+    /// notifications are kept; favorites count towards the limit.
+    ///
+    /// Returns the number of deleted notifications.
+    pub fn delete_excess_notifications(&self) -> Result<usize, AppError> {
         let mut conn = self.conn()?;
 
-        diesel::delete(notifications::table.filter(notifications::id.eq(id)))
+        let deleted = diesel::sql_query(
+            "DELETE FROM notifications WHERE id IN ( \
+                 SELECT id FROM ( \
+                     SELECT n.id, n.is_favorite, s.keep_messages, \
+                            ROW_NUMBER() OVER ( \
+                                PARTITION BY n.subscription_id ORDER BY n.timestamp DESC \
+                            ) AS position \
+                     FROM notifications n \
+                     JOIN subscriptions s ON s.id = n.subscription_id \
+                     WHERE s.keep_messages IS NOT NULL AND n.deleted_at IS NULL \
+                 ) WHERE position > keep_messages AND is_favorite = 0 \
+             )",
+        )
+        .execute(&mut *conn)?;
+
+        Ok(deleted)
+    }
+
+    /// Moves a notification to the trash.
+    pub fn soft_delete_notification(&self, id: &str) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::update(notifications::table.filter(notifications::id.eq(id)))
+            .set(notifications::deleted_at.eq(chrono::Utc::now().timestamp_millis()))
+            .execute(&mut *conn)?;
+
+        Ok(())
+    }
+
+    /// Restores a notification from the trash.
+    pub fn restore_notification(&self, id: &str) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::update(notifications::table.filter(notifications::id.eq(id)))
+            .set(notifications::deleted_at.eq(None::<i64>))
             .execute(&mut *conn)?;
 
         Ok(())
     }
 
+    /// Gets all trashed notifications of live subscriptions, most recently deleted first.
+    pub fn get_deleted_notifications(&self) -> Result<Vec<Notification>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<NotificationRow> = notifications::table
+            .inner_join(subscriptions::table)
+            .filter(notifications::deleted_at.is_not_null())
+            .filter(subscriptions::deleted_at.is_null())
+            .order(notifications::deleted_at.desc())
+            .select(NotificationRow::as_select())
+            .load(&mut *conn)?;
+
+        Ok(rows
+            .into_iter()
+            .map(NotificationRow::into_notification)
+            .collect())
+    }
+
+    /// Gets `(subscription_id, ntfy_id)` of a notification received from a
+    /// server; `None` for unknown notifications and ones without an ntfy ID.
+    pub fn get_notification_origin(&self, id: &str) -> Result<Option<(String, String)>, AppError> {
+        let mut conn = self.conn()?;
+
+        let row: Option<(String, Option<String>)> = notifications::table
+            .filter(notifications::id.eq(id))
+            .select((notifications::subscription_id, notifications::ntfy_id))
+            .first(&mut *conn)
+            .optional()?;
+
+        Ok(row.and_then(|(subscription_id, ntfy_id)| Some((subscription_id, ntfy_id?))))
+    }
+
+    /// Gets `(id, ntfy_id, subscription_id)` of trashed notifications.
+    ///
+    /// If `deleted_before` is set, only notifications trashed before that
+    /// timestamp (milliseconds) are returned.
+    pub fn get_deleted_notification_meta(
+        &self,
+        deleted_before: Option<i64>,
+    ) -> Result<Vec<(String, Option<String>, String)>, AppError> {
+        let mut conn = self.conn()?;
+
+        let mut query = notifications::table
+            .filter(notifications::deleted_at.is_not_null())
+            .select((
+                notifications::id,
+                notifications::ntfy_id,
+                notifications::subscription_id,
+            ))
+            .into_boxed();
+        if let Some(cutoff) = deleted_before {
+            query = query.filter(notifications::deleted_at.lt(cutoff));
+        }
+
+        Ok(query.load(&mut *conn)?)
+    }
+
     /// Gets the unread count for a subscription.
     pub fn get_unread_count(&self, subscription_id: &str) -> Result<i32, AppError> {
         use diesel::dsl::count_star;
@@ -209,6 +572,7 @@ impl Database {
         let count: i64 = notifications::table
             .filter(notifications::subscription_id.eq(subscription_id))
             .filter(notifications::read.eq(0))
+            .filter(notifications::deleted_at.is_null())
             .select(count_star())
             .first(&mut *conn)?;
 
@@ -224,7 +588,9 @@ impl Database {
         let count: i64 = notifications::table
             .inner_join(subscriptions::table)
             .filter(notifications::read.eq(0))
+            .filter(notifications::deleted_at.is_null())
             .filter(subscriptions::muted.eq(0))
+            .filter(subscriptions::deleted_at.is_null())
             .select(count_star())
             .first(&mut *conn)?;
 
@@ -0,0 +1,93 @@
+//! Outbox database queries.
+
+use diesel::prelude::*;
+
+use crate::db::connection::Database;
+use crate::db::models::OutboxRow;
+use crate::db::schema::outbox;
+use crate::error::AppError;
+use crate::models::{OutboxEntry, PublishMessage};
+
+impl Database {
+    /// Gets all queued messages, oldest first.
+    pub fn get_outbox(&self) -> Result<Vec<OutboxEntry>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<OutboxRow> = outbox::table
+            .order(outbox::created_at.asc())
+            .load(&mut *conn)?;
+
+        Ok(rows.into_iter().filter_map(OutboxRow::into_entry).collect())
+    }
+
+    /// Counts the messages waiting in the outbox.
+    pub fn get_outbox_count(&self) -> Result<i64, AppError> {
+        use diesel::dsl::count_star;
+
+        let mut conn = self.conn()?;
+
+        Ok(outbox::table.select(count_star()).first(&mut *conn)?)
+    }
+
+    /// Checks whether messages to a subscription are waiting in the outbox.
+    pub fn has_queued_messages(&self, subscription_id: &str) -> Result<bool, AppError> {
+        use diesel::dsl::count_star;
+
+        let mut conn = self.conn()?;
+
+        let count: i64 = outbox::table
+            .filter(outbox::subscription_id.eq(subscription_id))
+            .select(count_star())
+            .first(&mut *conn)?;
+
+        Ok(count > 0)
+    }
+
+    /// Queues a message to a subscription.
+    pub fn queue_message(
+        &self,
+        subscription_id: &str,
+        message: &PublishMessage,
+    ) -> Result<OutboxEntry, AppError> {
+        let row = OutboxRow {
+            id: uuid::Uuid::new_v4().to_string(),
+            subscription_id: subscription_id.to_string(),
+            message: serde_json::to_string(message)?,
+            attempts: 0,
+            created_at: chrono::Utc::now().timestamp_millis(),
+        };
+
+        let mut conn = self.conn()?;
+        diesel::insert_into(outbox::table)
+            .values(&row)
+            .execute(&mut *conn)?;
+
+        Ok(OutboxEntry {
+            id: row.id,
+            subscription_id: row.subscription_id,
+            message: message.clone(),
+            attempts: 0,
+            created_at: row.created_at,
+        })
+    }
+
+    /// Records a failed attempt to send a queued message.
+    pub fn record_outbox_attempt(&self, id: &str) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::update(outbox::table.filter(outbox::id.eq(id)))
+            .set(outbox::attempts.eq(outbox::attempts + 1))
+            .execute(&mut *conn)?;
+
+        Ok(())
+    }
+
+    /// Removes a message from the outbox.
+    pub fn delete_outbox_entry(&self, id: &str) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::delete(outbox::table.filter(outbox::id.eq(id))).execute(&mut *conn)?;
+
+        Ok(())
+    }
+}
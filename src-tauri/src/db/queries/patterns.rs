@@ -0,0 +1,55 @@
+//! Pattern subscription database queries.
+
+use diesel::prelude::*;
+
+use crate::db::connection::Database;
+use crate::db::models::SubscriptionPatternRow;
+use crate::db::schema::subscription_patterns;
+use crate::error::AppError;
+use crate::models::{CreateSubscriptionPattern, SubscriptionPattern};
+
+impl Database {
+    /// Gets all pattern subscriptions, oldest first.
+    pub fn get_subscription_patterns(&self) -> Result<Vec<SubscriptionPattern>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<SubscriptionPatternRow> = subscription_patterns::table
+            .order(subscription_patterns::created_at.asc())
+            .load(&mut *conn)?;
+
+        Ok(rows.into_iter().map(SubscriptionPattern::from).collect())
+    }
+
+    /// Adds a pattern subscription.
+    pub fn add_subscription_pattern(
+        &self,
+        pattern: CreateSubscriptionPattern,
+    ) -> Result<SubscriptionPattern, AppError> {
+        pattern.validate()?;
+
+        let row = SubscriptionPatternRow {
+            id: uuid::Uuid::new_v4().to_string(),
+            server_url: pattern.server_url,
+            pattern: pattern.pattern.trim().to_string(),
+            display_name: pattern.display_name.filter(|s| !s.is_empty()),
+            created_at: chrono::Utc::now().timestamp_millis(),
+        };
+
+        let mut conn = self.conn()?;
+        diesel::insert_into(subscription_patterns::table)
+            .values(&row)
+            .execute(&mut *conn)?;
+
+        Ok(row.into())
+    }
+
+    /// Removes a pattern subscription. Its children become plain subscriptions.
+    pub fn remove_subscription_pattern(&self, id: &str) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::delete(subscription_patterns::table.filter(subscription_patterns::id.eq(id)))
+            .execute(&mut *conn)?;
+
+        Ok(())
+    }
+}
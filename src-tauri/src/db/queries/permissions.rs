@@ -0,0 +1,94 @@
+//! Permission-related database queries.
+
+use diesel::prelude::*;
+use diesel::Connection;
+
+use crate::db::connection::Database;
+use crate::db::models::PermissionRow;
+use crate::db::schema::permissions;
+use crate::error::AppError;
+use crate::models::{Capability, Permission};
+
+impl Database {
+    /// Gets the remembered decision for a capability, if any.
+    pub fn get_permission(
+        &self,
+        subscription_id: &str,
+        capability: Capability,
+    ) -> Result<Option<bool>, AppError> {
+        let mut conn = self.conn()?;
+
+        let allowed: Option<i32> = permissions::table
+            .filter(permissions::subscription_id.eq(subscription_id))
+            .filter(permissions::capability.eq(capability.as_str()))
+            .select(permissions::allowed)
+            .first(&mut *conn)
+            .optional()?;
+
+        Ok(allowed.map(|a| a == 1))
+    }
+
+    /// Remembers a decision for a capability, replacing any previous one.
+    pub fn set_permission(
+        &self,
+        subscription_id: &str,
+        capability: Capability,
+        allowed: bool,
+    ) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            diesel::delete(
+                permissions::table
+                    .filter(permissions::subscription_id.eq(subscription_id))
+                    .filter(permissions::capability.eq(capability.as_str())),
+            )
+            .execute(conn)?;
+
+            diesel::insert_into(permissions::table)
+                .values(&PermissionRow {
+                    id: uuid::Uuid::new_v4().to_string(),
+                    subscription_id: subscription_id.to_string(),
+                    capability: capability.as_str().to_string(),
+                    allowed: i32::from(allowed),
+                    created_at: chrono::Utc::now().timestamp_millis(),
+                })
+                .execute(conn)?;
+
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
+    /// Forgets a remembered decision so the user is asked again.
+    pub fn revoke_permission(&self, id: &str) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::delete(permissions::table.filter(permissions::id.eq(id))).execute(&mut *conn)?;
+
+        Ok(())
+    }
+
+    /// Gets all remembered decisions, optionally for a single subscription.
+    pub fn get_permissions(
+        &self,
+        subscription_id: Option<&str>,
+    ) -> Result<Vec<Permission>, AppError> {
+        let mut conn = self.conn()?;
+
+        let mut query = permissions::table
+            .order(permissions::created_at.desc())
+            .into_boxed();
+        if let Some(subscription_id) = subscription_id {
+            query = query.filter(permissions::subscription_id.eq(subscription_id));
+        }
+
+        let rows: Vec<PermissionRow> = query.load(&mut *conn)?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(PermissionRow::into_permission)
+            .collect())
+    }
+}
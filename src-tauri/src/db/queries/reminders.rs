@@ -0,0 +1,93 @@
+//! Reminder database queries.
+
+use chrono::Local;
+use diesel::prelude::*;
+
+use crate::db::connection::Database;
+use crate::db::models::ReminderRow;
+use crate::db::schema::reminders;
+use crate::error::AppError;
+use crate::models::{CronSchedule, Reminder};
+
+impl Database {
+    /// Gets all reminders, oldest first.
+    pub fn get_reminders(&self) -> Result<Vec<Reminder>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<ReminderRow> = reminders::table
+            .order(reminders::created_at.asc())
+            .load(&mut *conn)?;
+
+        Ok(rows.into_iter().map(Reminder::from).collect())
+    }
+
+    /// Adds a reminder, first shown at the next time matching `schedule`.
+    ///
+    /// Schedules that never match are rejected.
+    pub fn create_reminder(
+        &self,
+        title: Option<&str>,
+        message: &str,
+        schedule: &str,
+    ) -> Result<Reminder, AppError> {
+        if message.trim().is_empty() {
+            return Err(AppError::Validation(
+                "Reminder message cannot be empty".to_string(),
+            ));
+        }
+        let next_run = CronSchedule::parse(schedule)?
+            .next_after(&Local::now())
+            .ok_or_else(|| AppError::Validation(format!("Schedule never matches: {schedule}")))?;
+
+        let row = ReminderRow {
+            id: uuid::Uuid::new_v4().to_string(),
+            title: title
+                .map(str::trim)
+                .filter(|t| !t.is_empty())
+                .map(str::to_string),
+            message: message.to_string(),
+            schedule: schedule.trim().to_string(),
+            next_run: Some(next_run.timestamp_millis()),
+            created_at: chrono::Utc::now().timestamp_millis(),
+        };
+
+        let mut conn = self.conn()?;
+        diesel::insert_into(reminders::table)
+            .values(&row)
+            .execute(&mut *conn)?;
+
+        Ok(row.into())
+    }
+
+    /// Removes a reminder.
+    pub fn delete_reminder(&self, id: &str) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::delete(reminders::table.filter(reminders::id.eq(id))).execute(&mut *conn)?;
+
+        Ok(())
+    }
+
+    /// Gets reminders due at `now` (milliseconds).
+    pub fn get_due_reminders(&self, now: i64) -> Result<Vec<Reminder>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<ReminderRow> = reminders::table
+            .filter(reminders::next_run.le(now))
+            .order(reminders::next_run.asc())
+            .load(&mut *conn)?;
+
+        Ok(rows.into_iter().map(Reminder::from).collect())
+    }
+
+    /// Sets when a reminder is next shown, or `None` for never.
+    pub fn set_reminder_next_run(&self, id: &str, next_run: Option<i64>) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::update(reminders::table.filter(reminders::id.eq(id)))
+            .set(reminders::next_run.eq(next_run))
+            .execute(&mut *conn)?;
+
+        Ok(())
+    }
+}
@@ -0,0 +1,117 @@
+//! Notification rule database queries.
+
+use diesel::prelude::*;
+
+use crate::db::connection::Database;
+use crate::db::models::RuleRow;
+use crate::db::schema::rules;
+use crate::error::AppError;
+use crate::models::{Rule, RuleInput};
+
+impl Database {
+    /// Gets all rules in the order they're evaluated, oldest first.
+    pub fn get_rules(&self) -> Result<Vec<Rule>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<RuleRow> = rules::table
+            .order(rules::created_at.asc())
+            .load(&mut *conn)?;
+
+        Ok(rows.into_iter().map(Rule::from).collect())
+    }
+
+    /// Creates a rule, evaluated after the existing ones.
+    pub fn create_rule(&self, input: RuleInput) -> Result<Rule, AppError> {
+        input.validate()?;
+        let row = Self::rule_row(
+            uuid::Uuid::new_v4().to_string(),
+            input,
+            chrono::Utc::now().timestamp_millis(),
+        )?;
+
+        let mut conn = self.conn()?;
+        diesel::insert_into(rules::table)
+            .values(&row)
+            .execute(&mut *conn)?;
+
+        Ok(row.into())
+    }
+
+    /// Creates rules in one transaction, evaluated after the existing ones in
+    /// the order given.
+    pub fn create_rules(&self, inputs: Vec<RuleInput>) -> Result<Vec<Rule>, AppError> {
+        let now = chrono::Utc::now().timestamp_millis();
+        let rows = inputs
+            .into_iter()
+            .zip(now..)
+            .map(|(input, created_at)| {
+                input.validate()?;
+                Self::rule_row(uuid::Uuid::new_v4().to_string(), input, created_at)
+            })
+            .collect::<Result<Vec<_>, AppError>>()?;
+        if rows.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let mut conn = self.conn()?;
+        diesel::insert_into(rules::table)
+            .values(&rows)
+            .execute(&mut *conn)?;
+
+        Ok(rows.into_iter().map(Rule::from).collect())
+    }
+
+    /// Updates a rule's name, conditions and actions, or enables or disables it.
+    pub fn update_rule(&self, id: &str, input: RuleInput) -> Result<Rule, AppError> {
+        input.validate()?;
+        let mut conn = self.conn()?;
+
+        let created_at: Option<i64> = rules::table
+            .filter(rules::id.eq(id))
+            .select(rules::created_at)
+            .first(&mut *conn)
+            .optional()?;
+        let created_at =
+            created_at.ok_or_else(|| AppError::NotFound(format!("Rule {id} not found")))?;
+        let row = Self::rule_row(id.to_string(), input, created_at)?;
+
+        diesel::update(rules::table.filter(rules::id.eq(id)))
+            .set((
+                rules::name.eq(&row.name),
+                rules::enabled.eq(row.enabled),
+                rules::conditions.eq(&row.conditions),
+                rules::actions.eq(&row.actions),
+            ))
+            .execute(&mut *conn)?;
+
+        Ok(row.into())
+    }
+
+    /// Deletes a rule.
+    pub fn delete_rule(&self, id: &str) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::delete(rules::table.filter(rules::id.eq(id))).execute(&mut *conn)?;
+
+        Ok(())
+    }
+
+    fn rule_row(id: String, input: RuleInput, created_at: i64) -> Result<RuleRow, AppError> {
+        let mut conditions = input.conditions;
+        conditions.topic = conditions.topic.map(|t| t.trim().to_string());
+        conditions.tags = conditions
+            .tags
+            .iter()
+            .map(|t| t.trim().to_string())
+            .collect();
+
+        Ok(RuleRow {
+            id,
+            name: input.name.trim().to_string(),
+            enabled: i32::from(input.enabled),
+            conditions: serde_json::to_string(&conditions)?,
+            actions: serde_json::to_string(&input.actions)?,
+            created_at,
+        })
+    }
+}
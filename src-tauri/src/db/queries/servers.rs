@@ -2,20 +2,27 @@
 
 use diesel::prelude::*;
 use diesel::Connection;
+use zeroize::Zeroizing;
 
 use crate::db::connection::Database;
 use crate::db::models::{NewServer, ServerRow};
-use crate::db::schema::{servers, subscriptions};
+use crate::db::schema::{notifications, servers, subscriptions};
 use crate::error::AppError;
-use crate::models::ServerConfig;
-use crate::services::credential_manager;
+use crate::models::{
+    CredentialProblem, MissingCredential, Priority, PublishDefaults, ServerConfig,
+    ServerRemovalImpact, ServerResolution,
+};
+use crate::services::{credential_manager, NtfyClient};
 
 impl Database {
-    /// Gets all configured servers with credentials from keychain.
+    /// Gets all configured (non-detached) servers with credentials from keychain.
     pub fn get_servers_with_credentials(&self) -> Result<Vec<ServerConfig>, AppError> {
         let mut conn = self.conn()?;
 
-        let rows: Vec<ServerRow> = servers::table.load(&mut *conn)?;
+        let rows: Vec<ServerRow> = servers::table
+            .filter(servers::detached.eq(0))
+            .select(ServerRow::as_select())
+            .load(&mut *conn)?;
         drop(conn);
 
         // Try to get passwords from OS keychain first, fall back to DB
@@ -25,7 +32,8 @@ impl Database {
                 let password = row
                     .username
                     .as_ref()
-                    .and_then(|u| credential_manager::get_password(u, &row.url).ok().flatten());
+                    .and_then(|u| credential_manager::get_password(u, &row.url).ok().flatten())
+                    .map(|password| (*password).clone());
 
                 ServerConfig {
                     url: row.url,
@@ -37,12 +45,66 @@ impl Database {
             .collect())
     }
 
+    /// Gets all configured (non-detached) servers without their passwords.
+    pub fn get_servers(&self) -> Result<Vec<ServerConfig>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<ServerRow> = servers::table
+            .filter(servers::detached.eq(0))
+            .select(ServerRow::as_select())
+            .load(&mut *conn)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ServerConfig {
+                url: row.url,
+                username: row.username,
+                password: None,
+                is_default: row.is_default == 1,
+            })
+            .collect())
+    }
+
+    /// Checks that every server with a username has its password in the OS
+    /// keychain, and returns the servers whose password is missing or can't
+    /// be read. Detached servers are skipped.
+    pub fn verify_credentials_consistency(&self) -> Result<Vec<MissingCredential>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<(String, Option<String>)> = servers::table
+            .filter(servers::detached.eq(0))
+            .select((servers::url, servers::username))
+            .load(&mut *conn)?;
+        drop(conn);
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(url, username)| {
+                let username = username.filter(|u| !u.is_empty())?;
+                let problem = match credential_manager::get_password(&username, &url) {
+                    Ok(Some(_)) => return None,
+                    Ok(None) => CredentialProblem::Missing,
+                    Err(e) => {
+                        log::warn!("Failed to read credential for {url}: {e}");
+                        CredentialProblem::Unreadable
+                    }
+                };
+                Some(MissingCredential {
+                    server_url: url,
+                    username,
+                    problem,
+                })
+            })
+            .collect())
+    }
+
     /// Gets the URL of the default server.
     pub fn get_default_server_url(&self) -> Result<String, AppError> {
         let mut conn = self.conn()?;
 
         let result: Option<String> = servers::table
             .filter(servers::is_default.eq(1))
+            .filter(servers::detached.eq(0))
             .select(servers::url)
             .first(&mut *conn)
             .optional()?;
@@ -50,7 +112,173 @@ impl Database {
         Ok(result.unwrap_or_else(|| "https://ntfy.sh".to_string()))
     }
 
+    /// Gets the username configured for a server, if any.
+    pub fn get_server_username(&self, url: &str) -> Result<Option<String>, AppError> {
+        let mut conn = self.conn()?;
+
+        let username: Option<Option<String>> = servers::table
+            .filter(servers::url.eq(url))
+            .select(servers::username)
+            .first(&mut *conn)
+            .optional()?;
+
+        Ok(username.flatten().filter(|u| !u.is_empty()))
+    }
+
+    /// Gets the publishing defaults of a server (empty if it has none).
+    pub fn get_server_publish_defaults(&self, url: &str) -> Result<PublishDefaults, AppError> {
+        let mut conn = self.conn()?;
+
+        let row: Option<(Option<i32>, String, i32, Option<String>)> = servers::table
+            .filter(servers::url.eq(url))
+            .select((
+                servers::default_priority,
+                servers::default_tags,
+                servers::default_markdown,
+                servers::default_delay,
+            ))
+            .first(&mut *conn)
+            .optional()?;
+
+        Ok(row.map_or_else(
+            PublishDefaults::default,
+            |(priority, tags, markdown, delay)| PublishDefaults {
+                priority: priority.map(|p| Priority::from(p as i8)),
+                tags: serde_json::from_str(&tags).unwrap_or_default(),
+                markdown: markdown == 1,
+                delay,
+            },
+        ))
+    }
+
+    /// Sets the publishing defaults of a server.
+    pub fn set_server_publish_defaults(
+        &self,
+        url: &str,
+        defaults: &PublishDefaults,
+    ) -> Result<(), AppError> {
+        let tags = serde_json::to_string(&defaults.tags)?;
+        let mut conn = self.conn()?;
+
+        let updated = diesel::update(servers::table.filter(servers::url.eq(url)))
+            .set((
+                servers::default_priority.eq(defaults.priority.map(|p| p as i32)),
+                servers::default_tags.eq(tags),
+                servers::default_markdown.eq(i32::from(defaults.markdown)),
+                servers::default_delay.eq(defaults.delay.as_deref()),
+            ))
+            .execute(&mut *conn)?;
+        if updated == 0 {
+            return Err(AppError::NotFound(format!("Server {url} not found")));
+        }
+
+        Ok(())
+    }
+
+    /// Gets how a server's host name is resolved (the system default if it
+    /// has no overrides).
+    pub fn get_server_resolution(&self, url: &str) -> Result<ServerResolution, AppError> {
+        let mut conn = self.conn()?;
+
+        let resolution: Option<String> = servers::table
+            .filter(servers::url.eq(url))
+            .select(servers::resolution)
+            .first(&mut *conn)
+            .optional()?;
+
+        Ok(resolution.map_or_else(ServerResolution::default, |r| parse_resolution(&r)))
+    }
+
+    /// Gets the URLs and resolution overrides of all configured servers that
+    /// have any.
+    pub fn get_server_resolutions(&self) -> Result<Vec<(String, ServerResolution)>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<(String, String)> = servers::table
+            .filter(servers::detached.eq(0))
+            .select((servers::url, servers::resolution))
+            .load(&mut *conn)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|(url, resolution)| (url, parse_resolution(&resolution)))
+            .filter(|(_, resolution)| !resolution.is_default())
+            .collect())
+    }
+
+    /// Sets how a server's host name is resolved.
+    pub fn set_server_resolution(
+        &self,
+        url: &str,
+        resolution: &ServerResolution,
+    ) -> Result<(), AppError> {
+        let resolution = serde_json::to_string(resolution)?;
+        let mut conn = self.conn()?;
+
+        let updated = diesel::update(servers::table.filter(servers::url.eq(url)))
+            .set(servers::resolution.eq(resolution))
+            .execute(&mut *conn)?;
+        if updated == 0 {
+            return Err(AppError::NotFound(format!("Server {url} not found")));
+        }
+
+        Ok(())
+    }
+
+    /// Gets the path of the PKCS#12 file a server presents as its client
+    /// certificate, if it has one.
+    pub fn get_server_client_certificate(&self, url: &str) -> Result<Option<String>, AppError> {
+        let mut conn = self.conn()?;
+
+        let path: Option<Option<String>> = servers::table
+            .filter(servers::url.eq(url))
+            .select(servers::client_certificate)
+            .first(&mut *conn)
+            .optional()?;
+
+        Ok(path.flatten())
+    }
+
+    /// Gets the URLs and client certificate paths of all configured servers
+    /// that have one.
+    pub fn get_server_client_certificates(&self) -> Result<Vec<(String, String)>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<(String, Option<String>)> = servers::table
+            .filter(servers::detached.eq(0))
+            .filter(servers::client_certificate.is_not_null())
+            .select((servers::url, servers::client_certificate))
+            .load(&mut *conn)?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(|(url, path)| Some((url, path?)))
+            .collect())
+    }
+
+    /// Sets the PKCS#12 file a server presents as its client certificate;
+    /// `None` removes it.
+    pub fn set_server_client_certificate(
+        &self,
+        url: &str,
+        path: Option<&str>,
+    ) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        let updated = diesel::update(servers::table.filter(servers::url.eq(url)))
+            .set(servers::client_certificate.eq(path))
+            .execute(&mut *conn)?;
+        if updated == 0 {
+            return Err(AppError::NotFound(format!("Server {url} not found")));
+        }
+
+        Ok(())
+    }
+
     /// Adds a new server.
+    ///
+    /// If the URL belongs to a server removed with history kept, that server is
+    /// re-attached so its subscriptions come back.
     pub fn add_server(&self, server: ServerConfig) -> Result<(), AppError> {
         server.validate()?;
 
@@ -60,6 +288,24 @@ impl Database {
         }
 
         let mut conn = self.conn()?;
+
+        let reattached = diesel::update(
+            servers::table
+                .filter(servers::url.eq(&server.url))
+                .filter(servers::detached.eq(1)),
+        )
+        .set((
+            servers::username.eq(server.username.as_deref()),
+            servers::is_default.eq(i32::from(server.is_default)),
+            servers::detached.eq(0),
+        ))
+        .execute(&mut *conn)?;
+
+        if reattached > 0 {
+            log::info!("Re-attached detached server {}", server.url);
+            return Ok(());
+        }
+
         let id = uuid::Uuid::new_v4().to_string();
 
         // Don't store password in database - it's in keychain
@@ -77,8 +323,165 @@ impl Database {
         Ok(())
     }
 
-    /// Removes a server and all its subscriptions.
-    pub fn remove_server(&self, url: &str) -> Result<(), AppError> {
+    /// Updates a server's URL, username, password or default flag.
+    ///
+    /// The keychain entry is keyed by username and URL, so it is migrated to
+    /// the new key (or replaced if a new password is given).
+    pub fn update_server(&self, original_url: &str, server: ServerConfig) -> Result<(), AppError> {
+        server.validate()?;
+
+        let old_username: Option<String> = {
+            let mut conn = self.conn()?;
+            servers::table
+                .filter(servers::url.eq(original_url))
+                .select(servers::username)
+                .first::<Option<String>>(&mut *conn)
+                .optional()?
+                .ok_or_else(|| AppError::NotFound(format!("Server {original_url} not found")))?
+        };
+
+        // Store the password under its new key before updating the DB, and
+        // only remove the old entry once the update is committed, so a
+        // failure on either side never loses it
+        let old_key = old_username
+            .as_deref()
+            .map(|username| (username, original_url));
+        let new_key = server
+            .username
+            .as_deref()
+            .filter(|u| !u.is_empty())
+            .map(|username| (username, server.url.as_str()));
+        let old_password = match old_key {
+            Some((username, url)) => credential_manager::get_password(username, url)?,
+            None => None,
+        };
+        let new_password = server.credentials().map(|(_, password)| password);
+        // The new password, or the old one moved to the new key
+        let password = new_password.or_else(|| old_password.as_deref().map(String::as_str));
+        let stored = match (new_key, password) {
+            (Some((username, url)), Some(password))
+                if new_key != old_key || new_password.is_some() =>
+            {
+                credential_manager::store_password(username, url, password)?;
+                true
+            }
+            _ => false,
+        };
+
+        // Puts the keychain back as it was if the update fails
+        let roll_back = |passphrase_copied: bool| {
+            if let (true, Some((username, url))) = (stored, new_key) {
+                let restored = match old_password.as_deref().filter(|_| new_key == old_key) {
+                    Some(password) => credential_manager::store_password(username, url, password),
+                    None => credential_manager::delete_password(username, url),
+                };
+                if let Err(e) = restored {
+                    log::warn!("Failed to restore credential after failed server update: {e}");
+                }
+            }
+            if passphrase_copied {
+                if let Err(e) = credential_manager::delete_certificate_passphrase(&server.url) {
+                    log::warn!("Failed to remove certificate passphrase copy: {e}");
+                }
+            }
+        };
+
+        // The certificate passphrase is keyed by URL too, and is moved the
+        // same way
+        let passphrase = if original_url == server.url {
+            None
+        } else {
+            match credential_manager::get_certificate_passphrase(original_url) {
+                Ok(passphrase) => passphrase.map(Zeroizing::new),
+                Err(e) => {
+                    roll_back(false);
+                    return Err(e);
+                }
+            }
+        };
+        if let Some(passphrase) = &passphrase {
+            if let Err(e) =
+                credential_manager::store_certificate_passphrase(&server.url, passphrase)
+            {
+                roll_back(false);
+                return Err(e);
+            }
+        }
+
+        let updated = self.conn().and_then(|mut conn| {
+            conn.transaction::<_, diesel::result::Error, _>(|conn| {
+                if server.is_default {
+                    diesel::update(servers::table)
+                        .set(servers::is_default.eq(0))
+                        .execute(conn)?;
+                }
+
+                diesel::update(servers::table.filter(servers::url.eq(original_url)))
+                    .set((
+                        servers::url.eq(&server.url),
+                        servers::username.eq(server.username.as_deref()),
+                        servers::is_default.eq(i32::from(server.is_default)),
+                    ))
+                    .execute(conn)?;
+
+                Ok(())
+            })
+            .map_err(AppError::from)
+        });
+        if let Err(e) = updated {
+            roll_back(passphrase.is_some());
+            return Err(e);
+        }
+
+        if passphrase.is_some() {
+            if let Err(e) = credential_manager::delete_certificate_passphrase(original_url) {
+                log::warn!("Failed to delete old certificate passphrase of updated server: {e}");
+            }
+        }
+        if let Some((username, url)) = old_key.filter(|key| Some(*key) != new_key) {
+            if let Err(e) = credential_manager::delete_password(username, url) {
+                log::warn!("Failed to delete old credential of updated server: {e}");
+            }
+        }
+        // Tokens were issued for the old credentials
+        NtfyClient::clear_session_tokens();
+
+        Ok(())
+    }
+
+    /// Counts the subscriptions and notifications that removing a server would delete.
+    pub fn get_server_removal_impact(&self, url: &str) -> Result<ServerRemovalImpact, AppError> {
+        use diesel::dsl::count_star;
+
+        let mut conn = self.conn()?;
+
+        let server_ids = servers::table
+            .filter(servers::url.eq(url))
+            .select(servers::id);
+
+        let subscription_count: i64 = subscriptions::table
+            .filter(subscriptions::server_id.eq_any(server_ids))
+            .select(count_star())
+            .first(&mut *conn)?;
+
+        let notification_count: i64 = notifications::table
+            .inner_join(subscriptions::table)
+            .filter(subscriptions::server_id.eq_any(server_ids))
+            .select(count_star())
+            .first(&mut *conn)?;
+
+        Ok(ServerRemovalImpact {
+            subscription_count: subscription_count as i32,
+            notification_count: notification_count as i32,
+        })
+    }
+
+    /// Removes a server.
+    ///
+    /// With `keep_history`, the server is detached instead: it disappears from
+    /// settings and its subscriptions stop syncing, but their notifications stay
+    /// readable. Otherwise all its subscriptions and notifications are deleted.
+    pub fn remove_server(&self, url: &str, keep_history: bool) -> Result<(), AppError> {
         let mut conn = self.conn()?;
 
         // Run DB operations in a transaction
@@ -90,6 +493,19 @@ impl Database {
                 .optional()?
                 .flatten();
 
+            if keep_history {
+                diesel::update(servers::table.filter(servers::url.eq(url)))
+                    .set((
+                        servers::detached.eq(1),
+                        servers::is_default.eq(0),
+                        servers::username.eq(None::<String>),
+                        servers::client_certificate.eq(None::<String>),
+                    ))
+                    .execute(conn)?;
+
+                return Ok(username);
+            }
+
             // Delete subscriptions for this server
             let server_ids: Vec<String> = servers::table
                 .filter(servers::url.eq(url))
@@ -106,10 +522,21 @@ impl Database {
             Ok(username)
         })?;
 
-        // Clean up keychain after successful transaction (best-effort)
-        if let Some(ref username) = username {
-            if let Err(e) = credential_manager::delete_password(username, url) {
+        drop(conn);
+        NtfyClient::clear_session_tokens();
+
+        if let Err(e) = credential_manager::delete_certificate_passphrase(url) {
+            log::warn!("Failed to clean up certificate passphrase of {url}: {e}");
+        }
+
+        // Clean up keychain after successful transaction (best-effort);
+        // entries that can't be deleted now are retried by maintenance
+        if let Some(username) = username {
+            if let Err(e) = credential_manager::delete_password(&username, url) {
                 log::warn!("Failed to clean up keychain for {username}@{url}: {e}");
+                let mut stale = self.get_stale_credentials()?;
+                stale.push((username, url.to_string()));
+                self.set_stale_credentials(&stale)?;
             }
         }
 
@@ -137,3 +564,10 @@ impl Database {
         Ok(())
     }
 }
+
+fn parse_resolution(json: &str) -> ServerResolution {
+    serde_json::from_str(json).unwrap_or_else(|e| {
+        log::warn!("Ignoring invalid server resolution: {e}");
+        ServerResolution::default()
+    })
+}
@@ -6,7 +6,12 @@ use crate::db::connection::Database;
 use crate::db::models::SettingRow;
 use crate::db::schema::settings;
 use crate::error::AppError;
-use crate::models::{AppSettings, NotificationDisplayMethod, NotificationSettings, ThemeMode};
+use crate::models::{
+    host_name, AppLockMethod, AppSettings, DeviceInfo, DndSchedule, DoNotDisturb, HttpTimeouts,
+    LogRedaction, NotificationDisplayMethod, NotificationSettings, Priority, ProxySettings,
+    ReceiptTopic, SubscriptionSort, TagPriority, ThemeMode, TrayIconStyle, WatchFolder,
+    DEFAULT_APP_LOCK_MINUTES, DEFAULT_DAILY_SUMMARY_TIME,
+};
 
 impl Database {
     /// Gets a string setting with a default fallback.
@@ -35,6 +40,12 @@ impl Database {
         Ok(result.map_or(default, |v| v == "true"))
     }
 
+    /// Gets an unsigned integer setting with a default fallback.
+    fn get_setting_u32(&self, key: &str, default: u32) -> Result<u32, AppError> {
+        let value = self.get_setting_string(key, &default.to_string())?;
+        Ok(value.parse().unwrap_or(default))
+    }
+
     /// Gets notification-specific settings only (does not fetch server credentials).
     /// Use this when displaying notifications to avoid unnecessary credential lookups.
     pub fn get_notification_settings(&self) -> Result<NotificationSettings, AppError> {
@@ -48,6 +59,11 @@ impl Database {
         let notification_show_actions = self.get_setting_bool("notification_show_actions", true)?;
         let notification_show_images = self.get_setting_bool("notification_show_images", true)?;
         let notification_sound = self.get_setting_bool("notification_sound", true)?;
+        let notification_group_by_topic =
+            self.get_setting_bool("notification_group_by_topic", true)?;
+        let min_toast_priority = self.get_min_toast_priority()?;
+        let hide_content_when_locked = self.get_hide_content_when_locked()?;
+        let screen_reader_announcements = self.get_screen_reader_announcements()?;
 
         Ok(NotificationSettings {
             notification_method,
@@ -55,9 +71,19 @@ impl Database {
             notification_show_actions,
             notification_show_images,
             notification_sound,
+            notification_group_by_topic,
+            min_toast_priority,
+            hide_content_when_locked,
+            screen_reader_announcements,
         })
     }
 
+    /// Gets the `min_toast_priority` setting.
+    pub fn get_min_toast_priority(&self) -> Result<Priority, AppError> {
+        let priority = self.get_setting_u32("min_toast_priority", Priority::Min as u32)?;
+        Ok(i8::try_from(priority).map_or(Priority::Min, Priority::from))
+    }
+
     /// Gets the `start_minimized` setting.
     pub fn get_start_minimized(&self) -> Result<bool, AppError> {
         self.get_setting_bool("start_minimized", false)
@@ -68,11 +94,244 @@ impl Database {
         self.get_setting_bool("minimize_to_tray", true)
     }
 
+    /// Whether newly received notifications should start expanded.
+    ///
+    /// True when both `compact_view` and `expand_new_messages` are enabled.
+    pub fn get_expand_new_notifications(&self) -> Result<bool, AppError> {
+        Ok(self.get_setting_bool("compact_view", false)?
+            && self.get_setting_bool("expand_new_messages", true)?)
+    }
+
     /// Gets the `delete_local_only` setting.
     pub fn get_delete_local_only(&self) -> Result<bool, AppError> {
         self.get_setting_bool("delete_local_only", true)
     }
 
+    /// Gets the `startup_delay_secs` setting.
+    pub fn get_startup_delay_secs(&self) -> Result<u32, AppError> {
+        self.get_setting_u32("startup_delay_secs", 0)
+    }
+
+    /// Gets the `wait_for_network` setting.
+    pub fn get_wait_for_network(&self) -> Result<bool, AppError> {
+        self.get_setting_bool("wait_for_network", true)
+    }
+
+    /// Gets the `credential_lock_minutes` setting.
+    pub fn get_credential_lock_minutes(&self) -> Result<u32, AppError> {
+        self.get_setting_u32("credential_lock_minutes", 0)
+    }
+
+    /// Gets the `app_lock_method` setting.
+    pub fn get_app_lock_method(&self) -> Result<AppLockMethod, AppError> {
+        let method_str = self.get_setting_string("app_lock_method", "off")?;
+        Ok(match method_str.as_str() {
+            "pin" => AppLockMethod::Pin,
+            "system" => AppLockMethod::System,
+            _ => AppLockMethod::Off,
+        })
+    }
+
+    /// Gets the `app_lock_minutes` setting.
+    pub fn get_app_lock_minutes(&self) -> Result<u32, AppError> {
+        self.get_setting_u32("app_lock_minutes", DEFAULT_APP_LOCK_MINUTES)
+    }
+
+    /// Gets the `log_redaction` setting.
+    pub fn get_log_redaction(&self) -> Result<LogRedaction, AppError> {
+        let redaction_str = self.get_setting_string("log_redaction", "off")?;
+        Ok(match redaction_str.as_str() {
+            "truncate" => LogRedaction::Truncate,
+            "hash" => LogRedaction::Hash,
+            _ => LogRedaction::Off,
+        })
+    }
+
+    /// Gets the `subscription_sort` setting.
+    pub fn get_subscription_sort(&self) -> Result<SubscriptionSort, AppError> {
+        let sort_str = self.get_setting_string("subscription_sort", "recent")?;
+        Ok(match sort_str.as_str() {
+            "alphabetical" => SubscriptionSort::Alphabetical,
+            "unread_first" => SubscriptionSort::UnreadFirst,
+            "manual" => SubscriptionSort::Manual,
+            _ => SubscriptionSort::Recent,
+        })
+    }
+
+    /// Gets the `tag_priorities` setting (stored as JSON).
+    pub fn get_tag_priorities(&self) -> Result<Vec<TagPriority>, AppError> {
+        let json = self.get_setting_string("tag_priorities", "[]")?;
+        Ok(serde_json::from_str(&json).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid tag_priorities setting: {e}");
+            Vec::new()
+        }))
+    }
+
+    /// Gets the `mentions` setting (stored as JSON).
+    pub fn get_mentions(&self) -> Result<Vec<String>, AppError> {
+        let json = self.get_setting_string("mentions", "[]")?;
+        Ok(serde_json::from_str(&json).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid mentions setting: {e}");
+            Vec::new()
+        }))
+    }
+
+    /// Gets the keychain entries (username, server URL) of removed servers
+    /// that couldn't be deleted yet (stored as JSON).
+    pub fn get_stale_credentials(&self) -> Result<Vec<(String, String)>, AppError> {
+        let json = self.get_setting_string("stale_credentials", "[]")?;
+        Ok(serde_json::from_str(&json).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid stale_credentials setting: {e}");
+            Vec::new()
+        }))
+    }
+
+    /// Sets the keychain entries of removed servers still to be deleted.
+    pub fn set_stale_credentials(&self, entries: &[(String, String)]) -> Result<(), AppError> {
+        self.set_setting("stale_credentials", &serde_json::to_string(entries)?)
+    }
+
+    /// Gets the `do_not_disturb` setting (stored as JSON).
+    pub fn get_do_not_disturb(&self) -> Result<DoNotDisturb, AppError> {
+        let json = self.get_setting_string("do_not_disturb", "{}")?;
+        Ok(serde_json::from_str(&json).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid do_not_disturb setting: {e}");
+            DoNotDisturb::default()
+        }))
+    }
+
+    /// Gets the `dnd_schedule` setting (stored as JSON).
+    pub fn get_dnd_schedule(&self) -> Result<DndSchedule, AppError> {
+        let json = self.get_setting_string("dnd_schedule", "{}")?;
+        Ok(serde_json::from_str(&json).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid dnd_schedule setting: {e}");
+            DndSchedule::default()
+        }))
+    }
+
+    /// Gets the `watch_folders` setting (stored as JSON).
+    pub fn get_watch_folders(&self) -> Result<Vec<WatchFolder>, AppError> {
+        let json = self.get_setting_string("watch_folders", "[]")?;
+        Ok(serde_json::from_str(&json).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid watch_folders setting: {e}");
+            Vec::new()
+        }))
+    }
+
+    /// Gets the `clipboard_share_subscription_id` setting (unset when empty).
+    pub fn get_clipboard_share_subscription_id(&self) -> Result<Option<String>, AppError> {
+        let id = self.get_setting_string("clipboard_share_subscription_id", "")?;
+        Ok(Some(id).filter(|id| !id.is_empty()))
+    }
+
+    /// Gets the `metrics_endpoint_port` setting (unset when empty).
+    pub fn get_metrics_endpoint_port(&self) -> Result<Option<u16>, AppError> {
+        let port = self.get_setting_string("metrics_endpoint_port", "")?;
+        Ok(port.parse().ok())
+    }
+
+    /// Gets the `receipt_topic` setting (stored as JSON, unset when empty).
+    pub fn get_receipt_topic(&self) -> Result<Option<ReceiptTopic>, AppError> {
+        let json = self.get_setting_string("receipt_topic", "")?;
+        if json.is_empty() {
+            return Ok(None);
+        }
+        Ok(serde_json::from_str(&json).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid receipt_topic setting: {e}");
+            None
+        }))
+    }
+
+    /// Gets the `proxy` setting (stored as JSON, the system proxy when empty).
+    pub fn get_proxy_settings(&self) -> Result<ProxySettings, AppError> {
+        let json = self.get_setting_string("proxy", "")?;
+        if json.is_empty() {
+            return Ok(ProxySettings::default());
+        }
+        Ok(serde_json::from_str(&json).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid proxy setting: {e}");
+            ProxySettings::default()
+        }))
+    }
+
+    /// Gets the `http_timeouts` setting (stored as JSON, the defaults when empty).
+    pub fn get_http_timeouts(&self) -> Result<HttpTimeouts, AppError> {
+        let json = self.get_setting_string("http_timeouts", "")?;
+        if json.is_empty() {
+            return Ok(HttpTimeouts::default());
+        }
+        Ok(serde_json::from_str(&json).unwrap_or_else(|e| {
+            log::warn!("Ignoring invalid http_timeouts setting: {e}");
+            HttpTimeouts::default()
+        }))
+    }
+
+    /// Gets this installation's device identity, generating the ID on first use.
+    pub fn get_device_info(&self) -> Result<DeviceInfo, AppError> {
+        let mut id = self.get_setting_string("device_id", "")?;
+        if id.is_empty() {
+            id = uuid::Uuid::new_v4().to_string();
+            self.set_setting("device_id", &id)?;
+        }
+        let name = Some(self.get_setting_string("device_name", "")?)
+            .filter(|name| !name.is_empty())
+            .unwrap_or_else(host_name);
+        Ok(DeviceInfo { id, name })
+    }
+
+    /// Gets the `tray_icon_style` setting.
+    pub fn get_tray_icon_style(&self) -> Result<TrayIconStyle, AppError> {
+        let style_str = self.get_setting_string("tray_icon_style", "color")?;
+        Ok(match style_str.as_str() {
+            "monochrome_light" => TrayIconStyle::MonochromeLight,
+            "monochrome_dark" => TrayIconStyle::MonochromeDark,
+            "auto" => TrayIconStyle::Auto,
+            _ => TrayIconStyle::Color,
+        })
+    }
+
+    /// Gets the `tray_icon_path` setting (unset when empty).
+    pub fn get_tray_icon_path(&self) -> Result<Option<String>, AppError> {
+        let path = self.get_setting_string("tray_icon_path", "")?;
+        Ok(Some(path).filter(|p| !p.is_empty()))
+    }
+
+    /// Gets the `tray_unread_icon_path` setting (unset when empty).
+    pub fn get_tray_unread_icon_path(&self) -> Result<Option<String>, AppError> {
+        let path = self.get_setting_string("tray_unread_icon_path", "")?;
+        Ok(Some(path).filter(|p| !p.is_empty()))
+    }
+
+    /// Gets the `daily_summary_enabled` setting.
+    pub fn get_daily_summary_enabled(&self) -> Result<bool, AppError> {
+        self.get_setting_bool("daily_summary_enabled", false)
+    }
+
+    /// Gets the `daily_summary_time` setting (`HH:MM`, local time).
+    pub fn get_daily_summary_time(&self) -> Result<String, AppError> {
+        self.get_setting_string("daily_summary_time", DEFAULT_DAILY_SUMMARY_TIME)
+    }
+
+    /// Gets the `link_previews_enabled` setting.
+    pub fn get_link_previews_enabled(&self) -> Result<bool, AppError> {
+        self.get_setting_bool("link_previews_enabled", false)
+    }
+
+    /// Gets the `hide_content_when_locked` setting.
+    pub fn get_hide_content_when_locked(&self) -> Result<bool, AppError> {
+        self.get_setting_bool("hide_content_when_locked", false)
+    }
+
+    /// Gets the `kiosk_mode` setting.
+    pub fn get_kiosk_mode(&self) -> Result<bool, AppError> {
+        self.get_setting_bool("kiosk_mode", false)
+    }
+
+    /// Gets the `screen_reader_announcements` setting.
+    pub fn get_screen_reader_announcements(&self) -> Result<bool, AppError> {
+        self.get_setting_bool("screen_reader_announcements", false)
+    }
+
     /// Gets all application settings.
     pub fn get_settings(&self) -> Result<AppSettings, AppError> {
         let theme_str = self.get_setting_string("theme", "system")?;
@@ -96,6 +355,21 @@ impl Database {
         let notification_show_actions = self.get_setting_bool("notification_show_actions", true)?;
         let notification_show_images = self.get_setting_bool("notification_show_images", true)?;
         let notification_sound = self.get_setting_bool("notification_sound", true)?;
+        let notification_group_by_topic =
+            self.get_setting_bool("notification_group_by_topic", true)?;
+        let min_toast_priority = self.get_min_toast_priority()?;
+        let tag_priorities = self.get_tag_priorities()?;
+        let mentions = self.get_mentions()?;
+        let do_not_disturb = self.get_do_not_disturb()?;
+        let dnd_schedule = self.get_dnd_schedule()?;
+
+        // Tray settings
+        let tray_icon_style = self.get_tray_icon_style()?;
+        let tray_icon_path = self.get_tray_icon_path()?;
+        let tray_unread_icon_path = self.get_tray_unread_icon_path()?;
+
+        // Accessibility settings
+        let screen_reader_announcements = self.get_screen_reader_announcements()?;
 
         // Message display settings
         let compact_view = self.get_setting_bool("compact_view", false)?;
@@ -107,6 +381,39 @@ impl Database {
         // Favorites settings
         let favorites_enabled = self.get_setting_bool("favorites_enabled", false)?;
 
+        // Startup settings
+        let startup_delay_secs = self.get_startup_delay_secs()?;
+        let wait_for_network = self.get_wait_for_network()?;
+
+        // Security settings
+        let credential_lock_minutes = self.get_credential_lock_minutes()?;
+        let app_lock_method = self.get_app_lock_method()?;
+        let app_lock_minutes = self.get_app_lock_minutes()?;
+
+        // Subscription list settings
+        let subscription_sort = self.get_subscription_sort()?;
+
+        // Daily summary settings
+        let daily_summary_enabled = self.get_daily_summary_enabled()?;
+        let daily_summary_time = self.get_daily_summary_time()?;
+
+        // Privacy settings
+        let link_previews_enabled = self.get_link_previews_enabled()?;
+        let hide_content_when_locked = self.get_hide_content_when_locked()?;
+        let log_redaction = self.get_log_redaction()?;
+
+        // Integration settings
+        let watch_folders = self.get_watch_folders()?;
+        let clipboard_share_subscription_id = self.get_clipboard_share_subscription_id()?;
+        let clipboard_share_confirm = self.get_setting_bool("clipboard_share_confirm", true)?;
+        let metrics_endpoint_port = self.get_metrics_endpoint_port()?;
+        let receipt_topic = self.get_receipt_topic()?;
+        let proxy = self.get_proxy_settings()?;
+        let http_timeouts = self.get_http_timeouts()?;
+
+        // Kiosk settings
+        let kiosk_mode = self.get_kiosk_mode()?;
+
         let servers = self.get_servers_with_credentials()?;
         let default_server = self.get_default_server_url()?;
 
@@ -121,10 +428,39 @@ impl Database {
             notification_show_actions,
             notification_show_images,
             notification_sound,
+            notification_group_by_topic,
+            min_toast_priority,
+            tag_priorities,
+            mentions,
+            do_not_disturb,
+            dnd_schedule,
+            tray_icon_style,
+            tray_icon_path,
+            tray_unread_icon_path,
+            screen_reader_announcements,
             compact_view,
             expand_new_messages,
             delete_local_only,
             favorites_enabled,
+            startup_delay_secs,
+            wait_for_network,
+            credential_lock_minutes,
+            app_lock_method,
+            app_lock_minutes,
+            subscription_sort,
+            daily_summary_enabled,
+            daily_summary_time,
+            link_previews_enabled,
+            hide_content_when_locked,
+            log_redaction,
+            watch_folders,
+            clipboard_share_subscription_id,
+            clipboard_share_confirm,
+            metrics_endpoint_port,
+            receipt_topic,
+            proxy,
+            http_timeouts,
+            kiosk_mode,
         })
     }
 
@@ -0,0 +1,51 @@
+//! Global shortcut database queries.
+
+use diesel::prelude::*;
+
+use crate::db::connection::Database;
+use crate::db::models::ShortcutRow;
+use crate::db::schema::shortcuts;
+use crate::error::AppError;
+use crate::models::{ShortcutAction, ShortcutBinding};
+
+impl Database {
+    /// Gets all bound shortcuts.
+    pub fn get_shortcuts(&self) -> Result<Vec<ShortcutBinding>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<ShortcutRow> = shortcuts::table
+            .order(shortcuts::action.asc())
+            .load(&mut *conn)?;
+
+        Ok(rows
+            .into_iter()
+            .filter_map(ShortcutRow::into_binding)
+            .collect())
+    }
+
+    /// Binds an action to an accelerator, or unbinds it when `accelerator` is `None`.
+    pub fn set_shortcut(
+        &self,
+        action: ShortcutAction,
+        accelerator: Option<&str>,
+    ) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        match accelerator {
+            Some(accelerator) => {
+                diesel::replace_into(shortcuts::table)
+                    .values(&ShortcutRow {
+                        action: action.as_str().to_string(),
+                        accelerator: accelerator.to_string(),
+                    })
+                    .execute(&mut *conn)?;
+            }
+            None => {
+                diesel::delete(shortcuts::table.filter(shortcuts::action.eq(action.as_str())))
+                    .execute(&mut *conn)?;
+            }
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+//! Aggregate notification statistics queries.
+
+use diesel::prelude::*;
+use diesel::sql_query;
+
+use crate::db::connection::Database;
+use crate::db::models::{BucketCountRow, TopicActivityRow};
+use crate::error::AppError;
+use crate::models::{Priority, PriorityCount, TopicActivity};
+
+impl Database {
+    /// Gets per-subscription notification counts since `since` (milliseconds),
+    /// busiest subscriptions first. Subscriptions without activity are omitted.
+    pub fn get_topic_activity(&self, since: i64) -> Result<Vec<TopicActivity>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<TopicActivityRow> = sql_query(
+            "SELECT s.id as subscription_id, COALESCE(s.display_name, s.topic) as name, \
+                    COUNT(*) as received, \
+                    SUM(CASE WHEN n.read = 0 THEN 1 ELSE 0 END) as unread \
+             FROM notifications n \
+             JOIN subscriptions s ON n.subscription_id = s.id \
+             WHERE n.deleted_at IS NULL AND s.deleted_at IS NULL AND n.timestamp >= ? \
+             GROUP BY s.id \
+             ORDER BY received DESC, name COLLATE NOCASE ASC",
+        )
+        .bind::<diesel::sql_types::BigInt, _>(since)
+        .load(&mut *conn)?;
+
+        Ok(rows.into_iter().map(TopicActivity::from).collect())
+    }
+
+    /// Gets the number of notifications received since `since` per local hour of day.
+    ///
+    /// Always returns 24 entries, indexed by hour.
+    pub fn get_hourly_counts(&self, since: i64) -> Result<Vec<i32>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<BucketCountRow> = sql_query(
+            "SELECT CAST(strftime('%H', n.timestamp / 1000, 'unixepoch', 'localtime') AS INTEGER) as bucket, \
+                    COUNT(*) as count \
+             FROM notifications n \
+             JOIN subscriptions s ON n.subscription_id = s.id \
+             WHERE n.deleted_at IS NULL AND s.deleted_at IS NULL AND n.timestamp >= ? \
+             GROUP BY bucket",
+        )
+        .bind::<diesel::sql_types::BigInt, _>(since)
+        .load(&mut *conn)?;
+
+        let mut hourly = vec![0; 24];
+        for row in rows {
+            if let Some(slot) = usize::try_from(row.bucket)
+                .ok()
+                .and_then(|h| hourly.get_mut(h))
+            {
+                *slot = row.count as i32;
+            }
+        }
+        Ok(hourly)
+    }
+
+    /// Gets the number of notifications received since `since` per priority level,
+    /// from min to max. Levels without notifications are omitted.
+    pub fn get_priority_counts(&self, since: i64) -> Result<Vec<PriorityCount>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<BucketCountRow> = sql_query(
+            "SELECT n.priority as bucket, COUNT(*) as count \
+             FROM notifications n \
+             JOIN subscriptions s ON n.subscription_id = s.id \
+             WHERE n.deleted_at IS NULL AND s.deleted_at IS NULL AND n.timestamp >= ? \
+             GROUP BY n.priority \
+             ORDER BY n.priority ASC",
+        )
+        .bind::<diesel::sql_types::BigInt, _>(since)
+        .load(&mut *conn)?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| PriorityCount {
+                priority: Priority::from(row.bucket as i8) as u8,
+                count: row.count as i32,
+            })
+            .collect())
+    }
+}
@@ -0,0 +1,81 @@
+//! Subscription settings database queries.
+
+use diesel::prelude::*;
+use diesel::Connection;
+
+use crate::db::connection::Database;
+use crate::db::models::SubscriptionSettingsRow;
+use crate::db::schema::{subscription_settings, subscriptions};
+use crate::error::AppError;
+use crate::models::SubscriptionSettings;
+
+impl Database {
+    /// Gets the notification settings a subscription overrides.
+    pub fn get_subscription_settings(
+        &self,
+        subscription_id: &str,
+    ) -> Result<SubscriptionSettings, AppError> {
+        let mut conn = self.conn()?;
+
+        let min_toast_priority: Option<i32> = subscriptions::table
+            .filter(subscriptions::id.eq(subscription_id))
+            .select(subscriptions::min_toast_priority)
+            .first(&mut *conn)
+            .optional()?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Subscription {subscription_id} not found"))
+            })?;
+        let row: Option<SubscriptionSettingsRow> = subscription_settings::table
+            .find(subscription_id)
+            .first(&mut *conn)
+            .optional()?;
+
+        let row = row.unwrap_or_else(|| SubscriptionSettingsRow {
+            subscription_id: subscription_id.to_string(),
+            sound: None,
+            display_method: None,
+            auto_mark_read: 0,
+            maintenance_windows: "[]".to_string(),
+        });
+        Ok(row.into_settings(min_toast_priority))
+    }
+
+    /// Sets the notification settings a subscription overrides. The minimum
+    /// priority is stored as the subscription's `min_toast_priority`.
+    pub fn set_subscription_settings(
+        &self,
+        subscription_id: &str,
+        settings: &SubscriptionSettings,
+    ) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        let row = SubscriptionSettingsRow {
+            subscription_id: subscription_id.to_string(),
+            sound: settings.sound.map(|s| s.as_str().to_string()),
+            display_method: settings.display_method.map(|m| m.as_str().to_string()),
+            auto_mark_read: i32::from(settings.auto_mark_read),
+            maintenance_windows: serde_json::to_string(&settings.maintenance_windows)?,
+        };
+        let updated =
+            conn.transaction::<_, diesel::result::Error, _>(|conn| {
+                let updated = diesel::update(
+                    subscriptions::table.filter(subscriptions::id.eq(subscription_id)),
+                )
+                .set(subscriptions::min_toast_priority.eq(settings.min_priority.map(|p| p as i32)))
+                .execute(conn)?;
+                if updated > 0 {
+                    diesel::replace_into(subscription_settings::table)
+                        .values(&row)
+                        .execute(conn)?;
+                }
+                Ok(updated)
+            })?;
+        if updated == 0 {
+            return Err(AppError::NotFound(format!(
+                "Subscription {subscription_id} not found"
+            )));
+        }
+
+        Ok(())
+    }
+}
@@ -4,26 +4,50 @@ use diesel::prelude::*;
 use diesel::sql_query;
 use diesel::Connection;
 
+use crate::config::local_topics::SERVER_URL as LOCAL_SERVER_URL;
 use crate::db::connection::Database;
 use crate::db::models::{NewServer, NewSubscription, SubscriptionQueryRow};
 use crate::db::schema::{servers, subscriptions};
 use crate::error::AppError;
-use crate::models::{CreateSubscription, Subscription};
+use crate::models::{
+    CreateLocalSubscription, CreateSubscription, FileMirror, MirrorFormat, Priority,
+    RetentionPolicy, Subscription, SubscriptionSort,
+};
 
 /// Base SELECT/FROM/JOIN shared by all subscription queries.
 const SUBSCRIPTION_BASE_QUERY: &str = "\
-    SELECT s.id, s.topic, srv.url as server_url, s.display_name, s.muted, s.last_sync, \
-           (SELECT MAX(n.timestamp) FROM notifications n WHERE n.subscription_id = s.id) as last_notif, \
-           (SELECT COUNT(*) FROM notifications n WHERE n.subscription_id = s.id AND n.read = 0) as unread \
+    SELECT s.id, s.topic, srv.url as server_url, srv.detached as server_detached, s.display_name, s.muted, s.trusted, s.pattern_id, s.auto_delete_after_hours, s.keep_messages, s.local, s.min_toast_priority, s.hide_content, s.exclude_from_sync, s.sync_window_hours, s.pinned, s.mirror_path, s.mirror_format, s.last_sync, s.deleted_at, \
+           (SELECT MAX(n.timestamp) FROM notifications n \
+               WHERE n.subscription_id = s.id AND n.deleted_at IS NULL) as last_notif, \
+           (SELECT COUNT(*) FROM notifications n \
+               WHERE n.subscription_id = s.id AND n.deleted_at IS NULL AND n.read = 0) as unread, \
+           (SELECT COUNT(*) FROM notifications n \
+               WHERE n.subscription_id = s.id AND n.deleted_at IS NULL) as total, \
+           (SELECT COUNT(*) FROM notifications n \
+               WHERE n.subscription_id = s.id AND n.deleted_at IS NULL \
+               AND n.timestamp >= (CAST(strftime('%s', 'now') AS INTEGER) - 86400) * 1000) as recent, \
+           (SELECT MAX(n.priority) FROM notifications n \
+               WHERE n.subscription_id = s.id AND n.deleted_at IS NULL AND n.read = 0) as max_unread_priority \
     FROM subscriptions s \
     JOIN servers srv ON s.server_id = srv.id";
 
 impl Database {
-    /// Returns all subscriptions ordered by most recent notification.
+    /// Returns all subscriptions not in the trash, pinned ones first, then ordered
+    /// by the `subscription_sort` setting.
     pub fn get_all_subscriptions(&self) -> Result<Vec<Subscription>, AppError> {
+        let order_by = match self.get_subscription_sort()? {
+            SubscriptionSort::Recent => "last_notif DESC NULLS LAST",
+            SubscriptionSort::Alphabetical => {
+                "COALESCE(s.display_name, s.topic) COLLATE NOCASE ASC"
+            }
+            SubscriptionSort::UnreadFirst => "unread > 0 DESC, last_notif DESC NULLS LAST",
+            SubscriptionSort::Manual => "s.sort_order ASC",
+        };
+
         let mut conn = self.conn()?;
 
-        let query = format!("{SUBSCRIPTION_BASE_QUERY} ORDER BY last_notif DESC NULLS LAST");
+        let query =
+            format!("{SUBSCRIPTION_BASE_QUERY} WHERE s.deleted_at IS NULL ORDER BY s.pinned DESC, {order_by}");
         let rows: Vec<SubscriptionQueryRow> = sql_query(query).load(&mut *conn)?;
 
         Ok(rows.into_iter().map(Subscription::from).collect())
@@ -47,6 +71,18 @@ impl Database {
         }))
     }
 
+    /// Returns `(subscription_id, last_sync)` pairs for all subscriptions.
+    pub fn get_last_sync_times(&self) -> Result<Vec<(String, Option<i64>)>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<(String, Option<i64>)> = subscriptions::table
+            .filter(subscriptions::deleted_at.is_null())
+            .select((subscriptions::id, subscriptions::last_sync))
+            .load(&mut *conn)?;
+
+        Ok(rows)
+    }
+
     /// Updates the last sync timestamp for a subscription.
     pub fn update_subscription_last_sync(&self, id: &str, timestamp: i64) -> Result<(), AppError> {
         let mut conn = self.conn()?;
@@ -65,9 +101,104 @@ impl Database {
 
         let (id, server_url, topic, display_name) = conn
             .transaction::<_, diesel::result::Error, _>(|conn| {
-                // Get or create server
+                let server_id = get_or_create_server(conn, &sub.server_url)?;
+                let id = insert_subscription(
+                    conn,
+                    &server_id,
+                    &sub.topic,
+                    sub.display_name.as_deref(),
+                    false,
+                )?;
+
+                Ok((id, sub.server_url, sub.topic, sub.display_name))
+            })?;
+
+        Ok(Subscription {
+            id,
+            topic,
+            server_url,
+            display_name,
+            unread_count: 0,
+            total_count: 0,
+            recent_count: 0,
+            max_unread_priority: None,
+            last_notification: None,
+            muted: false,
+            deleted_at: None,
+            detached: false,
+            trusted: false,
+            pattern_id: None,
+            auto_delete_after_hours: None,
+            keep_messages: None,
+            local: false,
+            min_toast_priority: None,
+            hide_content: false,
+            exclude_from_sync: false,
+            sync_window_hours: None,
+            pinned: false,
+            mirror: None,
+        })
+    }
+
+    /// Creates several subscriptions in one transaction.
+    ///
+    /// Topics that are already subscribed are left alone. Returns the ID of
+    /// each created subscription, or `None` for those already subscribed, in
+    /// the order given.
+    pub fn create_subscriptions(
+        &self,
+        subs: &[CreateSubscription],
+    ) -> Result<Vec<Option<String>>, AppError> {
+        for sub in subs {
+            sub.validate()?;
+        }
+        let mut conn = self.conn()?;
+
+        let ids = conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            let mut ids = Vec::with_capacity(subs.len());
+            for sub in subs {
+                let server_id = get_or_create_server(conn, &sub.server_url)?;
+                let exists: i64 = subscriptions::table
+                    .filter(subscriptions::server_id.eq(&server_id))
+                    .filter(subscriptions::topic.eq(&sub.topic))
+                    .filter(subscriptions::deleted_at.is_null())
+                    .count()
+                    .get_result(conn)?;
+                if exists > 0 {
+                    ids.push(None);
+                    continue;
+                }
+
+                ids.push(Some(insert_subscription(
+                    conn,
+                    &server_id,
+                    &sub.topic,
+                    sub.display_name.as_deref(),
+                    false,
+                )?));
+            }
+            Ok(ids)
+        })?;
+
+        Ok(ids)
+    }
+
+    /// Creates a local test subscription that never touches the network.
+    ///
+    /// Local subscriptions share a placeholder server that is stored detached,
+    /// so it never shows up among the configured servers.
+    pub fn create_local_subscription(
+        &self,
+        sub: CreateLocalSubscription,
+    ) -> Result<Subscription, AppError> {
+        sub.validate()?;
+
+        let id = {
+            let mut conn = self.conn()?;
+
+            conn.transaction::<_, diesel::result::Error, _>(|conn| {
                 let server_id: String = if let Some(id) = servers::table
-                    .filter(servers::url.eq(&sub.server_url))
+                    .filter(servers::url.eq(LOCAL_SERVER_URL))
                     .select(servers::id)
                     .first(conn)
                     .optional()?
@@ -75,57 +206,90 @@ impl Database {
                     id
                 } else {
                     let new_id = uuid::Uuid::new_v4().to_string();
-                    let new_server = NewServer {
-                        id: &new_id,
-                        url: &sub.server_url,
-                        username: None,
-                        is_default: 0,
-                    };
-
                     diesel::insert_into(servers::table)
-                        .values(&new_server)
+                        .values(&NewServer {
+                            id: &new_id,
+                            url: LOCAL_SERVER_URL,
+                            username: None,
+                            is_default: 0,
+                        })
+                        .execute(conn)?;
+                    diesel::update(servers::table.filter(servers::id.eq(&new_id)))
+                        .set(servers::detached.eq(1))
                         .execute(conn)?;
-
                     new_id
                 };
 
-                let id = uuid::Uuid::new_v4().to_string();
-                let display_name_ref = sub.display_name.as_deref().filter(|s| !s.is_empty());
+                insert_subscription(
+                    conn,
+                    &server_id,
+                    &sub.topic,
+                    sub.display_name.as_deref(),
+                    true,
+                )
+            })?
+        };
+
+        self.get_subscription_by_id(&id)?
+            .ok_or_else(|| AppError::NotFound(format!("Subscription {id} not found")))
+    }
 
-                let new_subscription = NewSubscription {
-                    id: &id,
-                    server_id: &server_id,
-                    topic: &sub.topic,
-                    display_name: display_name_ref,
-                    muted: 0,
-                };
+    /// Moves a subscription to the trash. Its notifications are kept until it is purged.
+    pub fn soft_delete_subscription(&self, id: &str) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
 
-                diesel::insert_into(subscriptions::table)
-                    .values(&new_subscription)
-                    .execute(conn)?;
+        diesel::update(subscriptions::table.filter(subscriptions::id.eq(id)))
+            .set(subscriptions::deleted_at.eq(chrono::Utc::now().timestamp_millis()))
+            .execute(&mut *conn)?;
 
-                Ok((id, sub.server_url, sub.topic, sub.display_name))
-            })?;
+        Ok(())
+    }
 
-        Ok(Subscription {
-            id,
-            topic,
-            server_url,
-            display_name,
-            unread_count: 0,
-            last_notification: None,
-            muted: false,
-        })
+    /// Restores a subscription from the trash.
+    pub fn restore_subscription(&self, id: &str) -> Result<Subscription, AppError> {
+        {
+            let mut conn = self.conn()?;
+
+            diesel::update(subscriptions::table.filter(subscriptions::id.eq(id)))
+                .set(subscriptions::deleted_at.eq(None::<i64>))
+                .execute(&mut *conn)?;
+        }
+
+        self.get_subscription_by_id(id)?
+            .ok_or_else(|| AppError::NotFound(format!("Subscription {id} not found")))
     }
 
-    /// Deletes a subscription and all its notifications (via ON DELETE CASCADE).
-    pub fn delete_subscription(&self, id: &str) -> Result<(), AppError> {
+    /// Returns all subscriptions in the trash, most recently deleted first.
+    pub fn get_deleted_subscriptions(&self) -> Result<Vec<Subscription>, AppError> {
         let mut conn = self.conn()?;
 
-        diesel::delete(subscriptions::table.filter(subscriptions::id.eq(id)))
-            .execute(&mut *conn)?;
+        let query = format!(
+            "{SUBSCRIPTION_BASE_QUERY} WHERE s.deleted_at IS NOT NULL ORDER BY s.deleted_at DESC"
+        );
+        let rows: Vec<SubscriptionQueryRow> = sql_query(query).load(&mut *conn)?;
 
-        Ok(())
+        Ok(rows.into_iter().map(Subscription::from).collect())
+    }
+
+    /// Permanently deletes trashed subscriptions and their notifications
+    /// (via ON DELETE CASCADE).
+    ///
+    /// If `deleted_before` is set, only subscriptions trashed before that
+    /// timestamp (milliseconds) are purged. Returns the number purged.
+    pub fn purge_deleted_subscriptions(
+        &self,
+        deleted_before: Option<i64>,
+    ) -> Result<usize, AppError> {
+        let mut conn = self.conn()?;
+
+        let mut query = diesel::delete(subscriptions::table)
+            .filter(subscriptions::deleted_at.is_not_null())
+            .into_boxed();
+        if let Some(cutoff) = deleted_before {
+            query = query.filter(subscriptions::deleted_at.lt(cutoff));
+        }
+
+        Ok(query.execute(&mut *conn)?)
     }
 
     /// Toggles the mute state of a subscription.
@@ -155,6 +319,193 @@ impl Database {
             .ok_or_else(|| AppError::NotFound(format!("Subscription {id} not found")))
     }
 
+    /// Sets whether actions from a subscription may run without prompting.
+    pub fn set_subscription_trusted(&self, id: &str, trusted: bool) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::update(subscriptions::table.filter(subscriptions::id.eq(id)))
+            .set(subscriptions::trusted.eq(i32::from(trusted)))
+            .execute(&mut *conn)?;
+
+        Ok(())
+    }
+
+    /// Sets whether toasts of a subscription hide the message content.
+    pub fn set_subscription_hide_content(&self, id: &str, hidden: bool) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::update(subscriptions::table.filter(subscriptions::id.eq(id)))
+            .set(subscriptions::hide_content.eq(i32::from(hidden)))
+            .execute(&mut *conn)?;
+
+        Ok(())
+    }
+
+    /// Sets whether a subscription is pinned.
+    pub fn set_subscription_pinned(&self, id: &str, pinned: bool) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::update(subscriptions::table.filter(subscriptions::id.eq(id)))
+            .set(subscriptions::pinned.eq(i32::from(pinned)))
+            .execute(&mut *conn)?;
+
+        Ok(())
+    }
+
+    /// Sets the file received notifications of a subscription are appended to.
+    /// `None` stops mirroring.
+    pub fn set_subscription_mirror(
+        &self,
+        id: &str,
+        mirror: Option<&FileMirror>,
+    ) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::update(subscriptions::table.filter(subscriptions::id.eq(id)))
+            .set((
+                subscriptions::mirror_path.eq(mirror.map(|m| m.path.as_str())),
+                subscriptions::mirror_format
+                    .eq(mirror.map_or(MirrorFormat::Jsonl, |m| m.format).as_str()),
+            ))
+            .execute(&mut *conn)?;
+
+        Ok(())
+    }
+
+    /// Gets the topic and file mirror of a subscription, if it has a mirror.
+    pub fn get_subscription_mirror(
+        &self,
+        id: &str,
+    ) -> Result<Option<(String, FileMirror)>, AppError> {
+        let mut conn = self.conn()?;
+
+        let row: Option<(String, Option<String>, String)> = subscriptions::table
+            .filter(subscriptions::id.eq(id))
+            .select((
+                subscriptions::topic,
+                subscriptions::mirror_path,
+                subscriptions::mirror_format,
+            ))
+            .first(&mut *conn)
+            .optional()?;
+
+        Ok(row.and_then(|(topic, path, format)| {
+            let mirror = FileMirror {
+                path: path?,
+                format: MirrorFormat::parse(&format),
+            };
+            Some((topic, mirror))
+        }))
+    }
+
+    /// Sets whether a subscription is skipped by notification history sync.
+    pub fn set_subscription_exclude_from_sync(
+        &self,
+        id: &str,
+        excluded: bool,
+    ) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::update(subscriptions::table.filter(subscriptions::id.eq(id)))
+            .set(subscriptions::exclude_from_sync.eq(i32::from(excluded)))
+            .execute(&mut *conn)?;
+
+        Ok(())
+    }
+
+    /// Sets how many hours back history sync fetches a subscription's
+    /// messages (`None` fetches everything since the last sync).
+    pub fn set_subscription_sync_window(
+        &self,
+        id: &str,
+        hours: Option<u32>,
+    ) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::update(subscriptions::table.filter(subscriptions::id.eq(id)))
+            .set(subscriptions::sync_window_hours.eq(hours.map(|h| h as i32)))
+            .execute(&mut *conn)?;
+
+        Ok(())
+    }
+
+    /// Sets how many hours a subscription's notifications are kept (`None` keeps them).
+    pub fn set_subscription_auto_delete(
+        &self,
+        id: &str,
+        hours: Option<u32>,
+    ) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::update(subscriptions::table.filter(subscriptions::id.eq(id)))
+            .set(subscriptions::auto_delete_after_hours.eq(hours.map(|h| h as i32)))
+            .execute(&mut *conn)?;
+
+        Ok(())
+    }
+
+    /// Sets how long a subscription's notifications are kept.
+    pub fn set_subscription_retention(
+        &self,
+        id: &str,
+        policy: RetentionPolicy,
+    ) -> Result<(), AppError> {
+        let (hours, count) = policy.limits()?;
+        let mut conn = self.conn()?;
+
+        diesel::update(subscriptions::table.filter(subscriptions::id.eq(id)))
+            .set((
+                subscriptions::auto_delete_after_hours.eq(hours.map(|h| h as i32)),
+                subscriptions::keep_messages.eq(count.map(|n| n as i32)),
+            ))
+            .execute(&mut *conn)?;
+
+        Ok(())
+    }
+
+    /// Sets the minimum priority shown as a toast for a subscription,
+    /// overriding the global `min_toast_priority`. `None` uses the global floor.
+    pub fn set_subscription_min_toast_priority(
+        &self,
+        id: &str,
+        priority: Option<Priority>,
+    ) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::update(subscriptions::table.filter(subscriptions::id.eq(id)))
+            .set(subscriptions::min_toast_priority.eq(priority.map(|p| p as i32)))
+            .execute(&mut *conn)?;
+
+        Ok(())
+    }
+
+    /// Sets the manual order of subscriptions to the order of the given IDs.
+    pub fn reorder_subscriptions(&self, ids: &[String]) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        conn.transaction::<_, diesel::result::Error, _>(|conn| {
+            for (position, id) in (0_i32..).zip(ids) {
+                diesel::update(subscriptions::table.filter(subscriptions::id.eq(id)))
+                    .set(subscriptions::sort_order.eq(position))
+                    .execute(conn)?;
+            }
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
+    /// Groups a subscription under the pattern subscription that created it.
+    pub fn set_subscription_pattern(&self, id: &str, pattern_id: &str) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::update(subscriptions::table.filter(subscriptions::id.eq(id)))
+            .set(subscriptions::pattern_id.eq(pattern_id))
+            .execute(&mut *conn)?;
+
+        Ok(())
+    }
+
     /// Gets a subscription by ID.
     #[allow(dead_code)]
     pub fn get_subscription_by_id(&self, id: &str) -> Result<Option<Subscription>, AppError> {
@@ -168,3 +519,73 @@ impl Database {
         Ok(rows.into_iter().next().map(Subscription::from))
     }
 }
+
+/// Returns the ID of the server with the given URL, adding it if needed.
+///
+/// Subscribing again re-attaches a server removed with history kept.
+fn get_or_create_server(conn: &mut SqliteConnection, url: &str) -> QueryResult<String> {
+    if let Some(id) = servers::table
+        .filter(servers::url.eq(url))
+        .select(servers::id)
+        .first::<String>(conn)
+        .optional()?
+    {
+        diesel::update(servers::table.filter(servers::id.eq(&id)))
+            .set(servers::detached.eq(0))
+            .execute(conn)?;
+        return Ok(id);
+    }
+
+    let new_id = uuid::Uuid::new_v4().to_string();
+    diesel::insert_into(servers::table)
+        .values(&NewServer {
+            id: &new_id,
+            url,
+            username: None,
+            is_default: 0,
+        })
+        .execute(conn)?;
+    Ok(new_id)
+}
+
+/// Inserts a subscription at the end of the manual order, returning its ID.
+///
+/// A trashed subscription for the same topic would violate the unique
+/// constraint, so it is dropped for good first.
+fn insert_subscription(
+    conn: &mut SqliteConnection,
+    server_id: &str,
+    topic: &str,
+    display_name: Option<&str>,
+    local: bool,
+) -> QueryResult<String> {
+    diesel::delete(
+        subscriptions::table
+            .filter(subscriptions::server_id.eq(server_id))
+            .filter(subscriptions::topic.eq(topic))
+            .filter(subscriptions::deleted_at.is_not_null()),
+    )
+    .execute(conn)?;
+
+    let id = uuid::Uuid::new_v4().to_string();
+
+    let max_sort_order: Option<i32> = subscriptions::table
+        .select(diesel::dsl::max(subscriptions::sort_order))
+        .first(conn)?;
+
+    let new_subscription = NewSubscription {
+        id: &id,
+        server_id,
+        topic,
+        display_name: display_name.filter(|s| !s.is_empty()),
+        muted: 0,
+        sort_order: max_sort_order.map_or(0, |o| o + 1),
+        local: i32::from(local),
+    };
+
+    diesel::insert_into(subscriptions::table)
+        .values(&new_subscription)
+        .execute(conn)?;
+
+    Ok(id)
+}
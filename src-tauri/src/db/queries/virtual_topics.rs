@@ -0,0 +1,195 @@
+//! Virtual topic database queries.
+
+use diesel::prelude::*;
+
+use crate::db::connection::Database;
+use crate::db::models::{NotificationRow, VirtualTopicRow};
+use crate::db::schema::{notifications, subscriptions, virtual_topics};
+use crate::error::AppError;
+use crate::models::{Notification, VirtualTopic, VirtualTopicInput};
+
+impl Database {
+    /// Gets all virtual topics with their unread counts, ordered by name.
+    pub fn get_virtual_topics(&self) -> Result<Vec<VirtualTopic>, AppError> {
+        self.load_virtual_topics()?
+            .into_iter()
+            .map(|topic| self.with_unread_count(topic))
+            .collect()
+    }
+
+    /// Gets the virtual topics including a notification, without unread counts.
+    pub fn get_virtual_topics_for(
+        &self,
+        notification: &Notification,
+    ) -> Result<Vec<VirtualTopic>, AppError> {
+        Ok(self
+            .load_virtual_topics()?
+            .into_iter()
+            .filter(|topic| topic.matches(notification))
+            .collect())
+    }
+
+    /// Gets the notifications included in a virtual topic, newest first.
+    pub fn get_virtual_topic_notifications(&self, id: &str) -> Result<Vec<Notification>, AppError> {
+        let topic = self.get_virtual_topic(id)?;
+        self.get_virtual_topic_notifications_where(&topic, false)
+    }
+
+    /// Marks all notifications included in a virtual topic as read.
+    pub fn mark_virtual_topic_read(&self, id: &str) -> Result<(), AppError> {
+        let topic = self.get_virtual_topic(id)?;
+        let ids: Vec<String> = self
+            .get_virtual_topic_notifications_where(&topic, true)?
+            .into_iter()
+            .map(|n| n.id)
+            .collect();
+
+        let mut conn = self.conn()?;
+        diesel::update(notifications::table.filter(notifications::id.eq_any(&ids)))
+            .set(notifications::read.eq(1))
+            .execute(&mut *conn)?;
+
+        Ok(())
+    }
+
+    /// Creates a virtual topic.
+    pub fn create_virtual_topic(&self, input: VirtualTopicInput) -> Result<VirtualTopic, AppError> {
+        input.validate()?;
+        let row = Self::virtual_topic_row(
+            uuid::Uuid::new_v4().to_string(),
+            input,
+            chrono::Utc::now().timestamp_millis(),
+        )?;
+
+        let mut conn = self.conn()?;
+        diesel::insert_into(virtual_topics::table)
+            .values(&row)
+            .execute(&mut *conn)?;
+        drop(conn);
+
+        self.with_unread_count(row.into())
+    }
+
+    /// Updates a virtual topic's subscriptions, filter and display settings.
+    pub fn update_virtual_topic(
+        &self,
+        id: &str,
+        input: VirtualTopicInput,
+    ) -> Result<VirtualTopic, AppError> {
+        input.validate()?;
+        let existing = self.get_virtual_topic(id)?;
+        let row = Self::virtual_topic_row(id.to_string(), input, existing.created_at)?;
+
+        let mut conn = self.conn()?;
+        diesel::update(virtual_topics::table.filter(virtual_topics::id.eq(id)))
+            .set((
+                virtual_topics::name.eq(&row.name),
+                virtual_topics::subscription_ids.eq(&row.subscription_ids),
+                virtual_topics::tags.eq(&row.tags),
+                virtual_topics::min_priority.eq(row.min_priority),
+                virtual_topics::muted.eq(row.muted),
+                virtual_topics::sound.eq(&row.sound),
+            ))
+            .execute(&mut *conn)?;
+        drop(conn);
+
+        self.with_unread_count(row.into())
+    }
+
+    /// Deletes a virtual topic. Its notifications are kept.
+    pub fn delete_virtual_topic(&self, id: &str) -> Result<(), AppError> {
+        let mut conn = self.conn()?;
+
+        diesel::delete(virtual_topics::table.filter(virtual_topics::id.eq(id)))
+            .execute(&mut *conn)?;
+
+        Ok(())
+    }
+
+    fn get_virtual_topic(&self, id: &str) -> Result<VirtualTopic, AppError> {
+        let mut conn = self.conn()?;
+
+        let row: Option<VirtualTopicRow> = virtual_topics::table
+            .filter(virtual_topics::id.eq(id))
+            .first(&mut *conn)
+            .optional()?;
+
+        row.map(VirtualTopic::from)
+            .ok_or_else(|| AppError::NotFound(format!("Virtual topic {id} not found")))
+    }
+
+    fn load_virtual_topics(&self) -> Result<Vec<VirtualTopic>, AppError> {
+        let mut conn = self.conn()?;
+
+        let rows: Vec<VirtualTopicRow> = virtual_topics::table
+            .order(virtual_topics::name.asc())
+            .load(&mut *conn)?;
+
+        Ok(rows.into_iter().map(VirtualTopic::from).collect())
+    }
+
+    fn with_unread_count(&self, mut topic: VirtualTopic) -> Result<VirtualTopic, AppError> {
+        let unread = self.get_virtual_topic_notifications_where(&topic, true)?;
+        topic.unread_count = i32::try_from(unread.len()).unwrap_or(i32::MAX);
+        Ok(topic)
+    }
+
+    /// Gets the notifications included in a virtual topic, only unread ones
+    /// if `unread_only`. Trashed notifications and subscriptions are left out.
+    fn get_virtual_topic_notifications_where(
+        &self,
+        topic: &VirtualTopic,
+        unread_only: bool,
+    ) -> Result<Vec<Notification>, AppError> {
+        let mut conn = self.conn()?;
+
+        let mut query = notifications::table
+            .inner_join(subscriptions::table)
+            .filter(notifications::deleted_at.is_null())
+            .filter(subscriptions::deleted_at.is_null())
+            .select(NotificationRow::as_select())
+            .order(notifications::timestamp.desc())
+            .into_boxed();
+        if !topic.subscription_ids.is_empty() {
+            query = query.filter(notifications::subscription_id.eq_any(&topic.subscription_ids));
+        }
+        if let Some(priority) = topic.min_priority {
+            query = query.filter(notifications::priority.ge(priority as i32));
+        }
+        if unread_only {
+            query = query.filter(notifications::read.eq(0));
+        }
+        let rows: Vec<NotificationRow> = query.load(&mut *conn)?;
+
+        // Tags are stored as JSON, so they're matched here
+        Ok(rows
+            .into_iter()
+            .map(NotificationRow::into_notification)
+            .filter(|n| topic.matches(n))
+            .collect())
+    }
+
+    fn virtual_topic_row(
+        id: String,
+        input: VirtualTopicInput,
+        created_at: i64,
+    ) -> Result<VirtualTopicRow, AppError> {
+        let mut subscription_ids = input.subscription_ids;
+        subscription_ids.sort();
+        subscription_ids.dedup();
+        let mut tags: Vec<String> = input.tags.iter().map(|t| t.trim().to_string()).collect();
+        tags.sort();
+        tags.dedup();
+
+        Ok(VirtualTopicRow {
+            id,
+            name: input.name.trim().to_string(),
+            subscription_ids: serde_json::to_string(&subscription_ids)?,
+            tags: serde_json::to_string(&tags)?,
+            min_priority: input.min_priority.map(|p| p as i32),
+            muted: i32::from(input.muted),
+            sound: input.sound.map(|s| s.as_str().to_string()),
+            created_at,
+        })
+    }
+}
@@ -6,6 +6,13 @@ diesel::table! {
         url -> Text,
         username -> Nullable<Text>,
         is_default -> Integer,
+        detached -> Integer,
+        default_priority -> Nullable<Integer>,
+        default_tags -> Text,
+        default_markdown -> Integer,
+        default_delay -> Nullable<Text>,
+        resolution -> Text,
+        client_certificate -> Nullable<Text>,
     }
 }
 
@@ -17,6 +24,38 @@ diesel::table! {
         display_name -> Nullable<Text>,
         muted -> Integer,
         last_sync -> Nullable<BigInt>,
+        deleted_at -> Nullable<BigInt>,
+        trusted -> Integer,
+        pattern_id -> Nullable<Text>,
+        sort_order -> Integer,
+        auto_delete_after_hours -> Nullable<Integer>,
+        local -> Integer,
+        min_toast_priority -> Nullable<Integer>,
+        hide_content -> Integer,
+        exclude_from_sync -> Integer,
+        pinned -> Integer,
+        mirror_path -> Nullable<Text>,
+        mirror_format -> Text,
+        keep_messages -> Nullable<Integer>,
+        sync_window_hours -> Nullable<Integer>,
+    }
+}
+
+diesel::table! {
+    channels (id) {
+        id -> Text,
+        name -> Text,
+        muted -> Integer,
+        sound -> Nullable<Integer>,
+        priority -> Nullable<Integer>,
+        created_at -> BigInt,
+    }
+}
+
+diesel::table! {
+    channel_tags (tag) {
+        tag -> Text,
+        channel_id -> Text,
     }
 }
 
@@ -35,6 +74,93 @@ diesel::table! {
         attachments -> Text,
         is_expanded -> Integer,
         is_favorite -> Integer,
+        deleted_at -> Nullable<BigInt>,
+        parsed -> Nullable<Text>,
+        link_preview -> Nullable<Text>,
+        acknowledged_by -> Nullable<Text>,
+        acknowledged_at -> Nullable<BigInt>,
+        note -> Nullable<Text>,
+        content_kind -> Text,
+        snoozed_until -> Nullable<BigInt>,
+        mentioned -> Integer,
+    }
+}
+
+diesel::table! {
+    connection_log (id) {
+        id -> Text,
+        subscription_id -> Text,
+        kind -> Text,
+        message -> Text,
+        created_at -> BigInt,
+    }
+}
+
+diesel::table! {
+    subscription_settings (subscription_id) {
+        subscription_id -> Text,
+        sound -> Nullable<Text>,
+        display_method -> Nullable<Text>,
+        auto_mark_read -> Integer,
+        maintenance_windows -> Text,
+    }
+}
+
+diesel::table! {
+    outbox (id) {
+        id -> Text,
+        subscription_id -> Text,
+        message -> Text,
+        attempts -> Integer,
+        created_at -> BigInt,
+    }
+}
+
+diesel::table! {
+    permissions (id) {
+        id -> Text,
+        subscription_id -> Text,
+        capability -> Text,
+        allowed -> Integer,
+        created_at -> BigInt,
+    }
+}
+
+diesel::table! {
+    blocked_topics (id) {
+        id -> Text,
+        server_pattern -> Text,
+        topic_pattern -> Text,
+        created_at -> BigInt,
+    }
+}
+
+diesel::table! {
+    blocked_urls (id) {
+        id -> Text,
+        host_pattern -> Text,
+        created_at -> BigInt,
+    }
+}
+
+diesel::table! {
+    keyword_filters (id) {
+        id -> Text,
+        subscription_id -> Nullable<Text>,
+        pattern -> Text,
+        is_regex -> Integer,
+        discard -> Integer,
+        created_at -> BigInt,
+    }
+}
+
+diesel::table! {
+    subscription_patterns (id) {
+        id -> Text,
+        server_url -> Text,
+        pattern -> Text,
+        display_name -> Nullable<Text>,
+        created_at -> BigInt,
     }
 }
 
@@ -45,7 +171,99 @@ diesel::table! {
     }
 }
 
+diesel::table! {
+    reminders (id) {
+        id -> Text,
+        title -> Nullable<Text>,
+        message -> Text,
+        schedule -> Text,
+        next_run -> Nullable<BigInt>,
+        created_at -> BigInt,
+    }
+}
+
+diesel::table! {
+    escalation_rules (id) {
+        id -> Text,
+        subscription_id -> Nullable<Text>,
+        min_priority -> Integer,
+        delay_minutes -> Integer,
+        max_repeats -> Integer,
+        publish_to -> Nullable<Text>,
+        created_at -> BigInt,
+    }
+}
+
+diesel::table! {
+    escalations (notification_id) {
+        notification_id -> Text,
+        count -> Integer,
+        last_at -> BigInt,
+    }
+}
+
+diesel::table! {
+    shortcuts (action) {
+        action -> Text,
+        accelerator -> Text,
+    }
+}
+
+diesel::table! {
+    rules (id) {
+        id -> Text,
+        name -> Text,
+        enabled -> Integer,
+        conditions -> Text,
+        actions -> Text,
+        created_at -> BigInt,
+    }
+}
+
+diesel::table! {
+    virtual_topics (id) {
+        id -> Text,
+        name -> Text,
+        subscription_ids -> Text,
+        tags -> Text,
+        min_priority -> Nullable<Integer>,
+        muted -> Integer,
+        sound -> Nullable<Text>,
+        created_at -> BigInt,
+    }
+}
+
+diesel::joinable!(channel_tags -> channels (channel_id));
+diesel::joinable!(connection_log -> subscriptions (subscription_id));
+diesel::joinable!(escalation_rules -> subscriptions (subscription_id));
+diesel::joinable!(escalations -> notifications (notification_id));
+diesel::joinable!(keyword_filters -> subscriptions (subscription_id));
 diesel::joinable!(subscriptions -> servers (server_id));
 diesel::joinable!(notifications -> subscriptions (subscription_id));
+diesel::joinable!(outbox -> subscriptions (subscription_id));
+diesel::joinable!(permissions -> subscriptions (subscription_id));
+diesel::joinable!(subscriptions -> subscription_patterns (pattern_id));
+diesel::joinable!(subscription_settings -> subscriptions (subscription_id));
 
-diesel::allow_tables_to_appear_in_same_query!(notifications, servers, settings, subscriptions,);
+diesel::allow_tables_to_appear_in_same_query!(
+    blocked_topics,
+    blocked_urls,
+    channel_tags,
+    channels,
+    connection_log,
+    escalation_rules,
+    escalations,
+    keyword_filters,
+    notifications,
+    outbox,
+    permissions,
+    reminders,
+    servers,
+    settings,
+    shortcuts,
+    subscription_patterns,
+    rules,
+    subscription_settings,
+    subscriptions,
+    virtual_topics,
+);
@@ -27,6 +27,9 @@ pub enum AppError {
     #[error("Invalid URL: {0}")]
     InvalidUrl(String),
 
+    #[error("Invalid input: {0}")]
+    Validation(String),
+
     #[error("Connection error: {0}")]
     Connection(String),
 
@@ -35,6 +38,12 @@ pub enum AppError {
 
     #[error("Updater error: {0}")]
     Updater(String),
+
+    #[error("Cancelled: {0}")]
+    Cancelled(String),
+
+    #[error("Locked by policy: {0}")]
+    PolicyLocked(String),
 }
 
 // Conversion from Diesel errors — log full detail, return generic message to frontend
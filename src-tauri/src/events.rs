@@ -0,0 +1,162 @@
+//! Typed events emitted to the frontend.
+//!
+//! Every event is a named payload type bound to the wire name the frontend
+//! listens for. The list is registered with `tauri_specta` in
+//! `export_bindings`, so event names and payload types are exported to
+//! TypeScript together with the commands. Emit events with [`emit`].
+
+use std::collections::BTreeMap;
+
+use serde::Serialize;
+use specta::Type;
+use tauri::{AppHandle, Emitter};
+
+use crate::models::{
+    ConfigFileStatus, DailySummary, LinkPreview, MissingCredential, Notification, OutboxEntry,
+    PermissionRequest, RuntimeStatus, ShortcutAction,
+};
+use crate::services::{NotificationBatchInserted, SyncSummary, UpdateInfo};
+
+/// Binds payload types to their event names.
+macro_rules! events {
+    ($($ty:ty => $name:literal),* $(,)?) => {
+        $(
+            impl tauri_specta::Event for $ty {
+                const NAME: &'static str = $name;
+            }
+        )*
+
+        /// Collects all events for the `tauri_specta` builder.
+        #[cfg(debug_assertions)]
+        pub fn collect() -> tauri_specta::Events {
+            tauri_specta::collect_events![$($ty),*]
+        }
+    };
+}
+
+events! {
+    NotificationNew => "notification:new",
+    NotificationBatchInserted => "notifications:batch_inserted",
+    SubscriptionsSynced => "subscriptions:synced",
+    SyncSummary => "sync:completed",
+    RuntimeStatus => "runtime:status",
+    PermissionRequest => "permission:request",
+    NavigateSubscription => "navigate:subscription",
+    WindowShown => "window:shown",
+    UpdateInfo => "update:available",
+    DailySummary => "summary:daily",
+    LinkPreviewReady => "notification:link_preview",
+    NotificationAnnouncement => "notification:announce",
+    ShortcutTriggered => "shortcut:triggered",
+    AppLockChanged => "app:lock_changed",
+    CredentialsMissing => "credentials:missing",
+    DndChanged => "dnd:changed",
+    NotificationUnsnoozed => "notification:unsnoozed",
+    OutboxSent => "outbox:sent",
+    OutboxFailed => "outbox:failed",
+    ActionExecuted => "action:executed",
+    ActionBroadcast => "action:broadcast",
+    ConfigFileLoaded => "settings:config_file",
+}
+
+/// A notification was received and stored.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct NotificationNew(pub Notification);
+
+/// A snoozed notification was shown again and marked unread.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct NotificationUnsnoozed(pub Notification);
+
+/// Subscriptions changed in the background (startup sync, auto-delete);
+/// the subscription list should be reloaded.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct SubscriptionsSynced;
+
+/// A link preview was fetched for a stored notification.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkPreviewReady {
+    pub notification_id: String,
+    pub preview: LinkPreview,
+}
+
+/// Text to announce to screen readers for a shown notification.
+///
+/// The frontend puts it in an ARIA live region, which screen readers such as
+/// Narrator (through UI Automation) and Orca announce like any live update.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct NotificationAnnouncement {
+    pub notification_id: String,
+    pub text: String,
+}
+
+/// A global shortcut was pressed.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ShortcutTriggered(pub ShortcutAction);
+
+/// The app was locked (`true`) or unlocked (`false`).
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct AppLockChanged(pub bool);
+
+/// Scheduled quiet hours began (`true`) or ended (`false`).
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct DndChanged(pub bool);
+
+/// Servers whose password is missing from or unreadable in the OS keychain,
+/// found at startup. Sync can't authenticate to them until it's entered again.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct CredentialsMissing(pub Vec<MissingCredential>);
+
+/// A queued message was sent.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct OutboxSent(pub OutboxEntry);
+
+/// A queued message was given up on and removed from the outbox.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxFailed {
+    pub entry: OutboxEntry,
+    pub error: String,
+}
+
+/// An `http` action button's request was sent; `error` is set if it failed.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionExecuted {
+    pub notification_id: String,
+    pub action_id: String,
+    pub error: Option<String>,
+}
+
+/// A `broadcast` action button was clicked. Android intents can't be sent
+/// from the desktop, so it's up to the frontend to show or copy them.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ActionBroadcast {
+    pub notification_id: String,
+    pub action_id: String,
+    pub intent: Option<String>,
+    pub extras: BTreeMap<String, String>,
+}
+
+/// The frontend should open the subscription with this ID, e.g. after its
+/// toast was clicked.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct NavigateSubscription(pub String);
+
+/// The main window was shown from the tray.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct WindowShown;
+
+/// Emits a typed event to all windows, logging failures.
+pub fn emit<E: tauri_specta::Event + Serialize>(handle: &AppHandle, event: &E) {
+    if let Err(e) = handle.emit(E::NAME, event) {
+        log::error!("Failed to emit {} event: {e}", E::NAME);
+    }
+}
+
+/// The configuration file changed and was loaded again. Settings it
+/// overwrote are listed; the settings should be reloaded.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct ConfigFileLoaded(pub ConfigFileStatus);
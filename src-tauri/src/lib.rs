@@ -11,52 +11,99 @@
 //! 2. Set up system tray with menu
 //! 3. Configure window close behavior (minimize to tray)
 //! 4. Spawn async task for:
+//!    - Waiting for the configured startup delay and network availability
 //!    - Syncing subscriptions from configured servers
 //!    - Fetching missed notifications
 //!    - Establishing WebSocket connections for real-time updates
 
+#[cfg(feature = "bench")]
+#[doc(hidden)]
+pub mod bench_support;
 mod commands;
 mod config;
 mod db;
 mod error;
+mod events;
 mod models;
+mod parsers;
 mod services;
+#[cfg(test)]
+mod test_support;
 
 use db::Database;
-use services::{ConnectionManager, SyncService, TrayManager};
+use models::{AppLockMethod, TrayIconStyle};
+use services::{
+    ConfigFileService, ConnectionManager, DndScheduler, EscalationService, FolderWatcher,
+    HotkeyService, KioskMode, LockService, MaintenanceService, MetricsEndpoint, MetricsService,
+    NetworkMonitor, OutboxService, PatternService, PermissionService, ProvisioningService,
+    ReminderService, RetentionService, SessionMonitor, SnoozeService, StatusService,
+    SummaryService, SyncCancellation, SyncService, ToastHistory, TrashService, TrayManager,
+    WriteRetryQueue,
+};
+use std::time::Instant;
 use tauri::{
     menu::{Menu, MenuItem},
     tray::TrayIconBuilder,
-    Emitter, Manager,
+    Manager, RunEvent,
 };
 
-/// Generate TypeScript bindings for all commands and types.
+/// The full set of commands exposed to the frontend.
 ///
-/// This only runs in debug builds. If binding export fails, we want to
-/// crash immediately to alert the developer.
-#[cfg(debug_assertions)]
-#[allow(clippy::expect_used)]
-pub fn export_bindings() {
-    use specta_typescript::{BigIntExportBehavior, Typescript};
-
-    let builder =
-        tauri_specta::Builder::<tauri::Wry>::new().commands(tauri_specta::collect_commands![
+/// Expands to `$collect![...]` so the same list feeds both
+/// `tauri_specta::collect_commands!` (bindings) and `tauri::generate_handler!`
+/// (invoke handler); a new command only needs to be added here.
+macro_rules! app_commands {
+    ($($collect:tt)+) => {
+        $($collect)+![
+            // Subscriptions
             commands::get_subscriptions,
             commands::add_subscription,
+            commands::import_subscriptions_from_file,
+            commands::list_subscription_templates,
+            commands::apply_subscription_template,
+            commands::get_publish_snippet,
             commands::remove_subscription,
+            commands::restore_subscription,
+            commands::get_deleted_subscriptions,
+            commands::reconnect_subscription,
             commands::toggle_mute,
+            // Notifications
             commands::get_notifications,
+            commands::get_all_notifications,
             commands::mark_as_read,
             commands::mark_all_as_read,
             commands::delete_notification,
+            commands::restore_notification,
+            commands::get_trash,
+            commands::empty_trash,
             commands::set_notification_expanded,
             commands::get_unread_count,
             commands::get_total_unread_count,
+            commands::open_notification_url,
+            commands::execute_notification_action,
+            commands::acknowledge_notification,
+            commands::set_notification_note,
+            commands::snooze_notification,
+            commands::unsnooze_notification,
+            commands::search_notifications,
+            // Settings
             commands::get_settings,
+            commands::get_config_file_status,
+            commands::get_policy,
             commands::set_theme,
             commands::add_server,
+            commands::update_server,
+            commands::get_server_removal_impact,
             commands::remove_server,
             commands::set_default_server,
+            commands::get_server_publish_defaults,
+            commands::set_server_publish_defaults,
+            commands::get_server_resolution,
+            commands::set_server_resolution,
+            commands::get_server_client_certificate,
+            commands::set_server_client_certificate,
+            commands::set_proxy_settings,
+            commands::set_http_timeouts,
             commands::set_minimize_to_tray,
             commands::set_start_minimized,
             commands::set_notification_method,
@@ -64,36 +111,212 @@ pub fn export_bindings() {
             commands::set_notification_show_actions,
             commands::set_notification_show_images,
             commands::set_notification_sound,
+            commands::set_notification_group_by_topic,
+            commands::set_min_toast_priority,
+            commands::set_tag_priorities,
+            commands::set_mentions,
+            commands::set_do_not_disturb,
+            commands::set_dnd_schedule,
+            commands::set_tray_icon_style,
+            commands::set_tray_icon_path,
+            commands::set_tray_unread_icon_path,
             commands::set_compact_view,
             commands::set_expand_new_messages,
             commands::set_delete_local_only,
             commands::set_favorites_enabled,
             commands::set_notification_favorite,
             commands::get_favorite_notifications,
+            commands::set_startup_delay,
+            commands::set_wait_for_network,
+            commands::set_credential_lock_minutes,
+            commands::set_subscription_sort,
+            commands::set_daily_summary_enabled,
+            commands::set_daily_summary_time,
+            commands::set_link_previews_enabled,
+            commands::set_hide_content_when_locked,
+            commands::set_log_redaction,
+            commands::set_watch_folders,
+            commands::set_clipboard_share,
+            commands::set_metrics_endpoint_port,
+            commands::set_receipt_topic,
+            commands::get_device_info,
+            commands::set_device_name,
+            commands::set_screen_reader_announcements,
+            commands::set_kiosk_mode,
+            commands::lock_credentials,
+            commands::verify_credentials_consistency,
+            // Shortcuts
+            commands::get_shortcuts,
+            commands::set_shortcut,
+            commands::share_clipboard,
+            commands::capture_and_publish,
+            commands::publish_message,
+            commands::get_outbox,
+            commands::discard_outbox_entry,
+            // Reminders
+            commands::get_reminders,
+            commands::create_reminder,
+            commands::delete_reminder,
+            // Escalation rules
+            commands::get_escalation_rules,
+            commands::create_escalation_rule,
+            commands::delete_escalation_rule,
+            // Sync
             commands::sync_subscriptions,
+            commands::cancel_sync,
+            // Status
+            commands::get_runtime_status,
+            commands::run_maintenance,
+            commands::get_internal_metrics,
+            commands::get_connection_log,
+            // Statistics
+            commands::get_daily_summary,
+            commands::generate_activity_report,
+            commands::get_server_stats,
+            // Subscription organization
+            commands::reorder_subscriptions,
+            commands::set_subscription_trusted,
+            commands::set_subscription_auto_delete,
+            commands::set_retention_policy,
+            commands::set_subscription_min_toast_priority,
+            commands::get_subscription_settings,
+            commands::set_subscription_settings,
+            commands::set_subscription_hide_content,
+            commands::set_subscription_exclude_from_sync,
+            commands::set_subscription_sync_window,
+            commands::set_subscription_mirror,
+            commands::set_subscription_pinned,
+            commands::add_local_subscription,
+            commands::generate_test_notifications,
+            commands::get_subscription_patterns,
+            commands::add_subscription_pattern,
+            commands::remove_subscription_pattern,
+            commands::refresh_subscription_patterns,
+            // Permissions
+            commands::request_permission,
+            commands::respond_permission,
+            commands::get_permissions,
+            commands::revoke_permission,
+            // App lock
+            commands::is_app_locked,
+            commands::lock_app,
+            commands::unlock_app,
+            commands::set_app_lock,
+            commands::set_app_lock_minutes,
+            // Blocklist
+            commands::get_blocked_topics,
+            commands::add_blocked_topic,
+            commands::remove_blocked_topic,
+            commands::get_blocked_urls,
+            commands::add_blocked_url,
+            commands::remove_blocked_url,
+            commands::get_keyword_filters,
+            commands::add_keyword_filter,
+            commands::remove_keyword_filter,
+            // Channels
+            commands::get_channels,
+            commands::create_channel,
+            commands::update_channel,
+            commands::delete_channel,
+            commands::get_virtual_topics,
+            commands::get_virtual_topic_notifications,
+            commands::mark_virtual_topic_read,
+            commands::create_virtual_topic,
+            commands::update_virtual_topic,
+            commands::delete_virtual_topic,
+            // Rules
+            commands::get_rules,
+            commands::create_rule,
+            commands::update_rule,
+            commands::delete_rule,
+            commands::export_rules,
+            commands::import_rules,
+            commands::preview_rule,
             // Update
             commands::check_for_update,
             commands::install_update,
             commands::get_app_version,
             commands::get_app_version_display,
-        ]);
+        ]
+    };
+}
+
+/// Command line flag skipping the TypeScript binding export of debug builds.
+pub const SKIP_BINDINGS_ARG: &str = "--skip-bindings";
+
+/// Where the TypeScript bindings are written.
+pub const BINDINGS_PATH: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/../ui/src/types/bindings.ts");
+
+/// Generate TypeScript bindings for all commands and types.
+///
+/// Debug builds run this on startup unless started with `--skip-bindings`;
+/// `cargo run --bin export-bindings` runs it on its own. Fails if the UI
+/// folder is missing, e.g. in a backend-only checkout.
+pub fn export_bindings() -> Result<(), String> {
+    use specta_typescript::{BigIntExportBehavior, Typescript};
+
+    let bindings_path = std::path::Path::new(BINDINGS_PATH);
+    if !bindings_path.parent().is_some_and(std::path::Path::is_dir) {
+        return Err(format!(
+            "{} not found",
+            bindings_path.parent().unwrap_or(bindings_path).display()
+        ));
+    }
+
+    let builder = tauri_specta::Builder::<tauri::Wry>::new()
+        .commands(app_commands!(tauri_specta::collect_commands))
+        .events(events::collect());
 
     // Configure TypeScript export to handle i64 as number (safe for timestamps up to year 285,616)
     let ts_config = Typescript::default().bigint(BigIntExportBehavior::Number);
 
-    let bindings_path = "../ui/src/types/bindings.ts";
-
     builder
         .export(ts_config, bindings_path)
-        .expect("Failed to export TypeScript bindings");
+        .map_err(|e| format!("Failed to export TypeScript bindings: {e}"))?;
 
     // Prepend @ts-nocheck to suppress errors in auto-generated code
-    let contents =
-        std::fs::read_to_string(bindings_path).expect("Failed to read generated bindings");
+    let contents = std::fs::read_to_string(bindings_path)
+        .map_err(|e| format!("Failed to read generated bindings: {e}"))?;
     std::fs::write(bindings_path, format!("// @ts-nocheck\n{contents}"))
-        .expect("Failed to write @ts-nocheck to bindings");
+        .map_err(|e| format!("Failed to write @ts-nocheck to bindings: {e}"))?;
 
-    println!("TypeScript bindings exported to {bindings_path}");
+    Ok(())
+}
+
+/// Wraps the invoke handler to time sync commands for the internal metrics.
+///
+/// Async commands are only spawned here and time themselves with a
+/// `CommandTimer` argument.
+fn timed_handler(
+    handler: impl Fn(tauri::ipc::Invoke) -> bool + Send + Sync + 'static,
+) -> impl Fn(tauri::ipc::Invoke) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        if MetricsService::is_self_timed(invoke.message.command()) {
+            return handler(invoke);
+        }
+        let command = invoke.message.command().to_string();
+        let started = Instant::now();
+        let handled = handler(invoke);
+        MetricsService::record_command(&command, started.elapsed());
+        handled
+    }
+}
+
+/// Wraps the invoke handler to refuse commands not allowed in kiosk mode.
+fn kiosk_handler(
+    handler: impl Fn(tauri::ipc::Invoke) -> bool + Send + Sync + 'static,
+) -> impl Fn(tauri::ipc::Invoke) -> bool + Send + Sync + 'static {
+    move |invoke| {
+        let webview = invoke.message.webview();
+        let refused = webview
+            .try_state::<KioskMode>()
+            .and_then(|kiosk| kiosk.check(invoke.message.command()).err());
+        if let Some(e) = refused {
+            invoke.resolver.reject(e);
+            return true;
+        }
+        handler(invoke)
+    }
 }
 
 /// Main application entry point.
@@ -107,9 +330,15 @@ pub fn export_bindings() {
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 #[allow(clippy::expect_used)]
 pub fn run() {
-    // Export TypeScript bindings in debug mode
+    // Export TypeScript bindings in debug mode. The log isn't set up yet, and
+    // a failed export shouldn't keep the app from starting.
     #[cfg(debug_assertions)]
-    export_bindings();
+    if !std::env::args().any(|arg| arg == SKIP_BINDINGS_ARG) {
+        match export_bindings() {
+            Ok(()) => println!("TypeScript bindings exported to {BINDINGS_PATH}"),
+            Err(e) => eprintln!("Skipping TypeScript bindings: {e}"),
+        }
+    }
 
     tauri::Builder::default()
         .plugin(tauri_plugin_shell::init())
@@ -126,18 +355,87 @@ pub fn run() {
         ))
         .plugin(tauri_plugin_window_state::Builder::new().build())
         .plugin(tauri_plugin_updater::Builder::new().build())
+        .plugin(tauri_plugin_global_shortcut::Builder::new().build())
         .setup(|app| {
             // Initialize database
             let app_data_dir = app.path().app_data_dir()?;
             std::fs::create_dir_all(&app_data_dir)?;
             let db_path = app_data_dir.join("ntfier.db");
             let db = Database::new(&db_path)?;
+
+            // Add servers and subscriptions provisioned for managed deployments
+            ProvisioningService::apply_from_env(&db);
+
+            // Apply the configuration file before the settings it overrides are read
+            let config_file =
+                ConfigFileService::new(app_data_dir.join(config::config_file::FILE_NAME));
+            config_file.load(&db);
+
+            // Apply log redaction before anything logs topics or messages
+            services::log_redaction::set_mode(db.get_log_redaction().unwrap_or_default());
+
+            // Apply server resolution overrides, the proxy, client
+            // certificates and timeouts before the first request
+            services::resolver::reload(&db);
+            services::proxy::reload(&db);
+            services::client_certificate::reload(&db);
+            services::NtfyClient::reload_timeouts(&db);
+
+            // Apply credential cache idle lock and start checking for idleness
+            let lock_minutes = db.get_credential_lock_minutes().unwrap_or(0);
+            if let Err(e) = services::credential_manager::set_idle_timeout(
+                services::credential_manager::idle_timeout_from_minutes(lock_minutes),
+            ) {
+                log::warn!("Failed to configure credential lock: {e}");
+            }
+            tauri::async_runtime::spawn(async {
+                let mut interval = tokio::time::interval(std::time::Duration::from_secs(
+                    config::credentials::IDLE_CHECK_INTERVAL_SECS,
+                ));
+                loop {
+                    interval.tick().await;
+                    if let Err(e) = services::credential_manager::lock_if_idle() {
+                        log::warn!("Credential idle check failed: {e}");
+                    }
+                }
+            });
+
+            // The app starts locked when a lock method is set
+            let app_lock_method = db.get_app_lock_method().unwrap_or_default();
+            app.manage(LockService::new(app_lock_method != AppLockMethod::Off));
+
+            // Kiosk mode is on by setting, or enforced by the command line flag
+            let kiosk_enforced = std::env::args().any(|arg| arg == services::KIOSK_ARG);
+            app.manage(KioskMode::new(
+                db.get_kiosk_mode().unwrap_or(false),
+                kiosk_enforced,
+            ));
+
             app.manage(db);
+            LockService::spawn_idle_watcher(app.handle().clone());
+            app.manage(config_file);
+            ConfigFileService::spawn_watcher(app.handle().clone());
 
             // Initialize connection manager
             let conn_manager = ConnectionManager::new(app.handle().clone());
             app.manage(conn_manager);
 
+            // Initialize sync cancellation handle
+            app.manage(SyncCancellation::new());
+            app.manage(PermissionService::new());
+            app.manage(ToastHistory::new());
+            app.manage(WriteRetryQueue::new());
+            WriteRetryQueue::spawn_worker(app.handle().clone());
+            app.manage(SessionMonitor::new());
+            app.manage(MetricsEndpoint::new());
+            // Track the lock state from the start so toasts during the startup sync are covered
+            SessionMonitor::spawn_watcher(app.handle().clone());
+
+            // Register the configured global shortcuts
+            if let Err(e) = HotkeyService::register_all(app.handle()) {
+                log::warn!("Failed to register global shortcuts: {e}");
+            }
+
             // Initialize tray manager
             let tray_manager = TrayManager::new();
             app.manage(tray_manager);
@@ -146,10 +444,13 @@ pub fn run() {
             if cfg!(debug_assertions) {
                 app.handle().plugin(
                     tauri_plugin_log::Builder::default()
-                        .level(log::LevelFilter::Info)
+                        .level(log::LevelFilter::Trace)
                         .build(),
                 )?;
             }
+            // The level is filtered here so the configuration file can change it
+            let config_file: tauri::State<ConfigFileService> = app.state();
+            log::set_max_level(config_file.log_level());
 
             // Tray icon setup
             let show = MenuItem::with_id(app, "show", "Show", true, None::<&str>)?;
@@ -187,7 +488,7 @@ pub fn run() {
                             let _ = window.show();
                             let _ = window.set_focus();
                             // Notify frontend to scroll to top
-                            let _ = app_handle.emit("window:shown", ());
+                            events::emit(app_handle, &events::WindowShown);
                         }
                     }
                 })
@@ -231,6 +532,20 @@ pub fn run() {
                                 let _ = win.hide();
                             }
                         }
+                        // The app lock's idle timer runs while the window is out of focus
+                        tauri::WindowEvent::Focused(focused) => {
+                            app_handle.state::<LockService>().set_focused(*focused);
+                        }
+                        // The auto tray icon style follows the OS theme
+                        tauri::WindowEvent::ThemeChanged(_)
+                            if db.get_tray_icon_style().ok() == Some(TrayIconStyle::Auto) =>
+                        {
+                            let tray_manager = app_handle.state::<TrayManager>().inner().clone();
+                            let handle = app_handle.clone();
+                            tauri::async_runtime::spawn(async move {
+                                tray_manager.reload_icons(&handle).await;
+                            });
+                        }
                         _ => {}
                     }
                 });
@@ -242,6 +557,9 @@ pub fn run() {
                 // Clean up old cached images (older than 24 hours)
                 services::image_cache::cleanup_old_images(24 * 60 * 60).await;
 
+                // Permanently delete trash older than the retention period
+                TrashService::purge_expired(&handle).await;
+
                 // Set up tray icon with custom icons
                 let tray_manager: tauri::State<TrayManager> = handle.state();
                 tray_manager.set_tray_icon(tray).await;
@@ -251,12 +569,37 @@ pub fn run() {
                     log::warn!("Failed to load tray icons: {e}");
                 }
 
+                // 0. Optional startup delay, then wait for the network to come up
+                // (on autostart the app may launch before Wi-Fi is connected)
+                let db: tauri::State<Database> = handle.state();
+                let startup_delay = db.get_startup_delay_secs().unwrap_or(0);
+                if startup_delay > 0 {
+                    log::info!("Delaying startup sync by {startup_delay} seconds");
+                    tokio::time::sleep(std::time::Duration::from_secs(u64::from(startup_delay)))
+                        .await;
+                }
+                if db.get_wait_for_network().unwrap_or(true) {
+                    NetworkMonitor::wait_for_network(&handle).await;
+                }
+
+                // Report servers whose keychain entry is gone (e.g. after an OS
+                // reinstall) instead of letting their sync fail silently
+                match db.verify_credentials_consistency() {
+                    Ok(missing) => {
+                        if !missing.is_empty() {
+                            log::warn!("{} server(s) have no readable credentials", missing.len());
+                            events::emit(&handle, &events::CredentialsMissing(missing));
+                        }
+                    }
+                    Err(e) => log::warn!("Failed to verify credentials: {e}"),
+                }
+
                 // 1. First sync subscriptions from all servers (creates new subscriptions)
                 SyncService::sync_subscriptions(&handle).await;
 
                 // Notify frontend that subscriptions are synced
                 log::info!("Emitting subscriptions:synced event");
-                let _ = handle.emit("subscriptions:synced", ());
+                events::emit(&handle, &events::SubscriptionsSynced);
 
                 // 2. Then sync notifications for all subscriptions (fetches missed messages)
                 SyncService::sync_notifications(&handle).await;
@@ -265,60 +608,70 @@ pub fn run() {
                 let conn_manager: tauri::State<ConnectionManager> = handle.state();
                 conn_manager.connect_all().await;
 
+                // Reconnect right away after waking from sleep or a network change
+                NetworkMonitor::spawn_wake_watcher(handle.clone());
+
                 // 4. Update tray icon based on unread count (force initial update)
                 tray_manager.initial_refresh(&handle).await;
 
+                // Poll topic listings for pattern subscriptions
+                PatternService::spawn_poller(handle.clone());
+
+                // Delete notifications past their subscription's retention
+                RetentionService::spawn_cleaner(handle.clone());
+
+                // Remove orphaned rows, cached images and keychain entries
+                MaintenanceService::spawn_scheduler(handle.clone());
+
+                // Show the daily digest at the configured time
+                SummaryService::spawn_scheduler(handle.clone());
+
+                // Show local reminders when they're due
+                ReminderService::spawn_scheduler(handle.clone());
+                DndScheduler::spawn(handle.clone());
+
+                // Escalate high priority notifications nobody took care of
+                EscalationService::spawn_scheduler(handle.clone());
+
+                // Send messages published while offline once servers are reachable
+                OutboxService::spawn_flusher(handle.clone());
+
+                // Show snoozed notifications again when their snooze runs out
+                SnoozeService::spawn_scheduler(handle.clone());
+
+                // Start periodic runtime status reporting for the status bar
+                StatusService::spawn_reporter(handle.clone());
+
+                // Publish new and changed files in watch folders
+                FolderWatcher::spawn_watcher(handle.clone());
+
+                // Serve Prometheus metrics if enabled
+                let metrics_port = db.get_metrics_endpoint_port().unwrap_or(None);
+                let metrics_endpoint: tauri::State<MetricsEndpoint> = handle.state();
+                if let Err(e) = metrics_endpoint.set_port(&handle, metrics_port).await {
+                    log::warn!("Failed to start metrics endpoint: {e}");
+                }
+
                 // 5. Check for updates (non-blocking)
                 if let Ok(Some(update_info)) =
                     services::UpdateService::check_for_update(&handle).await
                 {
-                    let _ = handle.emit("update:available", update_info);
+                    events::emit(&handle, &update_info);
                 }
             });
 
             Ok(())
         })
-        .invoke_handler(tauri::generate_handler![
-            // Subscriptions
-            commands::get_subscriptions,
-            commands::add_subscription,
-            commands::remove_subscription,
-            commands::toggle_mute,
-            // Notifications
-            commands::get_notifications,
-            commands::mark_as_read,
-            commands::mark_all_as_read,
-            commands::delete_notification,
-            commands::set_notification_expanded,
-            commands::get_unread_count,
-            commands::get_total_unread_count,
-            // Settings
-            commands::get_settings,
-            commands::set_theme,
-            commands::add_server,
-            commands::remove_server,
-            commands::set_default_server,
-            commands::set_minimize_to_tray,
-            commands::set_start_minimized,
-            commands::set_notification_method,
-            commands::set_notification_force_display,
-            commands::set_notification_show_actions,
-            commands::set_notification_show_images,
-            commands::set_notification_sound,
-            commands::set_compact_view,
-            commands::set_expand_new_messages,
-            commands::set_delete_local_only,
-            commands::set_favorites_enabled,
-            commands::set_notification_favorite,
-            commands::get_favorite_notifications,
-            // Sync
-            commands::sync_subscriptions,
-            // Update
-            commands::check_for_update,
-            commands::install_update,
-            commands::get_app_version,
-            commands::get_app_version_display,
-        ])
-        .run(tauri::generate_context!())
-        .expect("error while running Ntfier");
+        .invoke_handler(timed_handler(kiosk_handler(app_commands!(
+            tauri::generate_handler
+        ))))
+        .build(tauri::generate_context!())
+        .expect("error while building Ntfier")
+        .run(|app_handle, event| {
+            if matches!(event, RunEvent::Exit) {
+                // Abort in-flight HTTP requests so shutdown isn't held up by a hung server
+                let cancellation: tauri::State<SyncCancellation> = app_handle.state();
+                cancellation.cancel();
+            }
+        });
 }
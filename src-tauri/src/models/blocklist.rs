@@ -0,0 +1,229 @@
+//! Topic, URL and keyword blocklist entries.
+
+use regex::RegexBuilder;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use url::Url;
+
+use super::normalize_url;
+use super::notification::Notification;
+use super::pattern::wildcard_match;
+use crate::error::AppError;
+
+/// A blocklist entry preventing matching topics from being auto-created by sync.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockedTopic {
+    pub id: String,
+    /// Wildcard pattern for the server URL (`*` matches every server).
+    pub server_pattern: String,
+    /// Wildcard pattern for the topic name.
+    pub topic_pattern: String,
+    /// When the entry was added (milliseconds).
+    pub created_at: i64,
+}
+
+impl BlockedTopic {
+    /// Checks whether this entry blocks the given topic on the given server.
+    pub fn matches(&self, server_url: &str, topic: &str) -> bool {
+        wildcard_match(
+            normalize_url(&self.server_pattern),
+            normalize_url(server_url),
+        ) && wildcard_match(&self.topic_pattern, topic)
+    }
+}
+
+/// Request to add a blocklist entry.
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateBlockedTopic {
+    /// Defaults to `*` (all servers) when omitted.
+    pub server_pattern: Option<String>,
+    pub topic_pattern: String,
+}
+
+impl CreateBlockedTopic {
+    /// Validates the entry, rejecting empty patterns.
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.topic_pattern.trim().is_empty() {
+            return Err(AppError::Validation(
+                "Topic pattern cannot be empty".to_string(),
+            ));
+        }
+        if self
+            .server_pattern
+            .as_deref()
+            .is_some_and(|p| p.trim().is_empty())
+        {
+            return Err(AppError::Validation(
+                "Server pattern cannot be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A blocklist entry preventing links to matching hosts from being opened.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct BlockedUrl {
+    pub id: String,
+    /// Wildcard pattern for the host name (e.g. `*.example.com`).
+    pub host_pattern: String,
+    /// When the entry was added (milliseconds).
+    pub created_at: i64,
+}
+
+impl BlockedUrl {
+    /// Checks whether this entry blocks the given URL. Host names are compared
+    /// case-insensitively.
+    pub fn matches(&self, url: &Url) -> bool {
+        url.host_str().is_some_and(|host| {
+            wildcard_match(
+                &self.host_pattern.to_ascii_lowercase(),
+                &host.to_ascii_lowercase(),
+            )
+        })
+    }
+}
+
+/// Request to add a URL blocklist entry.
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateBlockedUrl {
+    pub host_pattern: String,
+}
+
+impl CreateBlockedUrl {
+    /// Validates the entry, rejecting empty patterns and patterns that are full URLs.
+    pub fn validate(&self) -> Result<(), AppError> {
+        let pattern = self.host_pattern.trim();
+        if pattern.is_empty() {
+            return Err(AppError::Validation(
+                "Host pattern cannot be empty".to_string(),
+            ));
+        }
+        if pattern.contains(['/', ':']) {
+            return Err(AppError::Validation(
+                "Host pattern must be a host name, not a URL".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// A keyword muting messages whose title or message contain it, e.g. a CI
+/// topic's heartbeats. Matching messages show no toast, and aren't stored at
+/// all with `discard`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct KeywordFilter {
+    pub id: String,
+    /// Subscription the filter applies to; `None` for all of them.
+    pub subscription_id: Option<String>,
+    /// Text to look for, ignoring case, or a regular expression.
+    pub pattern: String,
+    pub is_regex: bool,
+    /// Matching messages are dropped instead of stored without a toast.
+    pub discard: bool,
+    /// When the filter was added (milliseconds).
+    pub created_at: i64,
+}
+
+impl KeywordFilter {
+    /// Checks whether the filter applies to `notification`. Regular
+    /// expressions ignore case too.
+    pub fn matches(&self, notification: &Notification) -> bool {
+        if self
+            .subscription_id
+            .as_ref()
+            .is_some_and(|id| *id != notification.topic_id)
+        {
+            return false;
+        }
+
+        let texts = [&notification.title, &notification.message];
+        if self.is_regex {
+            RegexBuilder::new(&self.pattern)
+                .case_insensitive(true)
+                .build()
+                .is_ok_and(|re| texts.iter().any(|text| re.is_match(text)))
+        } else {
+            let pattern = self.pattern.to_lowercase();
+            texts
+                .iter()
+                .any(|text| text.to_lowercase().contains(&pattern))
+        }
+    }
+}
+
+/// Request to add a keyword filter.
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateKeywordFilter {
+    pub subscription_id: Option<String>,
+    pub pattern: String,
+    pub is_regex: bool,
+    pub discard: bool,
+}
+
+impl CreateKeywordFilter {
+    /// Validates the filter, rejecting empty patterns and invalid regular
+    /// expressions.
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.pattern.trim().is_empty() {
+            return Err(AppError::Validation("Keyword cannot be empty".to_string()));
+        }
+        if self.is_regex {
+            RegexBuilder::new(&self.pattern)
+                .build()
+                .map_err(|e| AppError::Validation(format!("Invalid regular expression: {e}")))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::NtfyMessage;
+
+    #[test]
+    fn test_keyword_filter_matches() {
+        let notification = NtfyMessage {
+            id: "m1".to_string(),
+            time: 0,
+            event: "message".to_string(),
+            topic: "ci".to_string(),
+            message: Some("Runner heartbeat OK".to_string()),
+            title: None,
+            priority: None,
+            tags: None,
+            click: None,
+            actions: None,
+            attachment: None,
+            content_type: None,
+        }
+        .into_notification("ci".to_string());
+        let mut filter = KeywordFilter {
+            id: "f1".to_string(),
+            subscription_id: None,
+            pattern: "HEARTBEAT".to_string(),
+            is_regex: false,
+            discard: false,
+            created_at: 0,
+        };
+        assert!(filter.matches(&notification));
+
+        filter.subscription_id = Some("deploys".to_string());
+        assert!(!filter.matches(&notification));
+        filter.subscription_id = Some("ci".to_string());
+        assert!(filter.matches(&notification));
+
+        filter.is_regex = true;
+        filter.pattern = r"^runner \w+ ok$".to_string();
+        assert!(filter.matches(&notification));
+        filter.pattern = "^heartbeat".to_string();
+        assert!(!filter.matches(&notification));
+    }
+}
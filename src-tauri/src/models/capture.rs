@@ -0,0 +1,16 @@
+//! Screenshot capture.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Part of the screen captured by `capture_and_publish`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CaptureMode {
+    /// A region picked by the user.
+    Region,
+    /// A window picked by the user (the foreground window on Windows).
+    Window,
+    /// The whole screen.
+    Screen,
+}
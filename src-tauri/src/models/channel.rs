@@ -0,0 +1,74 @@
+//! Notification channels mapped from tags.
+//!
+//! A channel groups notifications carrying certain tags, regardless of topic,
+//! and overrides how they are displayed. Besides its explicitly mapped tags, a
+//! channel also matches the tag `channel:<name>`.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::notification::{Notification, Priority};
+use crate::error::AppError;
+
+/// Tag prefix that refers to a channel by name.
+pub const CHANNEL_TAG_PREFIX: &str = "channel:";
+
+/// A notification channel with display settings that cut across topics.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct Channel {
+    pub id: String,
+    pub name: String,
+    /// Tags mapped to this channel.
+    pub tags: Vec<String>,
+    /// Muted channels store notifications as read without showing them.
+    pub muted: bool,
+    /// Overrides the global notification sound setting when set.
+    pub sound: Option<bool>,
+    /// Overrides the priority of notifications in this channel when set.
+    #[specta(type = Option<u8>)]
+    pub priority: Option<Priority>,
+    /// When the channel was created (milliseconds).
+    pub created_at: i64,
+}
+
+impl Channel {
+    /// Applies this channel's overrides to a notification before it is stored.
+    pub fn apply(&self, notification: &mut Notification) {
+        if let Some(priority) = self.priority {
+            notification.priority = priority;
+        }
+        if self.muted {
+            notification.read = true;
+        }
+    }
+}
+
+/// Data required to create or update a channel.
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ChannelInput {
+    pub name: String,
+    pub tags: Vec<String>,
+    pub muted: bool,
+    pub sound: Option<bool>,
+    #[specta(type = Option<u8>)]
+    pub priority: Option<Priority>,
+}
+
+impl ChannelInput {
+    /// Validates the channel data.
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.name.trim().is_empty() {
+            return Err(AppError::Validation(
+                "Channel name cannot be empty".to_string(),
+            ));
+        }
+        if self.tags.iter().any(|t| t.trim().is_empty()) {
+            return Err(AppError::Validation(
+                "Channel tags cannot be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
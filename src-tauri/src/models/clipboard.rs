@@ -0,0 +1,18 @@
+//! Clipboard quick-share.
+
+use serde::Deserialize;
+use specta::Type;
+
+/// Clipboard contents read by the frontend when the quick-share shortcut is
+/// pressed.
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum ClipboardContent {
+    Text {
+        text: String,
+    },
+    /// PNG image, base64-encoded.
+    Image {
+        data: String,
+    },
+}
@@ -0,0 +1,186 @@
+//! The optional `ntfier.toml` configuration file.
+//!
+//! Power users can keep servers, their proxies and logging in a file next to
+//! the database, e.g. managed with their dotfiles. Whatever the file sets
+//! overrides the app's settings when it is loaded; anything it leaves out
+//! keeps its current value.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::resolution::{IpPreference, ServerResolution};
+use super::settings::{LogRedaction, ServerConfig};
+use crate::error::AppError;
+
+/// Contents of the configuration file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFile {
+    /// Servers added if missing, and updated with the fields set here.
+    /// Servers missing from the file are left alone.
+    #[serde(default)]
+    pub servers: Vec<ConfigFileServer>,
+    #[serde(default)]
+    pub logging: ConfigFileLogging,
+}
+
+/// A server in the configuration file. Passwords aren't read from the file;
+/// they stay in the OS keychain and are entered in the app.
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFileServer {
+    pub url: String,
+    pub username: Option<String>,
+    pub default: Option<bool>,
+    pub ip_preference: Option<IpPreference>,
+    pub static_addresses: Option<Vec<String>>,
+    /// SOCKS5 proxy (`host:port`) the server is reached through; empty for none.
+    pub socks_proxy: Option<String>,
+}
+
+/// Logging options of the configuration file.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct ConfigFileLogging {
+    pub level: Option<LogLevel>,
+    pub redaction: Option<LogRedaction>,
+}
+
+/// Most verbose level written to the log.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum LogLevel {
+    Off,
+    Error,
+    Warn,
+    Info,
+    Debug,
+    Trace,
+}
+
+impl LogLevel {
+    /// The matching `log` filter.
+    pub const fn filter(self) -> log::LevelFilter {
+        match self {
+            Self::Off => log::LevelFilter::Off,
+            Self::Error => log::LevelFilter::Error,
+            Self::Warn => log::LevelFilter::Warn,
+            Self::Info => log::LevelFilter::Info,
+            Self::Debug => log::LevelFilter::Debug,
+            Self::Trace => log::LevelFilter::Trace,
+        }
+    }
+}
+
+impl ConfigFile {
+    /// Parses and validates the file's contents.
+    pub fn parse(contents: &str) -> Result<Self, AppError> {
+        let file: Self = toml::from_str(contents)
+            .map_err(|e| AppError::Validation(format!("Invalid configuration file: {e}")))?;
+
+        for server in &file.servers {
+            server.config().validate()?;
+            server.resolution(&ServerResolution::default()).validate()?;
+        }
+        if file
+            .servers
+            .iter()
+            .filter(|s| s.default == Some(true))
+            .count()
+            > 1
+        {
+            return Err(AppError::Validation(
+                "Only one server can be the default".to_string(),
+            ));
+        }
+        Ok(file)
+    }
+}
+
+impl ConfigFileServer {
+    /// The server as configured in the app, without a password.
+    pub fn config(&self) -> ServerConfig {
+        ServerConfig {
+            url: self.url.trim().trim_end_matches('/').to_string(),
+            username: self.username.clone().filter(|u| !u.is_empty()),
+            password: None,
+            is_default: self.default.unwrap_or(false),
+        }
+    }
+
+    /// Applies the resolution overrides set in the file to `current`.
+    pub fn resolution(&self, current: &ServerResolution) -> ServerResolution {
+        ServerResolution {
+            ip_preference: self.ip_preference.unwrap_or(current.ip_preference),
+            static_addresses: self.static_addresses.as_ref().map_or_else(
+                || current.static_addresses.clone(),
+                |addresses| addresses.iter().map(|a| a.trim().to_string()).collect(),
+            ),
+            socks_proxy: match &self.socks_proxy {
+                Some(proxy) if proxy.trim().is_empty() => None,
+                Some(proxy) => Some(proxy.trim().to_string()),
+                None => current.socks_proxy.clone(),
+            },
+        }
+    }
+}
+
+/// Outcome of the last load of the configuration file.
+#[derive(Debug, Clone, Default, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ConfigFileStatus {
+    /// Where the file is looked for.
+    pub path: String,
+    /// Whether the file exists and was applied.
+    pub loaded: bool,
+    /// Settings the file sets, which are overwritten whenever it is loaded,
+    /// e.g. `logging.redaction` or `servers.<url>.socks_proxy`.
+    pub managed: Vec<String>,
+    /// Settings whose value in the app differed from the file and were
+    /// overwritten by the last load.
+    pub overridden: Vec<String>,
+    /// Why the file couldn't be applied; the previous settings are kept.
+    pub error: Option<String>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config_file() {
+        let parsed = ConfigFile::parse(
+            r#"
+            [logging]
+            level = "debug"
+            redaction = "hash"
+
+            [[servers]]
+            url = "https://ntfy.example.com/"
+            username = "me"
+            default = true
+            socks_proxy = "127.0.0.1:9050"
+            "#,
+        );
+        assert!(parsed.is_ok());
+        let file = parsed.unwrap_or_default();
+        assert_eq!(file.logging.level, Some(LogLevel::Debug));
+        assert_eq!(file.logging.redaction, Some(LogRedaction::Hash));
+        assert_eq!(file.servers[0].config().url, "https://ntfy.example.com");
+
+        let current = ServerResolution {
+            ip_preference: IpPreference::Ipv4,
+            ..ServerResolution::default()
+        };
+        let resolution = file.servers[0].resolution(&current);
+        assert_eq!(resolution.ip_preference, IpPreference::Ipv4);
+        assert_eq!(resolution.socks_proxy.as_deref(), Some("127.0.0.1:9050"));
+
+        assert_eq!(ConfigFile::parse("").ok(), Some(ConfigFile::default()));
+        assert!(ConfigFile::parse("[logging]\ncolour = true").is_err());
+        assert!(ConfigFile::parse("[[servers]]\nurl = \"ftp://example.com\"").is_err());
+        assert!(
+            ConfigFile::parse("[[servers]]\nurl = \"https://a\"\nsocks_proxy = \"tor\"").is_err()
+        );
+    }
+}
@@ -0,0 +1,29 @@
+//! Identity of this ntfier installation.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// Stable identity of this installation, included in receipts and
+/// acknowledgments so other devices of the same user can be told apart.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DeviceInfo {
+    /// Random ID generated on first use; never changes.
+    pub id: String,
+    /// Name shown to others; the computer's host name unless renamed.
+    pub name: String,
+}
+
+/// Host name of this computer, used as the default device name.
+pub fn host_name() -> String {
+    std::env::var("COMPUTERNAME")
+        .or_else(|_| std::env::var("HOSTNAME"))
+        .ok()
+        .or_else(|| {
+            std::fs::read_to_string("/etc/hostname")
+                .ok()
+                .map(|name| name.trim().to_string())
+        })
+        .filter(|name| !name.is_empty())
+        .unwrap_or_else(|| "ntfier".to_string())
+}
@@ -0,0 +1,161 @@
+//! Escalation rules for unacknowledged high priority notifications.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::notification::{Notification, Priority};
+use super::settings::ReceiptTopic;
+use crate::config::escalation::MAX_REPEATS;
+use crate::error::AppError;
+
+/// A rule re-notifying about notifications nobody read or acknowledged.
+///
+/// Every `delay_minutes` a matching notification stays unread and
+/// unacknowledged, it's shown again with an alarm sound and, with
+/// `publish_to` set, an escalation message is published there, up to
+/// `max_repeats` times.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct EscalationRule {
+    pub id: String,
+    /// Subscription the rule applies to; `None` for all of them.
+    pub subscription_id: Option<String>,
+    /// Lowest priority escalated.
+    #[specta(type = u8)]
+    pub min_priority: Priority,
+    pub delay_minutes: u32,
+    pub max_repeats: u32,
+    /// Topic escalation messages are published to, e.g. a colleague's.
+    pub publish_to: Option<ReceiptTopic>,
+    /// When the rule was created (milliseconds).
+    pub created_at: i64,
+}
+
+/// Data required to create an escalation rule.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateEscalationRule {
+    pub subscription_id: Option<String>,
+    #[specta(type = u8)]
+    pub min_priority: Priority,
+    pub delay_minutes: u32,
+    pub max_repeats: u32,
+    pub publish_to: Option<ReceiptTopic>,
+}
+
+impl CreateEscalationRule {
+    /// Validates the rule. Only high and max priority notifications can be
+    /// escalated.
+    pub fn validate(&self) -> Result<(), AppError> {
+        if (self.min_priority as u8) < Priority::High as u8 {
+            return Err(AppError::Validation(
+                "Only high and max priority notifications can be escalated".to_string(),
+            ));
+        }
+        if self.delay_minutes == 0 {
+            return Err(AppError::Validation(
+                "Escalation delay must be at least a minute".to_string(),
+            ));
+        }
+        if !(1..=MAX_REPEATS).contains(&self.max_repeats) {
+            return Err(AppError::Validation(format!(
+                "Escalations must repeat 1 to {MAX_REPEATS} times"
+            )));
+        }
+        if let Some(ref topic) = self.publish_to {
+            topic.validate()?;
+        }
+        Ok(())
+    }
+}
+
+impl EscalationRule {
+    /// Checks whether the rule applies to `notification`.
+    pub fn matches(&self, notification: &Notification) -> bool {
+        notification.priority as u8 >= self.min_priority as u8
+            && self
+                .subscription_id
+                .as_ref()
+                .map_or(true, |id| *id == notification.topic_id)
+    }
+
+    /// Returns when a notification is escalated next (milliseconds), given
+    /// how often and when it was last escalated, or `None` once it was
+    /// escalated `max_repeats` times.
+    pub fn next_escalation(
+        &self,
+        notification: &Notification,
+        escalated: Escalated,
+    ) -> Option<i64> {
+        if escalated.count >= self.max_repeats {
+            return None;
+        }
+        let since = escalated.last_at.unwrap_or(notification.timestamp);
+        Some(since + i64::from(self.delay_minutes) * 60 * 1000)
+    }
+}
+
+/// How often and when a notification was escalated.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct Escalated {
+    pub count: u32,
+    /// When it was last escalated (milliseconds).
+    pub last_at: Option<i64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::NtfyMessage;
+
+    #[test]
+    fn test_escalation_rule() {
+        let rule = EscalationRule {
+            id: "rule".to_string(),
+            subscription_id: Some("sub".to_string()),
+            min_priority: Priority::High,
+            delay_minutes: 5,
+            max_repeats: 2,
+            publish_to: None,
+            created_at: 0,
+        };
+        let mut notification = NtfyMessage {
+            id: "m1".to_string(),
+            time: 1000,
+            event: "message".to_string(),
+            topic: "alerts".to_string(),
+            message: Some("Disk full".to_string()),
+            title: None,
+            priority: Some(4),
+            tags: None,
+            click: None,
+            actions: None,
+            attachment: None,
+            content_type: None,
+        }
+        .into_notification("sub".to_string());
+        assert!(rule.matches(&notification));
+
+        let first = rule.next_escalation(&notification, Escalated::default());
+        assert_eq!(first, Some(1_000_000 + 300_000));
+        let escalated = Escalated {
+            count: 1,
+            last_at: Some(2_000_000),
+        };
+        assert_eq!(
+            rule.next_escalation(&notification, escalated),
+            Some(2_300_000)
+        );
+        let escalated = Escalated {
+            count: 2,
+            last_at: Some(2_300_000),
+        };
+        assert_eq!(rule.next_escalation(&notification, escalated), None);
+
+        notification.priority = Priority::Default;
+        assert!(!rule.matches(&notification));
+        notification.priority = Priority::Max;
+        notification.topic_id = "other".to_string();
+        assert!(!rule.matches(&notification));
+    }
+}
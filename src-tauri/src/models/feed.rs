@@ -0,0 +1,52 @@
+//! The unified feed of notifications from all subscriptions.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::notification::{Notification, Priority};
+
+/// Which notifications the feed includes.
+#[derive(Debug, Clone, Default, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedFilter {
+    /// Subscriptions included; empty for all of them.
+    #[serde(default)]
+    pub subscription_ids: Vec<String>,
+    /// Only unread notifications are included.
+    #[serde(default)]
+    pub unread_only: bool,
+    /// Lowest priority included.
+    #[serde(default)]
+    #[specta(type = Option<u8>)]
+    pub min_priority: Option<Priority>,
+}
+
+/// Position in the feed: the feed continues with notifications older than
+/// this one. Stays valid while new notifications arrive, unlike an offset.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedCursor {
+    /// Timestamp of the last notification of a page (milliseconds).
+    pub timestamp: i64,
+    /// ID of the last notification of a page, ordering notifications with
+    /// the same timestamp.
+    pub id: String,
+}
+
+impl From<&Notification> for FeedCursor {
+    fn from(notification: &Notification) -> Self {
+        Self {
+            timestamp: notification.timestamp,
+            id: notification.id.clone(),
+        }
+    }
+}
+
+/// A page of the feed, newest first.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FeedPage {
+    pub notifications: Vec<Notification>,
+    /// Where the next page starts; `None` on the last page.
+    pub next_cursor: Option<FeedCursor>,
+}
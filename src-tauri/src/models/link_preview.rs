@@ -0,0 +1,188 @@
+//! Open Graph link previews for URLs in messages.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use url::Url;
+
+/// Preview metadata of a link found in a notification's message.
+#[derive(Debug, Clone, Default, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct LinkPreview {
+    /// The previewed URL as it appears in the message.
+    pub url: String,
+    pub title: Option<String>,
+    pub site_name: Option<String>,
+    pub description: Option<String>,
+    /// Remote thumbnail URL from `og:image`.
+    pub image_url: Option<String>,
+    /// Local path of the thumbnail in the image cache, if it was downloaded.
+    /// Cached images are cleaned up after a while, so the file may be gone.
+    pub image_path: Option<String>,
+}
+
+impl LinkPreview {
+    /// Whether the page provided anything worth showing.
+    pub const fn is_empty(&self) -> bool {
+        self.title.is_none() && self.description.is_none() && self.image_url.is_none()
+    }
+}
+
+/// Finds the first bare http(s) URL in a message.
+///
+/// URLs inside markdown links or images (`[text](url)`) are not bare and are skipped.
+pub fn find_bare_url(message: &str) -> Option<String> {
+    message.split_whitespace().find_map(|word| {
+        if !(word.starts_with("http://") || word.starts_with("https://")) {
+            return None;
+        }
+        // Drop punctuation that ends the sentence rather than the URL
+        let url = word.trim_end_matches(['.', ',', ';', ':', '!', '?', ')', ']', '>', '"', '\'']);
+        Url::parse(url)
+            .ok()
+            .filter(|u| u.host().is_some())
+            .map(|_| url.to_string())
+    })
+}
+
+/// Extracts Open Graph metadata from an HTML page.
+///
+/// Falls back to the `<title>` element when there is no `og:title`. Relative
+/// image URLs are resolved against `page_url`.
+pub fn parse_open_graph(html: &str, page_url: &str) -> LinkPreview {
+    let mut preview = LinkPreview {
+        url: page_url.to_string(),
+        ..Default::default()
+    };
+
+    let lower = html.to_ascii_lowercase();
+    let mut pos = 0;
+    while let Some(start) = lower[pos..].find("<meta") {
+        let start = pos + start;
+        let Some(end) = lower[start..].find('>') else {
+            break;
+        };
+        let tag = &html[start..start + end];
+        pos = start + end;
+
+        let key = attribute(tag, "property").or_else(|| attribute(tag, "name"));
+        let (Some(key), Some(content)) = (key, attribute(tag, "content")) else {
+            continue;
+        };
+        let content = decode_entities(content.trim());
+        if content.is_empty() {
+            continue;
+        }
+
+        let slot = match key.to_ascii_lowercase().as_str() {
+            "og:title" => &mut preview.title,
+            "og:site_name" => &mut preview.site_name,
+            "og:description" | "description" => &mut preview.description,
+            "og:image" | "og:image:url" => &mut preview.image_url,
+            _ => continue,
+        };
+        // The first tag wins; `description` only fills in for a missing `og:description`
+        if slot.is_none() {
+            *slot = Some(content);
+        }
+    }
+
+    if preview.title.is_none() {
+        preview.title = lower.find("<title").and_then(|start| {
+            let text_start = start + lower[start..].find('>')? + 1;
+            let text_end = text_start + lower[text_start..].find("</title")?;
+            Some(decode_entities(html[text_start..text_end].trim())).filter(|t| !t.is_empty())
+        });
+    }
+
+    preview.image_url = preview.image_url.and_then(|image| {
+        Url::parse(page_url)
+            .and_then(|base| base.join(&image))
+            .ok()
+            .filter(|u| matches!(u.scheme(), "http" | "https"))
+            .map(String::from)
+    });
+
+    preview
+}
+
+/// Returns the value of a quoted attribute in an HTML tag.
+fn attribute<'a>(tag: &'a str, name: &str) -> Option<&'a str> {
+    let lower = tag.to_ascii_lowercase();
+    let mut pos = 0;
+    while let Some(found) = lower[pos..].find(name) {
+        let at = pos + found;
+        pos = at + name.len();
+
+        // Must be a whole attribute name followed by `=`
+        let preceded_by_space = lower[..at].ends_with(|c: char| c.is_ascii_whitespace());
+        let rest = lower[pos..].trim_start();
+        if !preceded_by_space || !rest.starts_with('=') {
+            continue;
+        }
+
+        let value_start = tag.len() - rest.len() + 1;
+        let value = tag[value_start..].trim_start();
+        let quote = value.chars().next().filter(|c| *c == '"' || *c == '\'')?;
+        let value = &value[1..];
+        return value.find(quote).map(|end| &value[..end]);
+    }
+    None
+}
+
+/// Decodes the HTML entities commonly found in meta content.
+fn decode_entities(text: &str) -> String {
+    text.replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_find_bare_url() {
+        assert_eq!(
+            find_bare_url("Deployed, see https://example.com/run/1."),
+            Some("https://example.com/run/1".to_string())
+        );
+        assert_eq!(
+            find_bare_url("See [the run](https://example.com/run/1)"),
+            None
+        );
+        assert_eq!(find_bare_url("No links here"), None);
+    }
+
+    #[test]
+    fn test_parse_open_graph() {
+        let html = r#"<html><head>
+            <title>Fallback</title>
+            <meta property="og:title" content="Release v1.2 &amp; notes">
+            <meta property="og:site_name" content='GitHub'>
+            <meta name="description" content="Plain description">
+            <meta property="og:image" content="/img/card.png" />
+        </head></html>"#;
+        let preview = parse_open_graph(html, "https://example.com/releases/1");
+        assert_eq!(preview.title.as_deref(), Some("Release v1.2 & notes"));
+        assert_eq!(preview.site_name.as_deref(), Some("GitHub"));
+        assert_eq!(preview.description.as_deref(), Some("Plain description"));
+        assert_eq!(
+            preview.image_url.as_deref(),
+            Some("https://example.com/img/card.png")
+        );
+    }
+
+    #[test]
+    fn test_title_fallback() {
+        let preview = parse_open_graph(
+            "<head><TITLE> Status page </TITLE></head>",
+            "https://example.com",
+        );
+        assert_eq!(preview.title.as_deref(), Some("Status page"));
+        assert!(preview.image_url.is_none());
+    }
+}
@@ -0,0 +1,26 @@
+//! Results of cleaning up orphaned data.
+
+use serde::Serialize;
+use specta::Type;
+
+/// What a maintenance run removed.
+#[derive(Debug, Clone, Default, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct MaintenanceReport {
+    /// Notifications whose subscription no longer exists.
+    pub orphaned_notifications: u32,
+    /// Other rows left behind by deleted subscriptions, notifications or
+    /// channels (settings, permissions, escalations and the like).
+    pub orphaned_rows: u32,
+    /// Cached images no notification refers to any more.
+    pub cached_images: u32,
+    /// Keychain entries of removed servers.
+    pub credentials: u32,
+}
+
+impl MaintenanceReport {
+    /// Total number of items removed.
+    pub const fn total(&self) -> u32 {
+        self.orphaned_notifications + self.orphaned_rows + self.cached_images + self.credentials
+    }
+}
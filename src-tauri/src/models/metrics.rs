@@ -0,0 +1,67 @@
+//! Internal performance metrics for diagnosing slowdowns in the field.
+
+use serde::Serialize;
+use specta::Type;
+use std::time::Duration;
+
+/// Aggregated durations of one kind of operation.
+#[derive(Debug, Clone, Default, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TimingStats {
+    pub count: u32,
+    pub total_ms: f64,
+    pub mean_ms: f64,
+    pub max_ms: f64,
+}
+
+impl TimingStats {
+    /// Adds one measured duration.
+    pub fn record(&mut self, elapsed: Duration) {
+        let ms = elapsed.as_secs_f64() * 1000.0;
+        self.count += 1;
+        self.total_ms += ms;
+        self.mean_ms = self.total_ms / f64::from(self.count);
+        self.max_ms = self.max_ms.max(ms);
+    }
+}
+
+/// Timings of a single command.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CommandTiming {
+    pub command: String,
+    pub timing: TimingStats,
+}
+
+/// Metrics collected since the app started.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct InternalMetrics {
+    /// When collection started (milliseconds).
+    pub since: i64,
+    /// Command handling times until the command returned its result,
+    /// slowest in total first.
+    pub commands: Vec<CommandTiming>,
+    /// Time spent waiting for the database connection.
+    pub db_lock_wait: TimingStats,
+    /// WebSocket reconnects scheduled after a connection failed or closed.
+    pub ws_reconnects: u32,
+    /// Durations of completed per-subscription history syncs.
+    pub syncs: TimingStats,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_timing_stats_record() {
+        let mut stats = TimingStats::default();
+        stats.record(Duration::from_millis(10));
+        stats.record(Duration::from_millis(30));
+        assert_eq!(stats.count, 2);
+        assert!((stats.total_ms - 40.0).abs() < 1e-9);
+        assert!((stats.mean_ms - 20.0).abs() < 1e-9);
+        assert!((stats.max_ms - 30.0).abs() < 1e-9);
+    }
+}
@@ -1,12 +1,60 @@
+mod blocklist;
+mod capture;
+mod channel;
+mod clipboard;
+mod config_file;
+mod device;
+mod escalation;
+mod feed;
+mod link_preview;
+mod maintenance;
+mod metrics;
 mod notification;
+mod pattern;
+mod permission;
+mod provision;
+mod publish;
+mod reminder;
+mod resolution;
+mod rule;
 mod server_url;
 mod settings;
+mod shortcut;
+mod snippet;
+mod statistics;
+mod status;
 mod subscription;
+mod template;
+mod virtual_topic;
 
+pub use blocklist::*;
+pub use capture::*;
+pub use channel::*;
+pub use clipboard::*;
+pub use config_file::*;
+pub use device::*;
+pub use escalation::*;
+pub use feed::*;
+pub use link_preview::*;
+pub use maintenance::*;
+pub use metrics::*;
 pub use notification::*;
+pub use pattern::wildcard_match;
+pub use permission::*;
+pub use provision::*;
+pub use publish::*;
+pub use reminder::*;
+pub use resolution::*;
+pub use rule::*;
 pub use server_url::normalize_url;
 pub use settings::*;
+pub use shortcut::*;
+pub use snippet::*;
+pub use statistics::*;
+pub use status::*;
 pub use subscription::*;
+pub use template::*;
+pub use virtual_topic::*;
 
 // Re-export for future use
 #[allow(unused_imports)]
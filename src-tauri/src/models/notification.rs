@@ -6,6 +6,9 @@
 use serde::{Deserialize, Serialize};
 use serde_repr::{Deserialize_repr, Serialize_repr};
 use specta::Type;
+use std::collections::BTreeMap;
+
+use super::LinkPreview;
 
 /// Notification priority levels matching ntfy's 1-5 scale.
 ///
@@ -33,6 +36,46 @@ impl From<i8> for Priority {
     }
 }
 
+/// Format of a notification's message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ContentKind {
+    #[default]
+    Plain,
+    /// Declared as `text/markdown` by the publisher.
+    Markdown,
+}
+
+impl ContentKind {
+    /// Returns the value stored in the database.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Plain => "plain",
+            Self::Markdown => "markdown",
+        }
+    }
+
+    /// Parses a value stored in the database.
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "markdown" => Self::Markdown,
+            _ => Self::Plain,
+        }
+    }
+
+    /// Determines the format from ntfy's `content_type` field.
+    pub fn from_content_type(content_type: Option<&str>) -> Self {
+        let is_markdown = content_type
+            .and_then(|ct| ct.split(';').next())
+            .is_some_and(|mime| mime.trim().eq_ignore_ascii_case("text/markdown"));
+        if is_markdown {
+            Self::Markdown
+        } else {
+            Self::Plain
+        }
+    }
+}
+
 /// A notification stored in the local database.
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 #[serde(rename_all = "camelCase")]
@@ -54,6 +97,78 @@ pub struct Notification {
     pub is_expanded: bool,
     /// Whether the notification is marked as favorite.
     pub is_favorite: bool,
+    /// When the notification was moved to the trash (milliseconds), if it was.
+    pub deleted_at: Option<i64>,
+    /// Fields extracted from a known producer's payload, if recognized.
+    pub parsed: Option<ParsedMessage>,
+    /// Preview of the first bare URL in the message, once fetched.
+    pub link_preview: Option<LinkPreview>,
+    /// Who acknowledged the notification, if anyone did.
+    pub acknowledged_by: Option<String>,
+    /// When the notification was acknowledged (milliseconds).
+    pub acknowledged_at: Option<i64>,
+    /// The user's own note about the notification (kept locally).
+    pub note: Option<String>,
+    /// Whether the message is plain text or markdown.
+    pub content_kind: ContentKind,
+    /// When a snoozed notification is shown again (milliseconds).
+    pub snoozed_until: Option<i64>,
+    /// Whether the message mentions the user (see `mentions` in settings).
+    pub mentioned: bool,
+}
+
+impl Notification {
+    /// Returns a copy safe to show where others may see it: the title is
+    /// replaced, and the body, actions and attachments are dropped.
+    pub fn redacted(&self, title: String) -> Self {
+        Self {
+            title,
+            message: String::new(),
+            actions: Vec::new(),
+            attachments: Vec::new(),
+            parsed: None,
+            link_preview: None,
+            note: None,
+            ..self.clone()
+        }
+    }
+
+    /// Checks whether the title or message contains one of `mentions` as a
+    /// whole word, ignoring case.
+    pub fn mentions(&self, mentions: &[String]) -> bool {
+        let title = self.title.to_lowercase();
+        let message = self.message.to_lowercase();
+        mentions
+            .iter()
+            .map(|mention| mention.trim().to_lowercase())
+            .filter(|mention| !mention.is_empty())
+            .any(|mention| contains_word(&title, &mention) || contains_word(&message, &mention))
+    }
+}
+
+/// Checks whether `text` contains `word` not directly preceded or followed
+/// by a letter, digit or underscore.
+fn contains_word(text: &str, word: &str) -> bool {
+    let is_word_char = |c: char| c.is_alphanumeric() || c == '_';
+    text.match_indices(word).any(|(start, _)| {
+        let before = text[..start].chars().next_back();
+        let after = text[start + word.len()..].chars().next();
+        !before.is_some_and(is_word_char) && !after.is_some_and(is_word_char)
+    })
+}
+
+/// Fields extracted from a structured payload by a message parser.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ParsedMessage {
+    /// Producer the payload was recognized as (e.g. `grafana`).
+    pub source: String,
+    /// Producer-specific state, e.g. `firing`, `down` or `failure`.
+    pub status: Option<String>,
+    /// Host, instance or repository the message is about.
+    pub host: Option<String>,
+    /// Link to the alert, monitor or run.
+    pub link: Option<String>,
 }
 
 /// An action button attached to a notification.
@@ -61,12 +176,32 @@ pub struct Notification {
 #[serde(rename_all = "camelCase")]
 pub struct NotificationAction {
     pub id: String,
+    /// Action type: `view`, `http` or `broadcast`.
+    #[serde(default = "default_action_kind")]
+    pub action: String,
     pub label: String,
     pub url: Option<String>,
     pub method: Option<String>,
+    /// Request headers of an `http` action.
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// Request body of an `http` action.
+    #[serde(default)]
+    pub body: Option<String>,
+    /// Android intent of a `broadcast` action.
+    #[serde(default)]
+    pub intent: Option<String>,
+    /// Intent extras of a `broadcast` action.
+    #[serde(default)]
+    pub extras: BTreeMap<String, String>,
     pub clear: bool,
 }
 
+/// Actions stored before the type was kept were shown as links.
+fn default_action_kind() -> String {
+    "view".to_string()
+}
+
 /// A file attachment on a notification.
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 #[serde(rename_all = "camelCase")]
@@ -98,6 +233,8 @@ pub struct NtfyMessage {
     pub click: Option<String>,
     pub actions: Option<Vec<NtfyAction>>,
     pub attachment: Option<NtfyAttachment>,
+    /// `text/markdown` for markdown messages; absent for plain text.
+    pub content_type: Option<String>,
 }
 
 #[allow(dead_code)]
@@ -108,6 +245,10 @@ pub struct NtfyAction {
     pub label: String,
     pub url: Option<String>,
     pub method: Option<String>,
+    pub headers: Option<BTreeMap<String, String>>,
+    pub body: Option<String>,
+    pub intent: Option<String>,
+    pub extras: Option<BTreeMap<String, String>>,
     pub clear: Option<bool>,
 }
 
@@ -126,9 +267,14 @@ impl From<NtfyAction> for NotificationAction {
     fn from(action: NtfyAction) -> Self {
         Self {
             id: action.id,
+            action: action.action,
             label: action.label,
             url: action.url,
             method: action.method,
+            headers: action.headers.unwrap_or_default(),
+            body: action.body,
+            intent: action.intent,
+            extras: action.extras.unwrap_or_default(),
             clear: action.clear.unwrap_or(false),
         }
     }
@@ -179,6 +325,15 @@ impl NtfyMessage {
             read: false,
             is_expanded: false,
             is_favorite: false,
+            deleted_at: None,
+            parsed: None,
+            link_preview: None,
+            acknowledged_by: None,
+            acknowledged_at: None,
+            note: None,
+            content_kind: ContentKind::from_content_type(self.content_type.as_deref()),
+            snoozed_until: None,
+            mentioned: false,
         }
     }
 
@@ -187,3 +342,66 @@ impl NtfyMessage {
         &self.id
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_action_keeps_full_schema() -> Result<(), serde_json::Error> {
+        let json = r#"{"id":"a1","action":"http","label":"Close door","url":"https://api.example.com/door",
+            "method":"PUT","headers":{"Authorization":"Bearer x"},"body":"{\"open\":false}","clear":true}"#;
+        let action = NotificationAction::from(serde_json::from_str::<NtfyAction>(json)?);
+        assert_eq!(action.action, "http");
+        assert_eq!(
+            action.headers.get("Authorization").map(String::as_str),
+            Some("Bearer x")
+        );
+        assert_eq!(action.body.as_deref(), Some("{\"open\":false}"));
+        assert!(action.clear);
+
+        let json = r#"{"id":"a2","action":"broadcast","label":"Take photo",
+            "intent":"io.heckel.ntfy.USER_ACTION","extras":{"cmd":"pic","camera":"front"}}"#;
+        let action = NotificationAction::from(serde_json::from_str::<NtfyAction>(json)?);
+        assert_eq!(action.intent.as_deref(), Some("io.heckel.ntfy.USER_ACTION"));
+        assert_eq!(action.extras.len(), 2);
+        Ok(())
+    }
+
+    #[test]
+    fn test_stored_action_without_new_fields() -> Result<(), serde_json::Error> {
+        let json =
+            r#"{"id":"a1","label":"Open","url":"https://example.com","method":null,"clear":false}"#;
+        let action: NotificationAction = serde_json::from_str(json)?;
+        assert_eq!(action.action, "view");
+        assert!(action.headers.is_empty());
+        assert!(action.extras.is_empty());
+        Ok(())
+    }
+
+    #[test]
+    fn test_mentions() {
+        let notification = NtfyMessage {
+            id: "m1".to_string(),
+            time: 0,
+            event: "message".to_string(),
+            topic: "chat".to_string(),
+            message: Some("Could @Jan_K take a look? Thanks, Janet".to_string()),
+            title: Some("Review for Jan".to_string()),
+            priority: None,
+            tags: None,
+            click: None,
+            actions: None,
+            attachment: None,
+            content_type: None,
+        }
+        .into_notification("sub".to_string());
+
+        assert!(notification.mentions(&["jan".to_string()]));
+        assert!(notification.mentions(&["@jan_k".to_string()]));
+        assert!(notification.mentions(&["Janet".to_string()]));
+        assert!(!notification.mentions(&["@jan".to_string(), "jane".to_string()]));
+        assert!(!notification.mentions(&[" ".to_string()]));
+        assert!(!notification.mentions(&[]));
+    }
+}
@@ -0,0 +1,63 @@
+//! Wildcard pattern matching for topics and server URLs.
+
+/// Checks whether `text` matches a wildcard `pattern`.
+///
+/// `*` matches any sequence of characters (including none); every other
+/// character must match exactly.
+pub fn wildcard_match(pattern: &str, text: &str) -> bool {
+    let pattern: Vec<char> = pattern.chars().collect();
+    let text: Vec<char> = text.chars().collect();
+
+    let (mut p, mut t) = (0, 0);
+    // Position of the last `*` seen and the text index it was tried at
+    let mut backtrack: Option<(usize, usize)> = None;
+
+    while t < text.len() {
+        if p < pattern.len() && pattern[p] == '*' {
+            backtrack = Some((p, t));
+            p += 1;
+        } else if p < pattern.len() && pattern[p] == text[t] {
+            p += 1;
+            t += 1;
+        } else if let Some((star, star_t)) = backtrack {
+            // Let the last `*` swallow one more character and retry
+            p = star + 1;
+            t = star_t + 1;
+            backtrack = Some((star, t));
+        } else {
+            return false;
+        }
+    }
+
+    pattern[p..].iter().all(|&c| c == '*')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exact_match() {
+        assert!(wildcard_match("alerts", "alerts"));
+        assert!(!wildcard_match("alerts", "alerts2"));
+    }
+
+    #[test]
+    fn test_star_matches_any_sequence() {
+        assert!(wildcard_match("home-sensor-*", "home-sensor-kitchen"));
+        assert!(wildcard_match("home-sensor-*", "home-sensor-"));
+        assert!(wildcard_match("*", ""));
+        assert!(wildcard_match("*-backup", "nas-backup"));
+        assert!(!wildcard_match("home-sensor-*", "home-light-1"));
+    }
+
+    #[test]
+    fn test_multiple_stars_backtrack() {
+        assert!(wildcard_match("*a*b", "xaxxab"));
+        assert!(wildcard_match(
+            "https://*.example.com",
+            "https://ntfy.example.com"
+        ));
+        assert!(!wildcard_match("*a*b", "xaxxa"));
+    }
+}
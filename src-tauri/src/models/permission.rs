@@ -0,0 +1,71 @@
+//! Permission model for actions that reach outside the app.
+//!
+//! Messages from a topic can ask the app to make HTTP requests, run commands,
+//! write webhook output or open links. Unless the topic is trusted, each
+//! capability needs explicit user consent, which may be remembered per
+//! subscription.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// A capability a notification can ask the app to use.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum Capability {
+    /// Send an HTTP request from an action button.
+    HttpAction,
+    /// Run a local command or script.
+    RunCommand,
+    /// Write notification data to local webhook outputs.
+    Webhook,
+    /// Open a link from a click or view action in the browser.
+    OpenUrl,
+}
+
+impl Capability {
+    /// Returns the value stored in the database.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::HttpAction => "http_action",
+            Self::RunCommand => "run_command",
+            Self::Webhook => "webhook",
+            Self::OpenUrl => "open_url",
+        }
+    }
+
+    /// Parses a value stored in the database.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "http_action" => Some(Self::HttpAction),
+            "run_command" => Some(Self::RunCommand),
+            "webhook" => Some(Self::Webhook),
+            "open_url" => Some(Self::OpenUrl),
+            _ => None,
+        }
+    }
+}
+
+/// A remembered permission decision.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct Permission {
+    pub id: String,
+    pub subscription_id: String,
+    pub capability: Capability,
+    pub allowed: bool,
+    /// When the decision was made (milliseconds).
+    pub created_at: i64,
+}
+
+/// Payload of the `permission:request` event asking the user for consent.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PermissionRequest {
+    /// Identifier to pass back to `respond_permission`.
+    pub request_id: String,
+    pub subscription_id: String,
+    pub topic: String,
+    pub capability: Capability,
+    /// Human-readable description of what will happen (e.g. the target URL).
+    pub detail: String,
+}
@@ -0,0 +1,293 @@
+//! Servers and subscriptions provisioned for managed deployments.
+//!
+//! IT can pre-configure the client on a fleet of machines through a JSON
+//! file named by `NTFIER_PROVISION`, or for a single server through
+//! `NTFIER_SERVER`, `NTFIER_USERNAME`, `NTFIER_PASSWORD` and `NTFIER_TOPICS`
+//! (comma-separated).
+//!
+//! The file can also carry a policy pinning settings users can't change.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::pattern::wildcard_match;
+use super::resolution::ServerResolution;
+use super::settings::ServerConfig;
+use super::subscription::CreateSubscription;
+use crate::config::provisioning::{PASSWORD_VAR, SERVER_VAR, TOPICS_VAR, USERNAME_VAR};
+use crate::error::AppError;
+
+/// Servers and subscriptions to add if they're missing, and the policy.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Provisioning {
+    #[serde(default)]
+    pub servers: Vec<ProvisionedServer>,
+    #[serde(default)]
+    pub subscriptions: Vec<CreateSubscription>,
+    #[serde(default)]
+    pub policy: Policy,
+}
+
+/// Settings locked by the provisioning file. Commands changing them fail
+/// with `AppError::PolicyLocked`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct Policy {
+    /// Hosts of servers that can't be added or subscribed to, e.g. `ntfy.sh`;
+    /// `*` matches any characters.
+    #[serde(default)]
+    pub blocked_servers: Vec<String>,
+    /// SOCKS5 proxy (`host:port`) every server is reached through.
+    pub socks_proxy: Option<String>,
+    /// Whether publishing attachments is disabled.
+    #[serde(default)]
+    pub disable_attachments: bool,
+}
+
+impl Policy {
+    /// Validates the policy.
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.blocked_servers.iter().any(|s| s.trim().is_empty()) {
+            return Err(AppError::Validation(
+                "Blocked server cannot be empty".to_string(),
+            ));
+        }
+        ServerResolution {
+            socks_proxy: self.socks_proxy.clone(),
+            ..ServerResolution::default()
+        }
+        .validate()
+    }
+
+    /// Fails if servers at `url` can't be added or subscribed to.
+    pub fn check_server(&self, url: &str) -> Result<(), AppError> {
+        let host = url::Url::parse(url.trim())
+            .ok()
+            .and_then(|url| url.host_str().map(str::to_ascii_lowercase))
+            .unwrap_or_default();
+        let blocked = self
+            .blocked_servers
+            .iter()
+            .any(|pattern| wildcard_match(&pattern.trim().to_ascii_lowercase(), &host));
+        if blocked {
+            return Err(AppError::PolicyLocked(format!("Server {host} is blocked")));
+        }
+        Ok(())
+    }
+
+    /// Fails if `resolution` sets a different proxy than the one forced.
+    pub fn check_resolution(&self, resolution: &ServerResolution) -> Result<(), AppError> {
+        let Some(proxy) = &self.socks_proxy else {
+            return Ok(());
+        };
+        if resolution.socks_proxy.as_deref().map(str::trim) != Some(proxy.trim()) {
+            return Err(AppError::PolicyLocked(format!(
+                "Servers are reached through the SOCKS proxy {proxy}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// Fails if publishing attachments is disabled.
+    pub fn check_attachments(&self) -> Result<(), AppError> {
+        if self.disable_attachments {
+            return Err(AppError::PolicyLocked(
+                "Attachments are disabled".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// `resolution` with the forced proxy, if any.
+    pub fn resolution(&self, resolution: ServerResolution) -> ServerResolution {
+        match &self.socks_proxy {
+            Some(proxy) => ServerResolution {
+                socks_proxy: Some(proxy.trim().to_string()),
+                ..resolution
+            },
+            None => resolution,
+        }
+    }
+}
+
+/// A provisioned server. Its password is stored in the OS keychain when the
+/// server is added.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase", deny_unknown_fields)]
+pub struct ProvisionedServer {
+    pub url: String,
+    pub username: Option<String>,
+    pub password: Option<String>,
+    #[serde(default)]
+    pub default: bool,
+}
+
+impl ProvisionedServer {
+    /// The server as configured in the app.
+    pub fn config(&self) -> ServerConfig {
+        ServerConfig {
+            url: self.url.trim().trim_end_matches('/').to_string(),
+            username: self.username.clone().filter(|u| !u.is_empty()),
+            password: self.password.clone().filter(|p| !p.is_empty()),
+            is_default: self.default,
+        }
+    }
+}
+
+impl Provisioning {
+    /// Parses and validates a provisioning file.
+    pub fn parse(json: &str) -> Result<Self, AppError> {
+        let mut provisioning: Self = serde_json::from_str(json)?;
+        provisioning.policy.validate()?;
+        for server in &provisioning.servers {
+            server.config().validate()?;
+        }
+        for sub in &mut provisioning.subscriptions {
+            sub.server_url = sub.server_url.trim().trim_end_matches('/').to_string();
+            sub.validate()?;
+        }
+        Ok(provisioning)
+    }
+
+    /// Reads the single server provisioned through environment variables,
+    /// looked up with `var`. Returns `None` if `NTFIER_SERVER` isn't set.
+    pub fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Option<Self>, AppError> {
+        let Some(url) = var(SERVER_VAR).filter(|url| !url.trim().is_empty()) else {
+            if var(TOPICS_VAR).is_some() {
+                return Err(AppError::Validation(format!(
+                    "{TOPICS_VAR} requires {SERVER_VAR}"
+                )));
+            }
+            return Ok(None);
+        };
+
+        let server = ProvisionedServer {
+            url,
+            username: var(USERNAME_VAR),
+            password: var(PASSWORD_VAR),
+            default: false,
+        };
+        server.config().validate()?;
+
+        let server_url = server.config().url;
+        let subscriptions = var(TOPICS_VAR)
+            .unwrap_or_default()
+            .split(',')
+            .map(str::trim)
+            .filter(|topic| !topic.is_empty())
+            .map(|topic| CreateSubscription {
+                topic: topic.to_string(),
+                server_url: server_url.clone(),
+                display_name: None,
+            })
+            .collect::<Vec<_>>();
+        for sub in &subscriptions {
+            sub.validate()?;
+        }
+
+        Ok(Some(Self {
+            servers: vec![server],
+            subscriptions,
+            policy: Policy::default(),
+        }))
+    }
+
+    /// Adds the servers and subscriptions of `other`. Its policy is ignored.
+    pub fn merge(&mut self, other: Self) {
+        self.servers.extend(other.servers);
+        self.subscriptions.extend(other.subscriptions);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_provisioning_from_vars() {
+        let vars = HashMap::from([
+            (SERVER_VAR, "https://ntfy.corp.example/"),
+            (USERNAME_VAR, "fleet"),
+            (TOPICS_VAR, "alerts, deploys,,"),
+        ]);
+        let var = |name: &str| vars.get(name).map(ToString::to_string);
+
+        let provisioning = Provisioning::from_vars(var);
+        assert!(provisioning.is_ok());
+        let provisioning = provisioning.ok().flatten().unwrap_or_default();
+        assert_eq!(provisioning.servers.len(), 1);
+        assert_eq!(
+            provisioning.servers[0].config().url,
+            "https://ntfy.corp.example"
+        );
+        let topics: Vec<&str> = provisioning
+            .subscriptions
+            .iter()
+            .map(|s| s.topic.as_str())
+            .collect();
+        assert_eq!(topics, ["alerts", "deploys"]);
+        assert!(provisioning
+            .subscriptions
+            .iter()
+            .all(|s| s.server_url == "https://ntfy.corp.example"));
+
+        assert!(matches!(Provisioning::from_vars(|_| None), Ok(None)));
+        let topics_only = |name: &str| (name == TOPICS_VAR).then(|| "alerts".to_string());
+        assert!(Provisioning::from_vars(topics_only).is_err());
+    }
+
+    #[test]
+    fn test_parse_provisioning() {
+        let parsed = Provisioning::parse(
+            r#"{
+                "servers": [{ "url": "https://ntfy.corp.example", "default": true }],
+                "subscriptions": [{ "topic": "alerts", "serverUrl": "https://ntfy.corp.example/" }]
+            }"#,
+        );
+        assert!(parsed.is_ok());
+        let provisioning = parsed.unwrap_or_default();
+        assert!(provisioning.servers[0].default);
+        assert_eq!(
+            provisioning.subscriptions[0].server_url,
+            "https://ntfy.corp.example"
+        );
+
+        assert!(Provisioning::parse(r#"{ "servers": [{ "url": "ftp://x" }] }"#).is_err());
+        assert!(Provisioning::parse(r#"{ "topics": [] }"#).is_err());
+    }
+
+    #[test]
+    fn test_policy() {
+        let parsed = Provisioning::parse(
+            r#"{
+                "policy": {
+                    "blockedServers": ["ntfy.sh", "*.ntfy.sh"],
+                    "socksProxy": "proxy.corp.example:1080",
+                    "disableAttachments": true
+                }
+            }"#,
+        );
+        assert!(parsed.is_ok());
+        let policy = parsed.unwrap_or_default().policy;
+
+        assert!(matches!(
+            policy.check_server("https://NTFY.sh/"),
+            Err(AppError::PolicyLocked(_))
+        ));
+        assert!(policy.check_server("https://eu.ntfy.sh").is_err());
+        assert!(policy.check_server("https://ntfy.corp.example").is_ok());
+
+        let resolution = policy.resolution(ServerResolution::default());
+        assert!(policy.check_resolution(&resolution).is_ok());
+        assert!(policy
+            .check_resolution(&ServerResolution::default())
+            .is_err());
+        assert!(policy.check_attachments().is_err());
+        assert!(Policy::default().check_attachments().is_ok());
+
+        assert!(Provisioning::parse(r#"{ "policy": { "socksProxy": "tor" } }"#).is_err());
+        assert!(Provisioning::parse(r#"{ "policy": { "lockAll": true } }"#).is_err());
+    }
+}
@@ -0,0 +1,105 @@
+//! Messages published from the app.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::notification::Priority;
+use crate::error::AppError;
+
+/// A message to publish to a subscription's topic. Fields left unset fall
+/// back to the server's publishing defaults.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishMessage {
+    #[serde(default)]
+    pub title: String,
+    pub message: String,
+    /// Tags added to the server's default tags.
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[specta(type = Option<u8>)]
+    pub priority: Option<Priority>,
+    pub markdown: Option<bool>,
+    /// Delivery delay understood by ntfy, e.g. `30m` or `tomorrow, 10am`.
+    pub delay: Option<String>,
+}
+
+/// What happened to a published message.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(tag = "status", rename_all = "camelCase")]
+pub enum PublishOutcome {
+    Sent,
+    /// The server was unreachable; the message waits in the outbox.
+    Queued {
+        entry: OutboxEntry,
+    },
+}
+
+/// A message waiting in the outbox until its server is reachable again.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct OutboxEntry {
+    pub id: String,
+    pub subscription_id: String,
+    pub message: PublishMessage,
+    /// Failed attempts to send it while the server was reachable.
+    pub attempts: u32,
+    /// When the message was queued (milliseconds).
+    pub created_at: i64,
+}
+
+/// Publishing defaults of a server, merged into every message published to it.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PublishDefaults {
+    #[specta(type = Option<u8>)]
+    pub priority: Option<Priority>,
+    #[serde(default)]
+    pub tags: Vec<String>,
+    #[serde(default)]
+    pub markdown: bool,
+    pub delay: Option<String>,
+}
+
+impl PublishDefaults {
+    /// Validates the defaults, rejecting empty tags and delays.
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.tags.iter().any(|t| t.trim().is_empty()) {
+            return Err(AppError::Validation("Tag cannot be empty".to_string()));
+        }
+        if self.delay.as_ref().is_some_and(|d| d.trim().is_empty()) {
+            return Err(AppError::Validation("Delay cannot be empty".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Returns the default tags followed by `tags` not among them
+    /// (case-insensitive).
+    pub fn merge_tags<'a>(&'a self, tags: &[&'a str]) -> Vec<&'a str> {
+        let mut merged: Vec<&str> = self.tags.iter().map(String::as_str).collect();
+        for tag in tags {
+            if !merged.iter().any(|t| t.eq_ignore_ascii_case(tag)) {
+                merged.push(tag);
+            }
+        }
+        merged
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_tags() {
+        let defaults = PublishDefaults {
+            tags: vec!["house".to_string(), "robot".to_string()],
+            ..PublishDefaults::default()
+        };
+        assert_eq!(
+            defaults.merge_tags(&["Robot", "warning"]),
+            vec!["house", "robot", "warning"]
+        );
+        assert_eq!(PublishDefaults::default().merge_tags(&["a"]), vec!["a"]);
+    }
+}
@@ -0,0 +1,217 @@
+//! Local reminders and their cron-like schedules.
+
+use chrono::{DateTime, Datelike, NaiveDateTime, NaiveTime, TimeDelta, TimeZone, Timelike};
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::error::AppError;
+
+/// A reminder shown locally on a recurring schedule.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct Reminder {
+    pub id: String,
+    pub title: Option<String>,
+    pub message: String,
+    /// Cron expression, e.g. `0 9 * * 1-5` for 9:00 on weekdays.
+    pub schedule: String,
+    /// When the reminder is next shown (milliseconds), or `None` if never again.
+    pub next_run: Option<i64>,
+    /// When the reminder was created (milliseconds).
+    pub created_at: i64,
+}
+
+/// A parsed cron expression: minute, hour, day of month, month and day of
+/// week, each as a bit set of the values it matches.
+///
+/// Fields take `*`, values, ranges (`1-5`), lists (`1,15`) and steps (`*/10`).
+/// Day of week runs from 0 (Sunday) to 7 (Sunday again). As in cron, when both
+/// the day of month and the day of week are restricted, a day matching either
+/// one matches. `@hourly`, `@daily`, `@weekly`, `@monthly` and `@yearly` are
+/// accepted as shorthands.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CronSchedule {
+    minutes: u64,
+    hours: u64,
+    days: u64,
+    months: u64,
+    weekdays: u64,
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+impl CronSchedule {
+    /// Parses a cron expression.
+    pub fn parse(expression: &str) -> Result<Self, AppError> {
+        let expression = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            "@yearly" | "@annually" => "0 0 1 1 *",
+            expression => expression,
+        };
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minutes, hours, days, months, weekdays] = fields[..] else {
+            return Err(AppError::Validation(format!(
+                "Schedule must have 5 fields (minute hour day month weekday): {expression}"
+            )));
+        };
+
+        let mut weekday_bits = parse_field(weekdays, 0, 7)?;
+        // 7 is Sunday as well
+        if weekday_bits & (1 << 7) != 0 {
+            weekday_bits = (weekday_bits | 1) & !(1 << 7);
+        }
+        Ok(Self {
+            minutes: parse_field(minutes, 0, 59)?,
+            hours: parse_field(hours, 0, 23)?,
+            days: parse_field(days, 1, 31)?,
+            months: parse_field(months, 1, 12)?,
+            weekdays: weekday_bits,
+            days_restricted: days != "*",
+            weekdays_restricted: weekdays != "*",
+        })
+    }
+
+    /// Returns the first time matching the schedule strictly after `after`,
+    /// or `None` if nothing matches within the next eight years.
+    ///
+    /// Local times skipped by a daylight saving change are skipped as well.
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let tz = after.timezone();
+        let start = after.naive_local();
+        let mut t =
+            start.date().and_hms_opt(start.hour(), start.minute(), 0)? + TimeDelta::minutes(1);
+        // Long enough for schedules only matching February 29
+        let limit = t + TimeDelta::days(8 * 366);
+
+        while t < limit {
+            if !self.matches_day(&t) {
+                t = t.date().succ_opt()?.and_time(NaiveTime::MIN);
+            } else if !has_bit(self.hours, t.hour()) {
+                t = t.date().and_hms_opt(t.hour(), 0, 0)? + TimeDelta::hours(1);
+            } else if !has_bit(self.minutes, t.minute()) {
+                t += TimeDelta::minutes(1);
+            } else if let Some(next) = tz.from_local_datetime(&t).earliest() {
+                return Some(next);
+            } else {
+                t += TimeDelta::minutes(1);
+            }
+        }
+        None
+    }
+
+    fn matches_day(&self, t: &NaiveDateTime) -> bool {
+        if !has_bit(self.months, t.month()) {
+            return false;
+        }
+        let day = has_bit(self.days, t.day());
+        let weekday = has_bit(self.weekdays, t.weekday().num_days_from_sunday());
+        match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => day || weekday,
+            (true, false) => day,
+            (false, true) => weekday,
+            (false, false) => true,
+        }
+    }
+}
+
+const fn has_bit(bits: u64, value: u32) -> bool {
+    bits & (1 << value) != 0
+}
+
+/// Parses one cron field into a bit set of the values between `min` and `max`
+/// it matches.
+fn parse_field(field: &str, min: u32, max: u32) -> Result<u64, AppError> {
+    let invalid = || AppError::Validation(format!("Invalid schedule field: {field}"));
+    let value = |s: &str| {
+        s.parse::<u32>()
+            .ok()
+            .filter(|v| (min..=max).contains(v))
+            .ok_or_else(invalid)
+    };
+
+    let mut bits = 0;
+    for item in field.split(',') {
+        let (range, step) = match item.split_once('/') {
+            Some((range, step)) => (range, step.parse::<u32>().ok().filter(|s| *s > 0)),
+            None => (item, Some(1)),
+        };
+        let step = step.ok_or_else(invalid)?;
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((start, end)) = range.split_once('-') {
+            (value(start)?, value(end)?)
+        } else {
+            let start = value(range)?;
+            // `5/10` runs from 5 to the end of the range, like `5-max/10`
+            (start, if step > 1 { max } else { start })
+        };
+        if start > end {
+            return Err(invalid());
+        }
+        for v in (start..=end).step_by(step as usize) {
+            bits |= 1 << v;
+        }
+    }
+    Ok(bits)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn at(s: &str) -> Result<DateTime<Utc>, chrono::ParseError> {
+        Ok(NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M")?.and_utc())
+    }
+
+    #[test]
+    fn test_parse_rejects_invalid_expressions() {
+        for expression in [
+            "",
+            "* * * *",
+            "60 * * * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+        ] {
+            assert!(CronSchedule::parse(expression).is_err(), "{expression}");
+        }
+    }
+
+    #[test]
+    fn test_next_after() -> Result<(), Box<dyn std::error::Error>> {
+        let weekdays = CronSchedule::parse("0 9 * * 1-5")?;
+        // Friday 2026-10-16 after 9:00 -> Monday
+        assert_eq!(
+            weekdays.next_after(&at("2026-10-16 09:00")?),
+            Some(at("2026-10-19 09:00")?)
+        );
+
+        let every_15 = CronSchedule::parse("*/15 * * * *")?;
+        assert_eq!(
+            every_15.next_after(&at("2026-10-16 23:50")?),
+            Some(at("2026-10-17 00:00")?)
+        );
+
+        // Day of month or day of week: the 1st, or any Sunday
+        let either = CronSchedule::parse("30 8 1 * 7")?;
+        assert_eq!(
+            either.next_after(&at("2026-10-16 12:00")?),
+            Some(at("2026-10-18 08:30")?)
+        );
+
+        let leap_day = CronSchedule::parse("0 0 29 2 *")?;
+        assert_eq!(
+            leap_day.next_after(&at("2026-10-16 12:00")?),
+            Some(at("2028-02-29 00:00")?)
+        );
+        assert_eq!(
+            CronSchedule::parse("0 0 31 2 *")?.next_after(&at("2026-10-16 12:00")?),
+            None
+        );
+        Ok(())
+    }
+}
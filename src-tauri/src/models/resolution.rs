@@ -0,0 +1,140 @@
+//! Per-server overrides of how a server's host name is resolved.
+
+use std::net::IpAddr;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use crate::error::AppError;
+
+/// Address family tried first when a host has both IPv4 and IPv6 addresses.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum IpPreference {
+    /// Keep the order the system resolver returns.
+    #[default]
+    System,
+    Ipv4,
+    Ipv6,
+}
+
+/// How a server's host name is resolved, for servers only reachable over one
+/// address family or at a fixed address (e.g. a VPN-only host).
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerResolution {
+    #[serde(default)]
+    pub ip_preference: IpPreference,
+    /// IP addresses connected to instead of looking the host up in DNS.
+    #[serde(default)]
+    pub static_addresses: Vec<String>,
+    /// SOCKS5 proxy (`host:port`) the server is only ever reached through,
+    /// e.g. Tor for an onion service. Connections fail rather than going
+    /// direct when it is unavailable.
+    pub socks_proxy: Option<String>,
+}
+
+impl ServerResolution {
+    /// Validates the overrides, rejecting static addresses that aren't IP addresses.
+    pub fn validate(&self) -> Result<(), AppError> {
+        for address in &self.static_addresses {
+            if address.trim().parse::<IpAddr>().is_err() {
+                return Err(AppError::Validation(format!(
+                    "Invalid IP address: {address}"
+                )));
+            }
+        }
+        if let Some(proxy) = &self.socks_proxy {
+            let valid = proxy
+                .trim()
+                .rsplit_once(':')
+                .is_some_and(|(host, port)| !host.is_empty() && port.parse::<u16>().is_ok());
+            if !valid {
+                return Err(AppError::Validation(format!(
+                    "SOCKS proxy must be host:port: {proxy}"
+                )));
+            }
+        }
+        Ok(())
+    }
+
+    /// Whether the host is resolved as usual.
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// The static addresses, if any are set.
+    pub fn static_ips(&self) -> Vec<IpAddr> {
+        self.static_addresses
+            .iter()
+            .filter_map(|a| a.trim().parse().ok())
+            .collect()
+    }
+
+    /// Orders `addresses` so the preferred address family comes first,
+    /// keeping the order within each family.
+    pub fn order(&self, mut addresses: Vec<IpAddr>) -> Vec<IpAddr> {
+        match self.ip_preference {
+            IpPreference::System => {}
+            IpPreference::Ipv4 => addresses.sort_by_key(IpAddr::is_ipv6),
+            IpPreference::Ipv6 => addresses.sort_by_key(IpAddr::is_ipv4),
+        }
+        addresses
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_order_prefers_family() -> Result<(), Box<dyn std::error::Error>> {
+        let addresses: Vec<IpAddr> = vec![
+            "2001:db8::1".parse()?,
+            "192.0.2.1".parse()?,
+            "2001:db8::2".parse()?,
+            "192.0.2.2".parse()?,
+        ];
+        let resolution = |ip_preference| ServerResolution {
+            ip_preference,
+            ..ServerResolution::default()
+        };
+
+        assert_eq!(
+            resolution(IpPreference::System).order(addresses.clone()),
+            addresses
+        );
+        assert_eq!(
+            resolution(IpPreference::Ipv4).order(addresses.clone()),
+            [addresses[1], addresses[3], addresses[0], addresses[2]]
+        );
+        assert_eq!(
+            resolution(IpPreference::Ipv6).order(addresses.clone()),
+            [addresses[0], addresses[2], addresses[1], addresses[3]]
+        );
+        Ok(())
+    }
+
+    #[test]
+    fn test_validate_socks_proxy() {
+        let with = |proxy: &str| ServerResolution {
+            socks_proxy: Some(proxy.to_string()),
+            ..ServerResolution::default()
+        };
+        assert!(with("127.0.0.1:9050").validate().is_ok());
+        assert!(with("[::1]:9050").validate().is_ok());
+        assert!(with("127.0.0.1").validate().is_err());
+        assert!(with(":9050").validate().is_err());
+    }
+
+    #[test]
+    fn test_validate_static_addresses() {
+        let with = |address: &str| ServerResolution {
+            static_addresses: vec![address.to_string()],
+            ..ServerResolution::default()
+        };
+        assert!(with("100.64.0.1").validate().is_ok());
+        assert!(with(" fd7a:115c:a1e0::1 ").validate().is_ok());
+        assert!(with("host.tailnet.ts.net").validate().is_err());
+    }
+}
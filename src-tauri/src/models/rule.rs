@@ -0,0 +1,511 @@
+//! Rules filtering and routing incoming notifications.
+//!
+//! A rule's conditions all have to match a notification for its actions to
+//! run. Rules are evaluated in the order they were created, and the actions
+//! of every matching rule are combined.
+
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::notification::{Notification, Priority};
+use super::pattern::wildcard_match;
+use super::subscription::NotificationSound;
+use crate::config::rules::FILE_VERSION;
+use crate::error::AppError;
+
+/// Conditions a notification has to meet; unset ones match anything.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleConditions {
+    /// Topic the notification arrived on; `*` matches any characters.
+    pub topic: Option<String>,
+    /// Regular expression the title has to match somewhere.
+    pub title_regex: Option<String>,
+    /// Regular expression the message has to match somewhere.
+    pub message_regex: Option<String>,
+    /// Lowest priority matched.
+    #[specta(type = Option<u8>)]
+    pub min_priority: Option<Priority>,
+    /// Tags of which the notification has to carry one; empty for any.
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+/// What a rule does with the notifications it matches.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RuleAction {
+    /// No toast is shown. Takes precedence over `ForceToast`.
+    Mute,
+    /// The notification is stored as read.
+    MarkRead,
+    /// A toast is shown even in muted topics and below the toast priority floor.
+    ForceToast,
+    /// The toast plays this sound.
+    PlaySound { sound: NotificationSound },
+    /// A shell command is run, with the notification passed in `NTFIER_*`
+    /// environment variables. Asks for the `run_command` permission.
+    RunCommand { command: String },
+}
+
+/// A rule applied to incoming notifications.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct Rule {
+    pub id: String,
+    pub name: String,
+    /// Disabled rules are kept but not evaluated.
+    pub enabled: bool,
+    pub conditions: RuleConditions,
+    pub actions: Vec<RuleAction>,
+    /// When the rule was created (milliseconds).
+    pub created_at: i64,
+}
+
+/// Data required to create or update a rule.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RuleInput {
+    pub name: String,
+    pub enabled: bool,
+    pub conditions: RuleConditions,
+    pub actions: Vec<RuleAction>,
+}
+
+impl RuleInput {
+    /// Validates the rule, rejecting invalid regular expressions.
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.name.trim().is_empty() {
+            return Err(AppError::Validation(
+                "Rule name cannot be empty".to_string(),
+            ));
+        }
+        if self.actions.is_empty() {
+            return Err(AppError::Validation(
+                "Rule needs at least one action".to_string(),
+            ));
+        }
+        let conditions = &self.conditions;
+        if conditions
+            .topic
+            .as_ref()
+            .is_some_and(|t| t.trim().is_empty())
+        {
+            return Err(AppError::Validation(
+                "Rule topic cannot be empty".to_string(),
+            ));
+        }
+        if conditions.tags.iter().any(|t| t.trim().is_empty()) {
+            return Err(AppError::Validation(
+                "Rule tags cannot be empty".to_string(),
+            ));
+        }
+        for pattern in [&conditions.title_regex, &conditions.message_regex]
+            .into_iter()
+            .flatten()
+        {
+            Regex::new(pattern).map_err(|e| {
+                AppError::Validation(format!("Invalid regular expression {pattern}: {e}"))
+            })?;
+        }
+        let empty_command = self
+            .actions
+            .iter()
+            .any(|a| matches!(a, RuleAction::RunCommand { command } if command.trim().is_empty()));
+        if empty_command {
+            return Err(AppError::Validation(
+                "Rule command cannot be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+impl From<Rule> for RuleInput {
+    fn from(rule: Rule) -> Self {
+        Self {
+            name: rule.name,
+            enabled: rule.enabled,
+            conditions: rule.conditions,
+            actions: rule.actions,
+        }
+    }
+}
+
+/// Rules shared between installations by `export_rules` and `import_rules`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuleFile {
+    pub version: u32,
+    pub rules: Vec<RuleInput>,
+}
+
+impl RuleFile {
+    pub fn new(rules: Vec<Rule>) -> Self {
+        Self {
+            version: FILE_VERSION,
+            rules: rules.into_iter().map(RuleInput::from).collect(),
+        }
+    }
+
+    /// Parses a rule file, rejecting it as a whole if any rule is invalid.
+    pub fn parse(json: &str) -> Result<Vec<RuleInput>, AppError> {
+        let file: Self = serde_json::from_str(json)
+            .map_err(|e| AppError::Validation(format!("Invalid rule file: {e}")))?;
+        if file.version > FILE_VERSION {
+            return Err(AppError::Validation(format!(
+                "Rule file version {} is newer than this app supports",
+                file.version
+            )));
+        }
+        for rule in &file.rules {
+            rule.validate().map_err(|e| {
+                AppError::Validation(format!("Invalid rule \"{}\": {e}", rule.name))
+            })?;
+        }
+        Ok(file.rules)
+    }
+}
+
+/// A rule with its regular expressions compiled, so it can be evaluated
+/// against many notifications.
+#[derive(Debug, Clone)]
+pub struct CompiledRule {
+    rule: Rule,
+    title_regex: Option<Regex>,
+    message_regex: Option<Regex>,
+    /// Cleared if a regular expression doesn't compile, so the rule matches
+    /// nothing.
+    valid: bool,
+}
+
+impl CompiledRule {
+    /// Compiles the regular expressions of `rule`.
+    pub fn new(rule: Rule) -> Self {
+        let compile = |pattern: &Option<String>| pattern.as_deref().map(Regex::new).transpose();
+        let (title_regex, message_regex, valid) = match (
+            compile(&rule.conditions.title_regex),
+            compile(&rule.conditions.message_regex),
+        ) {
+            (Ok(title_regex), Ok(message_regex)) => (title_regex, message_regex, true),
+            _ => (None, None, false),
+        };
+        Self {
+            rule,
+            title_regex,
+            message_regex,
+            valid,
+        }
+    }
+
+    /// Checks whether `notification`, received on `topic`, meets the rule's
+    /// conditions. Disabled rules match nothing.
+    pub fn matches(&self, topic: &str, notification: &Notification) -> bool {
+        let conditions = &self.rule.conditions;
+        let regex_matches =
+            |regex: &Option<Regex>, text: &str| regex.as_ref().map_or(true, |re| re.is_match(text));
+
+        self.valid
+            && self.rule.enabled
+            && conditions
+                .topic
+                .as_ref()
+                .map_or(true, |pattern| wildcard_match(pattern, topic))
+            && regex_matches(&self.title_regex, &notification.title)
+            && regex_matches(&self.message_regex, &notification.message)
+            && conditions
+                .min_priority
+                .map_or(true, |p| notification.priority as u8 >= p as u8)
+            && (conditions.tags.is_empty()
+                || notification
+                    .tags
+                    .iter()
+                    .any(|t| conditions.tags.contains(t)))
+    }
+}
+
+/// What a rule would have done to recently stored notifications.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RulePreview {
+    /// Number of notifications the rule was evaluated against.
+    pub checked: u32,
+    /// Notifications the rule would have matched, newest first.
+    pub matches: Vec<Notification>,
+    /// Actions that would have run for each of them.
+    pub actions: Vec<RuleAction>,
+}
+
+impl RulePreview {
+    /// Evaluates the candidate `rule` against `notifications`, each with the
+    /// topic it arrived on. The rule is evaluated even if it's disabled.
+    pub fn run(
+        rule: RuleInput,
+        notifications: Vec<(String, Notification)>,
+    ) -> Result<Self, AppError> {
+        rule.validate()?;
+        let rule = CompiledRule::new(Rule {
+            id: String::new(),
+            name: rule.name,
+            enabled: true,
+            conditions: rule.conditions,
+            actions: rule.actions,
+            created_at: 0,
+        });
+
+        let checked = u32::try_from(notifications.len()).unwrap_or(u32::MAX);
+        let matches = notifications
+            .into_iter()
+            .filter(|(topic, notification)| rule.matches(topic, notification))
+            .map(|(_, notification)| notification)
+            .collect();
+        Ok(Self {
+            checked,
+            matches,
+            actions: rule.rule.actions,
+        })
+    }
+}
+
+/// The combined actions of the rules matching a notification.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RuleOutcome {
+    pub mute: bool,
+    pub mark_read: bool,
+    pub force_toast: bool,
+    /// Sound of the first matching rule playing one.
+    pub sound: Option<NotificationSound>,
+    /// Commands to run, in rule order.
+    pub commands: Vec<String>,
+}
+
+impl RuleOutcome {
+    /// Combines the actions of the `rules` matching `notification`.
+    pub fn evaluate(rules: &[CompiledRule], topic: &str, notification: &Notification) -> Self {
+        let mut outcome = Self::default();
+        for rule in rules.iter().filter(|r| r.matches(topic, notification)) {
+            for action in &rule.rule.actions {
+                match action {
+                    RuleAction::Mute => outcome.mute = true,
+                    RuleAction::MarkRead => outcome.mark_read = true,
+                    RuleAction::ForceToast => outcome.force_toast = true,
+                    RuleAction::PlaySound { sound } => {
+                        outcome.sound.get_or_insert(*sound);
+                    }
+                    RuleAction::RunCommand { command } => {
+                        outcome.commands.push(command.clone());
+                    }
+                }
+            }
+        }
+        outcome
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::NtfyMessage;
+
+    fn rule(conditions: RuleConditions, actions: Vec<RuleAction>) -> Rule {
+        Rule {
+            id: "r".to_string(),
+            name: "Rule".to_string(),
+            enabled: true,
+            conditions,
+            actions,
+            created_at: 0,
+        }
+    }
+
+    #[test]
+    fn test_rule_outcome() {
+        let notification = NtfyMessage {
+            id: "m1".to_string(),
+            time: 0,
+            event: "message".to_string(),
+            topic: "prod-db".to_string(),
+            message: Some("Backup failed on db-2".to_string()),
+            title: Some("Backup".to_string()),
+            priority: Some(4),
+            tags: Some(vec!["backup".to_string()]),
+            click: None,
+            actions: None,
+            attachment: None,
+            content_type: None,
+        }
+        .into_notification("sub".to_string());
+
+        let failures = rule(
+            RuleConditions {
+                topic: Some("prod-*".to_string()),
+                message_regex: Some(r"failed on db-\d+".to_string()),
+                min_priority: Some(Priority::High),
+                ..RuleConditions::default()
+            },
+            vec![
+                RuleAction::ForceToast,
+                RuleAction::PlaySound {
+                    sound: NotificationSound::Alarm,
+                },
+            ],
+        );
+        let backups = rule(
+            RuleConditions {
+                tags: vec!["backup".to_string()],
+                ..RuleConditions::default()
+            },
+            vec![
+                RuleAction::MarkRead,
+                RuleAction::PlaySound {
+                    sound: NotificationSound::Mail,
+                },
+                RuleAction::RunCommand {
+                    command: "notify-backup".to_string(),
+                },
+            ],
+        );
+        let mut staging = rule(
+            RuleConditions {
+                topic: Some("staging-*".to_string()),
+                ..RuleConditions::default()
+            },
+            vec![RuleAction::Mute],
+        );
+
+        let rules = [failures.clone(), backups, staging.clone()].map(CompiledRule::new);
+        let outcome = RuleOutcome::evaluate(&rules, "prod-db", &notification);
+        assert_eq!(
+            outcome,
+            RuleOutcome {
+                mute: false,
+                mark_read: true,
+                force_toast: true,
+                sound: Some(NotificationSound::Alarm),
+                commands: vec!["notify-backup".to_string()],
+            }
+        );
+
+        let matches = |rule: &Rule, topic: &str| {
+            CompiledRule::new(rule.clone()).matches(topic, &notification)
+        };
+        assert!(!matches(&staging, "prod-db"));
+        staging.conditions.topic = None;
+        assert!(matches(&staging, "prod-db"));
+        staging.enabled = false;
+        assert!(!matches(&staging, "prod-db"));
+        assert!(!matches(&failures, "staging-db"));
+        staging.enabled = true;
+        staging.conditions.title_regex = Some("(unclosed".to_string());
+        assert!(!matches(&staging, "prod-db"));
+    }
+
+    #[test]
+    fn test_validate_rule_input() {
+        let input = |title_regex: &str, actions: Vec<RuleAction>| RuleInput {
+            name: "Rule".to_string(),
+            enabled: true,
+            conditions: RuleConditions {
+                title_regex: Some(title_regex.to_string()),
+                ..RuleConditions::default()
+            },
+            actions,
+        };
+        assert!(input("^Backup", vec![RuleAction::Mute]).validate().is_ok());
+        assert!(input("(unclosed", vec![RuleAction::Mute])
+            .validate()
+            .is_err());
+        assert!(input("^Backup", Vec::new()).validate().is_err());
+        let empty_command = RuleAction::RunCommand {
+            command: " ".to_string(),
+        };
+        assert!(input("^Backup", vec![empty_command]).validate().is_err());
+    }
+
+    #[test]
+    fn test_rule_preview() -> Result<(), AppError> {
+        let notification = |topic: &str, message: &str| {
+            let notification = NtfyMessage {
+                id: message.to_string(),
+                time: 0,
+                event: "message".to_string(),
+                topic: topic.to_string(),
+                message: Some(message.to_string()),
+                title: None,
+                priority: Some(3),
+                tags: None,
+                click: None,
+                actions: None,
+                attachment: None,
+                content_type: None,
+            }
+            .into_notification("sub".to_string());
+            (topic.to_string(), notification)
+        };
+        let candidate = RuleInput {
+            name: "Deploys".to_string(),
+            enabled: false,
+            conditions: RuleConditions {
+                topic: Some("ci-*".to_string()),
+                message_regex: Some("^Deployed".to_string()),
+                ..RuleConditions::default()
+            },
+            actions: vec![RuleAction::Mute],
+        };
+
+        let preview = RulePreview::run(
+            candidate.clone(),
+            vec![
+                notification("ci-web", "Deployed web 1.2"),
+                notification("ci-web", "Build failed"),
+                notification("alerts", "Deployed by hand"),
+            ],
+        )?;
+        assert_eq!(preview.checked, 3);
+        assert_eq!(
+            preview
+                .matches
+                .iter()
+                .map(|n| n.message.as_str())
+                .collect::<Vec<_>>(),
+            vec!["Deployed web 1.2"]
+        );
+        assert_eq!(preview.actions, vec![RuleAction::Mute]);
+
+        let invalid = RuleInput {
+            actions: Vec::new(),
+            ..candidate
+        };
+        assert!(RulePreview::run(invalid, Vec::new()).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_rule_file() -> Result<(), AppError> {
+        let backups = rule(
+            RuleConditions {
+                tags: vec!["backup".to_string()],
+                ..RuleConditions::default()
+            },
+            vec![RuleAction::MarkRead],
+        );
+        let json = serde_json::to_string(&RuleFile::new(vec![backups]))?;
+        let rules = RuleFile::parse(&json)?;
+        assert_eq!(rules.len(), 1);
+        assert_eq!(
+            rules.first().map(|r| r.conditions.tags.clone()),
+            Some(vec!["backup".to_string()])
+        );
+
+        // One invalid rule rejects the whole file
+        let invalid = json.replace("mark_read", "run_command\",\"command\":\" ");
+        assert!(matches!(
+            RuleFile::parse(&invalid),
+            Err(AppError::Validation(_))
+        ));
+        let newer = json.replace(&format!("\"version\":{FILE_VERSION}"), "\"version\":99");
+        assert!(RuleFile::parse(&newer).is_err());
+        assert!(RuleFile::parse("not json").is_err());
+        Ok(())
+    }
+}
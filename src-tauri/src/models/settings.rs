@@ -1,9 +1,16 @@
 //! Application settings and server configuration.
 
+use chrono::{DateTime, Datelike, FixedOffset, Local, NaiveDateTime, NaiveTime, Utc, Weekday};
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use url::Url;
 
+use super::notification::{Notification, Priority};
+use super::subscription::validate_topic;
+use crate::config::http::{
+    CONNECT_TIMEOUT_SECS, MAX_TIMEOUT_SECS, POLL_TIMEOUT_SECS, READ_TIMEOUT_SECS,
+    REQUEST_TIMEOUT_SECS,
+};
 use crate::error::AppError;
 
 /// Theme mode for the application.
@@ -27,6 +34,552 @@ pub enum NotificationDisplayMethod {
     WindowsEnhanced,
 }
 
+impl NotificationDisplayMethod {
+    /// Returns the value stored in the database.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Native => "native",
+            Self::WindowsEnhanced => "windows_enhanced",
+        }
+    }
+
+    /// Parses a value stored in the database, defaulting to native.
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "windows_enhanced" => Self::WindowsEnhanced,
+            _ => Self::Native,
+        }
+    }
+}
+
+/// Ordering of the subscription list.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SubscriptionSort {
+    /// Most recent notification first.
+    #[default]
+    Recent,
+    /// By display name (or topic), case-insensitive.
+    Alphabetical,
+    /// Subscriptions with unread notifications first, then most recent.
+    UnreadFirst,
+    /// User-defined order set via `reorder_subscriptions`.
+    Manual,
+}
+
+/// Appearance of the tray icon.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum TrayIconStyle {
+    /// The colored app icon.
+    #[default]
+    Color,
+    /// White icon for dark taskbars.
+    MonochromeLight,
+    /// Dark icon for light taskbars.
+    MonochromeDark,
+    /// Monochrome icon matching the OS theme.
+    Auto,
+}
+
+/// How the app is unlocked after it locks itself.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum AppLockMethod {
+    /// The app never locks.
+    #[default]
+    Off,
+    /// A PIN stored in the OS keychain.
+    Pin,
+    /// OS authentication (Windows Hello, or polkit on Linux).
+    System,
+}
+
+/// How topic names and message content appear in log output.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum LogRedaction {
+    /// Logged as is.
+    #[default]
+    Off,
+    /// Only the first few characters are logged.
+    Truncate,
+    /// Replaced by a short hash, so the same topic can still be followed
+    /// through a log.
+    Hash,
+}
+
+impl LogRedaction {
+    /// Returns the value stored in the database.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Off => "off",
+            Self::Truncate => "truncate",
+            Self::Hash => "hash",
+        }
+    }
+}
+
+/// Priority given to notifications carrying a tag, for senders that can set
+/// tags but not the priority header.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TagPriority {
+    pub tag: String,
+    #[specta(type = u8)]
+    pub priority: Priority,
+}
+
+impl TagPriority {
+    /// Validates the mapping, rejecting empty tags.
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.tag.trim().is_empty() {
+            return Err(AppError::Validation("Tag cannot be empty".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Returns the highest priority mapped to any of `tags` (case-insensitive).
+    pub fn resolve(mappings: &[Self], tags: &[String]) -> Option<Priority> {
+        mappings
+            .iter()
+            .filter(|m| tags.iter().any(|tag| tag.eq_ignore_ascii_case(&m.tag)))
+            .map(|m| m.priority)
+            .max_by_key(|p| *p as u8)
+    }
+}
+
+/// A local folder whose new and changed files are published to a
+/// subscription's topic.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct WatchFolder {
+    /// Absolute path of the folder. Subfolders aren't watched.
+    pub path: String,
+    /// Subscription whose topic the messages are published to.
+    pub subscription_id: String,
+    /// Attach the file to the message; files over the size limit are only named.
+    #[serde(default)]
+    pub attach_file: bool,
+}
+
+impl WatchFolder {
+    /// Validates the folder, rejecting relative paths.
+    pub fn validate(&self) -> Result<(), AppError> {
+        if !std::path::Path::new(self.path.trim()).is_absolute() {
+            return Err(AppError::Validation(format!(
+                "Watch folder must be an absolute path: {}",
+                self.path
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Topic that delivery and read receipts are published to. It doesn't have
+/// to be subscribed to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ReceiptTopic {
+    pub server_url: String,
+    pub topic: String,
+}
+
+impl ReceiptTopic {
+    /// Validates the server URL and topic name.
+    pub fn validate(&self) -> Result<(), AppError> {
+        let url = Url::parse(self.server_url.trim())
+            .map_err(|e| AppError::InvalidUrl(format!("Invalid server URL: {e}")))?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(AppError::InvalidUrl(
+                "Server URL must use http or https".to_string(),
+            ));
+        }
+        validate_topic(&self.topic)
+    }
+}
+
+/// Which proxy HTTP requests and WebSocket connections go through.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ProxyMode {
+    /// The proxy set in the `HTTPS_PROXY`, `HTTP_PROXY` and `ALL_PROXY`
+    /// environment variables, skipping hosts listed in `NO_PROXY`.
+    #[default]
+    System,
+    /// Connections are made directly.
+    Off,
+    /// The HTTP proxy set in the proxy settings.
+    Manual,
+}
+
+/// Proxy settings. The password of a manual proxy is stored in the OS
+/// keychain.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ProxySettings {
+    #[serde(default)]
+    pub mode: ProxyMode,
+    #[serde(default)]
+    pub host: Option<String>,
+    #[serde(default)]
+    pub port: Option<u16>,
+    /// Username for basic authentication with the proxy.
+    #[serde(default)]
+    pub username: Option<String>,
+}
+
+impl ProxySettings {
+    /// Validates the settings; a manual proxy needs a host and port.
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.mode != ProxyMode::Manual {
+            return Ok(());
+        }
+        let host = self.host.as_deref().map(str::trim).unwrap_or_default();
+        if host.is_empty() {
+            return Err(AppError::Validation(
+                "Proxy host cannot be empty".to_string(),
+            ));
+        }
+        if host.contains("://") || host.contains(char::is_whitespace) {
+            return Err(AppError::Validation(format!("Invalid proxy host: {host}")));
+        }
+        if !self.port.is_some_and(|port| port > 0) {
+            return Err(AppError::Validation("Proxy port is required".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Address (`host:port`) of the manual proxy; `None` in other modes.
+    pub fn address(&self) -> Option<String> {
+        if self.mode != ProxyMode::Manual {
+            return None;
+        }
+        let host = self.host.as_deref()?.trim();
+        let port = self.port?;
+        if host.contains(':') && !host.starts_with('[') {
+            Some(format!("[{host}]:{port}"))
+        } else {
+            Some(format!("{host}:{port}"))
+        }
+    }
+}
+
+/// Timeouts of requests to ntfy servers, in seconds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(default, rename_all = "camelCase")]
+pub struct HttpTimeouts {
+    /// Time to establish a TCP/TLS connection to a server.
+    pub connect_secs: u32,
+    /// Time to wait between successive reads of a response.
+    pub read_secs: u32,
+    /// Overall deadline of a request, including the response body.
+    pub request_secs: u32,
+    /// Overall deadline of a message poll, which may return a large backlog.
+    pub poll_secs: u32,
+}
+
+impl Default for HttpTimeouts {
+    fn default() -> Self {
+        Self {
+            connect_secs: CONNECT_TIMEOUT_SECS,
+            read_secs: READ_TIMEOUT_SECS,
+            request_secs: REQUEST_TIMEOUT_SECS,
+            poll_secs: POLL_TIMEOUT_SECS,
+        }
+    }
+}
+
+impl HttpTimeouts {
+    /// Validates the timeouts, each of which must be 1 to `MAX_TIMEOUT_SECS`.
+    pub fn validate(&self) -> Result<(), AppError> {
+        let timeouts = [
+            self.connect_secs,
+            self.read_secs,
+            self.request_secs,
+            self.poll_secs,
+        ];
+        if timeouts
+            .iter()
+            .any(|secs| !(1..=MAX_TIMEOUT_SECS).contains(secs))
+        {
+            return Err(AppError::Validation(format!(
+                "Timeouts must be 1 to {MAX_TIMEOUT_SECS} seconds"
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// Do-not-disturb: toasts are held back while it's on or during its quiet
+/// hours, except for notifications matching one of its exceptions.
+/// Notifications are stored either way.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DoNotDisturb {
+    /// On until turned off, regardless of quiet hours.
+    #[serde(default)]
+    pub enabled: bool,
+    /// Daily window do-not-disturb is on for.
+    #[serde(default)]
+    pub quiet_hours: Option<QuietHours>,
+    #[serde(default)]
+    pub exceptions: DndExceptions,
+}
+
+impl DoNotDisturb {
+    /// Validates the quiet hours and exceptions.
+    pub fn validate(&self) -> Result<(), AppError> {
+        if let Some(quiet_hours) = &self.quiet_hours {
+            quiet_hours.bounds()?;
+            quiet_hours.timezone.validate()?;
+        }
+        if self.exceptions.keywords.iter().any(|k| k.trim().is_empty()) {
+            return Err(AppError::Validation("Keyword cannot be empty".to_string()));
+        }
+        Ok(())
+    }
+
+    /// Checks whether the toast for `notification` is held back at `now`.
+    pub fn suppresses(&self, notification: &Notification, now: DateTime<Utc>) -> bool {
+        let active = self.enabled
+            || self
+                .quiet_hours
+                .as_ref()
+                .is_some_and(|quiet_hours| quiet_hours.contains(now));
+        active && !self.exceptions.allows(notification)
+    }
+}
+
+/// Daily quiet hours as times of day (`HH:MM`). The window may span
+/// midnight (e.g. 22:00 to 07:00); equal start and end make it empty.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct QuietHours {
+    pub start: String,
+    pub end: String,
+    /// Time zone `start` and `end` are in.
+    #[serde(default)]
+    pub timezone: QuietHoursZone,
+}
+
+/// Time zone of quiet hours.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum QuietHoursZone {
+    /// The system's current time zone, so the window moves along when it changes.
+    #[default]
+    Local,
+    /// A fixed UTC offset (e.g. `+01:00`), so the window stays put while
+    /// travelling. Daylight saving time isn't applied.
+    Fixed { utc_offset: String },
+}
+
+impl QuietHoursZone {
+    fn validate(&self) -> Result<(), AppError> {
+        if let Self::Fixed { utc_offset } = self {
+            parse_utc_offset(utc_offset)?;
+        }
+        Ok(())
+    }
+
+    /// Returns the date and time at `now` in this zone.
+    ///
+    /// The system zone is looked up on every call, so a time zone change
+    /// applies to the next notification.
+    fn local_at(&self, now: DateTime<Utc>) -> Result<NaiveDateTime, AppError> {
+        Ok(match self {
+            Self::Local => now.with_timezone(&Local).naive_local(),
+            Self::Fixed { utc_offset } => now
+                .with_timezone(&parse_utc_offset(utc_offset)?)
+                .naive_local(),
+        })
+    }
+}
+
+fn parse_utc_offset(offset: &str) -> Result<FixedOffset, AppError> {
+    offset
+        .trim()
+        .parse()
+        .map_err(|_| AppError::Validation(format!("Invalid UTC offset: {offset}")))
+}
+
+impl QuietHours {
+    fn bounds(&self) -> Result<(NaiveTime, NaiveTime), AppError> {
+        time_bounds(&self.start, &self.end)
+    }
+
+    /// Checks whether `now` falls within the quiet hours.
+    pub fn contains(&self, now: DateTime<Utc>) -> bool {
+        let (Ok((start, end)), Ok(now)) = (self.bounds(), self.timezone.local_at(now)) else {
+            return false;
+        };
+        let now = now.time();
+        if start <= end {
+            start <= now && now < end
+        } else {
+            now >= start || now < end
+        }
+    }
+}
+
+fn time_bounds(start: &str, end: &str) -> Result<(NaiveTime, NaiveTime), AppError> {
+    let parse = |time: &str| {
+        NaiveTime::parse_from_str(time.trim(), "%H:%M")
+            .map_err(|_| AppError::Validation(format!("Invalid time of day: {time}")))
+    };
+    Ok((parse(start)?, parse(end)?))
+}
+
+/// Weekly do-not-disturb schedule: quiet hours per day of the week, during
+/// which only max priority notifications are shown as toasts. Notifications
+/// are stored and counted in the tray either way.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DndSchedule {
+    #[serde(default)]
+    pub enabled: bool,
+    #[serde(default)]
+    pub windows: Vec<ScheduledQuietHours>,
+    /// Time zone the windows are in.
+    #[serde(default)]
+    pub timezone: QuietHoursZone,
+}
+
+/// Quiet hours on some days of the week, as times of day (`HH:MM`). A window
+/// spanning midnight (e.g. 22:00 to 07:00) belongs to the day it starts on.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ScheduledQuietHours {
+    pub days: Vec<DayOfWeek>,
+    pub start: String,
+    pub end: String,
+}
+
+/// A day of the week.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "lowercase")]
+pub enum DayOfWeek {
+    Monday,
+    Tuesday,
+    Wednesday,
+    Thursday,
+    Friday,
+    Saturday,
+    Sunday,
+}
+
+impl From<Weekday> for DayOfWeek {
+    fn from(weekday: Weekday) -> Self {
+        match weekday {
+            Weekday::Mon => Self::Monday,
+            Weekday::Tue => Self::Tuesday,
+            Weekday::Wed => Self::Wednesday,
+            Weekday::Thu => Self::Thursday,
+            Weekday::Fri => Self::Friday,
+            Weekday::Sat => Self::Saturday,
+            Weekday::Sun => Self::Sunday,
+        }
+    }
+}
+
+impl ScheduledQuietHours {
+    /// Validates the times of day.
+    pub fn validate(&self) -> Result<(), AppError> {
+        time_bounds(&self.start, &self.end).map(|_| ())
+    }
+
+    /// Checks whether the date and time `now` falls within the window.
+    pub fn contains(&self, now: NaiveDateTime) -> bool {
+        let Ok((start, end)) = time_bounds(&self.start, &self.end) else {
+            return false;
+        };
+        let today = DayOfWeek::from(now.weekday());
+        let yesterday = DayOfWeek::from(now.weekday().pred());
+        let time = now.time();
+
+        if start <= end {
+            self.days.contains(&today) && start <= time && time < end
+        } else {
+            (self.days.contains(&today) && time >= start)
+                || (self.days.contains(&yesterday) && time < end)
+        }
+    }
+}
+
+impl DndSchedule {
+    /// Validates the windows and time zone.
+    pub fn validate(&self) -> Result<(), AppError> {
+        for window in &self.windows {
+            window.validate()?;
+        }
+        self.timezone.validate()
+    }
+
+    /// Checks whether the schedule is on and `now` falls within one of its windows.
+    pub fn is_quiet(&self, now: DateTime<Utc>) -> bool {
+        if !self.enabled {
+            return false;
+        }
+        let Ok(now) = self.timezone.local_at(now) else {
+            return false;
+        };
+        self.windows.iter().any(|window| window.contains(now))
+    }
+
+    /// Checks whether the toast for `notification` is held back at `now`.
+    pub fn suppresses(&self, notification: &Notification, now: DateTime<Utc>) -> bool {
+        notification.priority != Priority::Max && self.is_quiet(now)
+    }
+}
+
+/// Notifications shown despite do-not-disturb.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DndExceptions {
+    /// Subscriptions always shown, such as an alarm topic.
+    #[serde(default)]
+    pub subscription_ids: Vec<String>,
+    /// Lowest priority always shown; `None` lets no priority through.
+    #[serde(default)]
+    #[specta(type = Option<u8>)]
+    pub min_priority: Option<Priority>,
+    /// Words shown whenever they appear in the title or message (case-insensitive).
+    #[serde(default)]
+    pub keywords: Vec<String>,
+}
+
+impl Default for DndExceptions {
+    fn default() -> Self {
+        Self {
+            subscription_ids: Vec::new(),
+            min_priority: Some(Priority::High),
+            keywords: Vec::new(),
+        }
+    }
+}
+
+impl DndExceptions {
+    /// Checks whether a notification breaks through do-not-disturb.
+    pub fn allows(&self, notification: &Notification) -> bool {
+        if self.subscription_ids.contains(&notification.topic_id) {
+            return true;
+        }
+        if self
+            .min_priority
+            .is_some_and(|min| notification.priority as u8 >= min as u8)
+        {
+            return true;
+        }
+        let title = notification.title.to_lowercase();
+        let message = notification.message.to_lowercase();
+        self.keywords.iter().any(|keyword| {
+            let keyword = keyword.to_lowercase();
+            title.contains(&keyword) || message.contains(&keyword)
+        })
+    }
+}
+
 /// Configuration for a single ntfy server.
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 #[serde(rename_all = "camelCase")]
@@ -88,6 +641,35 @@ impl ServerConfig {
     }
 }
 
+/// What removing a server would delete.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerRemovalImpact {
+    /// Subscriptions on the server, including those in the trash.
+    pub subscription_count: i32,
+    /// Notifications stored for those subscriptions.
+    pub notification_count: i32,
+}
+
+/// A server whose password can't be loaded from the OS keychain.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct MissingCredential {
+    pub server_url: String,
+    pub username: String,
+    pub problem: CredentialProblem,
+}
+
+/// Why a server's password can't be loaded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum CredentialProblem {
+    /// The keychain has no entry, e.g. after reinstalling the OS.
+    Missing,
+    /// The keychain entry exists but couldn't be read.
+    Unreadable,
+}
+
 /// Notification-specific settings.
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 #[serde(rename_all = "camelCase")]
@@ -107,6 +689,19 @@ pub struct NotificationSettings {
     /// Play notification sound.
     #[serde(default = "default_true")]
     pub notification_sound: bool,
+    /// Group toasts by subscription in the Action Center (Windows Enhanced only).
+    #[serde(default = "default_true")]
+    pub notification_group_by_topic: bool,
+    /// Lowest priority shown as a toast; lower ones are only stored.
+    #[serde(default = "default_min_toast_priority")]
+    #[specta(type = u8)]
+    pub min_toast_priority: Priority,
+    /// Show only the topic in toasts while the session is locked.
+    #[serde(default)]
+    pub hide_content_when_locked: bool,
+    /// Announce shown notifications to screen readers.
+    #[serde(default)]
+    pub screen_reader_announcements: bool,
 }
 
 /// Application-wide settings.
@@ -140,6 +735,39 @@ pub struct AppSettings {
     /// Play notification sound.
     #[serde(default = "default_true")]
     pub notification_sound: bool,
+    /// Group toasts by subscription in the Action Center (Windows Enhanced only).
+    #[serde(default = "default_true")]
+    pub notification_group_by_topic: bool,
+    /// Lowest priority shown as a toast; lower ones are only stored.
+    /// Subscriptions may override it.
+    #[serde(default = "default_min_toast_priority")]
+    #[specta(type = u8)]
+    pub min_toast_priority: Priority,
+    /// Priorities given to notifications by tag, applied when they are received.
+    #[serde(default)]
+    pub tag_priorities: Vec<TagPriority>,
+    /// Words marking messages meant for the user (their name, handle), which
+    /// are flagged, shown even in muted topics and have their own sound.
+    #[serde(default)]
+    pub mentions: Vec<String>,
+    /// When toasts are held back, and which notifications are shown anyway.
+    #[serde(default)]
+    pub do_not_disturb: DoNotDisturb,
+    /// Weekly quiet hours during which only max priority toasts are shown.
+    #[serde(default)]
+    pub dnd_schedule: DndSchedule,
+    /// Appearance of the tray icon.
+    #[serde(default)]
+    pub tray_icon_style: TrayIconStyle,
+    /// Custom PNG or ICO file used as the tray icon instead of the style's icon.
+    #[serde(default)]
+    pub tray_icon_path: Option<String>,
+    /// Custom PNG or ICO file used as the tray icon while there are unread notifications.
+    #[serde(default)]
+    pub tray_unread_icon_path: Option<String>,
+    /// Announce notifications to screen readers in addition to showing toasts.
+    #[serde(default)]
+    pub screen_reader_announcements: bool,
     /// Show messages in collapsed accordion style.
     #[serde(default)]
     pub compact_view: bool,
@@ -152,12 +780,91 @@ pub struct AppSettings {
     /// Enable favorites feature (star icon on notifications).
     #[serde(default)]
     pub favorites_enabled: bool,
+    /// Seconds to wait after launch before the startup sync begins.
+    #[serde(default)]
+    pub startup_delay_secs: u32,
+    /// Wait for network connectivity before the startup sync begins.
+    #[serde(default = "default_true")]
+    pub wait_for_network: bool,
+    /// Minutes of inactivity after which cached credentials are cleared (0 = never).
+    #[serde(default)]
+    pub credential_lock_minutes: u32,
+    /// How the app is unlocked; `Off` disables the app lock.
+    #[serde(default)]
+    pub app_lock_method: AppLockMethod,
+    /// Minutes the main window may be out of focus before the app locks (0 = only on start).
+    #[serde(default = "default_app_lock_minutes")]
+    pub app_lock_minutes: u32,
+    /// Ordering of the subscription list.
+    #[serde(default)]
+    pub subscription_sort: SubscriptionSort,
+    /// Show a daily digest of received notifications.
+    #[serde(default)]
+    pub daily_summary_enabled: bool,
+    /// Local time of day (`HH:MM`) the daily digest is shown at.
+    #[serde(default = "default_daily_summary_time")]
+    pub daily_summary_time: String,
+    /// Fetch previews of URLs in messages (contacts the linked sites).
+    #[serde(default)]
+    pub link_previews_enabled: bool,
+    /// Show only the topic in toasts while the session is locked, and the full
+    /// message once it is unlocked.
+    #[serde(default)]
+    pub hide_content_when_locked: bool,
+    /// How topic names and message content are written to the log.
+    #[serde(default)]
+    pub log_redaction: LogRedaction,
+    /// Local folders whose new and changed files are published to a topic.
+    #[serde(default)]
+    pub watch_folders: Vec<WatchFolder>,
+    /// Subscription the clipboard quick-share shortcut publishes to; unset
+    /// disables quick-share.
+    #[serde(default)]
+    pub clipboard_share_subscription_id: Option<String>,
+    /// Ask before the clipboard quick-share shortcut publishes.
+    #[serde(default = "default_true")]
+    pub clipboard_share_confirm: bool,
+    /// Local port serving Prometheus metrics at `/metrics`; unset disables it.
+    #[serde(default)]
+    pub metrics_endpoint_port: Option<u16>,
+    /// Topic receipts are published to when messages are shown or read;
+    /// unset disables receipts.
+    #[serde(default)]
+    pub receipt_topic: Option<ReceiptTopic>,
+    /// Proxy HTTP requests and WebSocket connections go through.
+    #[serde(default)]
+    pub proxy: ProxySettings,
+    /// Timeouts of requests to ntfy servers.
+    #[serde(default)]
+    pub http_timeouts: HttpTimeouts,
+    /// Read-only mode for shared dashboards: only reading and handling
+    /// notifications is allowed. Always on when started with `--kiosk`.
+    #[serde(default)]
+    pub kiosk_mode: bool,
 }
 
 const fn default_true() -> bool {
     true
 }
 
+const fn default_min_toast_priority() -> Priority {
+    Priority::Min
+}
+
+const fn default_app_lock_minutes() -> u32 {
+    DEFAULT_APP_LOCK_MINUTES
+}
+
+/// Default idle time before the app locks.
+pub const DEFAULT_APP_LOCK_MINUTES: u32 = 5;
+
+fn default_daily_summary_time() -> String {
+    DEFAULT_DAILY_SUMMARY_TIME.to_string()
+}
+
+/// Default local time of the daily digest.
+pub const DEFAULT_DAILY_SUMMARY_TIME: &str = "20:00";
+
 impl Default for AppSettings {
     fn default() -> Self {
         Self {
@@ -176,10 +883,168 @@ impl Default for AppSettings {
             notification_show_actions: true,
             notification_show_images: true,
             notification_sound: true,
+            notification_group_by_topic: true,
+            min_toast_priority: default_min_toast_priority(),
+            tag_priorities: Vec::new(),
+            mentions: Vec::new(),
+            do_not_disturb: DoNotDisturb::default(),
+            dnd_schedule: DndSchedule::default(),
+            tray_icon_style: TrayIconStyle::Color,
+            tray_icon_path: None,
+            tray_unread_icon_path: None,
+            screen_reader_announcements: false,
             compact_view: false,
             expand_new_messages: true,
             delete_local_only: true,
             favorites_enabled: false,
+            startup_delay_secs: 0,
+            wait_for_network: true,
+            credential_lock_minutes: 0,
+            app_lock_method: AppLockMethod::Off,
+            app_lock_minutes: default_app_lock_minutes(),
+            subscription_sort: SubscriptionSort::Recent,
+            daily_summary_enabled: false,
+            daily_summary_time: default_daily_summary_time(),
+            link_previews_enabled: false,
+            hide_content_when_locked: false,
+            log_redaction: LogRedaction::Off,
+            watch_folders: Vec::new(),
+            clipboard_share_subscription_id: None,
+            clipboard_share_confirm: true,
+            metrics_endpoint_port: None,
+            receipt_topic: None,
+            proxy: ProxySettings::default(),
+            http_timeouts: HttpTimeouts::default(),
+            kiosk_mode: false,
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::NtfyMessage;
+
+    #[test]
+    fn test_tag_priority_resolve() {
+        let mappings = vec![
+            TagPriority {
+                tag: "warning".to_string(),
+                priority: Priority::High,
+            },
+            TagPriority {
+                tag: "skull".to_string(),
+                priority: Priority::Max,
+            },
+        ];
+        let tags = |t: &[&str]| t.iter().map(|s| (*s).to_string()).collect::<Vec<_>>();
+
+        assert_eq!(
+            TagPriority::resolve(&mappings, &tags(&["Warning", "skull"])),
+            Some(Priority::Max)
+        );
+        assert_eq!(
+            TagPriority::resolve(&mappings, &tags(&["warning"])),
+            Some(Priority::High)
+        );
+        assert_eq!(TagPriority::resolve(&mappings, &tags(&["info"])), None);
+    }
+
+    #[test]
+    fn test_http_timeouts() -> Result<(), Box<dyn std::error::Error>> {
+        assert!(HttpTimeouts::default().validate().is_ok());
+
+        // Timeouts left out of the stored JSON keep their defaults
+        let timeouts: HttpTimeouts = serde_json::from_str(r#"{ "pollSecs": 300 }"#)?;
+        assert_eq!(timeouts.poll_secs, 300);
+        assert_eq!(timeouts.connect_secs, HttpTimeouts::default().connect_secs);
+
+        let zero = HttpTimeouts {
+            read_secs: 0,
+            ..timeouts
+        };
+        assert!(matches!(zero.validate(), Err(AppError::Validation(_))));
+        let too_long = HttpTimeouts {
+            request_secs: MAX_TIMEOUT_SECS + 1,
+            ..timeouts
+        };
+        assert!(too_long.validate().is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn test_do_not_disturb_exceptions() -> Result<(), Box<dyn std::error::Error>> {
+        let dnd = DoNotDisturb {
+            enabled: false,
+            quiet_hours: Some(QuietHours {
+                start: "22:00".to_string(),
+                end: "07:00".to_string(),
+                timezone: QuietHoursZone::Fixed {
+                    utc_offset: "+02:00".to_string(),
+                },
+            }),
+            exceptions: DndExceptions {
+                subscription_ids: vec!["alarm".to_string()],
+                keywords: vec!["Outage".to_string()],
+                ..DndExceptions::default()
+            },
+        };
+        let notification = |topic_id: &str, priority: Priority, message: &str| {
+            let msg: NtfyMessage = serde_json::from_value(serde_json::json!({
+                "id": "m1",
+                "time": 0,
+                "event": "message",
+                "topic": topic_id,
+                "message": message,
+                "priority": priority as u8,
+            }))?;
+            Ok::<_, serde_json::Error>(msg.into_notification(topic_id.to_string()))
+        };
+        // 23:30 and 12:00 at UTC+2
+        let night = DateTime::parse_from_rfc3339("2026-10-16T21:30:00Z")?.to_utc();
+        let day = DateTime::parse_from_rfc3339("2026-10-16T10:00:00Z")?.to_utc();
+
+        let chatter = notification("news", Priority::Default, "Daily digest")?;
+        assert!(dnd.suppresses(&chatter, night));
+        assert!(!dnd.suppresses(&chatter, day));
+
+        assert!(!dnd.suppresses(&notification("alarm", Priority::Min, "Door")?, night));
+        assert!(!dnd.suppresses(&notification("news", Priority::High, "Digest")?, night));
+        assert!(!dnd.suppresses(
+            &notification("news", Priority::Low, "Partial outage")?,
+            night
+        ));
+        Ok(())
+    }
+
+    #[test]
+    fn test_dnd_schedule_windows() -> Result<(), Box<dyn std::error::Error>> {
+        let schedule = DndSchedule {
+            enabled: true,
+            windows: vec![ScheduledQuietHours {
+                days: vec![DayOfWeek::Friday],
+                start: "22:00".to_string(),
+                end: "07:00".to_string(),
+            }],
+            timezone: QuietHoursZone::Fixed {
+                utc_offset: "+00:00".to_string(),
+            },
+        };
+        let at =
+            |time: &str| Ok::<_, chrono::ParseError>(DateTime::parse_from_rfc3339(time)?.to_utc());
+
+        // 2026-10-16 is a Friday; the window runs into Saturday morning
+        assert!(!schedule.is_quiet(at("2026-10-16T21:59:00Z")?));
+        assert!(schedule.is_quiet(at("2026-10-16T22:00:00Z")?));
+        assert!(schedule.is_quiet(at("2026-10-17T06:59:00Z")?));
+        assert!(!schedule.is_quiet(at("2026-10-17T22:30:00Z")?));
+        // Thursday night isn't scheduled
+        assert!(!schedule.is_quiet(at("2026-10-16T03:00:00Z")?));
+        assert!(!DndSchedule {
+            enabled: false,
+            ..schedule
+        }
+        .is_quiet(at("2026-10-16T23:00:00Z")?));
+        Ok(())
+    }
+}
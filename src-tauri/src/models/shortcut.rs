@@ -0,0 +1,50 @@
+//! Global keyboard shortcuts.
+//!
+//! Each action can be bound to one accelerator (e.g. `CmdOrCtrl+Shift+N`),
+//! which works system-wide while the app is running. Nothing is bound by
+//! default so the app never takes a key combination another tool relies on.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+/// An action a global shortcut can trigger.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ShortcutAction {
+    /// Show and focus the main window.
+    ShowWindow,
+    /// Mark all notifications as read.
+    MarkAllRead,
+    /// Publish the clipboard contents to the quick-share topic.
+    ShareClipboard,
+}
+
+impl ShortcutAction {
+    /// Returns the value stored in the database.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::ShowWindow => "show_window",
+            Self::MarkAllRead => "mark_all_read",
+            Self::ShareClipboard => "share_clipboard",
+        }
+    }
+
+    /// Parses a value stored in the database.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "show_window" => Some(Self::ShowWindow),
+            "mark_all_read" => Some(Self::MarkAllRead),
+            "share_clipboard" => Some(Self::ShareClipboard),
+            _ => None,
+        }
+    }
+}
+
+/// A global shortcut bound to an action.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ShortcutBinding {
+    pub action: ShortcutAction,
+    /// Accelerator string, e.g. `CmdOrCtrl+Shift+N`.
+    pub accelerator: String,
+}
@@ -0,0 +1,119 @@
+//! Ready-to-paste snippets for publishing to a topic.
+
+use std::fmt::Write;
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::server_url::normalize_url;
+
+/// Language or tool a publish snippet is written for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum SnippetFlavor {
+    Curl,
+    Powershell,
+    Python,
+    GithubActions,
+}
+
+/// Placeholder for the password in snippets for servers with credentials.
+const PASSWORD_PLACEHOLDER: &str = "<password>";
+
+/// Builds a snippet that publishes a message to `topic` on `server_url`.
+///
+/// With a `username`, the snippet authenticates with basic auth and a password
+/// placeholder. The GitHub Actions step reads both from repository secrets.
+pub fn publish_snippet(
+    flavor: SnippetFlavor,
+    server_url: &str,
+    topic: &str,
+    username: Option<&str>,
+) -> String {
+    let url = format!("{}/{topic}", normalize_url(server_url));
+    let mut snippet = String::new();
+
+    match flavor {
+        SnippetFlavor::Curl => {
+            snippet.push_str("curl \\\n");
+            if let Some(user) = username {
+                let _ = writeln!(snippet, "  -u \"{user}:{PASSWORD_PLACEHOLDER}\" \\");
+            }
+            let _ = writeln!(
+                snippet,
+                "  -H \"Title: Hello\" \\\n  -d \"Message from curl\" \\\n  {url}"
+            );
+        }
+        SnippetFlavor::Powershell => {
+            snippet.push_str("$Request = @{\n  Method = \"POST\"\n");
+            let _ = writeln!(snippet, "  Uri = \"{url}\"");
+            snippet.push_str("  Headers = @{\n    Title = \"Hello\"\n");
+            if let Some(user) = username {
+                let _ = writeln!(
+                    snippet,
+                    "    Authorization = \"Basic \" + [Convert]::ToBase64String(\
+                     [Text.Encoding]::UTF8.GetBytes(\"{user}:{PASSWORD_PLACEHOLDER}\"))"
+                );
+            }
+            snippet.push_str(
+                "  }\n  Body = \"Message from PowerShell\"\n}\nInvoke-RestMethod @Request\n",
+            );
+        }
+        SnippetFlavor::Python => {
+            snippet.push_str("import requests\n\nrequests.post(\n");
+            let _ = writeln!(snippet, "    \"{url}\",");
+            snippet.push_str(
+                "    data=\"Message from Python\".encode(\"utf-8\"),\n    \
+                 headers={\"Title\": \"Hello\"},\n",
+            );
+            if let Some(user) = username {
+                let _ = writeln!(
+                    snippet,
+                    "    auth=(\"{user}\", \"{PASSWORD_PLACEHOLDER}\"),"
+                );
+            }
+            snippet.push_str(")\n");
+        }
+        SnippetFlavor::GithubActions => {
+            snippet.push_str("- name: Notify via ntfy\n  if: always()\n  run: |\n    curl \\\n");
+            if username.is_some() {
+                snippet.push_str(
+                    "      -u \"${{ secrets.NTFY_USERNAME }}:${{ secrets.NTFY_PASSWORD }}\" \\\n",
+                );
+            }
+            let _ = writeln!(
+                snippet,
+                "      -H \"Title: ${{{{ github.workflow }}}}: ${{{{ job.status }}}}\" \\\n      \
+                 -d \"${{{{ github.repository }}}}@${{{{ github.ref_name }}}}\" \\\n      \
+                 {url}"
+            );
+        }
+    }
+
+    snippet
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_curl_snippet_with_auth() {
+        let snippet = publish_snippet(
+            SnippetFlavor::Curl,
+            "https://ntfy.example.com/",
+            "alerts",
+            Some("alice"),
+        );
+        assert!(snippet.contains("-u \"alice:<password>\""));
+        assert!(snippet.ends_with("  https://ntfy.example.com/alerts\n"));
+    }
+
+    #[test]
+    fn test_github_actions_snippet_without_auth() {
+        let snippet = publish_snippet(SnippetFlavor::GithubActions, "https://ntfy.sh", "ci", None);
+        assert!(!snippet.contains("secrets."));
+        assert!(snippet.contains("-H \"Title: ${{ github.workflow }}: ${{ job.status }}\""));
+        assert!(snippet.contains("https://ntfy.sh/ci"));
+    }
+}
@@ -0,0 +1,211 @@
+//! Aggregated notification statistics.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+use std::fmt::Write;
+
+/// Notification counts of one subscription over a time range.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct TopicActivity {
+    pub subscription_id: String,
+    /// Display name, or the topic if none is set.
+    pub name: String,
+    /// Notifications received in the range.
+    pub received: i32,
+    /// Notifications received in the range that are still unread.
+    pub unread: i32,
+}
+
+/// Digest of notifications received since a point in time.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct DailySummary {
+    /// Start of the summarized range (milliseconds).
+    pub since: i64,
+    pub total_received: i32,
+    pub total_unread: i32,
+    /// Subscriptions with activity in the range, busiest first.
+    pub topics: Vec<TopicActivity>,
+}
+
+impl DailySummary {
+    /// Builds a summary from per-topic activity.
+    pub fn new(since: i64, topics: Vec<TopicActivity>) -> Self {
+        Self {
+            since,
+            total_received: topics.iter().map(|t| t.received).sum(),
+            total_unread: topics.iter().map(|t| t.unread).sum(),
+            topics,
+        }
+    }
+}
+
+/// Time range covered by an activity report, ending now.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportRange {
+    Day,
+    Week,
+    Month,
+}
+
+impl ReportRange {
+    /// Length of the range in seconds.
+    pub const fn secs(self) -> i64 {
+        match self {
+            Self::Day => 24 * 60 * 60,
+            Self::Week => 7 * 24 * 60 * 60,
+            Self::Month => 30 * 24 * 60 * 60,
+        }
+    }
+
+    const fn label(self) -> &'static str {
+        match self {
+            Self::Day => "last day",
+            Self::Week => "last 7 days",
+            Self::Month => "last 30 days",
+        }
+    }
+}
+
+/// Output format of an activity report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ReportFormat {
+    Json,
+    Markdown,
+}
+
+/// Number of notifications with a given priority.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct PriorityCount {
+    /// Priority level (1-5).
+    pub priority: u8,
+    pub count: i32,
+}
+
+/// Notification volume over a time range, for finding noisy producers.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ActivityReport {
+    pub range: ReportRange,
+    /// Start of the range (milliseconds).
+    pub since: i64,
+    pub total_received: i32,
+    /// Subscriptions with activity in the range, busiest first.
+    pub topics: Vec<TopicActivity>,
+    /// Notifications per local hour of day (index 0-23).
+    pub hourly: Vec<i32>,
+    /// Notifications per priority level, from min to max.
+    pub priorities: Vec<PriorityCount>,
+}
+
+impl ActivityReport {
+    /// Renders the report as a Markdown document.
+    pub fn to_markdown(&self) -> String {
+        let mut md = format!(
+            "# Notification activity ({})\n\n{} notifications received.\n",
+            self.range.label(),
+            self.total_received
+        );
+
+        md.push_str("\n## Topics\n\n| Topic | Received | Unread |\n| --- | ---: | ---: |\n");
+        for topic in &self.topics {
+            let _ = writeln!(
+                md,
+                "| {} | {} | {} |",
+                topic.name.replace('|', "\\|"),
+                topic.received,
+                topic.unread
+            );
+        }
+
+        md.push_str("\n## Busiest hours\n\n| Hour | Received |\n| --- | ---: |\n");
+        let mut hours: Vec<(usize, i32)> = self
+            .hourly
+            .iter()
+            .copied()
+            .enumerate()
+            .filter(|&(_, count)| count > 0)
+            .collect();
+        hours.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+        for (hour, count) in hours {
+            let _ = writeln!(md, "| {hour:02}:00 | {count} |");
+        }
+
+        md.push_str("\n## Priorities\n\n| Priority | Received |\n| --- | ---: |\n");
+        for p in &self.priorities {
+            let _ = writeln!(md, "| {} | {} |", p.priority, p.count);
+        }
+
+        md
+    }
+}
+
+/// Statistics of a self-hosted ntfy server.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ServerStats {
+    pub server_url: String,
+    /// Total messages published since the server's cache was created.
+    pub messages: i64,
+    /// Messages published per second, averaged by the server.
+    pub messages_rate: f64,
+    /// Number of user accounts, if the configured credentials have admin rights.
+    pub user_count: Option<u32>,
+    /// Number of admin accounts, if the configured credentials have admin rights.
+    pub admin_count: Option<u32>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> ActivityReport {
+        let mut hourly = vec![0; 24];
+        hourly[9] = 2;
+        hourly[21] = 5;
+        ActivityReport {
+            range: ReportRange::Week,
+            since: 0,
+            total_received: 7,
+            topics: vec![TopicActivity {
+                subscription_id: "sub-1".to_string(),
+                name: "a|b".to_string(),
+                received: 7,
+                unread: 3,
+            }],
+            hourly,
+            priorities: vec![PriorityCount {
+                priority: 3,
+                count: 7,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_markdown_sections() {
+        let md = report().to_markdown();
+        assert!(md.starts_with("# Notification activity (last 7 days)"));
+        assert!(md.contains("| a\\|b | 7 | 3 |"));
+        assert!(md.contains("| 3 | 7 |"));
+    }
+
+    #[test]
+    fn test_markdown_busiest_hours_first() {
+        let md = report().to_markdown();
+        let evening = md.find("| 21:00 | 5 |");
+        let morning = md.find("| 09:00 | 2 |");
+        assert!(evening.is_some() && evening < morning);
+        assert!(!md.contains("| 00:00 |"));
+    }
+
+    #[test]
+    fn test_daily_summary_totals() {
+        let summary = DailySummary::new(0, report().topics);
+        assert_eq!(summary.total_received, 7);
+        assert_eq!(summary.total_unread, 3);
+    }
+}
@@ -0,0 +1,117 @@
+//! Runtime status snapshots for the frontend status bar.
+
+use serde::Serialize;
+use specta::Type;
+
+/// Lifecycle state of a subscription's WebSocket connection.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionState {
+    /// Handshake in progress.
+    Connecting,
+    /// Connected and receiving messages.
+    Connected,
+    /// Disconnected and waiting for the next retry.
+    Reconnecting,
+}
+
+/// Kind of a connection error, telling network trouble from server problems.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum ConnectionErrorKind {
+    /// The handshake didn't finish in time.
+    Timeout,
+    /// The server couldn't be reached or the connection was reset.
+    Network,
+    /// TLS handshake or certificate error.
+    Tls,
+    /// The server rejected the credentials (HTTP 401 or 403).
+    Auth,
+    /// The server answered the handshake with another HTTP error.
+    Http,
+    /// The server closed an established connection.
+    Closed,
+    /// Anything else, such as protocol violations.
+    Other,
+}
+
+impl ConnectionErrorKind {
+    /// Returns the value stored in the database.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Timeout => "timeout",
+            Self::Network => "network",
+            Self::Tls => "tls",
+            Self::Auth => "auth",
+            Self::Http => "http",
+            Self::Closed => "closed",
+            Self::Other => "other",
+        }
+    }
+
+    /// Parses a value stored in the database, defaulting to `Other`.
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "timeout" => Self::Timeout,
+            "network" => Self::Network,
+            "tls" => Self::Tls,
+            "auth" => Self::Auth,
+            "http" => Self::Http,
+            "closed" => Self::Closed,
+            _ => Self::Other,
+        }
+    }
+}
+
+/// A connection error recorded for a subscription.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionLogEntry {
+    pub kind: ConnectionErrorKind,
+    pub message: String,
+    /// When the error happened (milliseconds).
+    pub timestamp: i64,
+}
+
+/// Status of a single subscription's WebSocket connection.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ConnectionStatus {
+    pub subscription_id: String,
+    pub state: ConnectionState,
+    /// Consecutive failed attempts since the last successful connection.
+    pub failed_attempts: u32,
+    /// When the current connection was established (milliseconds).
+    pub connected_since: Option<i64>,
+    /// When the next reconnect attempt is scheduled (milliseconds).
+    pub next_retry_at: Option<i64>,
+}
+
+/// Last successful sync of a subscription.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionSyncStatus {
+    pub subscription_id: String,
+    /// Unix timestamp in seconds of the last sync, if any.
+    pub last_sync: Option<i64>,
+}
+
+/// Aggregated runtime status, emitted periodically as `runtime:status`.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct RuntimeStatus {
+    pub connections: Vec<ConnectionStatus>,
+    pub last_syncs: Vec<SubscriptionSyncStatus>,
+    /// Number of connections currently waiting to reconnect.
+    pub pending_retries: u32,
+    /// Notification writes waiting to be retried.
+    pub pending_writes: u32,
+    /// Outgoing messages waiting in the outbox.
+    pub outbox_size: u32,
+    /// Notification actions waiting for the user's permission.
+    pub pending_actions: u32,
+    /// Total unread notifications across non-muted subscriptions.
+    pub total_unread: i32,
+    /// When this snapshot was taken (milliseconds).
+    pub timestamp: i64,
+}
@@ -1,10 +1,13 @@
 //! Subscription data structures.
 
+use chrono::{DateTime, Local, Utc};
 use serde::{Deserialize, Serialize};
 use specta::Type;
 use url::Url;
 
+use super::notification::Priority;
 use super::server_url::normalize_url;
+use super::settings::{NotificationDisplayMethod, ScheduledQuietHours};
 use crate::error::AppError;
 
 /// A subscription to a topic on an ntfy server.
@@ -16,10 +19,135 @@ pub struct Subscription {
     pub server_url: String,
     pub display_name: Option<String>,
     pub unread_count: i32,
+    /// Total number of stored notifications.
+    pub total_count: i32,
+    /// Number of notifications received in the last 24 hours.
+    pub recent_count: i32,
+    /// Highest priority among unread notifications (1-5), if any are unread.
+    #[specta(type = Option<u8>)]
+    pub max_unread_priority: Option<Priority>,
     /// Timestamp of the most recent notification (milliseconds).
     pub last_notification: Option<i64>,
     /// Whether notifications from this subscription are muted.
     pub muted: bool,
+    /// When the subscription was moved to the trash (milliseconds), if it was.
+    pub deleted_at: Option<i64>,
+    /// Whether the subscription's server was removed while keeping local history.
+    /// Detached subscriptions are read-only: they are never connected or synced.
+    pub detached: bool,
+    /// Whether actions from this topic may run without asking for permission.
+    pub trusted: bool,
+    /// Pattern subscription this one was created by, if any.
+    pub pattern_id: Option<String>,
+    /// Notifications older than this many hours are deleted automatically.
+    pub auto_delete_after_hours: Option<u32>,
+    /// Only this many newest notifications are kept; older ones are deleted
+    /// automatically.
+    pub keep_messages: Option<u32>,
+    /// Whether this is a local test topic that never touches the network.
+    pub local: bool,
+    /// Minimum priority shown as a toast, overriding the global `min_toast_priority`.
+    #[specta(type = Option<u8>)]
+    pub min_toast_priority: Option<Priority>,
+    /// Whether toasts show only the topic and unread count instead of the message.
+    pub hide_content: bool,
+    /// Whether missed messages are never fetched; only live messages arrive.
+    pub exclude_from_sync: bool,
+    /// History sync only fetches messages from this many hours back, even if
+    /// the last sync was longer ago.
+    pub sync_window_hours: Option<u32>,
+    /// Whether the subscription is listed and synced before the others.
+    pub pinned: bool,
+    /// Local file every received notification is appended to, if set.
+    pub mirror: Option<FileMirror>,
+}
+
+/// Format of a subscription's mirror file.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum MirrorFormat {
+    /// One JSON-encoded notification per line.
+    Jsonl,
+    /// One human-readable line per notification.
+    Log,
+}
+
+impl MirrorFormat {
+    /// Returns the value stored in the database.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Jsonl => "jsonl",
+            Self::Log => "log",
+        }
+    }
+
+    /// Parses a value stored in the database, defaulting to JSONL.
+    pub fn parse(value: &str) -> Self {
+        match value {
+            "log" => Self::Log,
+            _ => Self::Jsonl,
+        }
+    }
+}
+
+/// A local file received notifications are appended to, so other tools can
+/// tail them.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct FileMirror {
+    /// Absolute path of the file; created if it doesn't exist.
+    pub path: String,
+    pub format: MirrorFormat,
+}
+
+impl FileMirror {
+    /// Validates the mirror, rejecting relative paths.
+    pub fn validate(&self) -> Result<(), AppError> {
+        if !std::path::Path::new(self.path.trim()).is_absolute() {
+            return Err(AppError::Validation(format!(
+                "Mirror file must be an absolute path: {}",
+                self.path
+            )));
+        }
+        Ok(())
+    }
+}
+
+/// How long a subscription's notifications are kept.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(tag = "kind", rename_all = "snake_case")]
+pub enum RetentionPolicy {
+    /// Keep notifications until they are deleted by hand.
+    Forever,
+    /// Delete notifications older than this many days.
+    Days { days: u32 },
+    /// Delete all but this many newest notifications.
+    Messages { count: u32 },
+}
+
+impl RetentionPolicy {
+    /// Returns the subscription's `auto_delete_after_hours` and
+    /// `keep_messages` for the policy.
+    pub fn limits(self) -> Result<(Option<u32>, Option<u32>), AppError> {
+        match self {
+            Self::Forever => Ok((None, None)),
+            Self::Days { days: 0 } => Err(AppError::Validation(
+                "Notifications must be kept at least a day".to_string(),
+            )),
+            Self::Days { days } => days
+                .checked_mul(24)
+                .filter(|hours| i32::try_from(*hours).is_ok())
+                .map(|hours| (Some(hours), None))
+                .ok_or_else(|| AppError::Validation("Retention period is too long".to_string())),
+            Self::Messages { count: 0 } => Err(AppError::Validation(
+                "At least one notification must be kept".to_string(),
+            )),
+            Self::Messages { count } if i32::try_from(count).is_err() => Err(AppError::Validation(
+                "Too many notifications to keep".to_string(),
+            )),
+            Self::Messages { count } => Ok((None, Some(count))),
+        }
+    }
 }
 
 impl Subscription {
@@ -34,6 +162,90 @@ impl Subscription {
     }
 }
 
+/// Sound of a subscription's toasts.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "snake_case")]
+pub enum NotificationSound {
+    /// No sound, whatever the priority.
+    Silent,
+    Default,
+    Im,
+    Mail,
+    Reminder,
+    Sms,
+    /// Looping alarm; Windows Enhanced toasts with it stay until dismissed.
+    Alarm,
+}
+
+impl NotificationSound {
+    /// Returns the value stored in the database.
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Silent => "silent",
+            Self::Default => "default",
+            Self::Im => "im",
+            Self::Mail => "mail",
+            Self::Reminder => "reminder",
+            Self::Sms => "sms",
+            Self::Alarm => "alarm",
+        }
+    }
+
+    /// Parses a value stored in the database.
+    pub fn parse(value: &str) -> Option<Self> {
+        match value {
+            "silent" => Some(Self::Silent),
+            "default" => Some(Self::Default),
+            "im" => Some(Self::Im),
+            "mail" => Some(Self::Mail),
+            "reminder" => Some(Self::Reminder),
+            "sms" => Some(Self::Sms),
+            "alarm" => Some(Self::Alarm),
+            _ => None,
+        }
+    }
+}
+
+/// Notification settings of a subscription overriding the global ones.
+/// Unset fields use the global setting.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionSettings {
+    /// Sound played instead of the priority-based one (Windows Enhanced only;
+    /// native toasts can only be silenced). Toasts below default priority
+    /// stay silent.
+    pub sound: Option<NotificationSound>,
+    /// Minimum priority shown as a toast; the subscription's `min_toast_priority`.
+    #[specta(type = Option<u8>)]
+    pub min_priority: Option<Priority>,
+    pub display_method: Option<NotificationDisplayMethod>,
+    /// Whether new notifications are stored as read. They are still shown.
+    #[serde(default)]
+    pub auto_mark_read: bool,
+    /// Recurring windows in local time, such as a nightly backup, during
+    /// which new notifications are stored as read and not shown.
+    #[serde(default)]
+    pub maintenance_windows: Vec<ScheduledQuietHours>,
+}
+
+impl SubscriptionSettings {
+    /// Validates the maintenance windows.
+    pub fn validate(&self) -> Result<(), AppError> {
+        for window in &self.maintenance_windows {
+            window.validate()?;
+        }
+        Ok(())
+    }
+
+    /// Checks whether `now` falls within one of the maintenance windows.
+    pub fn in_maintenance(&self, now: DateTime<Utc>) -> bool {
+        let now = now.with_timezone(&Local).naive_local();
+        self.maintenance_windows
+            .iter()
+            .any(|window| window.contains(now))
+    }
+}
+
 /// Data required to create a new subscription.
 #[derive(Debug, Clone, Serialize, Deserialize, Type)]
 #[serde(rename_all = "camelCase")]
@@ -48,51 +260,324 @@ impl CreateSubscription {
     ///
     /// Checks that the topic is valid and the server URL is properly formatted.
     pub fn validate(&self) -> Result<(), AppError> {
-        // Validate topic
-        let topic = self.topic.trim();
-        if topic.is_empty() {
-            return Err(AppError::InvalidUrl("Topic cannot be empty".to_string()));
-        }
+        validate_topic(&self.topic)?;
+        validate_server_url(&self.server_url)
+    }
+}
 
-        // Topic should only contain alphanumeric characters, underscores, and hyphens
-        // ntfy allows topics matching pattern: [-_A-Za-z0-9]{1,64}
-        if topic.len() > 64 {
-            return Err(AppError::InvalidUrl(
-                "Topic must be 64 characters or less".to_string(),
-            ));
+/// A line of a subscription list that was not imported.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SkippedImportLine {
+    /// 1-based line number in the file.
+    pub line: u32,
+    pub text: String,
+    pub reason: String,
+}
+
+/// Result of importing a subscription list.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionImport {
+    pub created: Vec<Subscription>,
+    pub skipped: Vec<SkippedImportLine>,
+}
+
+/// Parses a subscription list with one topic per line.
+///
+/// Each line is a topic URL (`https://ntfy.sh/alerts`) or a bare topic on
+/// `default_server`, optionally followed by a comma and a display name, so
+/// two-column CSV files work as well. Blank lines, `#` comments and a CSV
+/// header are ignored. Returns the valid entries with their line numbers and
+/// the invalid lines.
+pub fn parse_subscription_list(
+    text: &str,
+    default_server: &str,
+) -> (Vec<(u32, CreateSubscription)>, Vec<SkippedImportLine>) {
+    let mut entries = Vec::new();
+    let mut skipped = Vec::new();
+
+    for (index, raw) in text.lines().enumerate() {
+        let line = raw.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
         }
 
-        let valid_topic = topic
-            .chars()
-            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
-        if !valid_topic {
-            return Err(AppError::InvalidUrl(
-                "Topic can only contain letters, numbers, hyphens, and underscores".to_string(),
-            ));
+        let (target, display_name) = match line.split_once(',') {
+            Some((target, name)) => (target.trim(), Some(unquote(name.trim()))),
+            None => (line, None),
+        };
+        let target = unquote(target);
+        if entries.is_empty() && skipped.is_empty() && is_csv_header(target) {
+            continue;
         }
 
-        // Validate server URL
-        if self.server_url.trim().is_empty() {
-            return Err(AppError::InvalidUrl(
-                "Server URL cannot be empty".to_string(),
-            ));
+        let entry =
+            split_topic_url(target, default_server).map(|(server_url, topic)| CreateSubscription {
+                topic,
+                server_url,
+                display_name: display_name.filter(|n| !n.is_empty()).map(String::from),
+            });
+        let line_number = u32::try_from(index + 1).unwrap_or(u32::MAX);
+        match entry.and_then(|entry| entry.validate().map(|()| entry)) {
+            Ok(entry) => entries.push((line_number, entry)),
+            Err(e) => skipped.push(SkippedImportLine {
+                line: line_number,
+                text: raw.to_string(),
+                reason: e.to_string(),
+            }),
         }
+    }
 
-        let parsed = Url::parse(&self.server_url)
-            .map_err(|e| AppError::InvalidUrl(format!("Invalid server URL: {e}")))?;
+    (entries, skipped)
+}
 
-        if !["http", "https"].contains(&parsed.scheme()) {
-            return Err(AppError::InvalidUrl(
-                "Server URL must use http or https scheme".to_string(),
+/// Splits a topic URL into server URL and topic; a bare topic belongs to
+/// `default_server`.
+fn split_topic_url(target: &str, default_server: &str) -> Result<(String, String), AppError> {
+    if !target.contains("://") {
+        return Ok((
+            normalize_url(default_server).to_string(),
+            target.to_string(),
+        ));
+    }
+
+    let mut url =
+        Url::parse(target).map_err(|e| AppError::InvalidUrl(format!("Invalid topic URL: {e}")))?;
+    let path = url.path().trim_end_matches('/').to_string();
+    let Some((prefix, topic)) = path.rsplit_once('/').filter(|(_, t)| !t.is_empty()) else {
+        return Err(AppError::InvalidUrl("Topic URL has no topic".to_string()));
+    };
+    let topic = topic.to_string();
+    url.set_path(prefix);
+    url.set_query(None);
+    url.set_fragment(None);
+    Ok((normalize_url(url.as_str()).to_string(), topic))
+}
+
+fn unquote(text: &str) -> &str {
+    text.strip_prefix('"')
+        .and_then(|t| t.strip_suffix('"'))
+        .unwrap_or(text)
+}
+
+fn is_csv_header(first_field: &str) -> bool {
+    ["url", "topic", "topic_url"]
+        .iter()
+        .any(|header| first_field.eq_ignore_ascii_case(header))
+}
+
+/// Checks that a topic name is valid for ntfy.
+pub(super) fn validate_topic(topic: &str) -> Result<(), AppError> {
+    let topic = topic.trim();
+    if topic.is_empty() {
+        return Err(AppError::InvalidUrl("Topic cannot be empty".to_string()));
+    }
+
+    // Topic should only contain alphanumeric characters, underscores, and hyphens
+    // ntfy allows topics matching pattern: [-_A-Za-z0-9]{1,64}
+    if topic.len() > 64 {
+        return Err(AppError::InvalidUrl(
+            "Topic must be 64 characters or less".to_string(),
+        ));
+    }
+
+    let valid_topic = topic
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid_topic {
+        return Err(AppError::InvalidUrl(
+            "Topic can only contain letters, numbers, hyphens, and underscores".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// Checks that a server URL is an http(s) URL with a host.
+fn validate_server_url(server_url: &str) -> Result<(), AppError> {
+    if server_url.trim().is_empty() {
+        return Err(AppError::InvalidUrl(
+            "Server URL cannot be empty".to_string(),
+        ));
+    }
+
+    let parsed = Url::parse(server_url)
+        .map_err(|e| AppError::InvalidUrl(format!("Invalid server URL: {e}")))?;
+
+    if !["http", "https"].contains(&parsed.scheme()) {
+        return Err(AppError::InvalidUrl(
+            "Server URL must use http or https scheme".to_string(),
+        ));
+    }
+
+    if parsed.host().is_none() {
+        return Err(AppError::InvalidUrl(
+            "Server URL must have a host".to_string(),
+        ));
+    }
+
+    Ok(())
+}
+
+/// A pattern subscription that auto-creates child subscriptions for matching topics.
+///
+/// Children are regular subscriptions grouped under the pattern via their `pattern_id`.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionPattern {
+    pub id: String,
+    pub server_url: String,
+    /// Topic pattern where `*` matches any sequence (e.g. `home-sensor-*`).
+    pub pattern: String,
+    pub display_name: Option<String>,
+    /// When the pattern was added (milliseconds).
+    pub created_at: i64,
+}
+
+/// Data required to create a local test subscription.
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateLocalSubscription {
+    pub topic: String,
+    pub display_name: Option<String>,
+}
+
+impl CreateLocalSubscription {
+    /// Validates the subscription data.
+    pub fn validate(&self) -> Result<(), AppError> {
+        validate_topic(&self.topic)
+    }
+}
+
+/// Data required to create a new pattern subscription.
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct CreateSubscriptionPattern {
+    pub server_url: String,
+    pub pattern: String,
+    pub display_name: Option<String>,
+}
+
+impl CreateSubscriptionPattern {
+    /// Validates the pattern data.
+    ///
+    /// Patterns follow ntfy's topic rules, with `*` additionally allowed as a wildcard.
+    pub fn validate(&self) -> Result<(), AppError> {
+        let pattern = self.pattern.trim();
+        if pattern.is_empty() {
+            return Err(AppError::Validation("Pattern cannot be empty".to_string()));
+        }
+
+        if pattern.len() > 64 {
+            return Err(AppError::Validation(
+                "Pattern must be 64 characters or less".to_string(),
             ));
         }
 
-        if parsed.host().is_none() {
-            return Err(AppError::InvalidUrl(
-                "Server URL must have a host".to_string(),
+        let valid_pattern = pattern
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_' || c == '*');
+        if !valid_pattern {
+            return Err(AppError::Validation(
+                "Pattern can only contain letters, numbers, hyphens, underscores, and *"
+                    .to_string(),
             ));
         }
 
+        validate_server_url(&self.server_url)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_retention_policy_limits() {
+        assert_eq!(RetentionPolicy::Forever.limits().ok(), Some((None, None)));
+        assert_eq!(
+            RetentionPolicy::Days { days: 30 }.limits().ok(),
+            Some((Some(720), None))
+        );
+        assert_eq!(
+            RetentionPolicy::Messages { count: 500 }.limits().ok(),
+            Some((None, Some(500)))
+        );
+        assert!(RetentionPolicy::Days { days: 0 }.limits().is_err());
+        assert!(RetentionPolicy::Days { days: u32::MAX }.limits().is_err());
+        assert!(RetentionPolicy::Messages { count: 0 }.limits().is_err());
+    }
+
+    #[test]
+    fn test_parse_subscription_list() {
+        let text = "url,display_name\n\
+            https://ntfy.sh/backups\n\
+            \n\
+            # Home automation\n\
+            https://ntfy.example.com/ntfy/garage-door/, \"Garage door\"\n\
+            alerts\n\
+            https://ntfy.sh/bad topic\n";
+        let (entries, skipped) = parse_subscription_list(text, "https://ntfy.sh/");
+
+        let entries: Vec<_> = entries
+            .iter()
+            .map(|(line, e)| {
+                (
+                    *line,
+                    e.server_url.as_str(),
+                    e.topic.as_str(),
+                    e.display_name.as_deref(),
+                )
+            })
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                (2, "https://ntfy.sh", "backups", None),
+                (
+                    5,
+                    "https://ntfy.example.com/ntfy",
+                    "garage-door",
+                    Some("Garage door")
+                ),
+                (6, "https://ntfy.sh", "alerts", None),
+            ]
+        );
+        assert_eq!(skipped.len(), 1);
+        assert_eq!(skipped[0].line, 7);
+    }
+
+    #[test]
+    fn test_topic_url_without_topic() {
+        let (entries, skipped) = parse_subscription_list("https://ntfy.sh/", "https://ntfy.sh");
+        assert!(entries.is_empty());
+        assert_eq!(skipped.len(), 1);
+    }
+
+    #[test]
+    fn test_maintenance_windows() -> Result<(), chrono::ParseError> {
+        use super::super::settings::DayOfWeek;
+
+        let nightly = ScheduledQuietHours {
+            days: vec![DayOfWeek::Friday, DayOfWeek::Saturday],
+            start: "23:30".to_string(),
+            end: "01:00".to_string(),
+        };
+        let at = |s: &str| chrono::NaiveDateTime::parse_from_str(s, "%Y-%m-%d %H:%M");
+        // 2026-10-16 is a Friday
+        assert!(nightly.contains(at("2026-10-16 23:45")?));
+        assert!(nightly.contains(at("2026-10-17 00:30")?));
+        assert!(!nightly.contains(at("2026-10-17 01:00")?));
+        assert!(!nightly.contains(at("2026-10-15 23:45")?));
+
+        let mut settings = SubscriptionSettings {
+            maintenance_windows: vec![nightly],
+            ..Default::default()
+        };
+        assert!(settings.validate().is_ok());
+        settings.maintenance_windows[0].end = "25:00".to_string();
+        assert!(settings.validate().is_err());
         Ok(())
     }
 }
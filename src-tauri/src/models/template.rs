@@ -0,0 +1,156 @@
+//! Subscription templates for common producers.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::notification::Priority;
+use super::settings::TagPriority;
+
+/// A preset for subscribing to a common producer.
+#[derive(Debug, Clone, Serialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct SubscriptionTemplate {
+    pub id: String,
+    pub name: String,
+    pub description: String,
+    /// Suggested topic name. A random suffix is added when the template is
+    /// applied without a topic, so the topic is hard to guess.
+    pub suggested_topic: String,
+    /// Toast floor set on the created subscription.
+    #[specta(type = Option<u8>)]
+    pub min_toast_priority: Option<Priority>,
+    /// Priorities for the tags the producer sends, added to the global
+    /// `tag_priorities` for tags not mapped yet.
+    pub tag_priorities: Vec<TagPriority>,
+}
+
+/// Parameters for applying a template.
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyTemplateParams {
+    pub server_url: String,
+    /// Topic to subscribe to; the suggested topic with a random suffix if unset.
+    pub topic: Option<String>,
+    /// Display name; the template's name if unset.
+    pub display_name: Option<String>,
+}
+
+struct TemplateSpec {
+    id: &'static str,
+    name: &'static str,
+    description: &'static str,
+    suggested_topic: &'static str,
+    min_toast_priority: Option<Priority>,
+    tag_priorities: &'static [(&'static str, Priority)],
+}
+
+const TEMPLATES: &[TemplateSpec] = &[
+    TemplateSpec {
+        id: "uptime_kuma",
+        name: "Uptime Kuma",
+        description: "Monitor down and up alerts",
+        suggested_topic: "uptime-kuma",
+        min_toast_priority: None,
+        tag_priorities: &[
+            ("red_circle", Priority::High),
+            ("green_circle", Priority::Low),
+        ],
+    },
+    TemplateSpec {
+        id: "watchtower",
+        name: "Watchtower",
+        description: "Container update reports, shown without toasts unless urgent",
+        suggested_topic: "watchtower",
+        min_toast_priority: Some(Priority::High),
+        tag_priorities: &[],
+    },
+    TemplateSpec {
+        id: "proxmox",
+        name: "Proxmox VE",
+        description: "Backup, replication and fencing notifications",
+        suggested_topic: "proxmox",
+        min_toast_priority: None,
+        tag_priorities: &[("error", Priority::High), ("info", Priority::Low)],
+    },
+    TemplateSpec {
+        id: "github_actions",
+        name: "GitHub Actions",
+        description: "Workflow run results",
+        suggested_topic: "github-actions",
+        min_toast_priority: None,
+        tag_priorities: &[("x", Priority::High), ("white_check_mark", Priority::Low)],
+    },
+];
+
+impl SubscriptionTemplate {
+    /// Returns the template catalog.
+    pub fn all() -> Vec<Self> {
+        TEMPLATES.iter().map(Self::from_spec).collect()
+    }
+
+    /// Returns the template with the given ID.
+    pub fn find(id: &str) -> Option<Self> {
+        TEMPLATES.iter().find(|t| t.id == id).map(Self::from_spec)
+    }
+
+    /// Returns `existing` with this template's tag priorities added for tags
+    /// that aren't mapped yet (case-insensitive).
+    pub fn merge_tag_priorities(&self, existing: &[TagPriority]) -> Vec<TagPriority> {
+        let mut merged = existing.to_vec();
+        for mapping in &self.tag_priorities {
+            if !merged
+                .iter()
+                .any(|m| m.tag.eq_ignore_ascii_case(&mapping.tag))
+            {
+                merged.push(mapping.clone());
+            }
+        }
+        merged
+    }
+
+    fn from_spec(spec: &TemplateSpec) -> Self {
+        Self {
+            id: spec.id.to_string(),
+            name: spec.name.to_string(),
+            description: spec.description.to_string(),
+            suggested_topic: spec.suggested_topic.to_string(),
+            min_toast_priority: spec.min_toast_priority,
+            tag_priorities: spec
+                .tag_priorities
+                .iter()
+                .map(|(tag, priority)| TagPriority {
+                    tag: (*tag).to_string(),
+                    priority: *priority,
+                })
+                .collect(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_merge_keeps_existing_mappings() -> Result<(), &'static str> {
+        let template =
+            SubscriptionTemplate::find("uptime_kuma").ok_or("uptime_kuma template missing")?;
+        let existing = vec![TagPriority {
+            tag: "Red_Circle".to_string(),
+            priority: Priority::Max,
+        }];
+        let merged = template.merge_tag_priorities(&existing);
+        assert_eq!(merged.len(), 2);
+        assert_eq!(merged[0], existing[0]);
+        assert_eq!(merged[1].tag, "green_circle");
+        Ok(())
+    }
+
+    #[test]
+    fn test_template_ids_are_unique() {
+        let templates = SubscriptionTemplate::all();
+        for (i, template) in templates.iter().enumerate() {
+            assert!(templates[i + 1..].iter().all(|t| t.id != template.id));
+        }
+    }
+}
@@ -0,0 +1,131 @@
+//! Virtual topics merging several subscriptions into one view.
+//!
+//! A virtual topic is a saved filter over the stored notifications, not a
+//! subscription of its own: nothing is stored twice, and its unread count is
+//! computed from the notifications it matches.
+
+use serde::{Deserialize, Serialize};
+use specta::Type;
+
+use super::notification::{Notification, Priority};
+use super::subscription::NotificationSound;
+use crate::error::AppError;
+
+/// A merged view of notifications from several subscriptions.
+#[derive(Debug, Clone, Serialize, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualTopic {
+    pub id: String,
+    pub name: String,
+    /// Subscriptions merged into the view; empty for all of them.
+    pub subscription_ids: Vec<String>,
+    /// Only notifications with one of these tags are included; empty for any.
+    pub tags: Vec<String>,
+    /// Lowest priority included.
+    #[specta(type = Option<u8>)]
+    pub min_priority: Option<Priority>,
+    /// Muted virtual topics show no toasts for the notifications they include.
+    pub muted: bool,
+    /// Sound of toasts for the notifications included, overriding the
+    /// subscription's.
+    pub sound: Option<NotificationSound>,
+    /// Number of unread notifications included.
+    pub unread_count: i32,
+    /// When the virtual topic was created (milliseconds).
+    pub created_at: i64,
+}
+
+impl VirtualTopic {
+    /// Checks whether `notification` is included in the view.
+    pub fn matches(&self, notification: &Notification) -> bool {
+        (self.subscription_ids.is_empty() || self.subscription_ids.contains(&notification.topic_id))
+            && (self.tags.is_empty() || notification.tags.iter().any(|t| self.tags.contains(t)))
+            && self
+                .min_priority
+                .map_or(true, |p| notification.priority as u8 >= p as u8)
+    }
+}
+
+/// Data required to create or update a virtual topic.
+#[derive(Debug, Clone, Deserialize, Type)]
+#[serde(rename_all = "camelCase")]
+pub struct VirtualTopicInput {
+    pub name: String,
+    pub subscription_ids: Vec<String>,
+    pub tags: Vec<String>,
+    #[specta(type = Option<u8>)]
+    pub min_priority: Option<Priority>,
+    pub muted: bool,
+    pub sound: Option<NotificationSound>,
+}
+
+impl VirtualTopicInput {
+    /// Validates the virtual topic data.
+    pub fn validate(&self) -> Result<(), AppError> {
+        if self.name.trim().is_empty() {
+            return Err(AppError::Validation(
+                "Virtual topic name cannot be empty".to_string(),
+            ));
+        }
+        if self.tags.iter().any(|t| t.trim().is_empty()) {
+            return Err(AppError::Validation(
+                "Virtual topic tags cannot be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::NtfyMessage;
+
+    #[test]
+    fn test_virtual_topic_matches() {
+        let mut topic = VirtualTopic {
+            id: "all-prod".to_string(),
+            name: "All prod".to_string(),
+            subscription_ids: vec!["db".to_string(), "web".to_string()],
+            tags: Vec::new(),
+            min_priority: None,
+            muted: false,
+            sound: None,
+            unread_count: 0,
+            created_at: 0,
+        };
+        let notification = NtfyMessage {
+            id: "m1".to_string(),
+            time: 0,
+            event: "message".to_string(),
+            topic: "db".to_string(),
+            message: Some("Replica lag".to_string()),
+            title: None,
+            priority: Some(4),
+            tags: Some(vec!["prod".to_string(), "warning".to_string()]),
+            click: None,
+            actions: None,
+            attachment: None,
+            content_type: None,
+        }
+        .into_notification("db".to_string());
+        assert!(topic.matches(&notification));
+
+        topic.tags = vec!["prod".to_string()];
+        topic.min_priority = Some(Priority::High);
+        assert!(topic.matches(&notification));
+
+        topic.min_priority = Some(Priority::Max);
+        assert!(!topic.matches(&notification));
+
+        topic.min_priority = None;
+        topic.tags = vec!["staging".to_string()];
+        assert!(!topic.matches(&notification));
+
+        topic.tags = Vec::new();
+        topic.subscription_ids = vec!["web".to_string()];
+        assert!(!topic.matches(&notification));
+        topic.subscription_ids = Vec::new();
+        assert!(topic.matches(&notification));
+    }
+}
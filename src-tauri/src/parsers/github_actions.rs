@@ -0,0 +1,65 @@
+//! GitHub Actions `workflow_run` and `workflow_job` webhook payloads.
+
+use serde_json::Value;
+
+use super::{string_at, MessageParser};
+use crate::models::ParsedMessage;
+
+pub struct GitHubActions;
+
+impl MessageParser for GitHubActions {
+    fn parse(&self, payload: &Value) -> Option<ParsedMessage> {
+        let run = payload
+            .get("workflow_run")
+            .or_else(|| payload.get("workflow_job"))?;
+        payload.get("repository")?;
+
+        // The conclusion (success, failure, ...) is only set once the run completes
+        Some(ParsedMessage {
+            source: "github_actions".to_string(),
+            status: string_at(run, "/conclusion").or_else(|| string_at(run, "/status")),
+            host: string_at(payload, "/repository/full_name"),
+            link: string_at(run, "/html_url"),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse;
+
+    #[test]
+    fn test_completed_run() {
+        let parsed = parse(
+            r#"{
+                "action": "completed",
+                "workflow_run": {
+                    "name": "CI",
+                    "status": "completed",
+                    "conclusion": "failure",
+                    "html_url": "https://github.com/octo/app/actions/runs/1"
+                },
+                "repository": {"full_name": "octo/app"}
+            }"#,
+        );
+        let parsed = parsed.as_ref();
+        assert_eq!(parsed.map(|p| p.source.as_str()), Some("github_actions"));
+        assert_eq!(parsed.and_then(|p| p.status.as_deref()), Some("failure"));
+        assert_eq!(parsed.and_then(|p| p.host.as_deref()), Some("octo/app"));
+    }
+
+    #[test]
+    fn test_in_progress_job_uses_status() {
+        let parsed = parse(
+            r#"{
+                "action": "in_progress",
+                "workflow_job": {"status": "in_progress", "conclusion": null},
+                "repository": {"full_name": "octo/app"}
+            }"#,
+        );
+        assert_eq!(
+            parsed.as_ref().and_then(|p| p.status.as_deref()),
+            Some("in_progress")
+        );
+    }
+}
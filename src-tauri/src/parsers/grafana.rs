@@ -0,0 +1,54 @@
+//! Grafana alerting webhook payloads.
+
+use serde_json::Value;
+
+use super::{string_at, MessageParser};
+use crate::models::ParsedMessage;
+
+pub struct Grafana;
+
+impl MessageParser for Grafana {
+    fn parse(&self, payload: &Value) -> Option<ParsedMessage> {
+        // Alert groups carry a status, a list of alerts and a group key
+        payload.get("alerts")?.as_array()?;
+        payload.get("groupKey")?;
+
+        Some(ParsedMessage {
+            source: "grafana".to_string(),
+            status: string_at(payload, "/status"),
+            host: string_at(payload, "/commonLabels/instance")
+                .or_else(|| string_at(payload, "/alerts/0/labels/instance")),
+            link: string_at(payload, "/alerts/0/generatorURL")
+                .or_else(|| string_at(payload, "/externalURL")),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse;
+
+    #[test]
+    fn test_firing_alert() {
+        let parsed = parse(
+            r#"{
+                "status": "firing",
+                "groupKey": "{}:{alertname=\"HighCPU\"}",
+                "commonLabels": {"alertname": "HighCPU"},
+                "alerts": [{
+                    "labels": {"instance": "web-01:9100"},
+                    "generatorURL": "https://grafana.example.com/alerting/1/view"
+                }],
+                "externalURL": "https://grafana.example.com/"
+            }"#,
+        );
+        let parsed = parsed.as_ref();
+        assert_eq!(parsed.map(|p| p.source.as_str()), Some("grafana"));
+        assert_eq!(parsed.and_then(|p| p.status.as_deref()), Some("firing"));
+        assert_eq!(parsed.and_then(|p| p.host.as_deref()), Some("web-01:9100"));
+        assert_eq!(
+            parsed.and_then(|p| p.link.as_deref()),
+            Some("https://grafana.example.com/alerting/1/view")
+        );
+    }
+}
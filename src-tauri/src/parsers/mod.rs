@@ -0,0 +1,64 @@
+//! Parsers for structured payloads of known producers.
+//!
+//! Some tools publish their raw webhook JSON as the ntfy message body. Each
+//! parser recognizes one producer's format and extracts common fields
+//! (status, host, link) that are stored alongside the notification.
+//!
+//! To support a new producer, implement [`MessageParser`] in its own module
+//! and add it to [`PARSERS`]. Parsers are tried in order; the first match wins.
+
+mod github_actions;
+mod grafana;
+mod uptime_kuma;
+
+use serde_json::Value;
+
+use crate::models::ParsedMessage;
+
+/// Extracts fields from one producer's payload format.
+pub trait MessageParser: Sync {
+    /// Parses a JSON payload, returning `None` if it isn't this producer's format.
+    fn parse(&self, payload: &Value) -> Option<ParsedMessage>;
+}
+
+/// Registered parsers, tried in order.
+static PARSERS: &[&dyn MessageParser] = &[
+    &grafana::Grafana,
+    &uptime_kuma::UptimeKuma,
+    &github_actions::GitHubActions,
+];
+
+/// Parses a message body with the first parser that recognizes it.
+pub fn parse(message: &str) -> Option<ParsedMessage> {
+    // Skip the JSON parse for plain text messages
+    if !message.trim_start().starts_with('{') {
+        return None;
+    }
+    let payload: Value = serde_json::from_str(message).ok()?;
+    PARSERS.iter().find_map(|parser| parser.parse(&payload))
+}
+
+/// Returns the non-empty string at a JSON pointer.
+fn string_at(payload: &Value, pointer: &str) -> Option<String> {
+    payload
+        .pointer(pointer)
+        .and_then(Value::as_str)
+        .filter(|s| !s.is_empty())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_plain_text_is_not_parsed() {
+        assert!(parse("Backup finished").is_none());
+        assert!(parse("{not json").is_none());
+    }
+
+    #[test]
+    fn test_unknown_json_is_not_parsed() {
+        assert!(parse(r#"{"hello": "world"}"#).is_none());
+    }
+}
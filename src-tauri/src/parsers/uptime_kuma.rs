@@ -0,0 +1,79 @@
+//! Uptime Kuma webhook payloads.
+
+use serde_json::Value;
+use url::Url;
+
+use super::{string_at, MessageParser};
+use crate::models::ParsedMessage;
+
+pub struct UptimeKuma;
+
+impl MessageParser for UptimeKuma {
+    fn parse(&self, payload: &Value) -> Option<ParsedMessage> {
+        let monitor = payload.get("monitor")?;
+        monitor.get("name")?;
+
+        // Heartbeat status codes: 0 = down, 1 = up, 2 = pending, 3 = maintenance
+        let status = payload
+            .pointer("/heartbeat/status")
+            .and_then(Value::as_i64)
+            .and_then(|status| match status {
+                0 => Some("down"),
+                1 => Some("up"),
+                2 => Some("pending"),
+                3 => Some("maintenance"),
+                _ => None,
+            })
+            .map(str::to_string);
+
+        // Monitors without a URL report the placeholder "https://"
+        let link = string_at(monitor, "/url")
+            .filter(|url| Url::parse(url).is_ok_and(|u| u.host().is_some()));
+        let host = string_at(monitor, "/hostname").or_else(|| {
+            link.as_deref()
+                .and_then(|url| Url::parse(url).ok())
+                .and_then(|url| url.host_str().map(str::to_string))
+        });
+
+        Some(ParsedMessage {
+            source: "uptime_kuma".to_string(),
+            status,
+            host,
+            link,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::parse;
+
+    #[test]
+    fn test_down_heartbeat() {
+        let parsed = parse(
+            r#"{
+                "heartbeat": {"status": 0, "msg": "Connection refused"},
+                "monitor": {"name": "Website", "url": "https://example.com/health", "hostname": null},
+                "msg": "[Website] [🔴 Down] Connection refused"
+            }"#,
+        );
+        let parsed = parsed.as_ref();
+        assert_eq!(parsed.map(|p| p.source.as_str()), Some("uptime_kuma"));
+        assert_eq!(parsed.and_then(|p| p.status.as_deref()), Some("down"));
+        assert_eq!(parsed.and_then(|p| p.host.as_deref()), Some("example.com"));
+    }
+
+    #[test]
+    fn test_placeholder_url_is_dropped() {
+        let parsed = parse(
+            r#"{
+                "heartbeat": {"status": 1},
+                "monitor": {"name": "Ping", "url": "https://", "hostname": "10.0.0.2"}
+            }"#,
+        );
+        let parsed = parsed.as_ref();
+        assert_eq!(parsed.and_then(|p| p.status.as_deref()), Some("up"));
+        assert_eq!(parsed.and_then(|p| p.host.as_deref()), Some("10.0.0.2"));
+        assert!(parsed.is_some_and(|p| p.link.is_none()));
+    }
+}
@@ -0,0 +1,117 @@
+//! Notification acknowledgments.
+//!
+//! Acknowledging a notification records who took care of it. On a topic
+//! shared by an on-call team, an ack message can also be published back to
+//! the topic, so everyone subscribed sees who took the alert.
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::Notification;
+use crate::services::{NtfyClient, OutgoingMessage};
+
+/// Tags of published ack messages (shown as a check mark by ntfy clients).
+const ACK_TAGS: [&str; 1] = ["white_check_mark"];
+
+/// Service for acknowledging notifications.
+pub struct AcknowledgmentService;
+
+impl AcknowledgmentService {
+    /// Acknowledges a notification as the server account's user (or the OS
+    /// user for servers without an account) and returns the updated notification.
+    ///
+    /// With `publish`, an ack message naming this device and including `note`
+    /// is sent to the topic.
+    /// Local and detached subscriptions have nowhere to publish to, so only
+    /// the local acknowledgment is recorded for them.
+    pub async fn acknowledge(
+        db: &Database,
+        id: &str,
+        note: Option<&str>,
+        publish: bool,
+    ) -> Result<Notification, AppError> {
+        let not_found = || AppError::NotFound(format!("Notification {id} not found"));
+        let notification = db.get_notification_by_id(id)?.ok_or_else(not_found)?;
+        let subscription = db
+            .get_subscription_by_id(&notification.topic_id)?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Subscription {} not found", notification.topic_id))
+            })?;
+
+        let servers = db.get_servers_with_credentials()?;
+        let credentials = servers
+            .iter()
+            .find(|s| s.url_matches(&subscription.server_url))
+            .and_then(|s| s.credentials());
+        let name = credentials.map_or_else(local_user_name, |(user, _)| user.to_string());
+
+        db.acknowledge_notification(id, &name)?;
+
+        if publish && !subscription.local && !subscription.detached {
+            let device = db.get_device_info()?;
+            let (title, message) = ack_message(
+                &notification.title,
+                &notification.message,
+                &format!("{name} on {}", device.name),
+                note,
+            );
+            let (username, password) = credentials.unzip();
+            NtfyClient::new()?
+                .publish(
+                    &subscription.server_url,
+                    &subscription.topic,
+                    &OutgoingMessage {
+                        title: &title,
+                        message: &message,
+                        tags: &ACK_TAGS,
+                        ..Default::default()
+                    },
+                    username,
+                    password,
+                )
+                .await?;
+        }
+
+        db.get_notification_by_id(id)?.ok_or_else(not_found)
+    }
+}
+
+/// Name of the logged-in OS user.
+fn local_user_name() -> String {
+    std::env::var("USERNAME")
+        .or_else(|_| std::env::var("USER"))
+        .unwrap_or_else(|_| "someone".to_string())
+}
+
+/// Builds the title and message of an ack for a notification with the given
+/// title and message.
+fn ack_message(title: &str, message: &str, name: &str, note: Option<&str>) -> (String, String) {
+    let subject = if title.is_empty() {
+        message.lines().next().unwrap_or_default()
+    } else {
+        title
+    };
+    let title = format!("Acknowledged: {subject}");
+
+    let mut message = format!("{name} acknowledged this alert");
+    if let Some(note) = note.map(str::trim).filter(|n| !n.is_empty()) {
+        message.push_str("\n\n");
+        message.push_str(note);
+    }
+    (title, message)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ack_message() {
+        let (title, message) = ack_message("Disk full", "db-1", "alice", Some(" cleaning up "));
+        assert_eq!(title, "Acknowledged: Disk full");
+        assert_eq!(message, "alice acknowledged this alert\n\ncleaning up");
+
+        let (title, message) = ack_message("", "CPU high\nhost-2", "bob", None);
+        assert_eq!(title, "Acknowledged: CPU high");
+        assert_eq!(message, "bob acknowledged this alert");
+    }
+}
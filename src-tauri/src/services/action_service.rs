@@ -0,0 +1,127 @@
+//! Running notification action buttons.
+//!
+//! - `view` actions open their link, after the checks of `UrlSafety::open`
+//! - `http` actions send a request (method, headers, body) chosen by whoever
+//!   published the message, so they pass the same link checks and, from
+//!   untrusted subscriptions, need the user's consent through the
+//!   `HttpAction` capability of `PermissionService`. The outcome is reported
+//!   with the `action:executed` event.
+//! - `broadcast` actions send an Android intent, which has no desktop
+//!   equivalent; they're passed to the frontend as `action:broadcast`.
+//!
+//! Actions with `clear` set mark the notification read and remove its toast
+//! once they succeed.
+
+use tauri::{AppHandle, Manager};
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::events::{self, ActionBroadcast, ActionExecuted};
+use crate::models::{Capability, Notification, NotificationAction};
+use crate::services::{NtfyClient, PermissionService, ToastHistory, TrayManager, UrlSafety};
+
+/// Service for running notification actions.
+pub struct ActionService;
+
+impl ActionService {
+    /// Runs the action `action_id` of a notification.
+    ///
+    /// Returns `false` if the user declined to open a link or run a request
+    /// from an untrusted subscription, and an error if the action failed the
+    /// checks or its request failed.
+    pub async fn execute(
+        handle: &AppHandle,
+        notification_id: &str,
+        action_id: &str,
+    ) -> Result<bool, AppError> {
+        let notification = handle
+            .state::<Database>()
+            .get_notification_by_id(notification_id)?
+            .ok_or_else(|| {
+                AppError::NotFound(format!("Notification {notification_id} not found"))
+            })?;
+        let action = notification
+            .actions
+            .iter()
+            .find(|a| a.id == action_id)
+            .cloned()
+            .ok_or_else(|| AppError::NotFound(format!("Action {action_id} not found")))?;
+
+        let done = match action.action.as_str() {
+            "view" => {
+                let url = action.url.as_deref().unwrap_or_default();
+                UrlSafety::open(handle, &notification.topic_id, url).await?
+            }
+            "http" => Self::send_request(handle, &notification, &action).await?,
+            "broadcast" => {
+                events::emit(
+                    handle,
+                    &ActionBroadcast {
+                        notification_id: notification_id.to_string(),
+                        action_id: action_id.to_string(),
+                        intent: action.intent.clone(),
+                        extras: action.extras.clone(),
+                    },
+                );
+                true
+            }
+            kind => {
+                return Err(AppError::Validation(format!(
+                    "Unsupported action type: {kind}"
+                )))
+            }
+        };
+
+        if done && action.clear {
+            let db: tauri::State<'_, Database> = handle.state();
+            db.mark_notification_read(notification_id)?;
+            handle
+                .state::<ToastHistory>()
+                .dismiss(handle, notification_id);
+            handle.state::<TrayManager>().refresh_from_db(handle);
+        }
+        Ok(done)
+    }
+
+    /// Sends the request of an `http` action, asking for consent first.
+    async fn send_request(
+        handle: &AppHandle,
+        notification: &Notification,
+        action: &NotificationAction,
+    ) -> Result<bool, AppError> {
+        let url = UrlSafety::check(
+            &handle.state::<Database>(),
+            action.url.as_deref().unwrap_or_default(),
+        )?;
+        if !matches!(url.scheme(), "http" | "https") {
+            return Err(AppError::InvalidUrl(format!(
+                "Actions can't send requests to {}: links",
+                url.scheme()
+            )));
+        }
+
+        let method = action.method.as_deref().unwrap_or("POST");
+        let detail = format!("{} {url}", method.to_ascii_uppercase());
+        if !PermissionService::request(
+            handle,
+            &notification.topic_id,
+            Capability::HttpAction,
+            &detail,
+        )
+        .await
+        {
+            return Ok(false);
+        }
+
+        let result = NtfyClient::new()?.execute_http_action(action).await;
+        events::emit(
+            handle,
+            &ActionExecuted {
+                notification_id: notification.id.clone(),
+                action_id: action.id.clone(),
+                error: result.as_ref().err().map(ToString::to_string),
+            },
+        );
+        result.map(|()| true)
+    }
+}
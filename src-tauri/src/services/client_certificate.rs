@@ -0,0 +1,191 @@
+//! Client certificates presented to servers behind an mTLS reverse proxy.
+//!
+//! A server's certificate is a PKCS#12 file, whose path is stored with the
+//! server and whose passphrase is kept in the OS keychain. Like the
+//! resolver's overrides, loaded certificates are kept in memory by host name
+//! so HTTP clients and WebSocket connections can present them without the app
+//! state.
+
+use std::collections::HashMap;
+use std::path::Path;
+use std::sync::{Arc, OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use rustls::pki_types::{CertificateDer, PrivateKeyDer, PrivatePkcs8KeyDer};
+use rustls::{ClientConfig, RootCertStore};
+use zeroize::Zeroizing;
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::services::credential_manager;
+
+/// Line length of base64 in PEM files.
+const PEM_LINE_LENGTH: usize = 64;
+
+/// A loaded client certificate.
+pub struct ClientCertificate {
+    /// The private key and certificate chain as PEM, for HTTP clients.
+    pem: Zeroizing<String>,
+    /// TLS configuration presenting the certificate, for WebSocket connections.
+    tls_config: Arc<ClientConfig>,
+}
+
+impl ClientCertificate {
+    /// Loads the private key and certificate chain of the PKCS#12 file at
+    /// `path`, encrypted with `passphrase` (empty if it isn't).
+    pub fn load(path: &Path, passphrase: &str) -> Result<Self, AppError> {
+        let failed = |reason: String| {
+            AppError::Credential(format!("Client certificate {}: {reason}", path.display()))
+        };
+
+        let data = std::fs::read(path).map_err(|e| failed(format!("can't be read: {e}")))?;
+        let keystore = p12_keystore::KeyStore::from_pkcs12(&data, passphrase)
+            .map_err(|e| failed(format!("can't be opened: {e}")))?;
+        let (_, key_chain) = keystore
+            .private_key_chain()
+            .ok_or_else(|| failed("contains no private key".to_string()))?;
+        if key_chain.chain().is_empty() {
+            return Err(failed("contains no certificate".to_string()));
+        }
+
+        let mut pem = pem_block("PRIVATE KEY", key_chain.key());
+        for certificate in key_chain.chain() {
+            pem.push_str(&pem_block("CERTIFICATE", certificate.as_der()));
+        }
+
+        let certificates = key_chain
+            .chain()
+            .iter()
+            .map(|certificate| CertificateDer::from(certificate.as_der().to_vec()))
+            .collect();
+        let key = PrivateKeyDer::Pkcs8(PrivatePkcs8KeyDer::from(key_chain.key().to_vec()));
+        let mut roots = RootCertStore::empty();
+        roots.extend(webpki_roots::TLS_SERVER_ROOTS.iter().cloned());
+        let tls_config = ClientConfig::builder()
+            .with_root_certificates(roots)
+            .with_client_auth_cert(certificates, key)
+            .map_err(|e| failed(format!("is invalid: {e}")))?;
+
+        Ok(Self {
+            pem,
+            tls_config: Arc::new(tls_config),
+        })
+    }
+
+    /// Identity presenting the certificate from an HTTP client.
+    pub fn identity(&self) -> Result<reqwest::Identity, AppError> {
+        reqwest::Identity::from_pem(self.pem.as_bytes())
+            .map_err(|e| AppError::Credential(format!("Invalid client certificate: {e}")))
+    }
+
+    /// TLS configuration presenting the certificate from a WebSocket connection.
+    pub fn tls_config(&self) -> Arc<ClientConfig> {
+        Arc::clone(&self.tls_config)
+    }
+}
+
+fn pem_block(label: &str, der: &[u8]) -> Zeroizing<String> {
+    let encoded = Zeroizing::new(STANDARD.encode(der));
+    let mut pem = Zeroizing::new(format!("-----BEGIN {label}-----\n"));
+    // base64 is ASCII, so every chunk is a whole string
+    for line in encoded.as_bytes().chunks(PEM_LINE_LENGTH) {
+        pem.push_str(std::str::from_utf8(line).unwrap_or_default());
+        pem.push('\n');
+    }
+    pem.push_str(&format!("-----END {label}-----\n"));
+    pem
+}
+
+type Certificates = HashMap<String, Arc<ClientCertificate>>;
+
+static CERTIFICATES: OnceLock<RwLock<Certificates>> = OnceLock::new();
+
+fn read_certificates() -> RwLockReadGuard<'static, Certificates> {
+    match CERTIFICATES.get_or_init(RwLock::default).read() {
+        Ok(certificates) => certificates,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+fn write_certificates() -> RwLockWriteGuard<'static, Certificates> {
+    match CERTIFICATES.get_or_init(RwLock::default).write() {
+        Ok(certificates) => certificates,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Loads the client certificates of all configured servers, replacing those
+/// loaded before. Certificates that fail to load are skipped.
+pub fn reload(db: &Database) {
+    let servers = match db.get_server_client_certificates() {
+        Ok(servers) => servers,
+        Err(e) => {
+            log::warn!("Failed to load client certificates: {e}");
+            return;
+        }
+    };
+
+    let certificates = servers
+        .into_iter()
+        .filter_map(|(url, path)| {
+            let host = host_of(&url)?;
+            let passphrase = credential_manager::get_certificate_passphrase(&url)
+                .unwrap_or_else(|e| {
+                    log::warn!("{e}");
+                    None
+                })
+                .map(Zeroizing::new)
+                .unwrap_or_default();
+            match ClientCertificate::load(Path::new(&path), &passphrase) {
+                Ok(certificate) => Some((host, Arc::new(certificate))),
+                Err(e) => {
+                    log::warn!("Not using the client certificate of {url}: {e}");
+                    None
+                }
+            }
+        })
+        .collect();
+    *write_certificates() = certificates;
+}
+
+fn host_of(url: &str) -> Option<String> {
+    let url = url::Url::parse(url).ok()?;
+    Some(
+        url.host_str()?
+            .trim_start_matches('[')
+            .trim_end_matches(']')
+            .to_ascii_lowercase(),
+    )
+}
+
+/// The client certificate presented to `host`, if its server has one.
+pub fn for_host(host: &str) -> Option<Arc<ClientCertificate>> {
+    read_certificates().get(&host.to_ascii_lowercase()).cloned()
+}
+
+/// The client certificate presented to the server at `url`, if it has one.
+pub fn for_server(url: &str) -> Option<Arc<ClientCertificate>> {
+    for_host(&host_of(url)?)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_pem_block() {
+        let pem = pem_block("CERTIFICATE", &[0xAB; 60]);
+        let lines: Vec<&str> = pem.lines().collect();
+        assert_eq!(lines.first(), Some(&"-----BEGIN CERTIFICATE-----"));
+        assert_eq!(lines.last(), Some(&"-----END CERTIFICATE-----"));
+        assert_eq!(lines.len(), 4);
+        assert_eq!(lines.get(1).map(|line| line.len()), Some(PEM_LINE_LENGTH));
+    }
+
+    #[test]
+    fn test_load_missing_file() {
+        let path = std::env::temp_dir().join("ntfier-missing-client-certificate.p12");
+        let result = ClientCertificate::load(&path, "");
+        assert!(matches!(result, Err(AppError::Credential(_))));
+    }
+}
@@ -0,0 +1,295 @@
+//! Applying the optional `ntfier.toml` configuration file.
+//!
+//! The file is loaded at startup and again whenever its modification time
+//! changes, checked at a fixed interval like watched folders. Settings it
+//! sets are written over the app's own, so on a conflict the file wins; the
+//! frontend is told which settings were overwritten through a settings event.
+//! A file that fails to parse is reported and the current settings are kept.
+
+use std::path::PathBuf;
+use std::sync::{Mutex, MutexGuard};
+use std::time::{Duration, SystemTime};
+
+use tauri::{AppHandle, Manager};
+
+use crate::config::config_file::POLL_INTERVAL_SECS;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::events::{self, ConfigFileLoaded};
+use crate::models::{ConfigFile, ConfigFileServer, ConfigFileStatus, ServerConfig};
+use crate::services::{log_redaction, policy, resolver, ConnectionManager};
+
+/// Settings the file sets and overwrote, and servers to reconnect.
+#[derive(Debug, Default)]
+struct Applied {
+    managed: Vec<String>,
+    overridden: Vec<String>,
+    reconnect: Vec<String>,
+}
+
+impl Applied {
+    /// Records that the file sets `key`, and overwrote it if `changed`.
+    fn record(&mut self, key: String, changed: bool) {
+        if changed {
+            self.overridden.push(key.clone());
+        }
+        self.managed.push(key);
+    }
+}
+
+#[derive(Debug)]
+struct LoadedState {
+    /// Modification time of the file when it was last loaded.
+    modified: Option<SystemTime>,
+    status: ConfigFileStatus,
+    log_level: log::LevelFilter,
+}
+
+/// Loads the configuration file and keeps the outcome of the last load.
+pub struct ConfigFileService {
+    path: PathBuf,
+    state: Mutex<LoadedState>,
+}
+
+impl ConfigFileService {
+    pub fn new(path: PathBuf) -> Self {
+        Self {
+            state: Mutex::new(LoadedState {
+                modified: None,
+                status: ConfigFileStatus {
+                    path: path.to_string_lossy().to_string(),
+                    ..ConfigFileStatus::default()
+                },
+                log_level: log::LevelFilter::Info,
+            }),
+            path,
+        }
+    }
+
+    /// Outcome of the last load.
+    pub fn status(&self) -> ConfigFileStatus {
+        self.lock_state().status.clone()
+    }
+
+    /// Most verbose level to log at: the file's, or info.
+    pub fn log_level(&self) -> log::LevelFilter {
+        self.lock_state().log_level
+    }
+
+    /// Loads the file, if there is one, and applies it to the settings.
+    /// Returns the URLs of servers whose connections should be reestablished.
+    pub fn load(&self, db: &Database) -> Vec<String> {
+        let modified = std::fs::metadata(&self.path)
+            .and_then(|meta| meta.modified())
+            .ok();
+        let contents = match std::fs::read_to_string(&self.path) {
+            Ok(contents) => Some(contents),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => {
+                log::warn!("Failed to read configuration file: {e}");
+                self.record_error(modified, e.to_string());
+                return Vec::new();
+            }
+        };
+
+        let Some(contents) = contents else {
+            let mut state = self.lock_state();
+            state.modified = modified;
+            state.status.loaded = false;
+            state.status.managed.clear();
+            state.status.overridden.clear();
+            state.status.error = None;
+            state.log_level = log::LevelFilter::Info;
+            log::set_max_level(state.log_level);
+            return Vec::new();
+        };
+
+        let file = match ConfigFile::parse(&contents) {
+            Ok(file) => file,
+            Err(e) => {
+                log::warn!("Ignoring configuration file: {e}");
+                self.record_error(modified, e.to_string());
+                return Vec::new();
+            }
+        };
+
+        let applied = Self::apply(db, &file);
+        resolver::reload(db);
+        let applied = match applied {
+            Ok(applied) => applied,
+            Err(e) => {
+                log::warn!("Failed to apply configuration file: {e}");
+                self.record_error(modified, e.to_string());
+                return Vec::new();
+            }
+        };
+        if !applied.overridden.is_empty() {
+            log::info!(
+                "Configuration file overrode {}",
+                applied.overridden.join(", ")
+            );
+        }
+
+        let mut state = self.lock_state();
+        state.modified = modified;
+        state.status.loaded = true;
+        state.status.managed = applied.managed;
+        state.status.overridden = applied.overridden;
+        state.status.error = None;
+        state.log_level = file
+            .logging
+            .level
+            .map_or(log::LevelFilter::Info, |level| level.filter());
+        log::set_max_level(state.log_level);
+        applied.reconnect
+    }
+
+    /// Spawns a background task that reloads the file when it changes and
+    /// reconnects the servers whose connection settings it changed.
+    pub fn spawn_watcher(handle: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(POLL_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+
+                let config_file: tauri::State<'_, Self> = handle.state();
+                let modified = std::fs::metadata(&config_file.path)
+                    .and_then(|meta| meta.modified())
+                    .ok();
+                if modified == config_file.lock_state().modified {
+                    continue;
+                }
+
+                log::info!("Configuration file changed, reloading");
+                let db: tauri::State<'_, Database> = handle.state();
+                let reconnect = config_file.load(&db);
+                events::emit(&handle, &ConfigFileLoaded(config_file.status()));
+
+                let conn_manager: tauri::State<'_, ConnectionManager> = handle.state();
+                for url in reconnect {
+                    conn_manager.disconnect_server(&url).await;
+                    conn_manager.connect_server(&url).await;
+                }
+            }
+        });
+    }
+
+    /// Writes the file's settings over the app's.
+    fn apply(db: &Database, file: &ConfigFile) -> Result<Applied, AppError> {
+        let mut applied = Applied::default();
+
+        let servers = db.get_servers()?;
+        for server in &file.servers {
+            let config = server.config();
+            if let Some(existing) = servers.iter().find(|s| s.url_matches(&config.url)) {
+                Self::apply_server(db, server, existing, &mut applied)?;
+            } else if let Err(e) = policy::current().check_server(&config.url) {
+                log::warn!("Not adding server from configuration file: {e}");
+            } else {
+                log::info!("Adding server {} from configuration file", config.url);
+                db.add_server(config.clone())?;
+                if config.is_default {
+                    db.set_default_server(&config.url)?;
+                }
+                Self::apply_server(db, server, &config, &mut applied)?;
+            }
+        }
+
+        if let Some(redaction) = file.logging.redaction {
+            let changed = db.get_log_redaction()? != redaction;
+            if changed {
+                db.set_setting("log_redaction", redaction.as_str())?;
+            }
+            log_redaction::set_mode(redaction);
+            applied.record("logging.redaction".to_string(), changed);
+        }
+        if file.logging.level.is_some() {
+            // Not stored in the database, so there is nothing to conflict with
+            applied.record("logging.level".to_string(), false);
+        }
+
+        Ok(applied)
+    }
+
+    /// Writes a server's settings from the file over the stored ones.
+    fn apply_server(
+        db: &Database,
+        server: &ConfigFileServer,
+        existing: &ServerConfig,
+        applied: &mut Applied,
+    ) -> Result<(), AppError> {
+        let url = existing.normalized_url().to_string();
+        let key = |field: &str| format!("servers.{url}.{field}");
+
+        let username = if server.username.is_some() {
+            server.config().username
+        } else {
+            existing.username.clone()
+        };
+        let is_default = server.default.unwrap_or(existing.is_default);
+        if server.username.is_some() {
+            applied.record(key("username"), username != existing.username);
+        }
+        if server.default.is_some() {
+            applied.record(key("default"), is_default != existing.is_default);
+        }
+
+        if username != existing.username || is_default != existing.is_default {
+            if username != existing.username {
+                applied.reconnect.push(existing.url.clone());
+            }
+            // Without a password the keychain entry is moved to the new username
+            db.update_server(
+                &existing.url,
+                ServerConfig {
+                    url: existing.url.clone(),
+                    username,
+                    password: None,
+                    is_default,
+                },
+            )?;
+        }
+
+        let current = db.get_server_resolution(&existing.url)?;
+        let resolution = server.resolution(&current);
+        if server.ip_preference.is_some() {
+            applied.record(
+                key("ip_preference"),
+                resolution.ip_preference != current.ip_preference,
+            );
+        }
+        if server.static_addresses.is_some() {
+            applied.record(
+                key("static_addresses"),
+                resolution.static_addresses != current.static_addresses,
+            );
+        }
+        if server.socks_proxy.is_some() {
+            applied.record(
+                key("socks_proxy"),
+                resolution.socks_proxy != current.socks_proxy,
+            );
+        }
+        if resolution != current {
+            db.set_server_resolution(&existing.url, &resolution)?;
+            applied.reconnect.push(existing.url.clone());
+        }
+
+        Ok(())
+    }
+
+    fn record_error(&self, modified: Option<SystemTime>, error: String) {
+        let mut state = self.lock_state();
+        state.modified = modified;
+        state.status.loaded = false;
+        state.status.overridden.clear();
+        state.status.error = Some(error);
+    }
+
+    fn lock_state(&self) -> MutexGuard<'_, LoadedState> {
+        match self.state.lock() {
+            Ok(state) => state,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
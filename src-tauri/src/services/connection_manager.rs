@@ -9,28 +9,192 @@ use pulldown_cmark::{Event, Parser, Tag, TagEnd};
 use std::collections::HashMap;
 use std::sync::atomic::{AtomicU64, Ordering};
 use std::sync::Arc;
-use tauri::{AppHandle, Emitter, Manager};
-use tokio::sync::{mpsc, RwLock};
-use tokio_tungstenite::{
-    connect_async,
-    tungstenite::{self, client::IntoClientRequest, http::HeaderValue, Message},
-};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::net::TcpStream;
+use tokio::sync::{mpsc, OwnedSemaphorePermit, RwLock, Semaphore};
+use tokio_tungstenite::tungstenite::handshake::client::Response;
+use tokio_tungstenite::tungstenite::{self, client::IntoClientRequest, http::HeaderValue, Message};
+use tokio_tungstenite::{MaybeTlsStream, WebSocketStream};
 use url::Url;
 
-use crate::config::connection::{JITTER_MAX_SECS, RETRY_BACKOFF_SECS};
+use crate::config::connection::{
+    HANDSHAKE_TIMEOUT_SECS, INGEST_BATCH_SIZE, INGEST_BUFFER_SIZE, JITTER_MAX_SECS,
+    MAX_CONCURRENT_TOASTS, RETRY_BACKOFF_SECS,
+};
 use crate::db::Database;
 use crate::error::AppError;
+use crate::events::{self, NotificationAnnouncement, NotificationNew};
 use crate::models::{
-    normalize_url, Notification, NotificationDisplayMethod, NotificationSettings, NtfyMessage,
-    Subscription,
+    normalize_url, ConnectionErrorKind, ConnectionState, ConnectionStatus, ContentKind,
+    Notification, NotificationDisplayMethod, NotificationSettings, NotificationSound, NtfyMessage,
+    Priority, RuleOutcome, Subscription, TagPriority,
+};
+use crate::parsers;
+use crate::services::{
+    log_redaction, resolver, DndScheduler, FileMirrorService, LinkPreviewService, LockService,
+    MetricsService, NetworkMonitor, ReceiptService, ReceiptState, RuleService, SessionMonitor,
+    SyncService, TrayManager, WriteRetryQueue,
 };
-use crate::services::TrayManager;
 
-/// Connection entry storing both the shutdown sender and a unique connection ID.
+/// Connection entry storing the shutdown sender, a unique connection ID and
+/// the connection's current status.
 /// The ID is used to detect stale connections after a race condition.
 struct ConnectionEntry {
     id: u64,
     shutdown_tx: mpsc::Sender<()>,
+    state: ConnectionState,
+    failed_attempts: u32,
+    connected_since: Option<i64>,
+    next_retry_at: Option<i64>,
+}
+
+type ConnectionMap = Arc<RwLock<HashMap<String, ConnectionEntry>>>;
+
+/// A subscription's connection: connects, hands received messages over for
+/// ingestion and reconnects with backoff, until it's shut down or replaced
+/// by a newer connection.
+///
+/// It doesn't depend on the app, so tests can run it against a mock server.
+struct ConnectionLoop {
+    subscription_id: String,
+    connection_id: u64,
+    ws_url: String,
+    auth_header: Option<String>,
+    /// Name to log instead of the URL, which contains the topic.
+    log_name: String,
+    connections: ConnectionMap,
+    /// Time to wait before reconnect attempt `n` (counted from 0).
+    backoff: fn(usize) -> Duration,
+}
+
+impl ConnectionLoop {
+    /// Runs the connection until it's shut down through `shutdown_rx`,
+    /// replaced in the connection map or `ingest_tx` is closed. Connection
+    /// errors are passed to `on_error` along with the subscription ID.
+    async fn run(
+        self,
+        ingest_tx: mpsc::Sender<NtfyMessage>,
+        mut shutdown_rx: mpsc::Receiver<()>,
+        on_error: impl Fn(&str, &tungstenite::Error) + Send + Sync,
+    ) {
+        let Self {
+            subscription_id: sub_id,
+            connection_id,
+            ws_url,
+            auth_header,
+            log_name,
+            connections,
+            backoff,
+        } = self;
+        let mut reconnect_attempt: usize = 0;
+
+        loop {
+            // Check if this connection is still the current one (race condition protection)
+            {
+                let conns = connections.read().await;
+                let is_current = conns
+                    .get(&sub_id)
+                    .is_some_and(|entry| entry.id == connection_id);
+                if !is_current {
+                    log::info!(
+                        "Connection {connection_id} for {sub_id} is no longer current, stopping"
+                    );
+                    return;
+                }
+            }
+
+            log::info!("Connecting to WebSocket: {log_name}");
+            ConnectionManager::update_entry(&connections, &sub_id, connection_id, |entry| {
+                entry.state = ConnectionState::Connecting;
+                entry.next_retry_at = None;
+            })
+            .await;
+
+            let connect_result =
+                ConnectionManager::open_websocket(&ws_url, auth_header.as_deref(), &log_name).await;
+
+            match connect_result {
+                Ok((ws_stream, _)) => {
+                    log::info!("Connected to {log_name}");
+                    // Reset backoff on successful connection
+                    reconnect_attempt = 0;
+                    ConnectionManager::update_entry(
+                        &connections,
+                        &sub_id,
+                        connection_id,
+                        |entry| {
+                            entry.state = ConnectionState::Connected;
+                            entry.failed_attempts = 0;
+                            entry.connected_since = Some(chrono::Utc::now().timestamp_millis());
+                            entry.next_retry_at = None;
+                        },
+                    )
+                    .await;
+                    let (_write, mut read) = ws_stream.split();
+
+                    loop {
+                        tokio::select! {
+                            msg = read.next() => {
+                                match msg {
+                                    Some(Ok(Message::Text(text))) => {
+                                        if let Ok(ntfy_msg) = serde_json::from_str::<NtfyMessage>(&text) {
+                                            // Waits while the buffer is full, which
+                                            // pauses reading from the socket
+                                            if ntfy_msg.event == "message"
+                                                && ingest_tx.send(ntfy_msg).await.is_err()
+                                            {
+                                                return;
+                                            }
+                                        }
+                                    }
+                                    Some(Err(e)) => {
+                                        log::error!("WebSocket error: {e}");
+                                        on_error(&sub_id, &e);
+                                        break;
+                                    }
+                                    None => {
+                                        log::info!("WebSocket closed");
+                                        on_error(&sub_id, &tungstenite::Error::ConnectionClosed);
+                                        break;
+                                    }
+                                    _ => {}
+                                }
+                            }
+                            _ = shutdown_rx.recv() => {
+                                log::info!("Shutting down connection for {sub_id}");
+                                return;
+                            }
+                        }
+                    }
+                }
+                Err(e) => {
+                    log::error!("Failed to connect to {log_name}: {e}");
+                    on_error(&sub_id, &e);
+                }
+            }
+
+            let total_delay = backoff(reconnect_attempt);
+
+            log::info!(
+                "Reconnecting in {} seconds (attempt {})...",
+                total_delay.as_secs(),
+                reconnect_attempt + 1
+            );
+            MetricsService::record_ws_reconnect();
+            let retry_at = chrono::Utc::now().timestamp_millis()
+                + i64::try_from(total_delay.as_millis()).unwrap_or(i64::MAX);
+            ConnectionManager::update_entry(&connections, &sub_id, connection_id, |entry| {
+                entry.state = ConnectionState::Reconnecting;
+                entry.failed_attempts += 1;
+                entry.connected_since = None;
+                entry.next_retry_at = Some(retry_at);
+            })
+            .await;
+            tokio::time::sleep(total_delay).await;
+            reconnect_attempt = (reconnect_attempt + 1).min(RETRY_BACKOFF_SECS.len() - 1);
+        }
+    }
 }
 
 /// Manages WebSocket connections to ntfy servers.
@@ -38,10 +202,16 @@ struct ConnectionEntry {
 /// Each subscription gets its own WebSocket connection that receives
 /// real-time notifications. Connections automatically reconnect on failure
 /// using exponential backoff with jitter.
+///
+/// Received messages are handed to a per-connection ingestion task through a
+/// bounded channel, so a flooding topic stops being read instead of piling
+/// up work.
 pub struct ConnectionManager {
     app_handle: AppHandle,
-    connections: Arc<RwLock<HashMap<String, ConnectionEntry>>>,
+    connections: ConnectionMap,
     next_connection_id: AtomicU64,
+    /// Slots for toasts being shown, shared by all connections.
+    toast_permits: Arc<Semaphore>,
 }
 
 impl ConnectionManager {
@@ -51,6 +221,7 @@ impl ConnectionManager {
             app_handle,
             connections: Arc::new(RwLock::new(HashMap::new())),
             next_connection_id: AtomicU64::new(1),
+            toast_permits: Arc::new(Semaphore::new(MAX_CONCURRENT_TOASTS)),
         }
     }
 
@@ -105,11 +276,20 @@ impl ConnectionManager {
     /// Establishes a WebSocket connection for a subscription.
     ///
     /// If a connection already exists for this subscription, it will be closed first.
+    /// Detached and local subscriptions are skipped.
     /// The connection runs in a background task and automatically reconnects on failure.
     /// Uses connection IDs to detect and handle race conditions where multiple
     /// `connect()` calls happen in quick succession.
     pub async fn connect(&self, subscription: &Subscription) -> Result<(), AppError> {
-        let (shutdown_tx, mut shutdown_rx) = mpsc::channel::<()>(1);
+        if subscription.detached || subscription.local {
+            log::debug!(
+                "Subscription {} is detached or local, not connecting",
+                subscription.id
+            );
+            return Ok(());
+        }
+
+        let (shutdown_tx, shutdown_rx) = mpsc::channel::<()>(1);
         let connection_id = self.generate_connection_id();
 
         {
@@ -122,11 +302,21 @@ impl ConnectionManager {
                 ConnectionEntry {
                     id: connection_id,
                     shutdown_tx,
+                    state: ConnectionState::Connecting,
+                    failed_attempts: 0,
+                    connected_since: None,
+                    next_retry_at: None,
                 },
             );
         }
 
         let ws_url = Self::build_ws_url(subscription)?;
+        // The URL contains the topic, so logs use this instead
+        let log_name = format!(
+            "{}/{}",
+            normalize_url(&subscription.server_url),
+            log_redaction::redact(&subscription.topic)
+        );
         let sub_id = subscription.id.clone();
         let is_muted = subscription.muted;
         let app_handle = self.app_handle.clone();
@@ -134,117 +324,182 @@ impl ConnectionManager {
 
         let auth_header = self.get_auth_header(&subscription.server_url);
 
-        tokio::spawn(async move {
-            let mut reconnect_attempt: usize = 0;
-
-            loop {
-                // Check if this connection is still the current one (race condition protection)
-                {
-                    let conns = connections.read().await;
-                    let is_current = conns
-                        .get(&sub_id)
-                        .is_some_and(|entry| entry.id == connection_id);
-                    if !is_current {
-                        log::info!(
-                            "Connection {connection_id} for {sub_id} is no longer current, stopping"
-                        );
-                        return;
-                    }
-                }
+        // Ends once the connection task below drops the sender
+        let (ingest_tx, ingest_rx) = mpsc::channel::<NtfyMessage>(INGEST_BUFFER_SIZE);
+        tokio::spawn(Self::run_ingestion(
+            app_handle.clone(),
+            sub_id.clone(),
+            is_muted,
+            ingest_rx,
+        ));
+
+        let connection = ConnectionLoop {
+            subscription_id: sub_id,
+            connection_id,
+            ws_url,
+            auth_header,
+            log_name,
+            connections,
+            backoff: |attempt| Duration::from_secs(Self::retry_delay(attempt)),
+        };
+        tokio::spawn(connection.run(ingest_tx, shutdown_rx, move |sub_id, e| {
+            Self::log_connection_error(&app_handle, sub_id, e);
+        }));
 
-                log::info!("Connecting to WebSocket: {ws_url}");
+        Ok(())
+    }
 
-                let connect_result = if let Some(ref auth) = auth_header {
-                    match ws_url.as_str().into_client_request() {
-                        Ok(mut request) => match HeaderValue::from_str(auth) {
-                            Ok(header_value) => {
-                                request.headers_mut().insert("Authorization", header_value);
-                                log::info!("Using auth header for WebSocket connection");
-                                connect_async(request).await
-                            }
-                            Err(e) => {
-                                log::error!("Invalid Authorization header: {e}");
-                                Err(tungstenite::Error::Io(std::io::Error::new(
-                                    std::io::ErrorKind::InvalidInput,
-                                    "Invalid auth header",
-                                )))
-                            }
-                        },
+    /// Opens a WebSocket connection, sending `auth_header` as the
+    /// `Authorization` header if given. The handshake times out after
+    /// `HANDSHAKE_TIMEOUT_SECS`, so a hung server can't stall reconnecting.
+    async fn open_websocket(
+        ws_url: &str,
+        auth_header: Option<&str>,
+        log_name: &str,
+    ) -> Result<(WebSocketStream<MaybeTlsStream<TcpStream>>, Response), tungstenite::Error> {
+        let connect_future = async {
+            if let Some(auth) = auth_header {
+                match ws_url.into_client_request() {
+                    Ok(mut request) => match HeaderValue::from_str(auth) {
+                        Ok(header_value) => {
+                            request.headers_mut().insert("Authorization", header_value);
+                            log::info!("Using auth header for WebSocket connection");
+                            resolver::connect_websocket(request).await
+                        }
                         Err(e) => {
-                            log::error!("Invalid WebSocket URL {ws_url}: {e}");
+                            log::error!("Invalid Authorization header: {e}");
                             Err(tungstenite::Error::Io(std::io::Error::new(
                                 std::io::ErrorKind::InvalidInput,
-                                "Invalid WebSocket URL",
+                                "Invalid auth header",
                             )))
                         }
-                    }
-                } else {
-                    log::info!("No auth header for WebSocket connection");
-                    connect_async(&ws_url).await
-                };
-
-                match connect_result {
-                    Ok((ws_stream, _)) => {
-                        log::info!("Connected to {ws_url}");
-                        // Reset backoff on successful connection
-                        reconnect_attempt = 0;
-                        let (_write, mut read) = ws_stream.split();
-
-                        loop {
-                            tokio::select! {
-                                msg = read.next() => {
-                                    match msg {
-                                        Some(Ok(Message::Text(text))) => {
-                                            if let Ok(ntfy_msg) = serde_json::from_str::<NtfyMessage>(&text) {
-                                                if ntfy_msg.event == "message" {
-                                                    Self::handle_notification(
-                                                        &app_handle,
-                                                        &sub_id,
-                                                        ntfy_msg,
-                                                        is_muted,
-                                                    ).await;
-                                                }
-                                            }
-                                        }
-                                        Some(Err(e)) => {
-                                            log::error!("WebSocket error: {e}");
-                                            break;
-                                        }
-                                        None => {
-                                            log::info!("WebSocket closed");
-                                            break;
-                                        }
-                                        _ => {}
-                                    }
-                                }
-                                _ = shutdown_rx.recv() => {
-                                    log::info!("Shutting down connection for {sub_id}");
-                                    return;
-                                }
-                            }
-                        }
-                    }
+                    },
                     Err(e) => {
-                        log::error!("Failed to connect to {ws_url}: {e}");
+                        log::error!("Invalid WebSocket URL {log_name}: {e}");
+                        Err(tungstenite::Error::Io(std::io::Error::new(
+                            std::io::ErrorKind::InvalidInput,
+                            "Invalid WebSocket URL",
+                        )))
                     }
                 }
+            } else {
+                log::info!("No auth header for WebSocket connection");
+                resolver::connect_websocket(ws_url).await
+            }
+        };
+
+        match tokio::time::timeout(
+            std::time::Duration::from_secs(HANDSHAKE_TIMEOUT_SECS),
+            connect_future,
+        )
+        .await
+        {
+            Ok(result) => result,
+            Err(_) => Err(tungstenite::Error::Io(std::io::Error::new(
+                std::io::ErrorKind::TimedOut,
+                "WebSocket handshake timed out",
+            ))),
+        }
+    }
 
-                // Exponential backoff with jitter
-                let delay = RETRY_BACKOFF_SECS[reconnect_attempt.min(RETRY_BACKOFF_SECS.len() - 1)];
-                let jitter = rand::random::<u64>() % JITTER_MAX_SECS;
-                let total_delay = delay + jitter;
-
-                log::info!(
-                    "Reconnecting in {} seconds (attempt {})...",
-                    total_delay,
-                    reconnect_attempt + 1
-                );
-                tokio::time::sleep(std::time::Duration::from_secs(total_delay)).await;
-                reconnect_attempt = (reconnect_attempt + 1).min(RETRY_BACKOFF_SECS.len() - 1);
+    /// Seconds to wait before reconnect attempt `attempt` (counted from 0):
+    /// exponential backoff with jitter.
+    fn retry_delay(attempt: usize) -> u64 {
+        let delay = RETRY_BACKOFF_SECS[attempt.min(RETRY_BACKOFF_SECS.len() - 1)];
+        delay + rand::random::<u64>() % JITTER_MAX_SECS
+    }
+
+    /// Reconnects a subscription right away instead of waiting out the backoff.
+    ///
+    /// Only a connection waiting to retry (or missing) is replaced, unless
+    /// `force` is set, which also restarts one that's connected or mid-handshake.
+    /// Either way the backoff starts over.
+    pub async fn reconnect(
+        &self,
+        subscription: &Subscription,
+        force: bool,
+    ) -> Result<(), AppError> {
+        if !force {
+            let conns = self.connections.read().await;
+            if conns
+                .get(&subscription.id)
+                .is_some_and(|entry| entry.state != ConnectionState::Reconnecting)
+            {
+                return Ok(());
             }
-        });
+        }
+        self.connect(subscription).await
+    }
 
-        Ok(())
+    /// Records a connection error in the subscription's connection log.
+    fn log_connection_error(
+        app_handle: &AppHandle,
+        subscription_id: &str,
+        error: &tungstenite::Error,
+    ) {
+        let kind = match error {
+            tungstenite::Error::Io(e) if e.kind() == std::io::ErrorKind::TimedOut => {
+                ConnectionErrorKind::Timeout
+            }
+            // Raised here for an invalid URL or auth header, not by the network
+            tungstenite::Error::Io(e) if e.kind() == std::io::ErrorKind::InvalidInput => {
+                ConnectionErrorKind::Other
+            }
+            tungstenite::Error::Io(_) => ConnectionErrorKind::Network,
+            tungstenite::Error::Tls(_) => ConnectionErrorKind::Tls,
+            tungstenite::Error::Http(response)
+                if matches!(response.status().as_u16(), 401 | 403) =>
+            {
+                ConnectionErrorKind::Auth
+            }
+            tungstenite::Error::Http(_) | tungstenite::Error::HttpFormat(_) => {
+                ConnectionErrorKind::Http
+            }
+            tungstenite::Error::ConnectionClosed | tungstenite::Error::AlreadyClosed => {
+                ConnectionErrorKind::Closed
+            }
+            _ => ConnectionErrorKind::Other,
+        };
+        let message = match error {
+            tungstenite::Error::ConnectionClosed => "Connection closed by the server".to_string(),
+            error => error.to_string(),
+        };
+
+        let db: tauri::State<Database> = app_handle.state();
+        if let Err(e) = db.add_connection_log_entry(subscription_id, kind, &message) {
+            log::warn!("Failed to record connection error: {e}");
+        }
+    }
+
+    /// Applies `update` to a connection entry if it still belongs to `connection_id`.
+    async fn update_entry(
+        connections: &ConnectionMap,
+        subscription_id: &str,
+        connection_id: u64,
+        update: impl FnOnce(&mut ConnectionEntry),
+    ) {
+        let mut conns = connections.write().await;
+        if let Some(entry) = conns
+            .get_mut(subscription_id)
+            .filter(|entry| entry.id == connection_id)
+        {
+            update(entry);
+        }
+    }
+
+    /// Returns a status snapshot of every managed connection.
+    pub async fn connection_statuses(&self) -> Vec<ConnectionStatus> {
+        let conns = self.connections.read().await;
+        conns
+            .iter()
+            .map(|(subscription_id, entry)| ConnectionStatus {
+                subscription_id: subscription_id.clone(),
+                state: entry.state,
+                failed_attempts: entry.failed_attempts,
+                connected_since: entry.connected_since,
+                next_retry_at: entry.next_retry_at,
+            })
+            .collect()
     }
 
     /// Closes the WebSocket connection for a subscription.
@@ -255,6 +510,27 @@ impl ConnectionManager {
         }
     }
 
+    /// Closes all WebSocket connections.
+    pub async fn disconnect_all(&self) {
+        let mut conns = self.connections.write().await;
+        for (_, entry) in conns.drain() {
+            let _ = entry.shutdown_tx.send(()).await;
+        }
+    }
+
+    /// Recovers from the system waking up or the network changing.
+    ///
+    /// Sockets opened before are likely dead without having noticed, so all
+    /// connections are closed right away. Once a server is reachable, missed
+    /// messages are synced and every subscription reconnects with a fresh
+    /// backoff, instead of waiting out the retries of the old connections.
+    pub async fn resume(&self) {
+        self.disconnect_all().await;
+        NetworkMonitor::wait_for_network(&self.app_handle).await;
+        SyncService::sync_notifications(&self.app_handle).await;
+        self.connect_all().await;
+    }
+
     /// Closes all WebSocket connections for subscriptions on a given server.
     pub async fn disconnect_server(&self, server_url: &str) {
         let db: tauri::State<Database> = self.app_handle.state();
@@ -267,6 +543,20 @@ impl ConnectionManager {
         }
     }
 
+    /// Establishes WebSocket connections for all subscriptions on a given server.
+    pub async fn connect_server(&self, server_url: &str) {
+        let db: tauri::State<Database> = self.app_handle.state();
+        if let Ok(subs) = db.get_all_subscriptions() {
+            for sub in subs {
+                if sub.server_url_matches(server_url) {
+                    if let Err(e) = self.connect(&sub).await {
+                        log::error!("Failed to connect subscription {}: {}", sub.id, e);
+                    }
+                }
+            }
+        }
+    }
+
     /// Establishes WebSocket connections for all subscriptions.
     pub async fn connect_all(&self) {
         let db: tauri::State<Database> = self.app_handle.state();
@@ -279,6 +569,21 @@ impl ConnectionManager {
         }
     }
 
+    /// Shows the main window with the subscription open.
+    #[cfg(windows)]
+    fn open_subscription(app_handle: &AppHandle, subscription_id: &str) {
+        use crate::events::NavigateSubscription;
+
+        if let Some(window) = app_handle.get_webview_window("main") {
+            let _ = window.show();
+            let _ = window.set_focus();
+        }
+        events::emit(
+            app_handle,
+            &NavigateSubscription(subscription_id.to_string()),
+        );
+    }
+
     /// Converts HTTP(S) URL to WebSocket URL for the subscription's topic.
     fn build_ws_url(subscription: &Subscription) -> Result<String, AppError> {
         let mut parsed = Url::parse(&subscription.server_url)
@@ -304,12 +609,96 @@ impl ConnectionManager {
         Ok(parsed.to_string())
     }
 
-    async fn handle_notification(
+    /// Converts an incoming ntfy message into the notification to store.
+    ///
+    /// Shared by the WebSocket and sync paths so both store identical rows:
+    /// known producer payloads are parsed, muted topics and channels mark it
+    /// read, tag priorities and then channel overrides are applied, and it starts expanded when compact
+    /// view auto-expands new messages.
+    pub fn prepare_notification(
+        db: &Database,
+        ntfy_msg: NtfyMessage,
+        subscription_id: &str,
+        is_muted: bool,
+    ) -> Notification {
+        let mut notification = ntfy_msg.into_notification(subscription_id.to_string());
+        notification.parsed = parsers::parse(&notification.message);
+        notification.mentioned = db
+            .get_mentions()
+            .is_ok_and(|mentions| notification.mentions(&mentions));
+
+        // Auto-mark as read for muted topics (unless the user is mentioned),
+        // those set to it and those in a maintenance window
+        if (is_muted && !notification.mentioned)
+            || db
+                .get_subscription_settings(subscription_id)
+                .is_ok_and(|s| s.auto_mark_read || s.in_maintenance(chrono::Utc::now()))
+        {
+            notification.read = true;
+        }
+
+        // Priorities mapped to tags in settings, for senders that can only set tags
+        if let Some(priority) = db
+            .get_tag_priorities()
+            .ok()
+            .and_then(|mappings| TagPriority::resolve(&mappings, &notification.tags))
+        {
+            notification.priority = priority;
+        }
+
+        // Apply channel overrides (priority, mute) from the notification's tags
+        if let Ok(Some(channel)) = db.get_channel_for_tags(&notification.tags) {
+            channel.apply(&mut notification);
+        }
+
+        notification.is_expanded = db.get_expand_new_notifications().unwrap_or(false);
+
+        notification
+    }
+
+    /// Stores messages received on a connection until the connection ends.
+    ///
+    /// Buffered messages are handled in batches, with one tray refresh per batch.
+    async fn run_ingestion(
+        app_handle: AppHandle,
+        subscription_id: String,
+        is_muted: bool,
+        mut messages: mpsc::Receiver<NtfyMessage>,
+    ) {
+        loop {
+            let mut batch = Vec::with_capacity(INGEST_BATCH_SIZE);
+            if messages.recv_many(&mut batch, INGEST_BATCH_SIZE).await == 0 {
+                break;
+            }
+
+            let mut stored = false;
+            for ntfy_msg in batch {
+                stored |=
+                    Self::handle_notification(&app_handle, &subscription_id, ntfy_msg, is_muted);
+            }
+
+            // Update tray icon to show unread badge
+            if stored {
+                let tray_manager: tauri::State<TrayManager> = app_handle.state();
+                tray_manager.refresh_from_db(&app_handle);
+            }
+        }
+    }
+
+    /// Stores an incoming message, notifies the frontend and shows it.
+    ///
+    /// Messages already stored (by ntfy ID) are skipped. The toast is skipped
+    /// as well while `MAX_CONCURRENT_TOASTS` toasts are being shown. A message
+    /// that fails to be stored is queued for a retry, which announces it once
+    /// stored, and isn't shown.
+    ///
+    /// Returns whether the message was new.
+    pub fn handle_notification(
         app_handle: &AppHandle,
         subscription_id: &str,
         ntfy_msg: NtfyMessage,
         is_muted: bool,
-    ) {
+    ) -> bool {
         let db: tauri::State<Database> = app_handle.state();
 
         // Check if notification already exists by ntfy_id to prevent duplicates
@@ -321,46 +710,279 @@ impl ConnectionManager {
                 "Notification {} already exists, skipping",
                 ntfy_msg.ntfy_id()
             );
-            return;
+            return false;
         }
 
         let ntfy_id = ntfy_msg.ntfy_id().to_string();
-        let mut notification = ntfy_msg.into_notification(subscription_id.to_string());
-
-        // Auto-mark as read for muted topics
-        if is_muted {
+        let topic = ntfy_msg.topic.clone();
+        let mut notification = Self::prepare_notification(&db, ntfy_msg, subscription_id, is_muted);
+        let keyword_filters = db
+            .get_keyword_filters_for(&notification)
+            .unwrap_or_default();
+        if keyword_filters.iter().any(|f| f.discard) {
+            log::debug!("Dropping notification {ntfy_id} matching a keyword filter");
+            return false;
+        }
+        let rules = RuleService::evaluate(&db, &topic, &notification);
+        if rules.mark_read {
             notification.read = true;
         }
 
+        MetricsService::record_received(subscription_id, 1);
         if let Err(e) = db.insert_notification_with_ntfy_id(&notification, &ntfy_id) {
-            log::error!("Failed to save notification: {e}");
+            // Announced once the retry stored it
+            log::warn!("Failed to save notification, will retry: {e}");
+            let retry_queue: tauri::State<WriteRetryQueue> = app_handle.state();
+            retry_queue.push(notification, ntfy_id, rules.commands);
+            return false;
         }
-
-        if let Err(e) = app_handle.emit("notification:new", &notification) {
-            log::error!("Failed to emit notification event: {e}");
+        Self::announce_stored(app_handle, &notification, rules.commands);
+
+        // Mentions are shown even in muted topics, as are toasts forced by
+        // rules; keyword filters mute them all
+        let suppressed = rules.mute || !keyword_filters.is_empty();
+        if !suppressed && (!is_muted || notification.mentioned || rules.force_toast) {
+            let conn_manager: tauri::State<Self> = app_handle.state();
+            match conn_manager.try_toast_permit() {
+                Some(permit) => {
+                    let handle = app_handle.clone();
+                    tokio::spawn(async move {
+                        Self::display_notification(
+                            &handle,
+                            &notification,
+                            &ntfy_id,
+                            false,
+                            rules.sound,
+                            rules.force_toast,
+                        )
+                        .await;
+                        drop(permit);
+                    });
+                }
+                None => log::debug!("Too many toasts being shown, skipping toast for {ntfy_id}"),
+            }
         }
+        true
+    }
 
-        // Update tray icon to show unread badge
-        let tray_manager: tauri::State<TrayManager> = app_handle.state();
-        tray_manager.refresh_from_db(app_handle).await;
+    /// Takes one of the `MAX_CONCURRENT_TOASTS` toast slots, held until the
+    /// permit is dropped. `None` while all of them are taken.
+    pub fn try_toast_permit(&self) -> Option<OwnedSemaphorePermit> {
+        Arc::clone(&self.toast_permits).try_acquire_owned().ok()
+    }
 
-        if !is_muted {
-            let handle = app_handle.clone();
-            let notif = notification.clone();
-            tokio::spawn(async move {
-                Self::show_notification(&handle, &notif).await;
-            });
+    /// Announces a notification that was just stored: mirrors it to the
+    /// subscription's file, notifies the frontend, fetches its link preview
+    /// and runs the `commands` of the rules it matched.
+    pub fn announce_stored(
+        app_handle: &AppHandle,
+        notification: &Notification,
+        commands: Vec<String>,
+    ) {
+        let db: tauri::State<Database> = app_handle.state();
+        if let Ok(Some((topic, mirror))) = db.get_subscription_mirror(&notification.topic_id) {
+            FileMirrorService::append(&mirror, &topic, &[notification]);
         }
+
+        events::emit(app_handle, &NotificationNew(notification.clone()));
+        LinkPreviewService::spawn(app_handle, notification);
+        RuleService::spawn_commands(app_handle, commands, notification);
     }
 
     /// Shows a notification using the configured display method.
-    pub async fn show_notification(app_handle: &AppHandle, notification: &Notification) {
+    ///
+    /// Notifications in a muted channel or virtual topic, below the toast
+    /// priority floor (the subscription's own, else the global
+    /// `min_toast_priority`), held back by do-not-disturb or its weekly
+    /// schedule, or arriving in one of the subscription's maintenance windows
+    /// are not shown. A subscription's settings override the global display
+    /// method and sound, a virtual topic's sound overrides the
+    /// subscription's, and a channel's sound setting overrides those;
+    /// mentions of the user play the IM sound instead. Subscriptions with
+    /// `hide_content` show only the topic and unread count; other toasts
+    /// show only the topic while the session is locked, if
+    /// `hide_content_when_locked` is set, or while the app is locked.
+    /// `ntfy_id` tags Windows toasts so they can be removed from the Action
+    /// Center later.
+    pub async fn show_notification(
+        app_handle: &AppHandle,
+        notification: &Notification,
+        ntfy_id: &str,
+    ) {
+        Self::display_notification(app_handle, notification, ntfy_id, false, None, false).await;
+    }
+
+    /// Shows a notification like `show_notification`, but without sound.
+    pub async fn show_notification_silently(
+        app_handle: &AppHandle,
+        notification: &Notification,
+        ntfy_id: &str,
+    ) {
+        Self::display_notification(app_handle, notification, ntfy_id, true, None, false).await;
+    }
+
+    /// Shows a notification like `show_notification`, with the sound and
+    /// forced toast of the rules it matched.
+    pub async fn show_notification_with_rules(
+        app_handle: &AppHandle,
+        notification: &Notification,
+        ntfy_id: &str,
+        rules: &RuleOutcome,
+    ) {
+        Self::display_notification(
+            app_handle,
+            notification,
+            ntfy_id,
+            false,
+            rules.sound,
+            rules.force_toast,
+        )
+        .await;
+    }
+
+    /// Shows a notification like `show_notification`, but with `sound`
+    /// whatever the sound settings.
+    pub async fn show_notification_with_sound(
+        app_handle: &AppHandle,
+        notification: &Notification,
+        ntfy_id: &str,
+        sound: NotificationSound,
+    ) {
+        Self::display_notification(app_handle, notification, ntfy_id, false, Some(sound), false)
+            .await;
+    }
+
+    /// Shows a notification, playing `sound` if set whatever the sound
+    /// settings. With `force` (from a rule), muted channels and virtual
+    /// topics and the toast priority floor don't hold the toast back.
+    async fn display_notification(
+        app_handle: &AppHandle,
+        notification: &Notification,
+        ntfy_id: &str,
+        silent: bool,
+        sound: Option<NotificationSound>,
+        force: bool,
+    ) {
         let db: tauri::State<'_, Database> = app_handle.state();
-        let Ok(settings) = db.get_notification_settings() else {
+        let channel = db.get_channel_for_tags(&notification.tags).ok().flatten();
+        if !force && channel.as_ref().is_some_and(|c| c.muted) {
+            return;
+        }
+        let virtual_topics = db.get_virtual_topics_for(notification).unwrap_or_default();
+        if !force && virtual_topics.iter().any(|t| t.muted) {
+            return;
+        }
+
+        let Ok(mut settings) = db.get_notification_settings() else {
             // Fallback to native if settings can't be read
             Self::show_native_notification(app_handle, notification, None);
             return;
         };
+        let mut overrides = db
+            .get_subscription_settings(&notification.topic_id)
+            .unwrap_or_default();
+        if let Some(method) = overrides.display_method {
+            settings.notification_method = method;
+        }
+        if let Some(sound) = overrides.sound {
+            settings.notification_sound = sound != NotificationSound::Silent;
+        }
+        if let Some(sound) = virtual_topics.iter().find_map(|t| t.sound) {
+            overrides.sound = Some(sound);
+            settings.notification_sound = sound != NotificationSound::Silent;
+        }
+        if let Some(sound) = channel.and_then(|c| c.sound) {
+            settings.notification_sound = sound;
+        }
+        // Mentions stand out with their own sound
+        if notification.mentioned && settings.notification_sound {
+            overrides.sound = Some(NotificationSound::Im);
+        }
+        if let Some(sound) = sound {
+            overrides.sound = Some(sound);
+            settings.notification_sound = sound != NotificationSound::Silent;
+        }
+        if silent {
+            settings.notification_sound = false;
+        }
+
+        let subscription = db
+            .get_subscription_by_id(&notification.topic_id)
+            .ok()
+            .flatten();
+        let min_priority = subscription
+            .as_ref()
+            .and_then(|sub| sub.min_toast_priority)
+            .unwrap_or(settings.min_toast_priority);
+        if !force && (notification.priority as u8) < min_priority as u8 {
+            return;
+        }
+        if db
+            .get_do_not_disturb()
+            .is_ok_and(|dnd| dnd.suppresses(notification, chrono::Utc::now()))
+        {
+            return;
+        }
+        if DndScheduler::suppresses(&db, notification) {
+            return;
+        }
+        if overrides.in_maintenance(chrono::Utc::now()) {
+            return;
+        }
+
+        // Catch-up toasts were reported when first shown
+        if !silent {
+            ReceiptService::send(
+                app_handle,
+                &notification.topic_id,
+                ntfy_id,
+                ReceiptState::Displayed,
+            );
+        }
+
+        let topic_name = subscription.as_ref().map_or("a topic", |sub| {
+            sub.display_name.as_deref().unwrap_or(&sub.topic)
+        });
+        let redacted;
+        let mut notification = notification;
+        let mut ntfy_id = ntfy_id;
+        let session: tauri::State<'_, SessionMonitor> = app_handle.state();
+        if subscription.as_ref().is_some_and(|sub| sub.hide_content) {
+            // Only the topic and unread count; tagging the toast with the
+            // subscription makes each new one replace the previous count
+            let unread = db
+                .get_unread_count(&notification.topic_id)
+                .unwrap_or(0)
+                .max(1);
+            let title = if unread == 1 {
+                format!("1 new message in {topic_name}")
+            } else {
+                format!("{unread} new messages in {topic_name}")
+            };
+            redacted = notification.redacted(title);
+            notification = &redacted;
+            ntfy_id = &notification.topic_id;
+        } else if settings.hide_content_when_locked && session.is_locked() {
+            // Keep message content off the lock screen, showing it in full on unlock
+            session.defer(notification, ntfy_id);
+            redacted = notification.redacted(format!("New message in {topic_name}"));
+            notification = &redacted;
+        } else if app_handle.state::<LockService>().is_locked() {
+            // Content stays hidden until the app is unlocked
+            redacted = notification.redacted(format!("New message in {topic_name}"));
+            notification = &redacted;
+        }
+
+        // Silent catch-up toasts were announced when first shown
+        if settings.screen_reader_announcements && !silent {
+            events::emit(
+                app_handle,
+                &NotificationAnnouncement {
+                    notification_id: notification.id.clone(),
+                    text: Self::announcement_text(notification, topic_name),
+                },
+            );
+        }
 
         match settings.notification_method {
             NotificationDisplayMethod::Native => {
@@ -368,17 +990,53 @@ impl ConnectionManager {
             }
             #[cfg(windows)]
             NotificationDisplayMethod::WindowsEnhanced => {
-                Self::show_winrt_notification(app_handle, notification, &settings).await;
+                Self::show_winrt_notification(
+                    app_handle,
+                    notification,
+                    ntfy_id,
+                    &settings,
+                    overrides.sound,
+                )
+                .await;
             }
             #[cfg(not(windows))]
             NotificationDisplayMethod::WindowsEnhanced => {
-                // Fallback to native on non-Windows platforms
+                // Fallback to native on non-Windows platforms (toast tags don't apply)
+                let _ = ntfy_id;
                 Self::show_native_notification(app_handle, notification, Some(&settings));
             }
         }
     }
 
-    /// Sanitizes text for Windows notification display by extracting plain text from markdown.
+    /// Builds the text read out by screen readers: urgency, topic, title and
+    /// message as plain text.
+    fn announcement_text(notification: &Notification, topic_name: &str) -> String {
+        let mut parts = Vec::new();
+        if notification.priority as u8 >= Priority::High as u8 {
+            parts.push("Urgent".to_string());
+        }
+        parts.push(format!("Notification from {topic_name}"));
+        for text in [
+            notification.title.clone(),
+            Self::plain_message(notification),
+        ] {
+            if !text.trim().is_empty() {
+                parts.push(text.trim().to_string());
+            }
+        }
+        parts.join(". ")
+    }
+
+    /// Returns the message as plain text. Markdown is only stripped from
+    /// messages the publisher declared as markdown.
+    fn plain_message(notification: &Notification) -> String {
+        match notification.content_kind {
+            ContentKind::Markdown => Self::sanitize_for_notification(&notification.message),
+            ContentKind::Plain => notification.message.clone(),
+        }
+    }
+
+    /// Sanitizes markdown for notification display by extracting plain text.
     ///
     /// Uses pulldown-cmark to parse markdown and extract only the text content,
     /// ignoring images and autolinks (URLs).
@@ -441,16 +1099,16 @@ impl ConnectionManager {
         use tauri_plugin_notification::NotificationExt;
 
         let title = if notification.title.is_empty() {
-            "New notification".to_string()
+            "New notification"
         } else {
-            Self::sanitize_for_notification(&notification.title)
+            &notification.title
         };
 
         let mut builder = app_handle
             .notification()
             .builder()
-            .title(&title)
-            .body(&notification.message);
+            .title(title)
+            .body(Self::plain_message(notification));
 
         // Add sound for notifications with priority >= Default (3) to ensure Windows shows them as toast popups
         // Respect notification_sound setting (defaults to true if settings unavailable)
@@ -474,103 +1132,360 @@ impl ConnectionManager {
     async fn show_winrt_notification(
         app_handle: &AppHandle,
         notification: &Notification,
+        ntfy_id: &str,
         settings: &NotificationSettings,
+        custom_sound: Option<NotificationSound>,
     ) {
         use crate::services::image_cache::{self, CachedImage};
 
         // Download image first (async), before creating Toast (which is not Send)
         let cached_image: Option<CachedImage> = if settings.notification_show_images {
-            image_cache::get_notification_image(&notification.attachments, &notification.message)
-                .await
+            let markdown = (notification.content_kind == ContentKind::Markdown)
+                .then_some(notification.message.as_str());
+            image_cache::get_notification_image(&notification.attachments, markdown).await
+        } else {
+            None
+        };
+
+        // Group toasts under a header per subscription in the Action Center
+        let header_title = if settings.notification_group_by_topic {
+            let db: tauri::State<'_, Database> = app_handle.state();
+            db.get_subscription_by_id(&notification.topic_id)
+                .ok()
+                .flatten()
+                .map(|sub| sub.display_name.unwrap_or(sub.topic))
         } else {
             None
         };
 
         // Now create and show the toast (sync part)
-        Self::show_winrt_notification_sync(app_handle, notification, settings, cached_image);
+        Self::show_winrt_notification_sync(
+            app_handle,
+            notification,
+            ntfy_id,
+            header_title.as_deref(),
+            settings,
+            custom_sound,
+            cached_image,
+        );
     }
 
     /// Synchronous part of `WinRT` notification display.
     ///
-    /// Separated from async to avoid Send issues with Toast type.
+    /// Separated from async to avoid Send issues with `WinRT` types.
+    /// The toast is tagged with the ntfy message ID and grouped by subscription
+    /// so it can be replaced or removed from the Action Center later.
     #[cfg(windows)]
     fn show_winrt_notification_sync(
         app_handle: &AppHandle,
         notification: &Notification,
+        ntfy_id: &str,
+        header_title: Option<&str>,
         settings: &NotificationSettings,
+        custom_sound: Option<NotificationSound>,
         cached_image: Option<crate::services::image_cache::CachedImage>,
     ) {
         use crate::services::image_cache::ImageOrientation;
-        use tauri_winrt_notification::{Duration, Scenario, Sound, Toast};
+        use crate::services::windows_toast::{
+            self, ImagePlacement, ToastButton, ToastContent, ToastHeader, ToastSound,
+            ACTION_ARG_PREFIX,
+        };
+        use crate::services::{ActionService, ToastHistory, UrlSafety};
 
         let title = if notification.title.is_empty() {
             "New notification"
         } else {
             &notification.title
         };
+        let body = Self::plain_message(notification);
 
         // Get the app's AUMID (Application User Model ID)
         // Tauri apps use the bundle identifier from tauri.conf.json
         let aumid = app_handle.config().identifier.as_str();
 
-        let mut toast = Toast::new(aumid)
-            .title(&Self::sanitize_for_notification(title))
-            .text1(&Self::sanitize_for_notification(&notification.message));
+        let priority = notification.priority as i32;
+
+        // Sound based on priority (only if notification_sound is enabled),
+        // unless the subscription has its own
+        let sound = if !settings.notification_sound || priority < 3 {
+            None
+        } else if let Some(custom_sound) = custom_sound {
+            ToastSound::from_setting(custom_sound)
+        } else if priority >= 4 {
+            Some(ToastSound::Sms) // Louder sound for high priority
+        } else {
+            Some(ToastSound::Default)
+        };
+
+        // Action buttons from ntfy (max 3 buttons supported by Windows).
+        // Links are opened by the OS directly, so those that fail the safety
+        // checks are left out; `http` and `broadcast` actions are run by the app.
+        let buttons = if settings.notification_show_actions {
+            let db: tauri::State<'_, Database> = app_handle.state();
+            notification
+                .actions
+                .iter()
+                .filter_map(|action| {
+                    let label = action.label.as_str();
+                    if matches!(action.action.as_str(), "http" | "broadcast") {
+                        return Some(ToastButton::App {
+                            label,
+                            arguments: format!("{ACTION_ARG_PREFIX}{}", action.id),
+                        });
+                    }
+                    action
+                        .url
+                        .as_deref()
+                        .filter(|url| UrlSafety::check(&db, url).is_ok())
+                        .map(|url| ToastButton::Link { label, url })
+                })
+                .take(3)
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        // Image display based on orientation:
+        // - Landscape/square images: hero image above text
+        // - Portrait images: inline image below text (avoids cropping)
+        let image = cached_image.as_ref().map(|cached| {
+            let placement = match cached.orientation {
+                ImageOrientation::Landscape => ImagePlacement::Hero,
+                ImageOrientation::Portrait => ImagePlacement::Inline,
+            };
+            (cached.path.as_path(), placement)
+        });
+
+        let content = ToastContent {
+            title,
+            body: &body,
+            header: header_title.map(|title| ToastHeader {
+                id: &notification.topic_id,
+                title,
+            }),
+            // Duration based on priority
+            long_duration: priority >= 4,
+            // Force display - ignores Focus Assist; a looping alarm sound
+            // only plays in an alarm toast
+            alarm: settings.notification_force_display || sound == Some(ToastSound::Alarm),
+            sound,
+            buttons,
+            image,
+        };
+
+        let handle = app_handle.clone();
+        let notification_id = notification.id.clone();
+        let subscription_id = notification.topic_id.clone();
+        let on_activated = move |arguments: String| {
+            let Some(action_id) = arguments.strip_prefix(ACTION_ARG_PREFIX) else {
+                // The toast itself was clicked
+                Self::open_subscription(&handle, &subscription_id);
+                return;
+            };
+            let handle = handle.clone();
+            let notification_id = notification_id.clone();
+            let action_id = action_id.to_string();
+            tauri::async_runtime::spawn(async move {
+                if let Err(e) = ActionService::execute(&handle, &notification_id, &action_id).await
+                {
+                    log::warn!("Failed to run action {action_id} from toast: {e}");
+                }
+            });
+        };
 
-        // Force display - ignores Focus Assist using Scenario::Alarm
-        if settings.notification_force_display {
-            toast = toast.scenario(Scenario::Alarm);
+        match windows_toast::show(
+            aumid,
+            &content,
+            ntfy_id,
+            &notification.topic_id,
+            on_activated,
+        ) {
+            Ok(()) => {
+                let history: tauri::State<'_, ToastHistory> = app_handle.state();
+                history.record(&notification.id, ntfy_id, &notification.topic_id);
+            }
+            Err(e) => {
+                log::error!("Failed to show WinRT notification: {e}");
+                // Fallback to native notification on error
+                Self::show_native_notification(app_handle, notification, Some(settings));
+            }
         }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::MockNtfyServer;
+
+    const SUBSCRIPTION_ID: &str = "sub";
 
-        // Duration based on priority
-        if notification.priority as i32 >= 4 {
-            toast = toast.duration(Duration::Long);
+    /// Waits up to five seconds for `condition` to hold.
+    async fn wait_until(mut condition: impl FnMut() -> bool) -> bool {
+        let deadline = tokio::time::Instant::now() + Duration::from_secs(5);
+        while !condition() {
+            if tokio::time::Instant::now() >= deadline {
+                return false;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
         }
+        true
+    }
 
-        // Sound based on priority (only if notification_sound is enabled)
-        if settings.notification_sound {
-            let sound = if notification.priority as i32 >= 4 {
-                Some(Sound::SMS) // Louder sound for high priority
-            } else if notification.priority as i32 >= 3 {
-                Some(Sound::Default)
-            } else {
-                None
+    /// A connection loop run for the test subscription.
+    struct TestLoop {
+        connections: ConnectionMap,
+        shutdown_tx: mpsc::Sender<()>,
+        /// Messages handed over for ingestion.
+        ingest_rx: mpsc::Receiver<NtfyMessage>,
+        /// Connection errors reported.
+        error_rx: mpsc::UnboundedReceiver<String>,
+        task: tokio::task::JoinHandle<()>,
+    }
+
+    impl TestLoop {
+        /// Starts a connection loop retrying every 20ms.
+        async fn start(ws_url: String, auth_header: Option<&str>) -> Self {
+            let connections = ConnectionMap::default();
+            let (shutdown_tx, shutdown_rx) = mpsc::channel(1);
+            connections.write().await.insert(
+                SUBSCRIPTION_ID.to_string(),
+                ConnectionEntry {
+                    id: 1,
+                    shutdown_tx: shutdown_tx.clone(),
+                    state: ConnectionState::Connecting,
+                    failed_attempts: 0,
+                    connected_since: None,
+                    next_retry_at: None,
+                },
+            );
+
+            let connection = ConnectionLoop {
+                subscription_id: SUBSCRIPTION_ID.to_string(),
+                connection_id: 1,
+                ws_url,
+                auth_header: auth_header.map(str::to_string),
+                log_name: "alerts".to_string(),
+                connections: Arc::clone(&connections),
+                backoff: |_| Duration::from_millis(20),
             };
-            if let Some(s) = sound {
-                toast = toast.sound(Some(s));
+            let (ingest_tx, ingest_rx) = mpsc::channel(INGEST_BUFFER_SIZE);
+            let (error_tx, error_rx) = mpsc::unbounded_channel();
+            let task = tokio::spawn(connection.run(ingest_tx, shutdown_rx, move |_, e| {
+                let _ = error_tx.send(e.to_string());
+            }));
+
+            Self {
+                connections,
+                shutdown_tx,
+                ingest_rx,
+                error_rx,
+                task,
             }
         }
 
-        // Action buttons from ntfy (max 3 buttons supported by Windows)
-        if settings.notification_show_actions && !notification.actions.is_empty() {
-            for action in notification.actions.iter().take(3) {
-                if let Some(ref url) = action.url {
-                    toast = toast.add_button(&action.label, url);
-                }
-            }
+        /// Whether the entry matches `condition`.
+        fn entry_matches(&self, condition: impl FnOnce(&ConnectionEntry) -> bool) -> bool {
+            self.connections
+                .try_read()
+                .is_ok_and(|conns| conns.get(SUBSCRIPTION_ID).is_some_and(condition))
         }
 
-        // Image display based on orientation:
-        // - Landscape/square images: use hero() for prominent display above text
-        // - Portrait images: use image() for inline display below text (avoids cropping)
-        if let Some(ref cached) = cached_image {
-            match cached.orientation {
-                ImageOrientation::Landscape => {
-                    // Hero image above text - ideal for landscape images
-                    toast = toast.hero(&cached.path, "");
-                }
-                ImageOrientation::Portrait => {
-                    // Inline image below text - better for portrait images
-                    // Windows will display it centered and properly scaled
-                    toast = toast.image(&cached.path, "");
-                }
+        /// Whether the loop is connected, with the failed attempts reset.
+        fn is_connected(&self) -> bool {
+            self.entry_matches(|entry| {
+                entry.state == ConnectionState::Connected && entry.failed_attempts == 0
+            })
+        }
+
+        /// Waits for the ID of the next message handed over for ingestion.
+        async fn next_message(&mut self) -> Option<String> {
+            tokio::time::timeout(Duration::from_secs(5), self.ingest_rx.recv())
+                .await
+                .ok()
+                .flatten()
+                .map(|message| message.id)
+        }
+
+        /// Waits for the loop to end.
+        async fn stopped(self) -> bool {
+            matches!(
+                tokio::time::timeout(Duration::from_secs(5), self.task).await,
+                Ok(Ok(()))
+            )
+        }
+    }
+
+    #[tokio::test]
+    async fn test_reconnect_to_mock_server() {
+        let server = MockNtfyServer::start().await;
+        assert!(server.is_ok());
+        let Ok(server) = server else { return };
+        let auth = "Basic dXNlcjpwYXNz";
+        let mut connection = TestLoop::start(server.ws_url("alerts"), Some(auth)).await;
+
+        assert!(wait_until(|| server.websocket_connections() == 1).await);
+        assert!(wait_until(|| connection.is_connected()).await);
+        server.publish("alerts", "m1", server.now(), "Disk full");
+        server.publish("other", "m2", server.now(), "Elsewhere");
+        assert_eq!(connection.next_message().await.as_deref(), Some("m1"));
+
+        // A lost connection is reported and the loop reconnects on its own
+        server.drop_connections();
+        assert!(wait_until(|| server.websocket_connections() == 2).await);
+        assert!(wait_until(|| connection.is_connected()).await);
+        assert!(connection.error_rx.try_recv().is_ok());
+        server.publish("alerts", "m3", server.now(), "Disk still full");
+        assert_eq!(connection.next_message().await.as_deref(), Some("m3"));
+        assert!(server
+            .requests()
+            .iter()
+            .all(|r| r.authorization.as_deref() == Some(auth)));
+
+        assert!(connection.shutdown_tx.send(()).await.is_ok());
+        assert!(connection.stopped().await);
+        assert_eq!(server.websocket_connections(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_retry_until_replaced() {
+        // A server hanging up right away, so every attempt fails
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await;
+        assert!(listener.is_ok());
+        let Ok(listener) = listener else { return };
+        let addr = listener.local_addr();
+        assert!(addr.is_ok());
+        let Ok(addr) = addr else { return };
+        let server = tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                drop(stream);
             }
+        });
+        let mut connection = TestLoop::start(format!("ws://{addr}/alerts/ws"), None).await;
+
+        let retried = wait_until(|| connection.entry_matches(|entry| entry.failed_attempts >= 3));
+        assert!(retried.await);
+        assert!(connection.error_rx.try_recv().is_ok());
+
+        // A newer connection took over, so this one gives up
+        if let Some(entry) = connection
+            .connections
+            .write()
+            .await
+            .get_mut(SUBSCRIPTION_ID)
+        {
+            entry.id = 2;
         }
+        assert!(connection.stopped().await);
+        server.abort();
+    }
 
-        if let Err(e) = toast.show() {
-            log::error!("Failed to show WinRT notification: {e}");
-            // Fallback to native notification on error
-            Self::show_native_notification(app_handle, notification, Some(settings));
+    #[test]
+    fn test_retry_delay() {
+        for attempt in 0..RETRY_BACKOFF_SECS.len() + 2 {
+            let base = RETRY_BACKOFF_SECS[attempt.min(RETRY_BACKOFF_SECS.len() - 1)];
+            let delay = ConnectionManager::retry_delay(attempt);
+            assert!((base..base + JITTER_MAX_SECS).contains(&delay));
         }
     }
 }
@@ -1,17 +1,57 @@
 use crate::error::AppError;
+use crate::services::NtfyClient;
 use keyring::Entry;
 use log::{debug, info};
 use std::collections::HashMap;
-use std::sync::{Mutex, OnceLock};
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
+use zeroize::Zeroizing;
 
 const SERVICE_NAME: &str = "ntfier";
 
-/// In-memory cache for credentials to avoid repeatedly accessing OS keychain
-/// Key: (username, `server_url`), Value: password
-static CREDENTIAL_CACHE: OnceLock<Mutex<HashMap<(String, String), String>>> = OnceLock::new();
+/// In-memory credential cache.
+///
+/// Passwords are wrapped in `Zeroizing` so their memory is wiped whenever an
+/// entry is removed, replaced or the cache is cleared. If an idle timeout is
+/// set, the cache locks (clears itself) once the user hasn't stored or used a
+/// credential for that long, forcing the next lookup back to the OS keychain.
+/// Background reads, such as loading the settings or connecting, don't count
+/// as use, so they can't keep the cache unlocked.
+#[derive(Default)]
+struct CredentialCache {
+    /// Key: (username, `server_url`), Value: password
+    entries: HashMap<(String, String), Zeroizing<String>>,
+    last_access: Option<Instant>,
+    idle_timeout: Option<Duration>,
+}
+
+impl CredentialCache {
+    /// Clears the cache if it has been idle longer than the timeout.
+    fn expire_if_idle(&mut self) {
+        let (Some(timeout), Some(last_access)) = (self.idle_timeout, self.last_access) else {
+            return;
+        };
+        if last_access.elapsed() >= timeout && !self.entries.is_empty() {
+            self.entries.clear();
+            NtfyClient::clear_session_tokens();
+            info!("Credential cache locked after idle timeout");
+        }
+    }
 
-fn get_cache() -> &'static Mutex<HashMap<(String, String), String>> {
-    CREDENTIAL_CACHE.get_or_init(|| Mutex::new(HashMap::new()))
+    /// Records a user access, expiring stale entries first.
+    fn touch(&mut self) {
+        self.expire_if_idle();
+        self.last_access = Some(Instant::now());
+    }
+}
+
+static CREDENTIAL_CACHE: OnceLock<Mutex<CredentialCache>> = OnceLock::new();
+
+fn lock_cache() -> Result<MutexGuard<'static, CredentialCache>, AppError> {
+    CREDENTIAL_CACHE
+        .get_or_init(|| Mutex::new(CredentialCache::default()))
+        .lock()
+        .map_err(|e| AppError::Credential(format!("Failed to lock credential cache: {e}")))
 }
 
 /// Create a keyring entry for the given username and server URL
@@ -32,25 +72,36 @@ pub fn store_password(username: &str, server_url: &str, password: &str) -> Resul
 
     // Update cache
     let cache_key = (username.to_string(), server_url.to_string());
-    let mut cache = get_cache()
-        .lock()
-        .map_err(|e| AppError::Credential(format!("Failed to lock credential cache: {e}")))?;
-    cache.insert(cache_key, password.to_string());
+    let mut cache = lock_cache()?;
+    cache.touch();
+    cache
+        .entries
+        .insert(cache_key, Zeroizing::new(password.to_string()));
 
     info!("Credential stored successfully");
     Ok(())
 }
 
+/// Reset the idle timeout for a user action that uses a credential
+/// Reads don't reset it themselves, as most happen in the background
+pub fn record_user_access() -> Result<(), AppError> {
+    lock_cache()?.touch();
+    Ok(())
+}
+
 /// Get password from cache or OS keychain
-pub fn get_password(username: &str, server_url: &str) -> Result<Option<String>, AppError> {
+/// Doesn't reset the idle timeout, see `record_user_access`
+pub fn get_password(
+    username: &str,
+    server_url: &str,
+) -> Result<Option<Zeroizing<String>>, AppError> {
     let cache_key = (username.to_string(), server_url.to_string());
 
     // Check cache first
     {
-        let cache = get_cache()
-            .lock()
-            .map_err(|e| AppError::Credential(format!("Failed to lock credential cache: {e}")))?;
-        if let Some(password) = cache.get(&cache_key) {
+        let mut cache = lock_cache()?;
+        cache.expire_if_idle();
+        if let Some(password) = cache.entries.get(&cache_key) {
             debug!("Credential retrieved from cache");
             return Ok(Some(password.clone()));
         }
@@ -61,12 +112,11 @@ pub fn get_password(username: &str, server_url: &str) -> Result<Option<String>,
     match entry.get_password() {
         Ok(password) => {
             debug!("Credential retrieved from keychain");
+            let password = Zeroizing::new(password);
 
             // Store in cache for future use
-            let mut cache = get_cache().lock().map_err(|e| {
-                AppError::Credential(format!("Failed to lock credential cache: {e}"))
-            })?;
-            cache.insert(cache_key, password.clone());
+            let mut cache = lock_cache()?;
+            cache.entries.insert(cache_key, password.clone());
 
             Ok(Some(password))
         }
@@ -82,12 +132,10 @@ pub fn get_password(username: &str, server_url: &str) -> Result<Option<String>,
 pub fn delete_password(username: &str, server_url: &str) -> Result<(), AppError> {
     debug!("Deleting credential from keychain");
 
-    // Remove from cache first
+    // Remove from cache first (the dropped password is zeroized)
     let cache_key = (username.to_string(), server_url.to_string());
-    let mut cache = get_cache()
-        .lock()
-        .map_err(|e| AppError::Credential(format!("Failed to lock credential cache: {e}")))?;
-    cache.remove(&cache_key);
+    let mut cache = lock_cache()?;
+    cache.entries.remove(&cache_key);
     drop(cache); // Release lock before calling keychain
 
     let entry = create_entry(username, server_url)?;
@@ -106,15 +154,196 @@ pub fn delete_password(username: &str, server_url: &str) -> Result<(), AppError>
     }
 }
 
-/// Clear all cached credentials from memory
-/// Useful for logout or security purposes
-#[allow(dead_code)]
+/// Move a stored password to a new username/server key
+/// The keychain key embeds both, so editing either would otherwise orphan the entry
+pub fn rename_entry(
+    old_username: &str,
+    old_server_url: &str,
+    new_username: &str,
+    new_server_url: &str,
+) -> Result<(), AppError> {
+    if old_username == new_username && old_server_url == new_server_url {
+        return Ok(());
+    }
+
+    let Some(password) = get_password(old_username, old_server_url)? else {
+        debug!("No credential to migrate");
+        return Ok(());
+    };
+
+    // Store under the new key before removing the old one so a failure can't lose it
+    store_password(new_username, new_server_url, &password)?;
+    delete_password(old_username, old_server_url)?;
+
+    info!("Credential migrated to new keychain entry");
+    Ok(())
+}
+
+/// Clear all cached credentials and session tokens from memory
+/// The next lookup has to go back to the OS keychain, which may prompt for re-authentication
 pub fn clear_cache() -> Result<(), AppError> {
     debug!("Clearing credential cache");
-    let mut cache = get_cache()
-        .lock()
-        .map_err(|e| AppError::Credential(format!("Failed to lock credential cache: {e}")))?;
-    cache.clear();
+    let mut cache = lock_cache()?;
+    cache.entries.clear();
+    NtfyClient::clear_session_tokens();
     info!("Credential cache cleared");
     Ok(())
 }
+
+/// Keychain key of the app lock PIN (can't clash with `username_server` keys)
+const APP_PIN_KEY: &str = "app_lock_pin";
+
+fn app_pin_entry() -> Result<Entry, AppError> {
+    Entry::new(SERVICE_NAME, APP_PIN_KEY)
+        .map_err(|e| AppError::Credential(format!("Failed to create keyring entry: {e}")))
+}
+
+/// Store the app lock PIN in the OS keychain
+pub fn store_app_pin(pin: &str) -> Result<(), AppError> {
+    app_pin_entry()?
+        .set_password(pin)
+        .map_err(|e| AppError::Credential(format!("Failed to store PIN: {e}")))?;
+    info!("App lock PIN stored");
+    Ok(())
+}
+
+/// Check a PIN against the one in the OS keychain
+/// Compares in constant time over the longer of the two, so neither the
+/// PIN nor its length leaks; without a stored PIN nothing matches
+pub fn verify_app_pin(pin: &str) -> Result<bool, AppError> {
+    let stored = match app_pin_entry()?.get_password() {
+        Ok(stored) => Zeroizing::new(stored),
+        Err(keyring::Error::NoEntry) => return Ok(false),
+        Err(e) => return Err(AppError::Credential(format!("Failed to read PIN: {e}"))),
+    };
+    Ok(constant_time_eq(stored.as_bytes(), pin.as_bytes()))
+}
+
+/// Compares two byte strings in time depending only on the longer length
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    let len = a.len().max(b.len());
+    let diff = (0..len).fold(u8::from(a.len() != b.len()), |acc, i| {
+        acc | (a.get(i).copied().unwrap_or(0) ^ b.get(i).copied().unwrap_or(0))
+    });
+    diff == 0
+}
+
+/// Delete the app lock PIN from the OS keychain
+pub fn delete_app_pin() -> Result<(), AppError> {
+    match app_pin_entry()?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(AppError::Credential(format!("Failed to delete PIN: {e}"))),
+    }
+}
+
+/// Keychain key of the proxy password (can't clash with `username_server` keys)
+const PROXY_PASSWORD_KEY: &str = "proxy_password";
+
+fn proxy_password_entry() -> Result<Entry, AppError> {
+    Entry::new(SERVICE_NAME, PROXY_PASSWORD_KEY)
+        .map_err(|e| AppError::Credential(format!("Failed to create keyring entry: {e}")))
+}
+
+/// Store the password of the manual proxy in the OS keychain
+pub fn store_proxy_password(password: &str) -> Result<(), AppError> {
+    proxy_password_entry()?
+        .set_password(password)
+        .map_err(|e| AppError::Credential(format!("Failed to store proxy password: {e}")))?;
+    info!("Proxy password stored");
+    Ok(())
+}
+
+/// Get the password of the manual proxy from the OS keychain
+pub fn get_proxy_password() -> Result<Option<String>, AppError> {
+    match proxy_password_entry()?.get_password() {
+        Ok(password) => Ok(Some(password)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(AppError::Credential(format!(
+            "Failed to read proxy password: {e}"
+        ))),
+    }
+}
+
+/// Delete the password of the manual proxy from the OS keychain
+pub fn delete_proxy_password() -> Result<(), AppError> {
+    match proxy_password_entry()?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(AppError::Credential(format!(
+            "Failed to delete proxy password: {e}"
+        ))),
+    }
+}
+
+/// Keychain key of a server's client certificate passphrase (usernames can't
+/// contain a colon, so it can't clash with `username_server` keys)
+fn certificate_passphrase_entry(server_url: &str) -> Result<Entry, AppError> {
+    Entry::new(SERVICE_NAME, &format!("client_certificate:{server_url}"))
+        .map_err(|e| AppError::Credential(format!("Failed to create keyring entry: {e}")))
+}
+
+/// Store the passphrase of a server's client certificate in the OS keychain
+pub fn store_certificate_passphrase(server_url: &str, passphrase: &str) -> Result<(), AppError> {
+    certificate_passphrase_entry(server_url)?
+        .set_password(passphrase)
+        .map_err(|e| {
+            AppError::Credential(format!("Failed to store certificate passphrase: {e}"))
+        })?;
+    info!("Client certificate passphrase stored");
+    Ok(())
+}
+
+/// Get the passphrase of a server's client certificate from the OS keychain
+pub fn get_certificate_passphrase(server_url: &str) -> Result<Option<String>, AppError> {
+    match certificate_passphrase_entry(server_url)?.get_password() {
+        Ok(passphrase) => Ok(Some(passphrase)),
+        Err(keyring::Error::NoEntry) => Ok(None),
+        Err(e) => Err(AppError::Credential(format!(
+            "Failed to read certificate passphrase: {e}"
+        ))),
+    }
+}
+
+/// Delete the passphrase of a server's client certificate from the OS keychain
+pub fn delete_certificate_passphrase(server_url: &str) -> Result<(), AppError> {
+    match certificate_passphrase_entry(server_url)?.delete_credential() {
+        Ok(()) | Err(keyring::Error::NoEntry) => Ok(()),
+        Err(e) => Err(AppError::Credential(format!(
+            "Failed to delete certificate passphrase: {e}"
+        ))),
+    }
+}
+
+/// Set how long the cache may sit idle before it locks itself.
+/// `None` keeps cached credentials for the app's lifetime.
+pub fn set_idle_timeout(timeout: Option<Duration>) -> Result<(), AppError> {
+    let mut cache = lock_cache()?;
+    cache.idle_timeout = timeout;
+    cache.expire_if_idle();
+    Ok(())
+}
+
+/// Clear the cache if it has been idle longer than the configured timeout
+pub fn lock_if_idle() -> Result<(), AppError> {
+    lock_cache()?.expire_if_idle();
+    Ok(())
+}
+
+/// Convert the `credential_lock_minutes` setting to an idle timeout (0 disables locking)
+pub fn idle_timeout_from_minutes(minutes: u32) -> Option<Duration> {
+    (minutes > 0).then(|| Duration::from_secs(u64::from(minutes) * 60))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_constant_time_eq() {
+        assert!(constant_time_eq(b"1234", b"1234"));
+        assert!(!constant_time_eq(b"1234", b"1235"));
+        assert!(!constant_time_eq(b"1234", b"12345"));
+        assert!(!constant_time_eq(b"12340", b"1234"));
+        assert!(!constant_time_eq(b"", b"0"));
+        assert!(constant_time_eq(b"", b""));
+    }
+}
@@ -0,0 +1,63 @@
+//! Weekly do-not-disturb schedule.
+//!
+//! The `dnd_schedule` setting is checked whenever a toast is about to be
+//! shown. In the background, the scheduler also watches for scheduled quiet
+//! hours beginning and ending and emits `dnd:changed`, so the UI can show
+//! while toasts are held back.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::time::Duration;
+
+use chrono::Utc;
+use tauri::{AppHandle, Manager};
+
+use crate::config::dnd::CHECK_INTERVAL_SECS;
+use crate::db::Database;
+use crate::events::{self, DndChanged};
+use crate::models::Notification;
+
+/// Whether scheduled quiet hours were on at the last check.
+static QUIET: AtomicBool = AtomicBool::new(false);
+
+/// Service for the weekly do-not-disturb schedule.
+pub struct DndScheduler;
+
+impl DndScheduler {
+    /// Spawns a background task that reports quiet hours beginning and ending.
+    pub fn spawn(handle: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(CHECK_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                Self::check(&handle);
+            }
+        });
+    }
+
+    /// Evaluates the schedule now, emitting `dnd:changed` if quiet hours
+    /// began or ended since the last check.
+    pub fn check(handle: &AppHandle) {
+        let db: tauri::State<Database> = handle.state();
+        let quiet = match db.get_dnd_schedule() {
+            Ok(schedule) => schedule.is_quiet(Utc::now()),
+            Err(e) => {
+                log::warn!("Failed to load do-not-disturb schedule: {e}");
+                return;
+            }
+        };
+
+        if QUIET.swap(quiet, Ordering::Relaxed) != quiet {
+            log::info!(
+                "Scheduled quiet hours {}",
+                if quiet { "began" } else { "ended" }
+            );
+            events::emit(handle, &DndChanged(quiet));
+        }
+    }
+
+    /// Checks whether the schedule holds back the toast for `notification` now.
+    pub fn suppresses(db: &Database, notification: &Notification) -> bool {
+        db.get_dnd_schedule()
+            .is_ok_and(|schedule| schedule.suppresses(notification, Utc::now()))
+    }
+}
@@ -0,0 +1,160 @@
+//! Escalation of high priority notifications nobody took care of.
+//!
+//! A notification matching an escalation rule that stays unread and
+//! unacknowledged is shown again with an alarm sound every `delay_minutes`,
+//! up to the rule's `max_repeats`. With `publish_to` set, an escalation
+//! message is published there as well, so someone else can step in.
+//! Reading or acknowledging the notification stops the escalation. When
+//! several rules match a notification, the oldest one applies.
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use crate::config::escalation::{CHECK_INTERVAL_SECS, MAX_AGE_SECS};
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{Notification, NotificationSound, Priority, ReceiptTopic};
+use crate::services::{ConnectionManager, OutgoingMessage, PublishService};
+
+/// Tags of published escalation messages (shown as a siren by ntfy clients).
+const ESCALATION_TAGS: [&str; 1] = ["rotating_light"];
+
+/// Service for escalating notifications.
+pub struct EscalationService;
+
+impl EscalationService {
+    /// Spawns a background task that escalates notifications as they become due.
+    pub fn spawn_scheduler(handle: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(CHECK_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+
+                let now = chrono::Utc::now().timestamp_millis();
+                let due = {
+                    let db: tauri::State<Database> = handle.state();
+                    match Self::due(&db, now) {
+                        Ok(due) => due,
+                        Err(e) => {
+                            log::warn!("Failed to check for escalations: {e}");
+                            continue;
+                        }
+                    }
+                };
+
+                for (notification, count, publish_to) in due {
+                    // Record first, so a failing toast doesn't repeat every check
+                    {
+                        let db: tauri::State<Database> = handle.state();
+                        if let Err(e) = db.record_escalation(&notification.id, count, now) {
+                            log::warn!("Failed to record escalation of {}: {e}", notification.id);
+                            continue;
+                        }
+                    }
+                    log::info!(
+                        "Escalating notification {} ({count} time(s))",
+                        notification.id
+                    );
+                    Self::escalate(&handle, &notification, count, publish_to.as_ref()).await;
+                }
+            }
+        });
+    }
+
+    /// Returns the notifications due for escalation at `now`, with how many
+    /// times they'll have been escalated and where to publish to.
+    fn due(
+        db: &Database,
+        now: i64,
+    ) -> Result<Vec<(Notification, u32, Option<ReceiptTopic>)>, AppError> {
+        let rules = db.get_escalation_rules()?;
+        let Some(min_priority) = rules.iter().map(|rule| rule.min_priority as u8).min() else {
+            return Ok(Vec::new());
+        };
+
+        let candidates = db.get_escalation_candidates(
+            Priority::from(min_priority as i8),
+            now - MAX_AGE_SECS * 1000,
+        )?;
+        Ok(candidates
+            .into_iter()
+            .filter_map(|(notification, escalated)| {
+                let rule = rules.iter().find(|rule| rule.matches(&notification))?;
+                let next = rule.next_escalation(&notification, escalated)?;
+                (next <= now).then(|| {
+                    let publish_to = rule.publish_to.clone();
+                    (notification, escalated.count + 1, publish_to)
+                })
+            })
+            .collect())
+    }
+
+    /// Shows a notification again with an alarm sound and publishes the
+    /// escalation message, if the rule has a topic for it.
+    async fn escalate(
+        handle: &AppHandle,
+        notification: &Notification,
+        count: u32,
+        publish_to: Option<&ReceiptTopic>,
+    ) {
+        let ntfy_id = {
+            let db: tauri::State<Database> = handle.state();
+            db.get_notification_origin(&notification.id)
+                .ok()
+                .flatten()
+                .map_or_else(|| notification.id.clone(), |(_, ntfy_id)| ntfy_id)
+        };
+        ConnectionManager::show_notification_with_sound(
+            handle,
+            notification,
+            &ntfy_id,
+            NotificationSound::Alarm,
+        )
+        .await;
+
+        let Some(target) = publish_to else {
+            return;
+        };
+        let db: tauri::State<'_, Database> = handle.state();
+        let (title, message) = escalation_message(&db, notification, count);
+        let outgoing = OutgoingMessage {
+            title: &title,
+            message: &message,
+            tags: &ESCALATION_TAGS,
+            priority: Some(Priority::Max),
+            ..Default::default()
+        };
+        if let Err(e) =
+            PublishService::publish_to(&db, &target.server_url, &target.topic, &outgoing, None)
+                .await
+        {
+            log::warn!("Failed to publish escalation: {e}");
+        }
+    }
+}
+
+/// Builds the title and message of a published escalation.
+fn escalation_message(db: &Database, notification: &Notification, count: u32) -> (String, String) {
+    let topic = db
+        .get_subscription_by_id(&notification.topic_id)
+        .ok()
+        .flatten()
+        .map_or_else(
+            || "a topic".to_string(),
+            |sub| sub.display_name.unwrap_or(sub.topic),
+        );
+    let device = db
+        .get_device_info()
+        .map_or_else(|_| "a device".to_string(), |device| device.name);
+    let subject = if notification.title.is_empty() {
+        notification.message.lines().next().unwrap_or_default()
+    } else {
+        &notification.title
+    };
+
+    (
+        format!("Unacknowledged alert in {topic}"),
+        format!("{subject}\n\nNot read or acknowledged on {device} (escalation {count})."),
+    )
+}
@@ -0,0 +1,68 @@
+//! Mirroring received notifications to local files.
+//!
+//! Subscriptions with a mirror append every received notification to a file,
+//! as JSON lines or a plain log, so local tools can tail it without an API.
+
+use std::fs::OpenOptions;
+use std::io::Write;
+
+use crate::error::AppError;
+use crate::models::{FileMirror, MirrorFormat, Notification};
+
+/// Service for appending notifications to mirror files.
+pub struct FileMirrorService;
+
+impl FileMirrorService {
+    /// Appends notifications of `topic` to a mirror file, logging failures.
+    pub fn append(mirror: &FileMirror, topic: &str, notifications: &[&Notification]) {
+        if let Err(e) = Self::try_append(mirror, topic, notifications) {
+            log::warn!("Failed to write mirror file {}: {e}", mirror.path);
+        }
+    }
+
+    fn try_append(
+        mirror: &FileMirror,
+        topic: &str,
+        notifications: &[&Notification],
+    ) -> Result<(), AppError> {
+        let mut lines = String::new();
+        for notification in notifications {
+            lines.push_str(&format_line(mirror.format, topic, notification)?);
+            lines.push('\n');
+        }
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&mirror.path)
+            .map_err(|e| AppError::NotFound(e.to_string()))?;
+        file.write_all(lines.as_bytes())
+            .map_err(|e| AppError::NotFound(e.to_string()))
+    }
+}
+
+/// Formats a notification as one line of a mirror file.
+fn format_line(
+    format: MirrorFormat,
+    topic: &str,
+    notification: &Notification,
+) -> Result<String, AppError> {
+    match format {
+        MirrorFormat::Jsonl => Ok(serde_json::to_string(notification)?),
+        MirrorFormat::Log => {
+            let time = chrono::DateTime::from_timestamp_millis(notification.timestamp)
+                .unwrap_or_default()
+                .with_timezone(&chrono::Local)
+                .to_rfc3339_opts(chrono::SecondsFormat::Secs, false);
+            let mut line = format!("{time} [{}] {topic}:", notification.priority as u8);
+            if !notification.title.is_empty() {
+                line.push(' ');
+                line.push_str(&notification.title);
+                line.push_str(" -");
+            }
+            line.push(' ');
+            line.push_str(&notification.message.replace('\n', "\\n"));
+            Ok(line)
+        }
+    }
+}
@@ -0,0 +1,244 @@
+//! Publishing messages for files appearing in watched folders.
+//!
+//! Folders are scanned at a fixed interval instead of relying on OS file
+//! events. A file is reported once its size and modification time stayed the
+//! same for a whole scan, so files still being written aren't published, and
+//! files already present when a folder is first scanned are never reported.
+
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use tauri::{AppHandle, Manager};
+
+use crate::config::folder_watcher::POLL_INTERVAL_SECS;
+use crate::config::publish::MAX_ATTACHMENT_BYTES;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::WatchFolder;
+use crate::services::{policy, OutgoingAttachment, OutgoingMessage, PublishService};
+
+/// Tags of published file messages (shown as a folder by ntfy clients).
+const FILE_TAGS: [&str; 1] = ["file_folder"];
+
+/// Size and modification time of a file, compared between scans.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct FileStamp {
+    len: u64,
+    modified: Option<SystemTime>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum FileEvent {
+    Created,
+    Modified,
+}
+
+/// Files of a watched folder as of the last scan and the last report.
+#[derive(Debug)]
+struct FolderState {
+    scanned: HashMap<PathBuf, FileStamp>,
+    reported: HashMap<PathBuf, FileStamp>,
+}
+
+impl FolderState {
+    /// Starts tracking a folder; the files it already holds aren't reported.
+    fn primed(files: HashMap<PathBuf, FileStamp>) -> Self {
+        Self {
+            reported: files.clone(),
+            scanned: files,
+        }
+    }
+
+    /// Applies a scan and returns the files that settled since they were last
+    /// reported, ordered by path.
+    fn apply_scan(&mut self, files: HashMap<PathBuf, FileStamp>) -> Vec<(PathBuf, FileEvent)> {
+        let mut events = Vec::new();
+        for (path, stamp) in &files {
+            if self.scanned.get(path) != Some(stamp) {
+                continue;
+            }
+            let event = match self.reported.get(path) {
+                Some(reported) if reported == stamp => continue,
+                Some(_) => FileEvent::Modified,
+                None => FileEvent::Created,
+            };
+            self.reported.insert(path.clone(), *stamp);
+            events.push((path.clone(), event));
+        }
+        self.reported.retain(|path, _| files.contains_key(path));
+        self.scanned = files;
+        events.sort_by(|a, b| a.0.cmp(&b.0));
+        events
+    }
+}
+
+/// Lists the files directly inside `dir`.
+fn scan(dir: &Path) -> std::io::Result<HashMap<PathBuf, FileStamp>> {
+    let mut files = HashMap::new();
+    for entry in std::fs::read_dir(dir)?.flatten() {
+        let Ok(meta) = entry.metadata() else {
+            continue;
+        };
+        if meta.is_file() {
+            let stamp = FileStamp {
+                len: meta.len(),
+                modified: meta.modified().ok(),
+            };
+            files.insert(entry.path(), stamp);
+        }
+    }
+    Ok(files)
+}
+
+/// Service publishing messages for new and changed files in watched folders.
+pub struct FolderWatcher;
+
+impl FolderWatcher {
+    /// Spawns a background task scanning the configured watch folders.
+    ///
+    /// The `watch_folders` setting is re-read on every scan, so changes apply
+    /// without a restart. Nothing is scanned while no folders are configured.
+    pub fn spawn_watcher(handle: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            // `None` marks a folder that couldn't be read, so it's logged once
+            let mut states: HashMap<String, Option<FolderState>> = HashMap::new();
+            let mut interval = tokio::time::interval(Duration::from_secs(POLL_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                let db: tauri::State<Database> = handle.state();
+                let folders = match db.get_watch_folders() {
+                    Ok(folders) => folders,
+                    Err(e) => {
+                        log::warn!("Failed to load watch folders: {e}");
+                        continue;
+                    }
+                };
+                states.retain(|path, _| folders.iter().any(|f| &f.path == path));
+
+                for folder in &folders {
+                    let files = match scan(Path::new(&folder.path)) {
+                        Ok(files) => files,
+                        Err(e) => {
+                            if !matches!(states.insert(folder.path.clone(), None), Some(None)) {
+                                log::warn!("Failed to scan watch folder {}: {e}", folder.path);
+                            }
+                            continue;
+                        }
+                    };
+                    let events = if let Some(Some(state)) = states.get_mut(&folder.path) {
+                        state.apply_scan(files)
+                    } else {
+                        states.insert(folder.path.clone(), Some(FolderState::primed(files)));
+                        continue;
+                    };
+                    for (path, event) in events {
+                        if let Err(e) = Self::publish(&db, folder, &path, event).await {
+                            log::warn!("Failed to publish {}: {e}", path.display());
+                        }
+                    }
+                }
+            }
+        });
+    }
+
+    /// Publishes a message for a file event to the folder's subscription.
+    async fn publish(
+        db: &Database,
+        folder: &WatchFolder,
+        path: &Path,
+        event: FileEvent,
+    ) -> Result<(), AppError> {
+        let name = path.file_name().map_or_else(
+            || path.display().to_string(),
+            |n| n.to_string_lossy().into_owned(),
+        );
+        let title = match event {
+            FileEvent::Created => format!("New file: {name}"),
+            FileEvent::Modified => format!("File changed: {name}"),
+        };
+        let message = path.display().to_string();
+        let outgoing = OutgoingMessage {
+            title: &title,
+            message: &message,
+            tags: &FILE_TAGS,
+            ..Default::default()
+        };
+
+        let len = std::fs::metadata(path).map_or(u64::MAX, |m| m.len());
+        let attach = folder.attach_file && !policy::current().disable_attachments;
+        let attachment = if attach && len <= MAX_ATTACHMENT_BYTES {
+            let data = tokio::fs::read(path).await.map_err(|e| {
+                AppError::NotFound(format!("Failed to read {}: {e}", path.display()))
+            })?;
+            Some(OutgoingAttachment {
+                filename: &name,
+                data,
+            })
+        } else {
+            None
+        };
+
+        PublishService::publish(db, &folder.subscription_id, &outgoing, attachment).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn files(entries: &[(&str, u64)]) -> HashMap<PathBuf, FileStamp> {
+        entries
+            .iter()
+            .map(|&(path, len)| {
+                (
+                    PathBuf::from(path),
+                    FileStamp {
+                        len,
+                        modified: None,
+                    },
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_files_are_reported_once_settled() {
+        let mut state = FolderState::primed(files(&[("old.txt", 1)]));
+
+        // New file still being written: not reported until unchanged for a scan
+        assert!(state
+            .apply_scan(files(&[("old.txt", 1), ("job.log", 10)]))
+            .is_empty());
+        assert!(state
+            .apply_scan(files(&[("old.txt", 1), ("job.log", 20)]))
+            .is_empty());
+        assert_eq!(
+            state.apply_scan(files(&[("old.txt", 1), ("job.log", 20)])),
+            vec![(PathBuf::from("job.log"), FileEvent::Created)]
+        );
+        assert!(state
+            .apply_scan(files(&[("old.txt", 1), ("job.log", 20)]))
+            .is_empty());
+
+        // Changed file
+        assert!(state
+            .apply_scan(files(&[("old.txt", 2), ("job.log", 20)]))
+            .is_empty());
+        assert_eq!(
+            state.apply_scan(files(&[("old.txt", 2), ("job.log", 20)])),
+            vec![(PathBuf::from("old.txt"), FileEvent::Modified)]
+        );
+    }
+
+    #[test]
+    fn test_recreated_file_is_reported_as_created() {
+        let mut state = FolderState::primed(files(&[("done.flag", 0)]));
+        assert!(state.apply_scan(files(&[])).is_empty());
+        assert!(state.apply_scan(files(&[("done.flag", 0)])).is_empty());
+        assert_eq!(
+            state.apply_scan(files(&[("done.flag", 0)])),
+            vec![(PathBuf::from("done.flag"), FileEvent::Created)]
+        );
+    }
+}
@@ -0,0 +1,78 @@
+//! Global keyboard shortcuts.
+//!
+//! Shortcuts are stored in the database and registered with the OS at startup
+//! and whenever one changes. A triggered shortcut emits its action to the
+//! frontend; showing the window is also handled here so it works while the
+//! window is hidden to the tray.
+
+use tauri::{AppHandle, Manager};
+use tauri_plugin_global_shortcut::{GlobalShortcutExt, Shortcut, ShortcutState};
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::events::{self, ShortcutTriggered, WindowShown};
+use crate::models::ShortcutAction;
+
+/// Service for registering the configured global shortcuts.
+pub struct HotkeyService;
+
+impl HotkeyService {
+    /// Parses an accelerator such as `CmdOrCtrl+Shift+N`.
+    pub fn parse(accelerator: &str) -> Result<Shortcut, AppError> {
+        accelerator
+            .trim()
+            .parse()
+            .map_err(|e| AppError::InvalidUrl(format!("Invalid shortcut '{accelerator}': {e}")))
+    }
+
+    /// Replaces all registered shortcuts with the ones stored in the database.
+    ///
+    /// A shortcut that can't be registered (usually because another
+    /// application already owns it) is skipped; the actions that failed are
+    /// returned.
+    pub fn register_all(handle: &AppHandle) -> Result<Vec<ShortcutAction>, AppError> {
+        let db: tauri::State<Database> = handle.state();
+        let global_shortcut = handle.global_shortcut();
+
+        if let Err(e) = global_shortcut.unregister_all() {
+            log::warn!("Failed to unregister global shortcuts: {e}");
+        }
+
+        let mut failed = Vec::new();
+        for binding in db.get_shortcuts()? {
+            let action = binding.action;
+            let registered = Self::parse(&binding.accelerator).and_then(|shortcut| {
+                global_shortcut
+                    .on_shortcut(shortcut, move |handle, _, event| {
+                        if event.state() == ShortcutState::Pressed {
+                            Self::trigger(handle, action);
+                        }
+                    })
+                    .map_err(|e| AppError::InvalidUrl(e.to_string()))
+            });
+            if let Err(e) = registered {
+                log::warn!(
+                    "Failed to register shortcut {} for {}: {e}",
+                    binding.accelerator,
+                    action.as_str()
+                );
+                failed.push(action);
+            }
+        }
+
+        Ok(failed)
+    }
+
+    /// Runs a triggered shortcut's action.
+    fn trigger(handle: &AppHandle, action: ShortcutAction) {
+        if action == ShortcutAction::ShowWindow {
+            if let Some(window) = handle.get_webview_window("main") {
+                let _ = window.show();
+                let _ = window.set_focus();
+                events::emit(handle, &WindowShown);
+            }
+        }
+
+        events::emit(handle, &ShortcutTriggered(action));
+    }
+}
@@ -4,9 +4,15 @@
 //! toast notifications, which require local file paths.
 
 use pulldown_cmark::{Event, Parser, Tag};
+use std::collections::HashSet;
 use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
 use tokio::fs;
 
+use crate::config::http::{CONNECT_TIMEOUT_SECS, IMAGE_DOWNLOAD_TIMEOUT_SECS};
+use crate::services::{log_redaction, proxy, resolver};
+
 /// Represents the orientation of an image for notification display.
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 pub enum ImageOrientation {
@@ -122,15 +128,16 @@ pub async fn download_and_cache_image(url: &str) -> Option<CachedImage> {
     }
 
     // Download the image
-    log::info!("Downloading image: {url}");
+    log::info!("Downloading image: {}", log_redaction::redact(url));
 
-    let client = match reqwest::Client::builder()
-        .timeout(std::time::Duration::from_secs(10))
-        .build()
-    {
+    let builder = reqwest::Client::builder()
+        .connect_timeout(Duration::from_secs(u64::from(CONNECT_TIMEOUT_SECS)))
+        .timeout(Duration::from_secs(IMAGE_DOWNLOAD_TIMEOUT_SECS))
+        .dns_resolver(Arc::new(resolver::OverrideResolver));
+    let client = match proxy::build_client(builder, &proxy::current()) {
         Ok(c) => c,
         Err(e) => {
-            log::error!("Failed to create HTTP client: {e}");
+            log::error!("{e}");
             return None;
         }
     };
@@ -138,14 +145,18 @@ pub async fn download_and_cache_image(url: &str) -> Option<CachedImage> {
     let response = match client.get(url).send().await {
         Ok(r) => r,
         Err(e) => {
-            log::error!("Failed to download image from {url}: {e}");
+            log::error!(
+                "Failed to download image from {}: {e}",
+                log_redaction::redact(url)
+            );
             return None;
         }
     };
 
     if !response.status().is_success() {
         log::error!(
-            "Failed to download image from {url}: HTTP {}",
+            "Failed to download image from {}: HTTP {}",
+            log_redaction::redact(url),
             response.status()
         );
         return None;
@@ -194,12 +205,12 @@ pub async fn download_and_cache_image(url: &str) -> Option<CachedImage> {
 ///
 /// Priority:
 /// 1. First image attachment (if MIME type starts with "image/")
-/// 2. First image URL found in the message markdown
+/// 2. First image URL found in the message, if it is markdown
 ///
 /// Returns `None` if no image is available or download fails.
 pub async fn get_notification_image(
     attachments: &[crate::models::Attachment],
-    message: &str,
+    markdown_message: Option<&str>,
 ) -> Option<CachedImage> {
     // First, try to get an image from attachments
     let image_attachment = attachments
@@ -213,7 +224,7 @@ pub async fn get_notification_image(
     }
 
     // Fallback: extract image URL from markdown message
-    if let Some(image_url) = extract_first_image_from_markdown(message) {
+    if let Some(image_url) = markdown_message.and_then(extract_first_image_from_markdown) {
         if let Some(cached) = download_and_cache_image(&image_url).await {
             return Some(cached);
         }
@@ -253,6 +264,37 @@ pub async fn cleanup_old_images(max_age_secs: u64) {
     }
 }
 
+/// Removes cached images not downloaded from any of `referenced_urls`.
+///
+/// Returns the number of removed images.
+pub async fn remove_unreferenced_images(referenced_urls: &[String]) -> u32 {
+    let referenced: HashSet<String> = referenced_urls
+        .iter()
+        .map(|url| get_cache_filename(url))
+        .collect();
+
+    let Ok(mut entries) = fs::read_dir(get_cache_dir()).await else {
+        return 0;
+    };
+
+    let mut removed = 0;
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let name = entry.file_name().to_string_lossy().into_owned();
+        if referenced.contains(&name) {
+            continue;
+        }
+        let path = entry.path();
+        match fs::remove_file(&path).await {
+            Ok(()) => {
+                log::debug!("Removed unreferenced cached image: {}", path.display());
+                removed += 1;
+            }
+            Err(e) => log::warn!("Failed to remove cached image {}: {e}", path.display()),
+        }
+    }
+    removed
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
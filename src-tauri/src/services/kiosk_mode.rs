@@ -0,0 +1,155 @@
+//! Kiosk mode.
+//!
+//! For a machine that only shows a shared alerts topic, such as a
+//! wall-mounted dashboard, kiosk mode makes the app read-only: only commands
+//! that read data or handle incoming notifications (mark as read,
+//! acknowledge, snooze) are let through, and everything else fails before it
+//! runs. Commands are checked by name in the invoke handler, so new commands
+//! are refused in kiosk mode until they're listed in `ALLOWED_COMMANDS`.
+//!
+//! The `kiosk_mode` setting can be turned off again from the app. Starting it
+//! with `--kiosk` enforces kiosk mode regardless of the setting.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::error::AppError;
+
+/// Command line flag that enforces kiosk mode.
+pub const KIOSK_ARG: &str = "--kiosk";
+
+/// Commands allowed in kiosk mode.
+const ALLOWED_COMMANDS: &[&str] = &[
+    // Subscriptions
+    "get_subscriptions",
+    "list_subscription_templates",
+    "get_publish_snippet",
+    "get_deleted_subscriptions",
+    "reconnect_subscription",
+    "get_subscription_settings",
+    "get_subscription_patterns",
+    // Notifications
+    "get_notifications",
+    "get_all_notifications",
+    "mark_as_read",
+    "mark_all_as_read",
+    "get_trash",
+    "set_notification_expanded",
+    "get_unread_count",
+    "get_total_unread_count",
+    "open_notification_url",
+    "acknowledge_notification",
+    "snooze_notification",
+    "unsnooze_notification",
+    "search_notifications",
+    "get_favorite_notifications",
+    "get_virtual_topics",
+    "get_virtual_topic_notifications",
+    "mark_virtual_topic_read",
+    // Rules
+    "get_rules",
+    // Settings
+    "get_settings",
+    "get_config_file_status",
+    "get_policy",
+    "get_server_removal_impact",
+    "get_server_publish_defaults",
+    "get_server_resolution",
+    "get_server_client_certificate",
+    "get_device_info",
+    "set_kiosk_mode",
+    "verify_credentials_consistency",
+    // Shortcuts, reminders and escalation rules
+    "get_shortcuts",
+    "get_reminders",
+    "get_escalation_rules",
+    "get_outbox",
+    // Sync and status
+    "sync_subscriptions",
+    "cancel_sync",
+    "get_runtime_status",
+    "get_internal_metrics",
+    "get_connection_log",
+    "get_daily_summary",
+    "generate_activity_report",
+    "get_server_stats",
+    // Permissions
+    "request_permission",
+    "respond_permission",
+    "get_permissions",
+    // App lock
+    "is_app_locked",
+    "lock_app",
+    "unlock_app",
+    // Blocklist and channels
+    "get_blocked_topics",
+    "get_blocked_urls",
+    "get_keyword_filters",
+    "get_channels",
+    // Update
+    "check_for_update",
+    "get_app_version",
+    "get_app_version_display",
+];
+
+/// Tracks whether kiosk mode is on.
+pub struct KioskMode {
+    enabled: AtomicBool,
+    /// Whether `--kiosk` was passed; kiosk mode can't be turned off then.
+    enforced: bool,
+}
+
+impl KioskMode {
+    pub const fn new(enabled: bool, enforced: bool) -> Self {
+        Self {
+            enabled: AtomicBool::new(enabled),
+            enforced,
+        }
+    }
+
+    /// Whether kiosk mode is on, by setting or by `--kiosk`.
+    pub fn is_enabled(&self) -> bool {
+        self.enforced || self.enabled.load(Ordering::Relaxed)
+    }
+
+    /// Turns kiosk mode on or off. Fails when turning it off while it's
+    /// enforced by `--kiosk`.
+    pub fn set_enabled(&self, enabled: bool) -> Result<(), AppError> {
+        if self.enforced && !enabled {
+            return Err(AppError::Credential(format!(
+                "Kiosk mode is enforced by {KIOSK_ARG}"
+            )));
+        }
+        self.enabled.store(enabled, Ordering::Relaxed);
+        Ok(())
+    }
+
+    /// Fails if kiosk mode is on and `command` isn't allowed in it.
+    pub fn check(&self, command: &str) -> Result<(), AppError> {
+        if self.is_enabled() && !ALLOWED_COMMANDS.contains(&command) {
+            return Err(AppError::Credential(format!(
+                "{command} is not available in kiosk mode"
+            )));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_kiosk_mode_check() {
+        let kiosk = KioskMode::new(false, false);
+        assert!(kiosk.check("remove_server").is_ok());
+
+        kiosk.set_enabled(true).ok();
+        assert!(kiosk.check("remove_server").is_err());
+        assert!(kiosk.check("mark_as_read").is_ok());
+        assert!(kiosk.set_enabled(false).is_ok());
+
+        let enforced = KioskMode::new(false, true);
+        assert!(enforced.check("set_theme").is_err());
+        assert!(enforced.set_enabled(false).is_err());
+    }
+}
@@ -0,0 +1,122 @@
+//! Background fetching of link previews.
+//!
+//! When `link_previews_enabled` is set, the first bare URL in a new message is
+//! fetched and its Open Graph metadata stored with the notification. The
+//! thumbnail goes through the image cache. Previews are off by default since
+//! fetching one reveals to the linked site that the message was received.
+
+use std::sync::Arc;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::config::http::{CONNECT_TIMEOUT_SECS, LINK_PREVIEW_TIMEOUT_SECS};
+use crate::config::link_previews::MAX_HTML_BYTES;
+use crate::db::Database;
+use crate::events::{self, LinkPreviewReady};
+use crate::models::{find_bare_url, parse_open_graph, LinkPreview, Notification};
+use crate::services::{image_cache, log_redaction, proxy, resolver};
+
+/// Service for fetching link previews.
+pub struct LinkPreviewService;
+
+impl LinkPreviewService {
+    /// Fetches a preview for the notification's first bare URL in the background.
+    ///
+    /// Does nothing if previews are disabled or the message has no bare URL.
+    pub fn spawn(handle: &AppHandle, notification: &Notification) {
+        let db: tauri::State<Database> = handle.state();
+        if !db.get_link_previews_enabled().unwrap_or(false) {
+            return;
+        }
+        let Some(url) = find_bare_url(&notification.message) else {
+            return;
+        };
+
+        let handle = handle.clone();
+        let notification_id = notification.id.clone();
+        tauri::async_runtime::spawn(async move {
+            let Some(preview) = Self::fetch(&url).await else {
+                return;
+            };
+
+            let db: tauri::State<Database> = handle.state();
+            if let Err(e) = db.set_notification_link_preview(&notification_id, &preview) {
+                log::warn!("Failed to store link preview: {e}");
+                return;
+            }
+
+            events::emit(
+                &handle,
+                &LinkPreviewReady {
+                    notification_id,
+                    preview,
+                },
+            );
+        });
+    }
+
+    /// Fetches a page and extracts its preview, caching the thumbnail.
+    ///
+    /// Returns `None` for non-HTML responses, failures and pages without metadata.
+    async fn fetch(url: &str) -> Option<LinkPreview> {
+        let builder = reqwest::Client::builder()
+            .connect_timeout(Duration::from_secs(u64::from(CONNECT_TIMEOUT_SECS)))
+            .timeout(Duration::from_secs(LINK_PREVIEW_TIMEOUT_SECS))
+            .dns_resolver(Arc::new(resolver::OverrideResolver));
+        let client = proxy::build_client(builder, &proxy::current())
+            .map_err(|e| log::error!("{e}"))
+            .ok()?;
+
+        let mut response = match client.get(url).send().await {
+            Ok(r) if r.status().is_success() => r,
+            Ok(r) => {
+                log::debug!(
+                    "Link preview of {} failed: HTTP {}",
+                    log_redaction::redact(url),
+                    r.status()
+                );
+                return None;
+            }
+            Err(e) => {
+                log::debug!("Link preview of {} failed: {e}", log_redaction::redact(url));
+                return None;
+            }
+        };
+
+        let is_html = response
+            .headers()
+            .get("content-type")
+            .and_then(|ct| ct.to_str().ok())
+            .is_some_and(|ct| ct.starts_with("text/html"));
+        if !is_html {
+            return None;
+        }
+
+        // Open Graph tags live in the head, so a prefix of the page is enough
+        let mut body = Vec::new();
+        while body.len() < MAX_HTML_BYTES {
+            match response.chunk().await {
+                Ok(Some(chunk)) => body.extend_from_slice(&chunk),
+                Ok(None) => break,
+                Err(e) => {
+                    log::debug!("Failed to read {}: {e}", log_redaction::redact(url));
+                    return None;
+                }
+            }
+        }
+        body.truncate(MAX_HTML_BYTES);
+
+        let mut preview = parse_open_graph(&String::from_utf8_lossy(&body), url);
+        if preview.is_empty() {
+            return None;
+        }
+
+        if let Some(ref image_url) = preview.image_url {
+            preview.image_path = image_cache::download_and_cache_image(image_url)
+                .await
+                .map(|cached| cached.path.display().to_string());
+        }
+
+        Some(preview)
+    }
+}
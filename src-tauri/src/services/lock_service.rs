@@ -0,0 +1,237 @@
+//! App lock.
+//!
+//! With an `app_lock_method` other than `Off`, the app starts locked and locks
+//! itself again once the main window has been out of focus for
+//! `app_lock_minutes`. While locked, commands returning notification content
+//! fail and toasts only name the topic; the frontend shows its lock screen on
+//! `app:lock_changed` until the user unlocks with the PIN or OS authentication.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::config::app_lock::{IDLE_CHECK_INTERVAL_SECS, MIN_PIN_LENGTH};
+use crate::db::Database;
+use crate::error::AppError;
+use crate::events::{self, AppLockChanged};
+use crate::models::AppLockMethod;
+use crate::services::credential_manager;
+
+/// Tracks whether the app is locked and when it was last used.
+pub struct LockService {
+    locked: AtomicBool,
+    /// Whether the main window has focus; the idle timer only runs without it.
+    focused: AtomicBool,
+    last_activity: Mutex<Instant>,
+}
+
+impl LockService {
+    pub fn new(locked: bool) -> Self {
+        Self {
+            locked: AtomicBool::new(locked),
+            focused: AtomicBool::new(false),
+            last_activity: Mutex::new(Instant::now()),
+        }
+    }
+
+    /// Whether the app is locked.
+    pub fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::Relaxed)
+    }
+
+    /// Fails if the app is locked; otherwise counts as activity.
+    ///
+    /// Called by commands that return notification content.
+    pub fn ensure_unlocked(&self) -> Result<(), AppError> {
+        if self.is_locked() {
+            return Err(AppError::Credential("App is locked".to_string()));
+        }
+        self.touch();
+        Ok(())
+    }
+
+    /// Records a focus change of the main window.
+    pub fn set_focused(&self, focused: bool) {
+        self.focused.store(focused, Ordering::Relaxed);
+        self.touch();
+    }
+
+    /// Locks the app if a lock method is configured.
+    pub fn lock(&self, handle: &AppHandle) {
+        let db: tauri::State<Database> = handle.state();
+        if db.get_app_lock_method().unwrap_or_default() == AppLockMethod::Off {
+            return;
+        }
+        if !self.locked.swap(true, Ordering::Relaxed) {
+            log::info!("App locked");
+            events::emit(handle, &AppLockChanged(true));
+        }
+    }
+
+    /// Unlocks the app after checking the PIN or asking the OS to
+    /// authenticate the user, depending on the configured method.
+    pub async fn unlock(handle: &AppHandle, pin: Option<String>) -> Result<(), AppError> {
+        let db: tauri::State<'_, Database> = handle.state();
+        let verified = match db.get_app_lock_method()? {
+            AppLockMethod::Off => true,
+            AppLockMethod::Pin => {
+                let pin = pin.ok_or_else(|| AppError::Credential("PIN required".to_string()))?;
+                credential_manager::verify_app_pin(&pin)?
+            }
+            AppLockMethod::System => tauri::async_runtime::spawn_blocking(Self::verify_with_os)
+                .await
+                .map_err(|e| AppError::Credential(format!("Authentication failed: {e}")))??,
+        };
+        if !verified {
+            return Err(AppError::Credential("Authentication failed".to_string()));
+        }
+
+        let service: tauri::State<'_, Self> = handle.state();
+        service.touch();
+        if service.locked.swap(false, Ordering::Relaxed) {
+            log::info!("App unlocked");
+            events::emit(handle, &AppLockChanged(false));
+        }
+        Ok(())
+    }
+
+    /// Changes the lock method. A PIN is required for `Pin`; switching to
+    /// `Off` removes the stored PIN and unlocks the app.
+    pub fn configure(
+        handle: &AppHandle,
+        method: AppLockMethod,
+        pin: Option<String>,
+    ) -> Result<(), AppError> {
+        match method {
+            AppLockMethod::Off => credential_manager::delete_app_pin()?,
+            AppLockMethod::Pin => {
+                let pin = pin.unwrap_or_default();
+                if pin.chars().count() < MIN_PIN_LENGTH {
+                    return Err(AppError::Credential(format!(
+                        "PIN must be at least {MIN_PIN_LENGTH} characters"
+                    )));
+                }
+                credential_manager::store_app_pin(&pin)?;
+            }
+            AppLockMethod::System => {
+                if !Self::os_auth_available() {
+                    return Err(AppError::Credential(
+                        "OS authentication is not available on this system".to_string(),
+                    ));
+                }
+                credential_manager::delete_app_pin()?;
+            }
+        }
+
+        let method_str = match method {
+            AppLockMethod::Off => "off",
+            AppLockMethod::Pin => "pin",
+            AppLockMethod::System => "system",
+        };
+        let db: tauri::State<Database> = handle.state();
+        db.set_setting("app_lock_method", method_str)?;
+
+        let service: tauri::State<Self> = handle.state();
+        if method == AppLockMethod::Off && service.locked.swap(false, Ordering::Relaxed) {
+            events::emit(handle, &AppLockChanged(false));
+        }
+        Ok(())
+    }
+
+    /// Spawns a background task that locks the app once the main window has
+    /// been out of focus for `app_lock_minutes`.
+    pub fn spawn_idle_watcher(handle: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(IDLE_CHECK_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+
+                let db: tauri::State<Database> = handle.state();
+                let minutes = db.get_app_lock_minutes().unwrap_or(0);
+                let service: tauri::State<Self> = handle.state();
+                if minutes == 0 || service.focused.load(Ordering::Relaxed) {
+                    continue;
+                }
+                let idle = service.lock_last_activity().elapsed();
+                if idle >= Duration::from_secs(u64::from(minutes) * 60) {
+                    service.lock(&handle);
+                }
+            }
+        });
+    }
+
+    /// Whether OS authentication can be used on this system.
+    #[cfg(windows)]
+    fn os_auth_available() -> bool {
+        use windows::Security::Credentials::UI::{
+            UserConsentVerifier, UserConsentVerifierAvailability,
+        };
+
+        UserConsentVerifier::CheckAvailabilityAsync()
+            .and_then(|op| op.get())
+            .is_ok_and(|availability| availability == UserConsentVerifierAvailability::Available)
+    }
+
+    /// Whether OS authentication can be used on this system.
+    #[cfg(target_os = "linux")]
+    fn os_auth_available() -> bool {
+        std::process::Command::new("pkcheck")
+            .arg("--version")
+            .output()
+            .is_ok_and(|output| output.status.success())
+    }
+
+    #[cfg(not(any(windows, target_os = "linux")))]
+    const fn os_auth_available() -> bool {
+        false
+    }
+
+    /// Asks Windows Hello to verify the user.
+    #[cfg(windows)]
+    fn verify_with_os() -> Result<bool, AppError> {
+        use windows::core::HSTRING;
+        use windows::Security::Credentials::UI::{
+            UserConsentVerificationResult, UserConsentVerifier,
+        };
+
+        let result = UserConsentVerifier::RequestVerificationAsync(&HSTRING::from("Unlock Ntfier"))
+            .and_then(|op| op.get())
+            .map_err(|e| AppError::Credential(format!("Windows Hello failed: {e}")))?;
+        Ok(result == UserConsentVerificationResult::Verified)
+    }
+
+    /// Asks polkit to authenticate the user through the desktop's agent.
+    #[cfg(target_os = "linux")]
+    fn verify_with_os() -> Result<bool, AppError> {
+        let status = std::process::Command::new("pkcheck")
+            .args([
+                "--action-id",
+                "org.freedesktop.policykit.exec",
+                "--process",
+                &std::process::id().to_string(),
+                "--allow-user-interaction",
+            ])
+            .status()
+            .map_err(|e| AppError::Credential(format!("polkit failed: {e}")))?;
+        Ok(status.success())
+    }
+
+    #[cfg(not(any(windows, target_os = "linux")))]
+    fn verify_with_os() -> Result<bool, AppError> {
+        Err(AppError::Credential(
+            "OS authentication is not available on this system".to_string(),
+        ))
+    }
+
+    fn touch(&self) {
+        *self.lock_last_activity() = Instant::now();
+    }
+
+    fn lock_last_activity(&self) -> std::sync::MutexGuard<'_, Instant> {
+        match self.last_activity.lock() {
+            Ok(last_activity) => last_activity,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
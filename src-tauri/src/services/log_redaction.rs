@@ -0,0 +1,88 @@
+//! Redaction of topic names and message content in log output.
+//!
+//! Users attach logs to bug reports, so with the `log_redaction` setting
+//! enabled, log lines show only a prefix or a short hash of topics and
+//! messages. IDs and server URLs are logged as usual. The mode is kept in
+//! memory so logging doesn't touch the database.
+
+use std::sync::atomic::{AtomicU8, Ordering};
+
+use crate::config::log_redaction::TRUNCATE_CHARS;
+use crate::models::LogRedaction;
+
+static MODE: AtomicU8 = AtomicU8::new(0);
+
+/// Sets how `redact` treats its input.
+pub fn set_mode(mode: LogRedaction) {
+    let value = match mode {
+        LogRedaction::Off => 0,
+        LogRedaction::Truncate => 1,
+        LogRedaction::Hash => 2,
+    };
+    MODE.store(value, Ordering::Relaxed);
+}
+
+fn mode() -> LogRedaction {
+    match MODE.load(Ordering::Relaxed) {
+        1 => LogRedaction::Truncate,
+        2 => LogRedaction::Hash,
+        _ => LogRedaction::Off,
+    }
+}
+
+/// Returns a topic name or message content as it should appear in the log.
+pub fn redact(text: &str) -> String {
+    redact_with(mode(), text)
+}
+
+fn redact_with(mode: LogRedaction, text: &str) -> String {
+    match mode {
+        LogRedaction::Off => text.to_string(),
+        LogRedaction::Truncate => {
+            let mut chars = text.chars();
+            let prefix: String = chars.by_ref().take(TRUNCATE_CHARS).collect();
+            if chars.next().is_some() {
+                format!("{prefix}…")
+            } else {
+                prefix
+            }
+        }
+        LogRedaction::Hash => format!("#{:08x}", fnv1a(text) as u32),
+    }
+}
+
+/// 64-bit FNV-1a, which unlike `DefaultHasher` is stable across Rust
+/// releases, so hashes match between logs of different app versions.
+fn fnv1a(text: &str) -> u64 {
+    text.bytes().fold(0xcbf2_9ce4_8422_2325, |hash, byte| {
+        (hash ^ u64::from(byte)).wrapping_mul(0x0100_0000_01b3)
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_truncate() {
+        assert_eq!(redact_with(LogRedaction::Truncate, "backups"), "backups");
+        assert_eq!(
+            redact_with(LogRedaction::Truncate, "home-alarm-garage"),
+            "home-ala…"
+        );
+    }
+
+    #[test]
+    fn test_hash_is_stable_and_hides_text() {
+        let hashed = redact_with(LogRedaction::Hash, "home-alarm");
+        assert_eq!(hashed, redact_with(LogRedaction::Hash, "home-alarm"));
+        assert_ne!(hashed, redact_with(LogRedaction::Hash, "home-alarms"));
+        assert!(!hashed.contains("home"));
+        assert_eq!(hashed.len(), 9);
+    }
+
+    #[test]
+    fn test_off_keeps_text() {
+        assert_eq!(redact_with(LogRedaction::Off, "home-alarm"), "home-alarm");
+    }
+}
@@ -0,0 +1,86 @@
+//! Cleanup of orphaned data.
+//!
+//! Removes what deleting something left behind: notifications and other rows
+//! of deleted subscriptions in databases from before foreign keys were
+//! enforced, cached images of notifications that are gone, and keychain
+//! entries of removed servers that couldn't be deleted at the time. Runs
+//! daily and on demand through `run_maintenance`.
+
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::config::maintenance::INTERVAL_SECS;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::events::{self, SubscriptionsSynced};
+use crate::models::MaintenanceReport;
+use crate::services::{credential_manager, image_cache, TrayManager};
+
+/// Service for removing orphaned data.
+pub struct MaintenanceService;
+
+impl MaintenanceService {
+    /// Removes orphaned rows, cached images and keychain entries.
+    pub async fn run(handle: &AppHandle) -> Result<MaintenanceReport, AppError> {
+        let (report, image_urls) = {
+            let db: tauri::State<'_, Database> = handle.state();
+            let report = MaintenanceReport {
+                orphaned_notifications: count(db.delete_orphaned_notifications()?),
+                orphaned_rows: count(db.delete_orphaned_rows()?),
+                credentials: Self::delete_stale_credentials(&db)?,
+                cached_images: 0,
+            };
+            (report, db.get_notification_image_urls()?)
+        };
+        let report = MaintenanceReport {
+            cached_images: image_cache::remove_unreferenced_images(&image_urls).await,
+            ..report
+        };
+
+        if report.total() > 0 {
+            log::info!("Maintenance removed orphaned data: {report:?}");
+        }
+        if report.orphaned_notifications > 0 {
+            handle.state::<TrayManager>().refresh_from_db(handle);
+            events::emit(handle, &SubscriptionsSynced);
+        }
+        Ok(report)
+    }
+
+    /// Spawns a background task that cleans up orphaned data daily.
+    pub fn spawn_scheduler(handle: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                if let Err(e) = Self::run(&handle).await {
+                    log::warn!("Maintenance failed: {e}");
+                }
+            }
+        });
+    }
+
+    /// Deletes keychain entries of removed servers, keeping those that still
+    /// can't be deleted for the next run.
+    fn delete_stale_credentials(db: &Database) -> Result<u32, AppError> {
+        let stale = db.get_stale_credentials()?;
+        if stale.is_empty() {
+            return Ok(0);
+        }
+
+        let (deleted, remaining): (Vec<_>, Vec<_>) =
+            stale.into_iter().partition(|(username, url)| {
+                credential_manager::delete_password(username, url)
+                    .map_err(|e| {
+                        log::warn!("Failed to clean up keychain for {username}@{url}: {e}");
+                    })
+                    .is_ok()
+            });
+        db.set_stale_credentials(&remaining)?;
+        Ok(count(deleted.len()))
+    }
+}
+
+fn count(n: usize) -> u32 {
+    u32::try_from(n).unwrap_or(u32::MAX)
+}
@@ -0,0 +1,231 @@
+//! Opt-in Prometheus metrics endpoint.
+//!
+//! With `metrics_endpoint_port` set, a listener on `127.0.0.1` serves
+//! `GET /metrics` in the Prometheus text format: unread notifications and
+//! messages received per topic, and the state of each topic's connection.
+//! It only answers that one request and is never reachable from other hosts.
+
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::sync::{Mutex, MutexGuard};
+
+use tauri::{AppHandle, Manager};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio_util::sync::CancellationToken;
+
+use crate::config::metrics_endpoint::MAX_REQUEST_BYTES;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{ConnectionState, ConnectionStatus, Subscription};
+use crate::services::{ConnectionManager, MetricsService};
+
+/// Runs the metrics listener while a port is configured.
+#[derive(Default)]
+pub struct MetricsEndpoint {
+    /// Port and stop token of the running listener, if any.
+    running: Mutex<Option<(u16, CancellationToken)>>,
+}
+
+impl MetricsEndpoint {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Listens on `port`, or stops listening with `None`.
+    ///
+    /// The new port is bound before the running listener is stopped, so it
+    /// keeps running if the new port can't be listened on.
+    pub async fn set_port(&self, handle: &AppHandle, port: Option<u16>) -> Result<(), AppError> {
+        let running_port = self.lock_running().as_ref().map(|(p, _)| *p);
+        if running_port == port {
+            return Ok(());
+        }
+
+        let listener = match port {
+            Some(port) => Some(TcpListener::bind(("127.0.0.1", port)).await.map_err(|e| {
+                AppError::Connection(format!("Failed to listen on port {port}: {e}"))
+            })?),
+            None => None,
+        };
+        let previous = self.lock_running().take();
+        if let Some((_, token)) = previous {
+            token.cancel();
+        }
+        let (Some(port), Some(listener)) = (port, listener) else {
+            return Ok(());
+        };
+        log::info!("Serving metrics on http://127.0.0.1:{port}/metrics");
+
+        let token = CancellationToken::new();
+        *self.lock_running() = Some((port, token.clone()));
+        let handle = handle.clone();
+        tauri::async_runtime::spawn(async move {
+            loop {
+                let stream = tokio::select! {
+                    () = token.cancelled() => return,
+                    accepted = listener.accept() => match accepted {
+                        Ok((stream, _)) => stream,
+                        Err(e) => {
+                            log::warn!("Failed to accept metrics connection: {e}");
+                            continue;
+                        }
+                    },
+                };
+                let handle = handle.clone();
+                tauri::async_runtime::spawn(async move {
+                    if let Err(e) = Self::serve(&handle, stream).await {
+                        log::debug!("Metrics request failed: {e}");
+                    }
+                });
+            }
+        });
+        Ok(())
+    }
+
+    /// Answers a single HTTP request and closes the connection.
+    async fn serve(handle: &AppHandle, mut stream: TcpStream) -> std::io::Result<()> {
+        let mut request = Vec::new();
+        let mut buf = [0u8; 1024];
+        while !request.windows(4).any(|w| w == b"\r\n\r\n") && request.len() < MAX_REQUEST_BYTES {
+            let read = stream.read(&mut buf).await?;
+            if read == 0 {
+                break;
+            }
+            request.extend_from_slice(&buf[..read]);
+        }
+
+        let request_line = String::from_utf8_lossy(&request);
+        let mut parts = request_line.split_whitespace();
+        let response = match (parts.next(), parts.next()) {
+            (Some("GET"), Some("/metrics")) => match Self::collect(handle).await {
+                Ok(body) => response("200 OK", "text/plain; version=0.0.4", &body),
+                Err(e) => {
+                    log::warn!("Failed to collect metrics: {e}");
+                    response("500 Internal Server Error", "text/plain", "")
+                }
+            },
+            _ => response("404 Not Found", "text/plain", ""),
+        };
+
+        stream.write_all(response.as_bytes()).await?;
+        stream.shutdown().await
+    }
+
+    async fn collect(handle: &AppHandle) -> Result<String, AppError> {
+        let db: tauri::State<Database> = handle.state();
+        let subscriptions = db.get_all_subscriptions()?;
+        let conn_manager: tauri::State<ConnectionManager> = handle.state();
+        let connections = conn_manager.connection_statuses().await;
+        Ok(render(
+            &subscriptions,
+            &connections,
+            &MetricsService::received_counts(),
+        ))
+    }
+
+    fn lock_running(&self) -> MutexGuard<'_, Option<(u16, CancellationToken)>> {
+        match self.running.lock() {
+            Ok(running) => running,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
+
+fn response(status: &str, content_type: &str, body: &str) -> String {
+    format!(
+        "HTTP/1.1 {status}\r\nContent-Type: {content_type}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    )
+}
+
+/// Renders the metrics in the Prometheus text exposition format.
+fn render(
+    subscriptions: &[Subscription],
+    connections: &[ConnectionStatus],
+    received: &HashMap<String, u64>,
+) -> String {
+    let labels = |sub: &Subscription| {
+        format!(
+            "server=\"{}\",topic=\"{}\"",
+            escape_label(sub.normalized_server_url()),
+            escape_label(&sub.topic)
+        )
+    };
+    let mut out = String::new();
+
+    out.push_str("# HELP ntfier_unread_notifications Unread notifications per topic.\n");
+    out.push_str("# TYPE ntfier_unread_notifications gauge\n");
+    for sub in subscriptions {
+        let _ = writeln!(
+            out,
+            "ntfier_unread_notifications{{{}}} {}",
+            labels(sub),
+            sub.unread_count
+        );
+    }
+
+    out.push_str(
+        "# HELP ntfier_messages_received_total Messages received per topic since the app started.\n",
+    );
+    out.push_str("# TYPE ntfier_messages_received_total counter\n");
+    for sub in subscriptions {
+        let count = received.get(&sub.id).copied().unwrap_or(0);
+        let _ = writeln!(
+            out,
+            "ntfier_messages_received_total{{{}}} {count}",
+            labels(sub)
+        );
+    }
+
+    out.push_str(
+        "# HELP ntfier_connection_state Connection state per topic (1 for the current state).\n",
+    );
+    out.push_str("# TYPE ntfier_connection_state gauge\n");
+    for sub in subscriptions.iter().filter(|s| !s.local && !s.detached) {
+        let current = connections
+            .iter()
+            .find(|c| c.subscription_id == sub.id)
+            .map_or("disconnected", |c| match c.state {
+                ConnectionState::Connecting => "connecting",
+                ConnectionState::Connected => "connected",
+                ConnectionState::Reconnecting => "reconnecting",
+            });
+        for state in ["connecting", "connected", "reconnecting", "disconnected"] {
+            let _ = writeln!(
+                out,
+                "ntfier_connection_state{{{},state=\"{state}\"}} {}",
+                labels(sub),
+                u8::from(state == current)
+            );
+        }
+    }
+
+    out
+}
+
+/// Escapes a Prometheus label value.
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escape_label() {
+        assert_eq!(escape_label("a\"b\\c\nd"), "a\\\"b\\\\c\\nd");
+    }
+
+    #[test]
+    fn test_response_sets_length() {
+        let r = response("200 OK", "text/plain", "abc");
+        assert!(r.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(r.contains("Content-Length: 3\r\n"));
+        assert!(r.ends_with("\r\n\r\nabc"));
+    }
+}
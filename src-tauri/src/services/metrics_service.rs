@@ -0,0 +1,192 @@
+//! Lightweight in-memory performance metrics.
+//!
+//! Command handling times, database lock waits, WebSocket reconnects and sync
+//! durations are aggregated for the lifetime of the process and can be read
+//! with the `get_internal_metrics` command. Messages received per topic are
+//! counted for the opt-in metrics endpoint. Nothing is persisted.
+//!
+//! The invoke handler times sync commands. Async commands return as soon as
+//! they're spawned, so they take a [`CommandTimer`] that records once the
+//! command finishes; they're listed in `ASYNC_COMMANDS` so the handler
+//! doesn't time them a second time.
+
+use std::collections::HashMap;
+use std::sync::{Mutex, MutexGuard, OnceLock};
+use std::time::{Duration, Instant};
+
+use specta::datatype::DataType;
+use specta::function::FunctionArg;
+use specta::TypeCollection;
+use tauri::ipc::{CommandArg, CommandItem, InvokeError};
+use tauri::Runtime;
+
+use crate::models::{CommandTiming, InternalMetrics, TimingStats};
+
+/// Commands timed by their [`CommandTimer`] instead of the invoke handler.
+const ASYNC_COMMANDS: &[&str] = &[
+    "unlock_app",
+    "sync_subscriptions",
+    "get_server_stats",
+    "empty_trash",
+    "acknowledge_notification",
+    "open_notification_url",
+    "execute_notification_action",
+    "publish_message",
+    "capture_and_publish",
+    "add_subscription",
+    "apply_subscription_template",
+    "import_subscriptions_from_file",
+    "remove_subscription",
+    "restore_subscription",
+    "reconnect_subscription",
+    "set_subscription_auto_delete",
+    "set_retention_policy",
+    "generate_test_notifications",
+    "add_subscription_pattern",
+    "remove_subscription_pattern",
+    "refresh_subscription_patterns",
+    "share_clipboard",
+    "request_permission",
+    "update_server",
+    "remove_server",
+    "set_server_resolution",
+    "set_server_client_certificate",
+    "set_proxy_settings",
+    "set_tray_icon_style",
+    "set_metrics_endpoint_port",
+    "set_tray_icon_path",
+    "set_tray_unread_icon_path",
+    "get_runtime_status",
+    "run_maintenance",
+    "check_for_update",
+    "install_update",
+];
+
+/// Command argument timing an async command until it finishes.
+///
+/// Records when dropped, i.e. when the command's future completes or is
+/// cancelled. The frontend never passes it.
+pub struct CommandTimer {
+    command: &'static str,
+    started: Instant,
+}
+
+impl<'de, R: Runtime> CommandArg<'de, R> for CommandTimer {
+    fn from_command(command: CommandItem<'de, R>) -> Result<Self, InvokeError> {
+        Ok(Self {
+            command: command.name,
+            started: Instant::now(),
+        })
+    }
+}
+
+impl FunctionArg for CommandTimer {
+    fn to_datatype(_: &mut TypeCollection) -> Option<DataType> {
+        None
+    }
+}
+
+impl Drop for CommandTimer {
+    fn drop(&mut self) {
+        MetricsService::record_command(self.command, self.started.elapsed());
+    }
+}
+
+struct Metrics {
+    since: i64,
+    commands: HashMap<String, TimingStats>,
+    db_lock_wait: TimingStats,
+    ws_reconnects: u32,
+    syncs: TimingStats,
+    /// Messages received per subscription ID.
+    received: HashMap<String, u64>,
+}
+
+static METRICS: OnceLock<Mutex<Metrics>> = OnceLock::new();
+
+/// Service for recording and reading internal metrics.
+pub struct MetricsService;
+
+impl MetricsService {
+    /// Records the time taken to handle a command.
+    pub fn record_command(command: &str, elapsed: Duration) {
+        Self::lock_metrics()
+            .commands
+            .entry(command.to_string())
+            .or_default()
+            .record(elapsed);
+    }
+
+    /// Whether a command is timed by its [`CommandTimer`] rather than the
+    /// invoke handler.
+    pub fn is_self_timed(command: &str) -> bool {
+        ASYNC_COMMANDS.contains(&command)
+    }
+
+    /// Records the time spent waiting for the database connection.
+    pub fn record_db_lock_wait(elapsed: Duration) {
+        Self::lock_metrics().db_lock_wait.record(elapsed);
+    }
+
+    /// Counts a scheduled WebSocket reconnect.
+    pub fn record_ws_reconnect() {
+        Self::lock_metrics().ws_reconnects += 1;
+    }
+
+    /// Records the duration of a completed subscription sync.
+    pub fn record_sync(elapsed: Duration) {
+        Self::lock_metrics().syncs.record(elapsed);
+    }
+
+    /// Counts messages received for a subscription.
+    pub fn record_received(subscription_id: &str, count: u64) {
+        *Self::lock_metrics()
+            .received
+            .entry(subscription_id.to_string())
+            .or_default() += count;
+    }
+
+    /// Returns the number of messages received per subscription ID.
+    pub fn received_counts() -> HashMap<String, u64> {
+        Self::lock_metrics().received.clone()
+    }
+
+    /// Returns a copy of the metrics collected so far.
+    pub fn snapshot() -> InternalMetrics {
+        let metrics = Self::lock_metrics();
+        let mut commands: Vec<CommandTiming> = metrics
+            .commands
+            .iter()
+            .map(|(command, timing)| CommandTiming {
+                command: command.clone(),
+                timing: timing.clone(),
+            })
+            .collect();
+        commands.sort_by(|a, b| b.timing.total_ms.total_cmp(&a.timing.total_ms));
+
+        InternalMetrics {
+            since: metrics.since,
+            commands,
+            db_lock_wait: metrics.db_lock_wait.clone(),
+            ws_reconnects: metrics.ws_reconnects,
+            syncs: metrics.syncs.clone(),
+        }
+    }
+
+    fn lock_metrics() -> MutexGuard<'static, Metrics> {
+        let metrics = METRICS.get_or_init(|| {
+            Mutex::new(Metrics {
+                since: chrono::Utc::now().timestamp_millis(),
+                commands: HashMap::new(),
+                db_lock_wait: TimingStats::default(),
+                ws_reconnects: 0,
+                syncs: TimingStats::default(),
+                received: HashMap::new(),
+            })
+        });
+        match metrics.lock() {
+            Ok(metrics) => metrics,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
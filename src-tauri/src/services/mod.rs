@@ -1,13 +1,89 @@
+mod acknowledgment_service;
+mod action_service;
+pub mod client_certificate;
+mod config_file;
 mod connection_manager;
 pub mod credential_manager;
+mod dnd_scheduler;
+mod escalation_service;
+mod file_mirror;
+mod folder_watcher;
+mod hotkey_service;
 pub mod image_cache;
+mod kiosk_mode;
+mod link_preview_service;
+mod lock_service;
+pub mod log_redaction;
+mod maintenance_service;
+mod metrics_endpoint;
+mod metrics_service;
+mod network_monitor;
 mod ntfy_client;
+mod outbox_service;
+mod pattern_service;
+mod permission_service;
+pub mod policy;
+mod provisioning;
+pub mod proxy;
+mod publish_service;
+mod receipt_service;
+mod reminder_service;
+pub mod resolver;
+mod retention_service;
+mod rule_service;
+mod screen_capture;
+mod session_monitor;
+mod snooze_service;
+mod status_service;
+mod summary_service;
 mod sync_service;
+mod template_service;
+mod test_data_service;
+mod toast_history;
+mod trash_service;
 mod tray_manager;
 mod update_service;
+mod url_safety;
+mod windows_toast;
+mod write_retry_queue;
 
+pub use acknowledgment_service::AcknowledgmentService;
+pub use action_service::ActionService;
+pub use config_file::ConfigFileService;
 pub use connection_manager::ConnectionManager;
-pub use ntfy_client::NtfyClient;
-pub use sync_service::SyncService;
+pub use dnd_scheduler::DndScheduler;
+pub use escalation_service::EscalationService;
+pub use file_mirror::FileMirrorService;
+pub use folder_watcher::FolderWatcher;
+pub use hotkey_service::HotkeyService;
+pub use kiosk_mode::{KioskMode, KIOSK_ARG};
+pub use link_preview_service::LinkPreviewService;
+pub use lock_service::LockService;
+pub use maintenance_service::MaintenanceService;
+pub use metrics_endpoint::MetricsEndpoint;
+pub use metrics_service::{CommandTimer, MetricsService};
+pub use network_monitor::NetworkMonitor;
+pub use ntfy_client::{NtfyClient, OutgoingAttachment, OutgoingMessage};
+pub use outbox_service::OutboxService;
+pub use pattern_service::PatternService;
+pub use permission_service::PermissionService;
+pub use provisioning::ProvisioningService;
+pub use publish_service::PublishService;
+pub use receipt_service::{ReceiptService, ReceiptState};
+pub use reminder_service::ReminderService;
+pub use retention_service::RetentionService;
+pub use rule_service::RuleService;
+pub use screen_capture::ScreenCapture;
+pub use session_monitor::SessionMonitor;
+pub use snooze_service::SnoozeService;
+pub use status_service::StatusService;
+pub use summary_service::SummaryService;
+pub use sync_service::{NotificationBatchInserted, SyncCancellation, SyncService, SyncSummary};
+pub use template_service::TemplateService;
+pub use test_data_service::TestDataService;
+pub use toast_history::ToastHistory;
+pub use trash_service::TrashService;
 pub use tray_manager::TrayManager;
 pub use update_service::{UpdateInfo, UpdateService};
+pub use url_safety::UrlSafety;
+pub use write_retry_queue::WriteRetryQueue;
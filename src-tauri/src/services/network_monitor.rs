@@ -0,0 +1,178 @@
+//! Network availability detection.
+//!
+//! Used to hold back the startup sync until at least one configured server is
+//! reachable, so autostart on boot doesn't burn through reconnect backoff
+//! before Wi-Fi is up, and to tell whether a failed publish should be queued
+//! in the outbox.
+//!
+//! Also watches for the system waking from sleep and for network changes, so
+//! connections are reestablished right away. Both are polled: a wake-up shows
+//! as the wall clock jumping ahead between two checks, since timers don't run
+//! while suspended, and a network change as a different local address being
+//! used to reach the internet.
+
+use std::net::{IpAddr, Ipv4Addr, Ipv6Addr, SocketAddr, UdpSocket};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+use tokio::net::TcpStream;
+use url::Url;
+
+use crate::config::startup::{
+    NETWORK_CHECK_INTERVAL_SECS, NETWORK_PROBE_TIMEOUT_SECS, NETWORK_WAIT_MAX_SECS,
+};
+use crate::config::wake::{CHECK_INTERVAL_SECS, SLEEP_THRESHOLD_SECS};
+use crate::db::Database;
+use crate::services::ConnectionManager;
+
+/// Documentation addresses (RFC 5737 and RFC 3849) used to find the local
+/// address of the default route. Nothing is sent to them.
+const ROUTE_PROBE_V4: Ipv4Addr = Ipv4Addr::new(192, 0, 2, 1);
+const ROUTE_PROBE_V6: Ipv6Addr = Ipv6Addr::new(0x2001, 0xdb8, 0, 0, 0, 0, 0, 1);
+
+/// Service for detecting network availability.
+pub struct NetworkMonitor;
+
+impl NetworkMonitor {
+    /// Waits until any configured server accepts a TCP connection.
+    ///
+    /// Gives up after `NETWORK_WAIT_MAX_SECS` and returns `false`, letting the
+    /// caller continue with its normal retry logic.
+    pub async fn wait_for_network(handle: &AppHandle) -> bool {
+        let db: tauri::State<Database> = handle.state();
+        let targets: Vec<(String, u16)> = match db.get_settings() {
+            Ok(settings) => settings
+                .servers
+                .iter()
+                .filter_map(|s| Self::probe_target(&s.url))
+                .collect(),
+            Err(e) => {
+                log::warn!("Failed to load servers for network check: {e}");
+                return false;
+            }
+        };
+
+        if targets.is_empty() {
+            return true;
+        }
+
+        let started = Instant::now();
+        let max_wait = Duration::from_secs(NETWORK_WAIT_MAX_SECS);
+
+        loop {
+            for (host, port) in &targets {
+                if Self::probe(host, *port).await {
+                    log::info!(
+                        "Network available after {:.1}s",
+                        started.elapsed().as_secs_f32()
+                    );
+                    return true;
+                }
+            }
+
+            if started.elapsed() >= max_wait {
+                log::warn!("Network still unavailable after {NETWORK_WAIT_MAX_SECS}s, continuing");
+                return false;
+            }
+
+            log::info!("Waiting for network...");
+            tokio::time::sleep(Duration::from_secs(NETWORK_CHECK_INTERVAL_SECS)).await;
+        }
+    }
+
+    /// Spawns a background task that has the connection manager resume when
+    /// the system wakes from sleep or the network changes.
+    pub fn spawn_wake_watcher(handle: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(CHECK_INTERVAL_SECS));
+            let mut last_check = chrono::Utc::now().timestamp();
+            let mut addresses = Self::local_addresses();
+            loop {
+                interval.tick().await;
+
+                let now = chrono::Utc::now().timestamp();
+                let slept = Self::slept(last_check, now);
+                last_check = now;
+                let current = Self::local_addresses();
+                // Losing the network needs no recovery; gaining one does
+                let network_changed = current != addresses && current != (None, None);
+                addresses = current;
+
+                if slept {
+                    log::info!("System woke from sleep, reconnecting");
+                } else if network_changed {
+                    log::info!("Network changed, reconnecting");
+                } else {
+                    continue;
+                }
+                let conn_manager: tauri::State<ConnectionManager> = handle.state();
+                conn_manager.resume().await;
+                // Recovery may take a while; that isn't sleep
+                last_check = chrono::Utc::now().timestamp();
+                addresses = Self::local_addresses();
+            }
+        });
+    }
+
+    /// Whether the wall clock advanced far more than the check interval
+    /// between checks at `last_check` and `now` (seconds).
+    const fn slept(last_check: i64, now: i64) -> bool {
+        now - last_check > CHECK_INTERVAL_SECS as i64 + SLEEP_THRESHOLD_SECS
+    }
+
+    /// Local IPv4 and IPv6 addresses of the default routes, if any.
+    fn local_addresses() -> (Option<IpAddr>, Option<IpAddr>) {
+        let local_address = |bind: IpAddr, probe: IpAddr| {
+            // Connecting a UDP socket only picks a route; nothing is sent
+            let socket = UdpSocket::bind(SocketAddr::new(bind, 0)).ok()?;
+            socket.connect(SocketAddr::new(probe, 9)).ok()?;
+            socket.local_addr().ok().map(|address| address.ip())
+        };
+        (
+            local_address(Ipv4Addr::UNSPECIFIED.into(), ROUTE_PROBE_V4.into()),
+            local_address(Ipv6Addr::UNSPECIFIED.into(), ROUTE_PROBE_V6.into()),
+        )
+    }
+
+    /// Checks whether a server accepts a TCP connection.
+    pub async fn is_reachable(server_url: &str) -> bool {
+        match Self::probe_target(server_url) {
+            Some((host, port)) => Self::probe(&host, port).await,
+            None => false,
+        }
+    }
+
+    /// Extracts host and port from a server URL.
+    fn probe_target(server_url: &str) -> Option<(String, u16)> {
+        let parsed = Url::parse(server_url).ok()?;
+        let host = parsed.host_str()?.to_string();
+        let port = parsed.port_or_known_default()?;
+        Some((host, port))
+    }
+
+    /// Attempts a single TCP connection with a short timeout.
+    async fn probe(host: &str, port: u16) -> bool {
+        let connect = TcpStream::connect((host, port));
+        matches!(
+            tokio::time::timeout(Duration::from_secs(NETWORK_PROBE_TIMEOUT_SECS), connect).await,
+            Ok(Ok(_))
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_slept() {
+        let interval = CHECK_INTERVAL_SECS as i64;
+        assert!(!NetworkMonitor::slept(1000, 1000 + interval));
+        assert!(!NetworkMonitor::slept(
+            1000,
+            1000 + interval + SLEEP_THRESHOLD_SECS
+        ));
+        assert!(NetworkMonitor::slept(1000, 1000 + 3600));
+        // The clock being set back isn't sleep
+        assert!(!NetworkMonitor::slept(1000, 500));
+    }
+}
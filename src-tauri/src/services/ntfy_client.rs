@@ -1,10 +1,21 @@
 use base64::{engine::general_purpose::STANDARD, Engine};
-use reqwest::Client;
+use reqwest::{Client, ClientBuilder, Method, RequestBuilder, Response, StatusCode};
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::error::Error as StdError;
-
+use std::sync::{Arc, Mutex, MutexGuard, OnceLock, RwLock};
+use std::time::Duration;
+use tokio_util::sync::CancellationToken;
+
+use crate::config::http::{
+    POOL_IDLE_TIMEOUT_SECS, POOL_MAX_IDLE_PER_HOST, SESSION_TOKEN_MARGIN_SECS, TCP_KEEPALIVE_SECS,
+};
+use crate::db::Database;
 use crate::error::AppError;
-use crate::models::{normalize_url, NtfyMessage};
+use crate::models::{normalize_url, HttpTimeouts, NotificationAction, NtfyMessage, Priority};
+use crate::services::client_certificate::{self, ClientCertificate};
+use crate::services::proxy::{self, ProxyConfig};
+use crate::services::{log_redaction, resolver};
 
 #[allow(dead_code)]
 #[derive(Debug, Deserialize)]
@@ -22,17 +33,340 @@ pub struct NtfySubscription {
     pub display_name: Option<String>,
 }
 
+/// Topic listing returned by servers that expose one.
+///
+/// Stock ntfy has no such endpoint; some self-hosted setups add it either as a
+/// bare array or wrapped in an object.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum NtfyTopicListing {
+    Names(Vec<String>),
+    Wrapped { topics: Vec<String> },
+}
+
+/// Server-wide message statistics from `/v1/stats`.
+#[derive(Debug, Deserialize)]
+pub struct NtfyStats {
+    #[serde(default)]
+    pub messages: i64,
+    /// Messages published per second, averaged by the server.
+    #[serde(default)]
+    pub messages_rate: f64,
+}
+
+/// A user account as listed by the admin API; only its role is used.
+#[derive(Debug, Deserialize)]
+pub struct NtfyUser {
+    #[serde(default)]
+    pub role: String,
+}
+
+/// A message to publish to a topic.
+#[derive(Debug, Default)]
+pub struct OutgoingMessage<'a> {
+    pub title: &'a str,
+    pub message: &'a str,
+    pub tags: &'a [&'a str],
+    /// Priority; the server's default priority if unset.
+    pub priority: Option<Priority>,
+    pub markdown: bool,
+    /// Delivery delay understood by ntfy, e.g. `30m`.
+    pub delay: Option<&'a str>,
+}
+
+/// A file attached to a published message.
+#[derive(Debug)]
+pub struct OutgoingAttachment<'a> {
+    pub filename: &'a str,
+    pub data: Vec<u8>,
+}
+
+/// Messages returned by a poll request.
+#[derive(Debug)]
+pub struct PolledMessages {
+    pub messages: Vec<NtfyMessage>,
+    /// Server time from the response's `Date` header (Unix seconds), if sent.
+    pub server_time: Option<i64>,
+}
+
+/// Session token issued by `/v1/account/token`.
+#[derive(Debug, Deserialize)]
+struct NtfyToken {
+    token: String,
+    /// Expiry as a Unix timestamp; 0 or missing if it never expires.
+    #[serde(default)]
+    expires: Option<i64>,
+}
+
+/// Cached session token of a user on a server.
+#[derive(Debug, Clone)]
+enum SessionToken {
+    Issued {
+        token: String,
+        expires: Option<i64>,
+    },
+    /// The server issues no tokens, so requests use basic auth.
+    Unsupported,
+}
+
+/// Session tokens by server URL and username, so requests authenticate with
+/// a token instead of sending the password every time.
+static SESSION_TOKENS: OnceLock<Mutex<HashMap<(String, String), SessionToken>>> = OnceLock::new();
+
+fn session_tokens() -> MutexGuard<'static, HashMap<(String, String), SessionToken>> {
+    match SESSION_TOKENS.get_or_init(Mutex::default).lock() {
+        Ok(tokens) => tokens,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Timeouts from the settings, kept in memory like the proxy so clients can
+/// be built without the app state.
+static TIMEOUTS: OnceLock<RwLock<HttpTimeouts>> = OnceLock::new();
+
+/// The timeouts in effect; the defaults until the settings are loaded.
+fn timeouts() -> HttpTimeouts {
+    let lock = TIMEOUTS.get_or_init(RwLock::default);
+    match lock.read() {
+        Ok(timeouts) => *timeouts,
+        Err(poisoned) => *poisoned.into_inner(),
+    }
+}
+
+/// The proxy, generation of resolver overrides and timeouts a client was
+/// built for. Clients are rebuilt when any of them changes, so no pooled
+/// connection outlives a server being moved to a SOCKS proxy.
+type ClientSettings = (ProxyConfig, u64, HttpTimeouts);
+
+fn client_settings() -> ClientSettings {
+    (proxy::current(), resolver::generation(), timeouts())
+}
+
+/// HTTP client shared by all `NtfyClient`s, so connections to a server are
+/// pooled and reused instead of being opened for every call.
+static SHARED_CLIENT: Mutex<Option<(ClientSettings, Client)>> = Mutex::new(None);
+
+/// HTTP clients presenting a server's client certificate, by server URL, with
+/// the certificate and settings they were built for.
+type CertificateClients = HashMap<String, (Arc<ClientCertificate>, ClientSettings, Client)>;
+
+static CERTIFICATE_CLIENTS: OnceLock<Mutex<CertificateClients>> = OnceLock::new();
+
 pub struct NtfyClient {
     client: Client,
+    cancel_token: Option<CancellationToken>,
 }
 
 impl NtfyClient {
+    /// Creates a client with connect, read and overall request timeouts applied.
+    ///
+    /// All clients share one connection pool, except for requests to servers
+    /// with a client certificate, which get a pool of their own.
     pub fn new() -> Result<Self, AppError> {
-        let client = Client::builder()
-            .build()
-            .map_err(|e| AppError::Connection(format!("Failed to create HTTP client: {e}")))?;
+        Ok(Self {
+            client: Self::shared_client()?,
+            cancel_token: None,
+        })
+    }
+
+    /// Forgets all session tokens, so the next requests authenticate with
+    /// the password again. Called when credentials change or are locked.
+    pub fn clear_session_tokens() {
+        session_tokens().clear();
+        log::debug!("Session tokens cleared");
+    }
+
+    /// Loads the timeouts from the settings, replacing those loaded before.
+    /// Clients built from now on apply them.
+    pub fn reload_timeouts(db: &Database) {
+        let timeouts = match db.get_http_timeouts() {
+            Ok(timeouts) => timeouts,
+            Err(e) => {
+                log::warn!("Failed to load HTTP timeouts: {e}");
+                return;
+            }
+        };
+        let lock = TIMEOUTS.get_or_init(RwLock::default);
+        match lock.write() {
+            Ok(mut current) => *current = timeouts,
+            Err(poisoned) => *poisoned.into_inner() = timeouts,
+        }
+    }
+
+    fn shared_client() -> Result<Client, AppError> {
+        let settings = client_settings();
+        let mut shared = match SHARED_CLIENT.lock() {
+            Ok(shared) => shared,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if let Some((_, client)) = shared.as_ref().filter(|(built, _)| *built == settings) {
+            return Ok(client.clone());
+        }
+
+        let client = proxy::build_client(Self::client_builder(&settings.2), &settings.0)?;
+
+        *shared = Some((settings, client.clone()));
+        Ok(client)
+    }
+
+    fn client_builder(timeouts: &HttpTimeouts) -> ClientBuilder {
+        Client::builder()
+            .connect_timeout(Duration::from_secs(u64::from(timeouts.connect_secs)))
+            .read_timeout(Duration::from_secs(u64::from(timeouts.read_secs)))
+            .timeout(Duration::from_secs(u64::from(timeouts.request_secs)))
+            .pool_idle_timeout(Duration::from_secs(POOL_IDLE_TIMEOUT_SECS))
+            .pool_max_idle_per_host(POOL_MAX_IDLE_PER_HOST)
+            .tcp_keepalive(Duration::from_secs(TCP_KEEPALIVE_SECS))
+            .dns_resolver(Arc::new(resolver::OverrideResolver))
+    }
+
+    /// The HTTP client for requests to the server at `server_url`: the shared
+    /// one, or one presenting the server's client certificate if it has one.
+    fn client_for(&self, server_url: &str) -> Result<Client, AppError> {
+        let Some(certificate) = client_certificate::for_server(server_url) else {
+            return Ok(self.client.clone());
+        };
+        let key = normalize_url(server_url).to_string();
+
+        let settings = client_settings();
+        let mut clients = match CERTIFICATE_CLIENTS.get_or_init(Mutex::default).lock() {
+            Ok(clients) => clients,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if let Some((_, _, client)) = clients
+            .get(&key)
+            .filter(|(cached, built, _)| Arc::ptr_eq(cached, &certificate) && *built == settings)
+        {
+            return Ok(client.clone());
+        }
+
+        let builder = Self::client_builder(&settings.2).identity(certificate.identity()?);
+        let client = proxy::build_client(builder, &settings.0)?;
+        clients.insert(key, (certificate, settings, client.clone()));
+        Ok(client)
+    }
+
+    /// Attaches a cancellation token; in-flight requests abort once it is cancelled.
+    #[must_use]
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancel_token = Some(token);
+        self
+    }
+
+    /// Sends a request, returning `AppError::Cancelled` if the token fires first.
+    async fn send<F>(&self, request: RequestBuilder, on_error: F) -> Result<Response, AppError>
+    where
+        F: FnOnce(reqwest::Error) -> AppError,
+    {
+        let result = match self.cancel_token {
+            Some(ref token) => tokio::select! {
+                result = request.send() => result,
+                () = token.cancelled() => {
+                    return Err(AppError::Cancelled("HTTP request cancelled".to_string()));
+                }
+            },
+            None => request.send().await,
+        };
 
-        Ok(Self { client })
+        result.map_err(on_error)
+    }
+
+    /// Sends a request authenticated as `username`, if credentials are given.
+    ///
+    /// Where the server issues session tokens, a cached token is sent instead
+    /// of the password. If the server rejects it, the request is sent once
+    /// more with a fresh token.
+    async fn send_authenticated<F>(
+        &self,
+        request: RequestBuilder,
+        server_url: &str,
+        credentials: Option<(&str, &str)>,
+        on_error: F,
+    ) -> Result<Response, AppError>
+    where
+        F: Fn(reqwest::Error) -> AppError + Send + Sync,
+    {
+        let Some((username, password)) = credentials.filter(|(user, _)| !user.is_empty()) else {
+            return self.send(request, on_error).await;
+        };
+        let key = (normalize_url(server_url).to_string(), username.to_string());
+
+        let cached = session_tokens().get(&key).cloned();
+        let renew_before = chrono::Utc::now().timestamp() + SESSION_TOKEN_MARGIN_SECS;
+        let token = match cached {
+            Some(SessionToken::Issued { token, expires })
+                if expires.map_or(true, |e| e == 0 || e > renew_before) =>
+            {
+                Some(token)
+            }
+            Some(SessionToken::Unsupported) => None,
+            _ => self.issue_session_token(&key, password).await,
+        };
+        let Some(token) = token else {
+            let request = Self::with_auth(request, Some(username), Some(password));
+            return self.send(request, on_error).await;
+        };
+
+        let retry = request.try_clone();
+        let response = self.send(request.bearer_auth(token), &on_error).await?;
+        let Some(retry) = retry.filter(|_| response.status() == StatusCode::UNAUTHORIZED) else {
+            return Ok(response);
+        };
+
+        log::info!("Session token for {username} on {server_url} was rejected, renewing");
+        session_tokens().remove(&key);
+        let retry = match self.issue_session_token(&key, password).await {
+            Some(token) => retry.bearer_auth(token),
+            None => Self::with_auth(retry, Some(username), Some(password)),
+        };
+        self.send(retry, on_error).await
+    }
+
+    /// Requests a session token for the server URL and username in `key` and
+    /// caches it. Servers without the token endpoint are remembered as
+    /// issuing none.
+    async fn issue_session_token(&self, key: &(String, String), password: &str) -> Option<String> {
+        let (server_url, username) = key;
+        let url = format!("{server_url}/v1/account/token");
+        let request = self.client_for(server_url).ok()?.post(&url).header(
+            "Authorization",
+            Self::create_auth_header(username, password),
+        );
+
+        let response = self
+            .send(request, |e| AppError::Connection(e.to_string()))
+            .await
+            .ok()?;
+        let status = response.status();
+        if matches!(
+            status,
+            StatusCode::NOT_FOUND | StatusCode::METHOD_NOT_ALLOWED
+        ) {
+            log::info!("{server_url} issues no session tokens, using basic auth");
+            session_tokens().insert(key.clone(), SessionToken::Unsupported);
+            return None;
+        }
+
+        // Anything else, such as rejected credentials or rate limiting, may
+        // pass, so it isn't cached; the request itself reports it
+        if !status.is_success() {
+            log::info!("{server_url} issued no session token ({status}), using basic auth");
+            return None;
+        }
+        let Ok(issued) = response.json::<NtfyToken>().await else {
+            log::warn!("Invalid session token from {server_url}, using basic auth");
+            return None;
+        };
+
+        log::info!("Obtained session token for {username} on {server_url}");
+        session_tokens().insert(
+            key.clone(),
+            SessionToken::Issued {
+                token: issued.token.clone(),
+                expires: issued.expires,
+            },
+        );
+        Some(issued.token)
     }
 
     fn create_auth_header(username: &str, password: &str) -> String {
@@ -51,15 +385,10 @@ impl NtfyClient {
         let url = format!("{}/v1/account", normalize_url(server_url));
         log::info!("Fetching account from: {url}");
 
-        let auth_header = Self::create_auth_header(username, password);
+        let request = self.client_for(server_url)?.get(&url);
 
         let response = self
-            .client
-            .get(&url)
-            .header("Authorization", auth_header)
-            .send()
-            .await
-            .map_err(|e| {
+            .send_authenticated(request, server_url, Some((username, password)), |e| {
                 log::error!(
                     "Failed to connect: {} (is_connect: {}, is_timeout: {}, is_request: {})",
                     e,
@@ -71,7 +400,8 @@ impl NtfyClient {
                     log::error!("Error source: {source:?}");
                 }
                 AppError::Connection(format!("Failed to connect to {server_url}: {e}"))
-            })?;
+            })
+            .await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -87,8 +417,6 @@ impl NtfyClient {
             .await
             .map_err(|e| AppError::Connection(format!("Failed to read response: {e}")))?;
 
-        log::info!("ntfy account API response: {text}");
-
         let account: NtfyAccount = serde_json::from_str(&text).map_err(|e| {
             AppError::Connection(format!("Failed to parse response: {e} - body: {text}"))
         })?;
@@ -101,6 +429,160 @@ impl NtfyClient {
         Ok(account)
     }
 
+    /// Fetch server-wide message statistics from `/v1/stats`
+    pub async fn get_stats(
+        &self,
+        server_url: &str,
+        username: Option<&str>,
+        password: Option<&str>,
+    ) -> Result<NtfyStats, AppError> {
+        let url = format!("{}/v1/stats", normalize_url(server_url));
+        log::info!("Fetching stats from: {url}");
+
+        let request = self.client_for(server_url)?.get(&url);
+
+        let response = self
+            .send_authenticated(request, server_url, username.zip(password), |e| {
+                log::error!("Failed to fetch stats: {e}");
+                AppError::Connection(format!("Failed to fetch stats from {server_url}: {e}"))
+            })
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            log::error!("Server returned {status}: {body}");
+            return Err(AppError::Connection(format!(
+                "Failed to fetch stats: {status} - {body}"
+            )));
+        }
+
+        let text = response
+            .text()
+            .await
+            .map_err(|e| AppError::Connection(format!("Failed to read response: {e}")))?;
+
+        serde_json::from_str(&text)
+            .map_err(|e| AppError::Connection(format!("Failed to parse stats: {e} - body: {text}")))
+    }
+
+    /// Fetch all user accounts via the admin API (`/v1/users`)
+    /// Returns `None` if the credentials lack admin rights or the server has no user management
+    pub async fn list_users(
+        &self,
+        server_url: &str,
+        username: &str,
+        password: &str,
+    ) -> Result<Option<Vec<NtfyUser>>, AppError> {
+        let url = format!("{}/v1/users", normalize_url(server_url));
+        log::info!("Fetching users from: {url}");
+
+        let request = self.client_for(server_url)?.get(&url);
+
+        let response = self
+            .send_authenticated(request, server_url, Some((username, password)), |e| {
+                log::error!("Failed to fetch users: {e}");
+                AppError::Connection(format!("Failed to fetch users from {server_url}: {e}"))
+            })
+            .await?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::UNAUTHORIZED
+            || status == reqwest::StatusCode::FORBIDDEN
+            || status == reqwest::StatusCode::NOT_FOUND
+        {
+            log::info!("No admin access to users on {server_url} ({status})");
+            return Ok(None);
+        }
+
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            log::error!("Server returned {status}: {body}");
+            return Err(AppError::Connection(format!(
+                "Failed to fetch users: {status} - {body}"
+            )));
+        }
+
+        let text = response
+            .text()
+            .await
+            .map_err(|e| AppError::Connection(format!("Failed to read response: {e}")))?;
+
+        let users: Vec<NtfyUser> = serde_json::from_str(&text).map_err(|e| {
+            AppError::Connection(format!("Failed to parse users: {e} - body: {text}"))
+        })?;
+
+        Ok(Some(users))
+    }
+
+    /// Adds basic auth to a request if a non-empty username is given
+    fn with_auth(
+        request: RequestBuilder,
+        username: Option<&str>,
+        password: Option<&str>,
+    ) -> RequestBuilder {
+        match (username, password) {
+            (Some(user), Some(pass)) if !user.is_empty() => {
+                request.header("Authorization", Self::create_auth_header(user, pass))
+            }
+            _ => request,
+        }
+    }
+
+    /// Fetch the list of topics from servers that expose `/v1/topics`
+    /// Returns `None` if the server doesn't support topic listing
+    pub async fn list_topics(
+        &self,
+        server_url: &str,
+        username: Option<&str>,
+        password: Option<&str>,
+    ) -> Result<Option<Vec<String>>, AppError> {
+        let url = format!("{}/v1/topics", normalize_url(server_url));
+        log::info!("Fetching topic listing from: {url}");
+
+        let request = self.client_for(server_url)?.get(&url);
+
+        let response = self
+            .send_authenticated(request, server_url, username.zip(password), |e| {
+                log::error!("Failed to fetch topic listing: {e}");
+                AppError::Connection(format!("Failed to fetch topics from {server_url}: {e}"))
+            })
+            .await?;
+
+        let status = response.status();
+        if status == reqwest::StatusCode::NOT_FOUND
+            || status == reqwest::StatusCode::METHOD_NOT_ALLOWED
+        {
+            log::info!("Server {server_url} does not support topic listing");
+            return Ok(None);
+        }
+
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            log::error!("Server returned {status}: {body}");
+            return Err(AppError::Connection(format!(
+                "Failed to fetch topics: {status} - {body}"
+            )));
+        }
+
+        let text = response
+            .text()
+            .await
+            .map_err(|e| AppError::Connection(format!("Failed to read response: {e}")))?;
+
+        // A server that answers with something else (e.g. a web app page) has no listing
+        let topics = match serde_json::from_str::<NtfyTopicListing>(&text) {
+            Ok(NtfyTopicListing::Names(topics) | NtfyTopicListing::Wrapped { topics }) => topics,
+            Err(e) => {
+                log::info!("Server {server_url} returned no usable topic listing: {e}");
+                return Ok(None);
+            }
+        };
+
+        log::info!("Fetched {} topics from {server_url}", topics.len());
+        Ok(Some(topics))
+    }
+
     /// Delete a message from the ntfy server
     pub async fn delete_message(
         &self,
@@ -113,20 +595,19 @@ impl NtfyClient {
         let base = normalize_url(server_url);
         let url = format!("{base}/{topic}/{message_id}");
 
-        log::info!("Deleting message from: {url}");
-
-        let mut request = self.client.delete(&url);
+        log::info!(
+            "Deleting message {message_id} from: {base}/{}",
+            log_redaction::redact(topic)
+        );
 
-        if let (Some(user), Some(pass)) = (username, password) {
-            if !user.is_empty() {
-                request = request.header("Authorization", Self::create_auth_header(user, pass));
-            }
-        }
+        let request = self.client_for(server_url)?.delete(&url);
 
-        let response = request.send().await.map_err(|e| {
-            log::error!("Failed to delete message: {e}");
-            AppError::Connection(format!("Failed to delete message from {server_url}: {e}"))
-        })?;
+        let response = self
+            .send_authenticated(request, server_url, username.zip(password), |e| {
+                log::error!("Failed to delete message: {e}");
+                AppError::Connection(format!("Failed to delete message from {server_url}: {e}"))
+            })
+            .await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -137,7 +618,168 @@ impl NtfyClient {
             )));
         }
 
-        log::info!("Successfully deleted message {message_id} from {server_url}/{topic}");
+        log::info!(
+            "Successfully deleted message {message_id} from {base}/{}",
+            log_redaction::redact(topic)
+        );
+        Ok(())
+    }
+
+    /// Sends the request of an ntfy `http` action button.
+    ///
+    /// The method defaults to POST, as in ntfy. Fails unless the server
+    /// answers with a success status.
+    pub async fn execute_http_action(&self, action: &NotificationAction) -> Result<(), AppError> {
+        let url = action
+            .url
+            .as_deref()
+            .ok_or_else(|| AppError::InvalidUrl(format!("Action {} has no URL", action.label)))?;
+        let method = match action.method.as_deref().map(str::trim) {
+            None | Some("") => Method::POST,
+            Some(method) => Method::from_bytes(method.to_ascii_uppercase().as_bytes())
+                .map_err(|_| AppError::InvalidUrl(format!("Invalid HTTP method: {method}")))?,
+        };
+
+        log::info!("Running action {}: {method} {url}", action.label);
+
+        let mut request = self.client.request(method, url);
+        for (name, value) in &action.headers {
+            request = request.header(name, value);
+        }
+        if let Some(ref body) = action.body {
+            request = request.body(body.clone());
+        }
+
+        let response = self
+            .send(request, |e| {
+                log::error!("Failed to run action: {e}");
+                AppError::Connection(format!("Failed to reach {url}: {e}"))
+            })
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            log::error!("Action {} returned {status}", action.label);
+            return Err(AppError::Connection(format!(
+                "{} failed: {url} returned {status}",
+                action.label
+            )));
+        }
+
+        log::info!("Action {} succeeded", action.label);
+        Ok(())
+    }
+
+    /// Publish a message with a file attached to a topic
+    /// The file is the request body; the message fields are sent as query
+    /// parameters so they may contain any Unicode
+    pub async fn publish_attachment(
+        &self,
+        server_url: &str,
+        topic: &str,
+        message: &OutgoingMessage<'_>,
+        attachment: OutgoingAttachment<'_>,
+        username: Option<&str>,
+        password: Option<&str>,
+    ) -> Result<(), AppError> {
+        let url = format!("{}/{topic}", normalize_url(server_url));
+
+        log::info!(
+            "Publishing attachment to: {}/{}",
+            normalize_url(server_url),
+            log_redaction::redact(topic)
+        );
+
+        let mut query = vec![
+            ("title", message.title.to_string()),
+            ("message", message.message.to_string()),
+            ("tags", message.tags.join(",")),
+            ("filename", attachment.filename.to_string()),
+        ];
+        if let Some(priority) = message.priority {
+            query.push(("priority", (priority as u8).to_string()));
+        }
+        if message.markdown {
+            query.push(("markdown", "yes".to_string()));
+        }
+        if let Some(delay) = message.delay {
+            query.push(("delay", delay.to_string()));
+        }
+        let request = self
+            .client_for(server_url)?
+            .put(&url)
+            .query(&query)
+            .body(attachment.data);
+
+        let response = self
+            .send_authenticated(request, server_url, username.zip(password), |e| {
+                log::error!("Failed to publish attachment: {e}");
+                AppError::Connection(format!("Failed to publish to {server_url}: {e}"))
+            })
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            log::error!("Server returned {status} on publish: {body}");
+            return Err(AppError::Connection(format!(
+                "Failed to publish attachment: {status} - {body}"
+            )));
+        }
+
+        Ok(())
+    }
+
+    /// Publish a message to a topic
+    /// Sent as JSON to the server root so titles and messages may contain any Unicode
+    pub async fn publish(
+        &self,
+        server_url: &str,
+        topic: &str,
+        message: &OutgoingMessage<'_>,
+        username: Option<&str>,
+        password: Option<&str>,
+    ) -> Result<(), AppError> {
+        let base = normalize_url(server_url);
+
+        log::info!(
+            "Publishing message to: {base}/{}",
+            log_redaction::redact(topic)
+        );
+
+        let mut body = serde_json::json!({
+            "topic": topic,
+            "title": message.title,
+            "message": message.message,
+            "tags": message.tags,
+        });
+        if let Some(priority) = message.priority {
+            body["priority"] = (priority as u8).into();
+        }
+        if message.markdown {
+            body["markdown"] = true.into();
+        }
+        if let Some(delay) = message.delay {
+            body["delay"] = delay.into();
+        }
+        let request = self.client_for(server_url)?.post(base).json(&body);
+
+        let response = self
+            .send_authenticated(request, server_url, username.zip(password), |e| {
+                log::error!("Failed to publish message: {e}");
+                AppError::Connection(format!("Failed to publish to {server_url}: {e}"))
+            })
+            .await?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            log::error!("Server returned {status} on publish: {body}");
+            return Err(AppError::Connection(format!(
+                "Failed to publish message: {status} - {body}"
+            )));
+        }
+
         Ok(())
     }
 
@@ -150,7 +792,7 @@ impl NtfyClient {
         since: Option<i64>,
         username: Option<&str>,
         password: Option<&str>,
-    ) -> Result<Vec<NtfyMessage>, AppError> {
+    ) -> Result<PolledMessages, AppError> {
         let base = normalize_url(server_url);
 
         // Build URL with poll parameter to get historical messages
@@ -161,21 +803,22 @@ impl NtfyClient {
             None => format!("{base}/{topic}/json?poll=1&since=all"),
         };
 
-        log::info!("Fetching messages from: {url}");
-
-        let mut request = self.client.get(&url);
+        log::info!(
+            "Fetching messages from: {base}/{} (since: {since:?})",
+            log_redaction::redact(topic)
+        );
 
-        // Add auth header if credentials provided
-        if let (Some(user), Some(pass)) = (username, password) {
-            if !user.is_empty() {
-                request = request.header("Authorization", Self::create_auth_header(user, pass));
-            }
-        }
+        let request = self
+            .client_for(server_url)?
+            .get(&url)
+            .timeout(Duration::from_secs(u64::from(timeouts().poll_secs)));
 
-        let response = request.send().await.map_err(|e| {
-            log::error!("Failed to fetch messages: {e}");
-            AppError::Connection(format!("Failed to fetch messages from {server_url}: {e}"))
-        })?;
+        let response = self
+            .send_authenticated(request, server_url, username.zip(password), |e| {
+                log::error!("Failed to fetch messages: {e}");
+                AppError::Connection(format!("Failed to fetch messages from {server_url}: {e}"))
+            })
+            .await?;
 
         if !response.status().is_success() {
             let status = response.status();
@@ -186,6 +829,13 @@ impl NtfyClient {
             )));
         }
 
+        let server_time = response
+            .headers()
+            .get(reqwest::header::DATE)
+            .and_then(|date| date.to_str().ok())
+            .and_then(|date| chrono::DateTime::parse_from_rfc2822(date).ok())
+            .map(|date| date.timestamp());
+
         let text = response
             .text()
             .await
@@ -205,7 +855,10 @@ impl NtfyClient {
                     }
                 }
                 Err(e) => {
-                    log::warn!("Failed to parse message: {e} - line: {line}");
+                    log::warn!(
+                        "Failed to parse message: {e} - line: {}",
+                        log_redaction::redact(line)
+                    );
                 }
             }
         }
@@ -214,8 +867,72 @@ impl NtfyClient {
             "Fetched {} messages from {}/{}",
             messages.len(),
             server_url,
-            topic
+            log_redaction::redact(topic)
+        );
+        Ok(PolledMessages {
+            messages,
+            server_time,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::MockNtfyServer;
+
+    #[tokio::test]
+    async fn test_publish_and_poll_mock_server() {
+        let server = MockNtfyServer::start().await;
+        assert!(server.is_ok());
+        let Ok(server) = server else { return };
+        let client = NtfyClient::new();
+        assert!(client.is_ok());
+        let Ok(client) = client else { return };
+
+        server.set_clock_offset(-120);
+        let now = server.now();
+        server.publish("alerts", "m1", now - 60, "First");
+        server.publish("other", "m2", now - 30, "Elsewhere");
+        let message = OutgoingMessage {
+            title: "Deploy",
+            message: "Second",
+            ..Default::default()
+        };
+        let published = client
+            .publish(server.url(), "alerts", &message, Some("user"), Some("pass"))
+            .await;
+        assert!(published.is_ok());
+
+        let polled = client
+            .get_messages(server.url(), "alerts", None, None, None)
+            .await;
+        assert!(polled.is_ok());
+        let Ok(polled) = polled else { return };
+        let messages: Vec<Option<&str>> = polled
+            .messages
+            .iter()
+            .map(|m| m.message.as_deref())
+            .collect();
+        assert_eq!(messages, [Some("First"), Some("Second")]);
+        assert!(polled
+            .server_time
+            .is_some_and(|time| (time - server.now()).abs() <= 2));
+
+        let recent = client
+            .get_messages(server.url(), "alerts", Some(now - 10), None, None)
+            .await;
+        assert_eq!(recent.map(|p| p.messages.len()).ok(), Some(1));
+
+        // The server issues no session tokens, so basic auth is sent instead
+        let requests = server.requests();
+        assert!(requests.iter().any(|r| r.path == "/v1/account/token"));
+        let publish = requests
+            .iter()
+            .find(|r| r.method == "POST" && r.path == "/");
+        assert_eq!(
+            publish.and_then(|r| r.authorization.as_deref()),
+            Some(NtfyClient::create_auth_header("user", "pass").as_str())
         );
-        Ok(messages)
     }
 }
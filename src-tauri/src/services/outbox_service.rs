@@ -0,0 +1,133 @@
+//! Outbox for messages published while offline.
+//!
+//! A message published while its server is unreachable is kept in the
+//! outbox instead of failing, and sent by a background task once the server
+//! accepts connections again. Messages to a subscription are sent in the
+//! order they were published, so a new one waits behind any already queued.
+//! A queued message is given up on after `MAX_ATTEMPTS` failures with the
+//! server reachable, e.g. because it rejects the message.
+
+use std::collections::HashSet;
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use crate::config::outbox::{FLUSH_INTERVAL_SECS, MAX_ATTEMPTS};
+use crate::db::Database;
+use crate::error::AppError;
+use crate::events::{self, OutboxFailed, OutboxSent};
+use crate::models::{PublishMessage, PublishOutcome};
+use crate::services::{NetworkMonitor, PublishService};
+
+/// Service for publishing through the outbox.
+pub struct OutboxService;
+
+impl OutboxService {
+    /// Publishes a message to a subscription's topic, queueing it if the
+    /// server is unreachable or earlier messages to it are still queued.
+    pub async fn publish(
+        db: &Database,
+        subscription_id: &str,
+        message: &PublishMessage,
+    ) -> Result<PublishOutcome, AppError> {
+        let subscription = db.get_subscription_by_id(subscription_id)?.ok_or_else(|| {
+            AppError::NotFound(format!("Subscription {subscription_id} not found"))
+        })?;
+
+        if subscription.local || !db.has_queued_messages(subscription_id)? {
+            match PublishService::publish_message(db, subscription_id, message).await {
+                Ok(()) => return Ok(PublishOutcome::Sent),
+                Err(AppError::Connection(e))
+                    if !subscription.local
+                        && !NetworkMonitor::is_reachable(&subscription.server_url).await =>
+                {
+                    log::info!("Server unreachable, queueing message in the outbox: {e}");
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        let entry = db.queue_message(subscription_id, message)?;
+        Ok(PublishOutcome::Queued { entry })
+    }
+
+    /// Spawns a background task that sends queued messages once their
+    /// servers are reachable.
+    pub fn spawn_flusher(handle: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(FLUSH_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                Self::flush(&handle).await;
+            }
+        });
+    }
+
+    /// Tries to send every queued message, oldest first.
+    async fn flush(handle: &AppHandle) {
+        let db: tauri::State<'_, Database> = handle.state();
+        let entries = match db.get_outbox() {
+            Ok(entries) => entries,
+            Err(e) => {
+                log::warn!("Failed to load the outbox: {e}");
+                return;
+            }
+        };
+
+        // Subscriptions whose next message couldn't be sent; later ones wait
+        let mut held: HashSet<String> = HashSet::new();
+        for entry in entries {
+            if held.contains(&entry.subscription_id) {
+                continue;
+            }
+
+            let error =
+                match PublishService::publish_message(&db, &entry.subscription_id, &entry.message)
+                    .await
+                {
+                    Ok(()) => {
+                        if let Err(e) = db.delete_outbox_entry(&entry.id) {
+                            log::warn!("Failed to remove sent message from the outbox: {e}");
+                        }
+                        log::info!("Sent queued message {}", entry.id);
+                        events::emit(handle, &OutboxSent(entry));
+                        continue;
+                    }
+                    Err(e) => e,
+                };
+            held.insert(entry.subscription_id.clone());
+
+            let server_url = db
+                .get_subscription_by_id(&entry.subscription_id)
+                .ok()
+                .flatten()
+                .map(|sub| sub.server_url);
+            let reachable = match server_url {
+                Some(url) => NetworkMonitor::is_reachable(&url).await,
+                None => true,
+            };
+            if !reachable {
+                continue;
+            }
+
+            if entry.attempts + 1 < MAX_ATTEMPTS {
+                if let Err(e) = db.record_outbox_attempt(&entry.id) {
+                    log::warn!("Failed to record outbox attempt: {e}");
+                }
+                continue;
+            }
+
+            log::warn!("Giving up on queued message {}: {error}", entry.id);
+            if let Err(e) = db.delete_outbox_entry(&entry.id) {
+                log::warn!("Failed to remove message from the outbox: {e}");
+            }
+            events::emit(
+                handle,
+                &OutboxFailed {
+                    entry,
+                    error: error.to_string(),
+                },
+            );
+        }
+    }
+}
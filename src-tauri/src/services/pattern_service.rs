@@ -0,0 +1,147 @@
+//! Pattern subscriptions for servers with many per-device topics.
+//!
+//! Polls the topic listing of servers that expose one and creates a child
+//! subscription for each topic matching a pattern, grouped under the pattern
+//! via `pattern_id`. Servers without a listing are skipped silently.
+
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::config::patterns::POLL_INTERVAL_SECS;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{wildcard_match, CreateSubscription, Subscription, SubscriptionPattern};
+use crate::services::{log_redaction, ConnectionManager, NtfyClient};
+
+/// Creates child subscriptions for topics matching pattern subscriptions.
+pub struct PatternService;
+
+impl PatternService {
+    /// Refreshes all pattern subscriptions, returning the children created.
+    pub async fn refresh_all(handle: &AppHandle) -> Vec<Subscription> {
+        let db: tauri::State<Database> = handle.state();
+
+        let patterns = match db.get_subscription_patterns() {
+            Ok(p) => p,
+            Err(e) => {
+                log::error!("Failed to get subscription patterns: {e}");
+                return Vec::new();
+            }
+        };
+
+        let mut created = Vec::new();
+        for pattern in &patterns {
+            match Self::refresh(handle, pattern).await {
+                Ok(subs) => created.extend(subs),
+                Err(e) => log::warn!(
+                    "Failed to refresh pattern {} on {}: {}",
+                    pattern.pattern,
+                    pattern.server_url,
+                    e
+                ),
+            }
+        }
+
+        created
+    }
+
+    /// Refreshes a single pattern subscription, returning the children created.
+    ///
+    /// Topics that already have a subscription (including trashed ones) or that
+    /// match the blocklist are skipped.
+    pub async fn refresh(
+        handle: &AppHandle,
+        pattern: &SubscriptionPattern,
+    ) -> Result<Vec<Subscription>, AppError> {
+        let db: tauri::State<Database> = handle.state();
+        let conn_manager: tauri::State<ConnectionManager> = handle.state();
+
+        let settings = db.get_settings()?;
+        let server = settings
+            .servers
+            .iter()
+            .find(|s| s.url_matches(&pattern.server_url));
+        let (username, password) = match server {
+            Some(s) => (s.username.as_deref(), s.password.as_deref()),
+            None => (None, None),
+        };
+
+        let client = NtfyClient::new()?;
+        let Some(topics) = client
+            .list_topics(&pattern.server_url, username, password)
+            .await?
+        else {
+            return Ok(Vec::new());
+        };
+
+        let mut existing = db.get_all_subscriptions()?;
+        existing.extend(db.get_deleted_subscriptions()?);
+        let blocklist = db.get_blocked_topics()?;
+
+        let mut created = Vec::new();
+        for topic in topics {
+            if !wildcard_match(&pattern.pattern, &topic) {
+                continue;
+            }
+
+            let already_exists = existing
+                .iter()
+                .any(|s| s.server_url_matches(&pattern.server_url) && s.topic == topic);
+            if already_exists {
+                continue;
+            }
+
+            if blocklist
+                .iter()
+                .any(|b| b.matches(&pattern.server_url, &topic))
+            {
+                log::info!(
+                    "Topic is blocklisted, skipping: {}",
+                    log_redaction::redact(&topic)
+                );
+                continue;
+            }
+
+            log::info!(
+                "Creating subscription {} for pattern {}",
+                log_redaction::redact(&topic),
+                log_redaction::redact(&pattern.pattern)
+            );
+            let mut sub = match db.create_subscription(CreateSubscription {
+                topic,
+                server_url: pattern.server_url.clone(),
+                display_name: None,
+            }) {
+                Ok(s) => s,
+                Err(e) => {
+                    log::warn!("Failed to create pattern child subscription: {e}");
+                    continue;
+                }
+            };
+            db.set_subscription_pattern(&sub.id, &pattern.id)?;
+            sub.pattern_id = Some(pattern.id.clone());
+
+            if let Err(e) = conn_manager.connect(&sub).await {
+                log::error!("Failed to connect to {}: {}", sub.id, e);
+            }
+
+            created.push(sub);
+        }
+
+        Ok(created)
+    }
+
+    /// Spawns a background task that refreshes all patterns periodically.
+    pub fn spawn_poller(handle: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(POLL_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                let created = Self::refresh_all(&handle).await;
+                if !created.is_empty() {
+                    log::info!("Pattern refresh created {} subscriptions", created.len());
+                }
+            }
+        });
+    }
+}
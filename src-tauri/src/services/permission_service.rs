@@ -0,0 +1,138 @@
+//! Consent checks for actions that reach outside the app.
+//!
+//! Before a notification may make an HTTP request, run a command or write a
+//! webhook, the action must pass through `PermissionService::request`. Trusted
+//! subscriptions are allowed outright; otherwise a remembered decision is used,
+//! and failing that the user is prompted via the `permission:request` event.
+//! Unanswered prompts are denied.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+use tokio::sync::oneshot;
+
+use crate::config::permissions::PROMPT_TIMEOUT_SECS;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::events;
+use crate::models::{Capability, PermissionRequest};
+
+/// A prompt waiting for the user's answer.
+struct PendingPrompt {
+    subscription_id: String,
+    capability: Capability,
+    responder: oneshot::Sender<bool>,
+}
+
+/// Permission prompts awaiting a response from the frontend.
+#[derive(Default)]
+pub struct PermissionService {
+    pending: Mutex<HashMap<String, PendingPrompt>>,
+}
+
+impl PermissionService {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Checks whether a subscription may use a capability, prompting if needed.
+    ///
+    /// `detail` describes the concrete action (e.g. the URL or command) and is
+    /// shown to the user. Returns `false` on denial, timeout or any error.
+    pub async fn request(
+        handle: &AppHandle,
+        subscription_id: &str,
+        capability: Capability,
+        detail: &str,
+    ) -> bool {
+        let db: tauri::State<Database> = handle.state();
+
+        let subscription = match db.get_subscription_by_id(subscription_id) {
+            Ok(Some(s)) => s,
+            Ok(None) => return false,
+            Err(e) => {
+                log::error!("Failed to load subscription for permission check: {e}");
+                return false;
+            }
+        };
+
+        if subscription.trusted {
+            return true;
+        }
+
+        match db.get_permission(subscription_id, capability) {
+            Ok(Some(allowed)) => return allowed,
+            Ok(None) => {}
+            Err(e) => {
+                log::error!("Failed to load permission: {e}");
+                return false;
+            }
+        }
+
+        let request_id = uuid::Uuid::new_v4().to_string();
+        let (tx, rx) = oneshot::channel();
+
+        let service: tauri::State<Self> = handle.state();
+        service.lock_pending().insert(
+            request_id.clone(),
+            PendingPrompt {
+                subscription_id: subscription_id.to_string(),
+                capability,
+                responder: tx,
+            },
+        );
+
+        let prompt = PermissionRequest {
+            request_id: request_id.clone(),
+            subscription_id: subscription_id.to_string(),
+            topic: subscription.topic,
+            capability,
+            detail: detail.to_string(),
+        };
+        events::emit(handle, &prompt);
+
+        if let Ok(Ok(allowed)) =
+            tokio::time::timeout(Duration::from_secs(PROMPT_TIMEOUT_SECS), rx).await
+        {
+            return allowed;
+        }
+
+        log::info!("Permission prompt {request_id} unanswered, denying");
+        service.lock_pending().remove(&request_id);
+        false
+    }
+
+    /// Answers a pending prompt, optionally remembering the decision.
+    pub fn respond(
+        &self,
+        db: &Database,
+        request_id: &str,
+        allow: bool,
+        remember: bool,
+    ) -> Result<(), AppError> {
+        let prompt = self.lock_pending().remove(request_id).ok_or_else(|| {
+            AppError::NotFound(format!("Permission request {request_id} not found"))
+        })?;
+
+        if remember {
+            db.set_permission(&prompt.subscription_id, prompt.capability, allow)?;
+        }
+
+        // The requester may have timed out in the meantime
+        let _ = prompt.responder.send(allow);
+        Ok(())
+    }
+
+    /// Number of actions waiting for the user's answer.
+    pub fn pending_count(&self) -> usize {
+        self.lock_pending().len()
+    }
+
+    fn lock_pending(&self) -> std::sync::MutexGuard<'_, HashMap<String, PendingPrompt>> {
+        match self.pending.lock() {
+            Ok(pending) => pending,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
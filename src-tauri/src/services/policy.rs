@@ -0,0 +1,29 @@
+//! The policy locking settings in managed deployments.
+//!
+//! The policy comes from the provisioning file and is set once at startup.
+//! It's kept in memory so the resolver and background tasks can check it
+//! without the app state.
+
+use std::sync::{OnceLock, RwLock};
+
+use crate::models::Policy;
+
+static POLICY: OnceLock<RwLock<Policy>> = OnceLock::new();
+
+/// Replaces the policy in effect.
+pub fn set(policy: Policy) {
+    let lock = POLICY.get_or_init(RwLock::default);
+    match lock.write() {
+        Ok(mut current) => *current = policy,
+        Err(poisoned) => *poisoned.into_inner() = policy,
+    }
+}
+
+/// The policy in effect; the default one locks nothing.
+pub fn current() -> Policy {
+    let lock = POLICY.get_or_init(RwLock::default);
+    match lock.read() {
+        Ok(policy) => policy.clone(),
+        Err(poisoned) => poisoned.into_inner().clone(),
+    }
+}
@@ -0,0 +1,89 @@
+//! Applying provisioned servers and subscriptions at startup.
+//!
+//! Provisioning only adds what is missing, so applying it on every start is
+//! idempotent: servers the user already has, including their credentials,
+//! are left as they are, and existing subscriptions aren't duplicated.
+//! The provisioned policy is put in effect before anything else.
+
+use crate::config::provisioning::FILE_VAR;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{Policy, Provisioning};
+use crate::services::policy;
+
+/// Service for provisioning managed deployments.
+pub struct ProvisioningService;
+
+impl ProvisioningService {
+    /// Sets the provisioned policy and adds the servers and subscriptions
+    /// provisioned through the environment. Failures are logged; they never
+    /// keep the app from starting.
+    pub fn apply_from_env(db: &Database) {
+        let provisioning = match Self::from_env() {
+            Ok(Some(provisioning)) => provisioning,
+            Ok(None) => return,
+            Err(e) => {
+                log::warn!("Ignoring provisioning: {e}");
+                return;
+            }
+        };
+
+        if provisioning.policy != Policy::default() {
+            log::info!("Applying provisioned policy");
+        }
+        policy::set(provisioning.policy.clone());
+
+        if let Err(e) = Self::apply(db, &provisioning) {
+            log::warn!("Failed to apply provisioning: {e}");
+        }
+    }
+
+    /// Reads the provisioning file and environment variables, if set.
+    fn from_env() -> Result<Option<Provisioning>, AppError> {
+        let mut provisioning = match std::env::var_os(FILE_VAR) {
+            Some(path) => {
+                let json = std::fs::read_to_string(&path).map_err(|e| {
+                    AppError::NotFound(format!(
+                        "Provisioning file {} unreadable: {e}",
+                        path.to_string_lossy()
+                    ))
+                })?;
+                Some(Provisioning::parse(&json)?)
+            }
+            None => None,
+        };
+
+        if let Some(from_vars) = Provisioning::from_vars(|name| std::env::var(name).ok())? {
+            provisioning
+                .get_or_insert_with(Provisioning::default)
+                .merge(from_vars);
+        }
+        Ok(provisioning)
+    }
+
+    fn apply(db: &Database, provisioning: &Provisioning) -> Result<(), AppError> {
+        let servers = db.get_servers()?;
+        for server in &provisioning.servers {
+            let config = server.config();
+            if servers.iter().any(|s| s.url_matches(&config.url)) {
+                continue;
+            }
+
+            log::info!("Adding provisioned server {}", config.url);
+            db.add_server(config.clone())?;
+            if config.is_default {
+                db.set_default_server(&config.url)?;
+            }
+        }
+
+        let added = db
+            .create_subscriptions(&provisioning.subscriptions)?
+            .iter()
+            .flatten()
+            .count();
+        if added > 0 {
+            log::info!("Added {added} provisioned subscription(s)");
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,107 @@
+//! Publishing messages to a subscription's topic.
+//!
+//! Resolves the subscription's server and credentials, so features that send
+//! messages only need to know which subscription to publish to. The server's
+//! publishing defaults are merged into every message.
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::PublishMessage;
+use crate::services::{log_redaction, NtfyClient, OutgoingAttachment, OutgoingMessage};
+
+/// Service for publishing to subscriptions.
+pub struct PublishService;
+
+impl PublishService {
+    /// Publishes a message, optionally with a file attached, to a subscription's
+    /// topic. Local, detached and trashed subscriptions are rejected.
+    ///
+    /// The server's default tags are added to the message's, and its default
+    /// priority, markdown flag and delay apply where the message sets none.
+    pub async fn publish(
+        db: &Database,
+        subscription_id: &str,
+        message: &OutgoingMessage<'_>,
+        attachment: Option<OutgoingAttachment<'_>>,
+    ) -> Result<(), AppError> {
+        let subscription = db.get_subscription_by_id(subscription_id)?.ok_or_else(|| {
+            AppError::NotFound(format!("Subscription {subscription_id} not found"))
+        })?;
+        if subscription.local || subscription.detached || subscription.deleted_at.is_some() {
+            return Err(AppError::Connection(format!(
+                "Subscription {subscription_id} can't be published to"
+            )));
+        }
+
+        Self::publish_to(
+            db,
+            &subscription.server_url,
+            &subscription.topic,
+            message,
+            attachment,
+        )
+        .await
+    }
+
+    /// Publishes a message composed in the app to a subscription's topic.
+    pub async fn publish_message(
+        db: &Database,
+        subscription_id: &str,
+        message: &PublishMessage,
+    ) -> Result<(), AppError> {
+        let tags: Vec<&str> = message.tags.iter().map(String::as_str).collect();
+        let outgoing = OutgoingMessage {
+            title: &message.title,
+            message: &message.message,
+            tags: &tags,
+            priority: message.priority,
+            markdown: message.markdown.unwrap_or_default(),
+            delay: message.delay.as_deref(),
+        };
+        Self::publish(db, subscription_id, &outgoing, None).await
+    }
+
+    /// Publishes to a topic on a server that needn't be subscribed to, with
+    /// the server's credentials and publishing defaults.
+    pub async fn publish_to(
+        db: &Database,
+        server_url: &str,
+        topic: &str,
+        message: &OutgoingMessage<'_>,
+        attachment: Option<OutgoingAttachment<'_>>,
+    ) -> Result<(), AppError> {
+        let servers = db.get_servers_with_credentials()?;
+        let (username, password) = servers
+            .iter()
+            .find(|s| s.url_matches(server_url))
+            .and_then(|s| s.credentials())
+            .unzip();
+
+        let defaults = db.get_server_publish_defaults(server_url)?;
+        let tags = defaults.merge_tags(message.tags);
+        let message = &OutgoingMessage {
+            tags: &tags,
+            priority: message.priority.or(defaults.priority),
+            markdown: message.markdown || defaults.markdown,
+            delay: message.delay.or(defaults.delay.as_deref()),
+            ..*message
+        };
+
+        let client = NtfyClient::new()?;
+        match attachment {
+            Some(attachment) => {
+                client
+                    .publish_attachment(server_url, topic, message, attachment, username, password)
+                    .await?;
+            }
+            None => {
+                client
+                    .publish(server_url, topic, message, username, password)
+                    .await?;
+            }
+        }
+
+        log::info!("Published message to {}", log_redaction::redact(topic));
+        Ok(())
+    }
+}
@@ -0,0 +1,86 @@
+//! Delivery and read receipts.
+//!
+//! With `receipt_topic` set, a small JSON receipt (message ID, device ID and
+//! name, and state) is published to that topic whenever a message from a
+//! server is shown as a toast or marked as read, so senders can confirm it
+//! reached someone.
+//! Messages on the receipt topic itself never get receipts, so subscribing to
+//! it can't start a loop.
+
+use serde::Serialize;
+use tauri::{AppHandle, Manager};
+
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::Priority;
+use crate::services::{OutgoingMessage, PublishService};
+
+/// What happened to a message.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReceiptState {
+    Displayed,
+    Read,
+}
+
+/// Body of a published receipt.
+#[derive(Debug, Serialize)]
+struct Receipt<'a> {
+    message_id: &'a str,
+    device_id: &'a str,
+    device: &'a str,
+    state: ReceiptState,
+}
+
+/// Service for publishing receipts.
+pub struct ReceiptService;
+
+impl ReceiptService {
+    /// Publishes a receipt for the message `ntfy_id` of a subscription in the
+    /// background, if receipts are enabled.
+    pub fn send(handle: &AppHandle, subscription_id: &str, ntfy_id: &str, state: ReceiptState) {
+        let handle = handle.clone();
+        let subscription_id = subscription_id.to_string();
+        let ntfy_id = ntfy_id.to_string();
+        tauri::async_runtime::spawn(async move {
+            if let Err(e) = Self::publish(&handle, &subscription_id, &ntfy_id, state).await {
+                log::warn!("Failed to publish receipt: {e}");
+            }
+        });
+    }
+
+    async fn publish(
+        handle: &AppHandle,
+        subscription_id: &str,
+        ntfy_id: &str,
+        state: ReceiptState,
+    ) -> Result<(), AppError> {
+        let db: tauri::State<Database> = handle.state();
+        let Some(target) = db.get_receipt_topic()? else {
+            return Ok(());
+        };
+        let Some(subscription) = db.get_subscription_by_id(subscription_id)? else {
+            return Ok(());
+        };
+        if subscription.local
+            || (subscription.server_url_matches(&target.server_url)
+                && subscription.topic == target.topic)
+        {
+            return Ok(());
+        }
+
+        let device = db.get_device_info()?;
+        let body = serde_json::to_string(&Receipt {
+            message_id: ntfy_id,
+            device_id: &device.id,
+            device: &device.name,
+            state,
+        })?;
+        let message = OutgoingMessage {
+            message: &body,
+            priority: Some(Priority::Min),
+            ..Default::default()
+        };
+        PublishService::publish_to(&db, &target.server_url, &target.topic, &message, None).await
+    }
+}
@@ -0,0 +1,82 @@
+//! Local reminders shown on a cron-like schedule.
+//!
+//! Reminders aren't published anywhere: when one is due, it's shown through
+//! the same display pipeline as received messages, so the display method,
+//! sound and lock settings apply. A reminder missed while the app wasn't
+//! running is shown once on the next start, then follows its schedule again.
+
+use std::time::Duration;
+
+use chrono::Local;
+use tauri::{AppHandle, Manager};
+
+use crate::config::reminders::CHECK_INTERVAL_SECS;
+use crate::db::Database;
+use crate::models::{CronSchedule, NtfyMessage, Reminder};
+use crate::services::ConnectionManager;
+
+/// Tags of reminder notifications (shown as an alarm clock by ntfy clients).
+const REMINDER_TAGS: [&str; 1] = ["alarm_clock"];
+
+/// Service for showing due reminders.
+pub struct ReminderService;
+
+impl ReminderService {
+    /// Spawns a background task that shows reminders as they become due.
+    pub fn spawn_scheduler(handle: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(CHECK_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+
+                let now = Local::now();
+                let due = {
+                    let db: tauri::State<Database> = handle.state();
+                    match db.get_due_reminders(now.timestamp_millis()) {
+                        Ok(due) => due,
+                        Err(e) => {
+                            log::warn!("Failed to load due reminders: {e}");
+                            continue;
+                        }
+                    }
+                };
+
+                for reminder in due {
+                    // Reschedule first, so a failing toast doesn't repeat every check
+                    let next_run = CronSchedule::parse(&reminder.schedule)
+                        .ok()
+                        .and_then(|schedule| schedule.next_after(&now))
+                        .map(|next| next.timestamp_millis());
+                    let db: tauri::State<Database> = handle.state();
+                    if let Err(e) = db.set_reminder_next_run(&reminder.id, next_run) {
+                        log::warn!("Failed to reschedule reminder {}: {e}", reminder.id);
+                        continue;
+                    }
+                    Self::show(&handle, &reminder).await;
+                }
+            }
+        });
+    }
+
+    /// Shows a reminder as a notification.
+    async fn show(handle: &AppHandle, reminder: &Reminder) {
+        let ntfy_id = format!("reminder-{}", reminder.id);
+        let notification = NtfyMessage {
+            id: ntfy_id.clone(),
+            time: chrono::Utc::now().timestamp(),
+            event: "message".to_string(),
+            topic: String::new(),
+            message: Some(reminder.message.clone()),
+            title: reminder.title.clone(),
+            priority: None,
+            tags: Some(REMINDER_TAGS.iter().map(ToString::to_string).collect()),
+            click: None,
+            actions: None,
+            attachment: None,
+            content_type: None,
+        }
+        .into_notification(reminder.id.clone());
+
+        ConnectionManager::show_notification(handle, &notification, &ntfy_id).await;
+    }
+}
@@ -0,0 +1,249 @@
+//! Host name resolution honoring per-server overrides.
+//!
+//! Servers can prefer IPv4 or IPv6, be pinned to static addresses, or be
+//! reachable only through a SOCKS5 proxy. The overrides are kept in memory by
+//! host name and applied both to HTTP requests, through the DNS resolver of
+//! the app's HTTP clients, and to WebSocket connections.
+//!
+//! HTTP requests and WebSocket connections to a server with a SOCKS proxy go
+//! through it, with the host name resolved by the proxy. As a backstop, the
+//! resolver refuses to look such a host up, so if a request ever misses the
+//! proxy it fails rather than reaching the server directly.
+//! A proxy forced by the policy applies to every configured server. Other
+//! WebSocket connections go through the app's HTTP proxy, if there is one,
+//! and present their server's client certificate, if it has one.
+
+use std::collections::HashMap;
+use std::io;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{OnceLock, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use reqwest::dns::{Addrs, Name, Resolve, Resolving};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_socks::tcp::Socks5Stream;
+use tokio_tungstenite::tungstenite::client::IntoClientRequest;
+use tokio_tungstenite::tungstenite::error::UrlError;
+use tokio_tungstenite::tungstenite::handshake::client::Response;
+use tokio_tungstenite::{tungstenite, Connector, MaybeTlsStream, WebSocketStream};
+
+use crate::db::Database;
+use crate::models::ServerResolution;
+use crate::services::proxy::{self, HttpProxy};
+use crate::services::{client_certificate, policy};
+
+type Overrides = HashMap<String, ServerResolution>;
+
+/// Longest response head accepted from an HTTP proxy.
+const MAX_PROXY_RESPONSE_BYTES: usize = 8 * 1024;
+
+static OVERRIDES: OnceLock<RwLock<Overrides>> = OnceLock::new();
+
+static GENERATION: AtomicU64 = AtomicU64::new(0);
+
+fn read_overrides() -> RwLockReadGuard<'static, Overrides> {
+    match OVERRIDES.get_or_init(RwLock::default).read() {
+        Ok(overrides) => overrides,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+fn write_overrides() -> RwLockWriteGuard<'static, Overrides> {
+    match OVERRIDES.get_or_init(RwLock::default).write() {
+        Ok(overrides) => overrides,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// Loads the overrides of all configured servers, replacing those loaded before.
+pub fn reload(db: &Database) {
+    let policy = policy::current();
+    let servers = db.get_server_resolutions().and_then(|mut servers| {
+        if policy.socks_proxy.is_some() {
+            // Servers without overrides aren't listed, but get the proxy too
+            for server in db.get_servers()? {
+                if !servers.iter().any(|(url, _)| *url == server.url) {
+                    servers.push((server.url, ServerResolution::default()));
+                }
+            }
+        }
+        Ok(servers)
+    });
+    let servers = match servers {
+        Ok(servers) => servers,
+        Err(e) => {
+            log::warn!("Failed to load server resolution overrides: {e}");
+            return;
+        }
+    };
+
+    let overrides = servers
+        .into_iter()
+        .filter_map(|(url, resolution)| {
+            let url = url::Url::parse(&url).ok()?;
+            Some((
+                url.host_str()?.to_ascii_lowercase(),
+                policy.resolution(resolution),
+            ))
+        })
+        .collect();
+    *write_overrides() = overrides;
+    GENERATION.fetch_add(1, Ordering::Relaxed);
+}
+
+fn override_for(host: &str) -> Option<ServerResolution> {
+    read_overrides().get(&host.to_ascii_lowercase()).cloned()
+}
+
+/// The SOCKS proxy `host` is only reached through, if its server has one.
+pub fn socks_proxy_for(host: &str) -> Option<String> {
+    override_for(host).and_then(|resolution| resolution.socks_proxy)
+}
+
+/// Changes whenever the overrides are reloaded, so HTTP clients can tell
+/// when their pooled connections may no longer match them.
+pub fn generation() -> u64 {
+    GENERATION.load(Ordering::Relaxed)
+}
+
+/// Resolves `host` to the addresses to connect to, in order, applying the
+/// overrides of its server.
+pub async fn lookup(host: &str, port: u16) -> io::Result<Vec<SocketAddr>> {
+    let resolution = override_for(host);
+    if resolution.as_ref().is_some_and(|r| r.socks_proxy.is_some()) {
+        return Err(io::Error::new(
+            io::ErrorKind::PermissionDenied,
+            format!("{host} is only reachable through its SOCKS proxy"),
+        ));
+    }
+
+    let addresses: Vec<IpAddr> = match resolution.as_ref().map(ServerResolution::static_ips) {
+        Some(ips) if !ips.is_empty() => ips,
+        _ => tokio::net::lookup_host((host, port))
+            .await?
+            .map(|address| address.ip())
+            .collect(),
+    };
+    let addresses = match resolution {
+        Some(resolution) => resolution.order(addresses),
+        None => addresses,
+    };
+
+    Ok(addresses
+        .into_iter()
+        .map(|ip| SocketAddr::new(ip, port))
+        .collect())
+}
+
+/// DNS resolver of the app's HTTP clients.
+pub struct OverrideResolver;
+
+impl Resolve for OverrideResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            // The connector fills in the port
+            let addresses = lookup(name.as_str(), 0).await?;
+            Ok(Box::new(addresses.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// Opens a WebSocket connection like `tokio_tungstenite::connect_async`,
+/// resolving the host with its server's overrides and presenting its client
+/// certificate.
+pub async fn connect_websocket<R>(
+    request: R,
+) -> Result<(WebSocketStream<MaybeTlsStream<TcpStream>>, Response), tungstenite::Error>
+where
+    R: IntoClientRequest + Unpin,
+{
+    let request = request.into_client_request()?;
+    let uri = request.uri();
+    let host = uri
+        .host()
+        .ok_or(tungstenite::Error::Url(UrlError::NoHostName))?
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_string();
+    let port = uri
+        .port_u16()
+        .or_else(|| match uri.scheme_str() {
+            Some("wss") => Some(443),
+            Some("ws") => Some(80),
+            _ => None,
+        })
+        .ok_or(tungstenite::Error::Url(UrlError::UnsupportedUrlScheme))?;
+    let secure = uri.scheme_str() == Some("wss");
+
+    let socket = if let Some(proxy) = socks_proxy_for(&host) {
+        socks5_connect(&proxy, &host, port).await?
+    } else if let Some(proxy) = proxy::for_websocket(&host, secure) {
+        http_connect(&proxy, &host, port).await?
+    } else {
+        let addresses = lookup(&host, port).await?;
+        TcpStream::connect(&addresses[..]).await?
+    };
+    let connector = client_certificate::for_host(&host)
+        .filter(|_| secure)
+        .map(|certificate| Connector::Rustls(certificate.tls_config()));
+    tokio_tungstenite::client_async_tls_with_config(request, socket, None, connector).await
+}
+
+/// Opens a tunnel to `host` through an HTTP proxy with `CONNECT`.
+async fn http_connect(proxy: &HttpProxy, host: &str, port: u16) -> io::Result<TcpStream> {
+    let address = &proxy.address;
+    let failed = |reason: String| io::Error::new(io::ErrorKind::ConnectionRefused, reason);
+
+    let mut stream = TcpStream::connect(address)
+        .await
+        .map_err(|e| failed(format!("HTTP proxy {address} unavailable: {e}")))?;
+
+    let target = if host.contains(':') {
+        format!("[{host}]:{port}")
+    } else {
+        format!("{host}:{port}")
+    };
+    let authorization = proxy
+        .authorization
+        .as_ref()
+        .map(|authorization| format!("Proxy-Authorization: {authorization}\r\n"))
+        .unwrap_or_default();
+    let request = format!("CONNECT {target} HTTP/1.1\r\nHost: {target}\r\n{authorization}\r\n");
+    stream.write_all(request.as_bytes()).await?;
+
+    // Read the response head a byte at a time, so none of the tunneled
+    // stream is consumed
+    let mut head = Vec::new();
+    while !head.ends_with(b"\r\n\r\n") {
+        if head.len() >= MAX_PROXY_RESPONSE_BYTES {
+            return Err(failed(format!(
+                "HTTP proxy {address} sent an invalid response"
+            )));
+        }
+        head.push(stream.read_u8().await?);
+    }
+    let head = String::from_utf8_lossy(&head);
+    let status_line = head.lines().next().unwrap_or_default();
+    if status_line.split_whitespace().nth(1) != Some("200") {
+        return Err(failed(format!(
+            "HTTP proxy {address} refused connection to {host}: {status_line}"
+        )));
+    }
+
+    Ok(stream)
+}
+
+/// Connects to `host` through a SOCKS5 proxy without authentication. The
+/// host name is resolved by the proxy, as onion services require.
+async fn socks5_connect(proxy: &str, host: &str, port: u16) -> io::Result<TcpStream> {
+    let stream = Socks5Stream::connect(proxy, (host, port))
+        .await
+        .map_err(|e| {
+            io::Error::new(
+                io::ErrorKind::ConnectionRefused,
+                format!("SOCKS proxy {proxy} refused connection to {host}: {e}"),
+            )
+        })?;
+    Ok(stream.into_inner())
+}
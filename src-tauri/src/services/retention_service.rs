@@ -0,0 +1,54 @@
+//! Per-subscription retention of notifications.
+//!
+//! Subscriptions with `auto_delete_after_hours` set have their notifications
+//! permanently deleted once they are older than that window, and those with
+//! `keep_messages` set keep only that many of the newest. This is separate
+//! from the trash: deleted notifications are removed locally only, and
+//! favorites are never deleted.
+
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::config::retention::CHECK_INTERVAL_SECS;
+use crate::db::Database;
+use crate::events::{self, SubscriptionsSynced};
+use crate::services::TrayManager;
+
+/// Service for deleting notifications past their subscription's retention.
+pub struct RetentionService;
+
+impl RetentionService {
+    /// Deletes notifications past their subscription's retention and
+    /// refreshes the UI if any were removed.
+    pub fn run(handle: &AppHandle) {
+        let db: tauri::State<Database> = handle.state();
+
+        let expired = db.delete_expired_notifications().unwrap_or_else(|e| {
+            log::warn!("Failed to auto-delete notifications: {e}");
+            0
+        });
+        let excess = db.delete_excess_notifications().unwrap_or_else(|e| {
+            log::warn!("Failed to delete notifications over the kept count: {e}");
+            0
+        });
+
+        if expired + excess > 0 {
+            log::info!("Auto-deleted {expired} expired and {excess} excess notifications");
+            let tray_manager: tauri::State<TrayManager> = handle.state();
+            tray_manager.refresh_from_db(handle);
+            events::emit(handle, &SubscriptionsSynced);
+        }
+    }
+
+    /// Spawns a background task that periodically deletes notifications past
+    /// their retention.
+    pub fn spawn_cleaner(handle: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(CHECK_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                Self::run(&handle);
+            }
+        });
+    }
+}
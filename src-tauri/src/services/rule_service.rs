@@ -0,0 +1,131 @@
+//! Applying notification rules to incoming notifications.
+//!
+//! Commands configured in rules are run through the system shell. The
+//! notification is passed in environment variables, never substituted into
+//! the command line, so message content can't inject shell syntax.
+
+use std::sync::{Arc, RwLock};
+use std::time::Duration;
+
+use tauri::AppHandle;
+
+use crate::config::rules::COMMAND_TIMEOUT_SECS;
+use crate::db::Database;
+use crate::models::{Capability, CompiledRule, Notification, RuleOutcome};
+use crate::services::PermissionService;
+
+/// Enabled rules with their regular expressions compiled. Loaded on first
+/// use and dropped whenever a rule changes.
+static RULES: RwLock<Option<Arc<Vec<CompiledRule>>>> = RwLock::new(None);
+
+/// Service evaluating notification rules.
+pub struct RuleService;
+
+impl RuleService {
+    /// Combines the actions of the rules matching a notification received on
+    /// `topic`. If the rules can't be loaded, none match.
+    pub fn evaluate(db: &Database, topic: &str, notification: &Notification) -> RuleOutcome {
+        RuleOutcome::evaluate(&Self::rules(db), topic, notification)
+    }
+
+    /// Drops the cached rules, so the next evaluation loads them again.
+    /// Called whenever rules are created, changed or deleted.
+    pub fn invalidate() {
+        match RULES.write() {
+            Ok(mut rules) => *rules = None,
+            Err(poisoned) => *poisoned.into_inner() = None,
+        }
+    }
+
+    fn rules(db: &Database) -> Arc<Vec<CompiledRule>> {
+        let cached = match RULES.read() {
+            Ok(rules) => rules.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        };
+        if let Some(rules) = cached {
+            return rules;
+        }
+
+        // Loaded under the write lock, so an invalidation can't be missed
+        let mut cache = match RULES.write() {
+            Ok(cache) => cache,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        if let Some(rules) = cache.as_ref() {
+            return Arc::clone(rules);
+        }
+        let rules = match db.get_rules() {
+            Ok(rules) => rules,
+            Err(e) => {
+                log::warn!("Failed to load rules: {e}");
+                return Arc::default();
+            }
+        };
+        let rules: Arc<Vec<CompiledRule>> = Arc::new(
+            rules
+                .into_iter()
+                .filter(|rule| rule.enabled)
+                .map(CompiledRule::new)
+                .collect(),
+        );
+        *cache = Some(Arc::clone(&rules));
+        rules
+    }
+
+    /// Runs the commands of matching rules in the background, once the
+    /// subscription was given the `run_command` permission.
+    pub fn spawn_commands(handle: &AppHandle, commands: Vec<String>, notification: &Notification) {
+        if commands.is_empty() {
+            return;
+        }
+        let handle = handle.clone();
+        let notification = notification.clone();
+        tauri::async_runtime::spawn(async move {
+            for command in commands {
+                if !PermissionService::request(
+                    &handle,
+                    &notification.topic_id,
+                    Capability::RunCommand,
+                    &command,
+                )
+                .await
+                {
+                    continue;
+                }
+                Self::run(&command, &notification).await;
+            }
+        });
+    }
+
+    async fn run(command: &str, notification: &Notification) {
+        #[cfg(windows)]
+        let mut process = {
+            let mut process = tokio::process::Command::new("cmd");
+            process.arg("/C").arg(command);
+            process
+        };
+        #[cfg(not(windows))]
+        let mut process = {
+            let mut process = tokio::process::Command::new("sh");
+            process.arg("-c").arg(command);
+            process
+        };
+        process
+            .env("NTFIER_ID", &notification.id)
+            .env("NTFIER_SUBSCRIPTION_ID", &notification.topic_id)
+            .env("NTFIER_TITLE", &notification.title)
+            .env("NTFIER_MESSAGE", &notification.message)
+            .env("NTFIER_PRIORITY", (notification.priority as u8).to_string())
+            .env("NTFIER_TAGS", notification.tags.join(","))
+            .kill_on_drop(true);
+
+        let status =
+            tokio::time::timeout(Duration::from_secs(COMMAND_TIMEOUT_SECS), process.status()).await;
+        match status {
+            Ok(Ok(status)) if status.success() => log::debug!("Rule command succeeded"),
+            Ok(Ok(status)) => log::warn!("Rule command exited with {status}"),
+            Ok(Err(e)) => log::warn!("Failed to run rule command: {e}"),
+            Err(_) => log::warn!("Rule command timed out after {COMMAND_TIMEOUT_SECS}s"),
+        }
+    }
+}
@@ -0,0 +1,168 @@
+//! Screenshots for capture-and-publish.
+//!
+//! Screenshots are taken with the platform's own tools and written to a
+//! temporary PNG file: `screencapture` on macOS, `gnome-screenshot` or
+//! `spectacle` on Linux, and a PowerShell script on Windows. Region and window
+//! captures are interactive where the tool supports it. Windows has no
+//! scriptable region picker, so it captures the foreground window or the
+//! whole screen only.
+
+use std::path::Path;
+
+use crate::error::AppError;
+use crate::models::CaptureMode;
+
+/// Service for taking screenshots.
+pub struct ScreenCapture;
+
+impl ScreenCapture {
+    /// Takes a screenshot and returns it as PNG data.
+    ///
+    /// Returns `AppError::Cancelled` if the user dismissed an interactive capture.
+    pub async fn capture(mode: CaptureMode) -> Result<Vec<u8>, AppError> {
+        let path =
+            std::env::temp_dir().join(format!("ntfier-capture-{}.png", uuid::Uuid::new_v4()));
+        let result = Self::capture_to(mode, &path).await;
+        let data = tokio::fs::read(&path).await;
+        let _ = tokio::fs::remove_file(&path).await;
+        result?;
+
+        match data {
+            Ok(data) if !data.is_empty() => Ok(data),
+            _ => Err(AppError::Cancelled("Screenshot cancelled".to_string())),
+        }
+    }
+
+    #[cfg(target_os = "macos")]
+    async fn capture_to(mode: CaptureMode, path: &Path) -> Result<(), AppError> {
+        let mut args = vec!["-x"];
+        match mode {
+            CaptureMode::Region => args.push("-i"),
+            CaptureMode::Window => args.push("-iW"),
+            CaptureMode::Screen => {}
+        }
+        run("screencapture", &args, path).await
+    }
+
+    #[cfg(target_os = "linux")]
+    async fn capture_to(mode: CaptureMode, path: &Path) -> Result<(), AppError> {
+        let gnome_arg = match mode {
+            CaptureMode::Region => Some("-a"),
+            CaptureMode::Window => Some("-w"),
+            CaptureMode::Screen => None,
+        };
+        match run(
+            "gnome-screenshot",
+            &gnome_arg.into_iter().collect::<Vec<_>>(),
+            path,
+        )
+        .await
+        {
+            Err(AppError::NotFound(_)) => {}
+            result => return result,
+        }
+
+        let spectacle_arg = match mode {
+            CaptureMode::Region => "-r",
+            CaptureMode::Window => "-a",
+            CaptureMode::Screen => "-f",
+        };
+        run("spectacle", &["-b", "-n", spectacle_arg], path).await
+    }
+
+    #[cfg(windows)]
+    async fn capture_to(mode: CaptureMode, path: &Path) -> Result<(), AppError> {
+        let bounds = match mode {
+            CaptureMode::Region => {
+                return Err(AppError::Validation(
+                    "Region capture is not supported on Windows".to_string(),
+                ))
+            }
+            CaptureMode::Window => WINDOWS_FOREGROUND_BOUNDS,
+            CaptureMode::Screen => WINDOWS_SCREEN_BOUNDS,
+        };
+        let script = format!(
+            "Add-Type -AssemblyName System.Drawing\n{bounds}\n{WINDOWS_CAPTURE}'{}', [System.Drawing.Imaging.ImageFormat]::Png)",
+            path.display().to_string().replace('\'', "''")
+        );
+        let output = tokio::process::Command::new("powershell")
+            .args(["-NoProfile", "-NonInteractive", "-Command", &script])
+            // CREATE_NO_WINDOW, so no console flashes up in the screenshot
+            .creation_flags(0x0800_0000)
+            .output()
+            .await
+            .map_err(|e| AppError::NotFound(format!("Failed to run PowerShell: {e}")))?;
+        if !output.status.success() {
+            return Err(AppError::Connection(format!(
+                "Screenshot failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(())
+    }
+
+    #[cfg(not(any(windows, target_os = "linux", target_os = "macos")))]
+    #[allow(clippy::unused_async)]
+    async fn capture_to(_mode: CaptureMode, _path: &Path) -> Result<(), AppError> {
+        Err(AppError::NotFound(
+            "Screenshots are not supported on this platform".to_string(),
+        ))
+    }
+}
+
+/// PowerShell setting `$b` to the bounds of the whole virtual screen.
+#[cfg(windows)]
+const WINDOWS_SCREEN_BOUNDS: &str = "Add-Type -AssemblyName System.Windows.Forms\n\
+    $b = [System.Windows.Forms.SystemInformation]::VirtualScreen";
+
+/// PowerShell setting `$b` to the bounds of the foreground window.
+#[cfg(windows)]
+const WINDOWS_FOREGROUND_BOUNDS: &str = "Add-Type @'\n\
+    using System;\n\
+    using System.Runtime.InteropServices;\n\
+    public struct Rect { public int Left, Top, Right, Bottom; }\n\
+    public static class Fg {\n\
+      [DllImport(\"user32.dll\")] public static extern IntPtr GetForegroundWindow();\n\
+      [DllImport(\"user32.dll\")] public static extern bool GetWindowRect(IntPtr h, out Rect r);\n\
+    }\n\
+    '@\n\
+    $r = New-Object Rect\n\
+    [void][Fg]::GetWindowRect([Fg]::GetForegroundWindow(), [ref]$r)\n\
+    $b = New-Object System.Drawing.Rectangle($r.Left, $r.Top, ($r.Right - $r.Left), ($r.Bottom - $r.Top))";
+
+/// PowerShell copying the screen within `$b` to a PNG; the arguments of
+/// `Save` are appended.
+#[cfg(windows)]
+const WINDOWS_CAPTURE: &str = "$bmp = New-Object System.Drawing.Bitmap($b.Width, $b.Height)\n\
+    $g = [System.Drawing.Graphics]::FromImage($bmp)\n\
+    $g.CopyFromScreen($b.Left, $b.Top, 0, 0, $bmp.Size)\n\
+    $bmp.Save(";
+
+/// Runs a screenshot tool writing to `path`, appended as the last argument.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+async fn run(program: &str, args: &[&str], path: &Path) -> Result<(), AppError> {
+    let status = tokio::process::Command::new(program)
+        .args(args)
+        .arg(screenshot_arg(program, path))
+        .status()
+        .await
+        .map_err(|e| AppError::NotFound(format!("Failed to run {program}: {e}")))?;
+
+    // Interactive tools exit with an error when the capture is dismissed
+    if !status.success() {
+        return Err(AppError::Cancelled(format!(
+            "{program} exited with {status}"
+        )));
+    }
+    Ok(())
+}
+
+/// Returns the output argument for a screenshot tool.
+#[cfg(any(target_os = "linux", target_os = "macos"))]
+fn screenshot_arg(program: &str, path: &Path) -> std::ffi::OsString {
+    match program {
+        "gnome-screenshot" => format!("--file={}", path.display()).into(),
+        "spectacle" => format!("--output={}", path.display()).into(),
+        _ => path.as_os_str().to_owned(),
+    }
+}
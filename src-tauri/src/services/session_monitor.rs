@@ -0,0 +1,141 @@
+//! Workstation lock detection.
+//!
+//! With `hide_content_when_locked` set, toasts shown while the session is
+//! locked only name the topic, so message content doesn't end up on the lock
+//! screen. They are remembered and shown again in full once the session is
+//! unlocked.
+//!
+//! The lock state is polled: on Windows by checking whether the lock screen
+//! process is running, on Linux through logind's `LockedHint`. On other
+//! platforms the session is always treated as unlocked.
+
+use std::collections::VecDeque;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Mutex;
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::config::session::{LOCK_CHECK_INTERVAL_SECS, MAX_CATCH_UP_TOASTS};
+use crate::models::Notification;
+use crate::services::ConnectionManager;
+
+/// Tracks the session lock state and toasts redacted while locked.
+#[derive(Default)]
+pub struct SessionMonitor {
+    locked: AtomicBool,
+    /// Redacted toasts as (notification, ntfy ID), oldest first.
+    deferred: Mutex<VecDeque<(Notification, String)>>,
+}
+
+impl SessionMonitor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Whether the session was locked at the last check.
+    pub fn is_locked(&self) -> bool {
+        self.locked.load(Ordering::Relaxed)
+    }
+
+    /// Remembers a toast shown without its content, to show it in full on
+    /// unlock. Only the last `MAX_CATCH_UP_TOASTS` are kept.
+    pub fn defer(&self, notification: &Notification, ntfy_id: &str) {
+        let mut deferred = self.lock_deferred();
+        if deferred.len() >= MAX_CATCH_UP_TOASTS {
+            deferred.pop_front();
+        }
+        deferred.push_back((notification.clone(), ntfy_id.to_string()));
+    }
+
+    /// Spawns a background task that polls the lock state and catches up on
+    /// redacted toasts when the session is unlocked.
+    pub fn spawn_watcher(handle: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(LOCK_CHECK_INTERVAL_SECS));
+            let mut available = false;
+            loop {
+                interval.tick().await;
+
+                // An unknown state keeps the last one; if it was never known,
+                // detection isn't available at all
+                let Some(locked) = Self::check_locked().await else {
+                    if available {
+                        continue;
+                    }
+                    log::info!("Session lock detection is not available, stopping watcher");
+                    return;
+                };
+                available = true;
+
+                let monitor: tauri::State<Self> = handle.state();
+                let was_locked = monitor.locked.swap(locked, Ordering::Relaxed);
+                if was_locked && !locked {
+                    log::info!("Session unlocked");
+                    Self::catch_up(&handle).await;
+                } else if !was_locked && locked {
+                    log::info!("Session locked");
+                }
+            }
+        });
+    }
+
+    /// Shows the most recent redacted toasts again, in full and silently.
+    async fn catch_up(handle: &AppHandle) {
+        let deferred = std::mem::take(&mut *handle.state::<Self>().lock_deferred());
+        for (notification, ntfy_id) in deferred {
+            ConnectionManager::show_notification_silently(handle, &notification, &ntfy_id).await;
+        }
+    }
+
+    /// Checks whether the lock screen, `LogonUI.exe`, is running. `None` if
+    /// the process list can't be read.
+    #[cfg(windows)]
+    async fn check_locked() -> Option<bool> {
+        let output = tokio::process::Command::new("tasklist")
+            .args(["/FI", "IMAGENAME eq LogonUI.exe", "/FO", "CSV", "/NH"])
+            // CREATE_NO_WINDOW, so no console flashes up every check
+            .creation_flags(0x0800_0000)
+            .output()
+            .await
+            .ok()
+            .filter(|output| output.status.success())?;
+
+        Some(
+            String::from_utf8_lossy(&output.stdout)
+                .to_ascii_lowercase()
+                .contains("logonui.exe"),
+        )
+    }
+
+    /// Asks logind whether the current session is locked. `None` if logind
+    /// isn't available.
+    #[cfg(target_os = "linux")]
+    async fn check_locked() -> Option<bool> {
+        let session = std::env::var("XDG_SESSION_ID").unwrap_or_else(|_| "self".to_string());
+        let output = tokio::process::Command::new("loginctl")
+            .args(["show-session", &session, "--property=LockedHint", "--value"])
+            .output()
+            .await
+            .ok()
+            .filter(|output| output.status.success())?;
+
+        match String::from_utf8_lossy(&output.stdout).trim() {
+            "yes" => Some(true),
+            "no" => Some(false),
+            _ => None,
+        }
+    }
+
+    #[cfg(not(any(windows, target_os = "linux")))]
+    #[allow(clippy::unused_async)]
+    async fn check_locked() -> Option<bool> {
+        None
+    }
+
+    fn lock_deferred(&self) -> std::sync::MutexGuard<'_, VecDeque<(Notification, String)>> {
+        match self.deferred.lock() {
+            Ok(deferred) => deferred,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
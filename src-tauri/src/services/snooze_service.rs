@@ -0,0 +1,69 @@
+//! Snoozed notifications shown again later.
+//!
+//! Snoozing a notification removes its toast; once the snooze runs out the
+//! notification is marked unread and shown again through the usual display
+//! pipeline, so the display method, sound and do-not-disturb settings apply.
+//! A snooze that ran out while the app wasn't running fires on the next start.
+
+use std::time::Duration;
+
+use tauri::{AppHandle, Manager};
+
+use crate::config::snooze::CHECK_INTERVAL_SECS;
+use crate::db::Database;
+use crate::events::{self, NotificationUnsnoozed};
+use crate::services::{ConnectionManager, TrayManager};
+
+/// Service for showing snoozed notifications again.
+pub struct SnoozeService;
+
+impl SnoozeService {
+    /// Spawns a background task that shows snoozed notifications again as
+    /// their snooze runs out.
+    pub fn spawn_scheduler(handle: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(CHECK_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+
+                let now = chrono::Utc::now().timestamp_millis();
+                let due = {
+                    let db: tauri::State<Database> = handle.state();
+                    match db.get_due_snoozed_notifications(now) {
+                        Ok(due) => due,
+                        Err(e) => {
+                            log::warn!("Failed to load snoozed notifications: {e}");
+                            continue;
+                        }
+                    }
+                };
+                if due.is_empty() {
+                    continue;
+                }
+
+                for mut notification in due {
+                    // End the snooze first, so a failing toast doesn't repeat every check
+                    let ntfy_id = {
+                        let db: tauri::State<Database> = handle.state();
+                        if let Err(e) = db.end_notification_snooze(&notification.id) {
+                            log::warn!("Failed to end snooze of {}: {e}", notification.id);
+                            continue;
+                        }
+                        db.get_notification_origin(&notification.id)
+                            .ok()
+                            .flatten()
+                            .map_or_else(|| notification.id.clone(), |(_, ntfy_id)| ntfy_id)
+                    };
+                    notification.read = false;
+                    notification.snoozed_until = None;
+
+                    events::emit(&handle, &NotificationUnsnoozed(notification.clone()));
+                    ConnectionManager::show_notification(&handle, &notification, &ntfy_id).await;
+                }
+
+                let tray_manager: tauri::State<TrayManager> = handle.state();
+                tray_manager.refresh_from_db(&handle);
+            }
+        });
+    }
+}
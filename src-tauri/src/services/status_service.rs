@@ -0,0 +1,73 @@
+//! Runtime status aggregation for the frontend status bar.
+//!
+//! Combines connection states from the `ConnectionManager` with sync
+//! timestamps, unread counts and queue sizes into a single snapshot.
+
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::config::status::REPORT_INTERVAL_SECS;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::events;
+use crate::models::{ConnectionState, RuntimeStatus, SubscriptionSyncStatus};
+use crate::services::{ConnectionManager, PermissionService, WriteRetryQueue};
+
+/// Service for collecting and broadcasting runtime status.
+pub struct StatusService;
+
+impl StatusService {
+    /// Collects a snapshot of connection states, last sync times, unread
+    /// counts and queue sizes.
+    pub async fn collect(handle: &AppHandle) -> Result<RuntimeStatus, AppError> {
+        let conn_manager: tauri::State<ConnectionManager> = handle.state();
+        let connections = conn_manager.connection_statuses().await;
+
+        let pending_retries = connections
+            .iter()
+            .filter(|c| c.state == ConnectionState::Reconnecting)
+            .count() as u32;
+
+        let db: tauri::State<Database> = handle.state();
+        let last_syncs = db
+            .get_last_sync_times()?
+            .into_iter()
+            .map(|(subscription_id, last_sync)| SubscriptionSyncStatus {
+                subscription_id,
+                last_sync,
+            })
+            .collect();
+        let total_unread = db.get_total_unread_count()?;
+        let outbox_size = u32::try_from(db.get_outbox_count()?).unwrap_or(u32::MAX);
+
+        let retry_queue: tauri::State<WriteRetryQueue> = handle.state();
+        let pending_writes = u32::try_from(retry_queue.len()).unwrap_or(u32::MAX);
+        let permissions: tauri::State<PermissionService> = handle.state();
+        let pending_actions = u32::try_from(permissions.pending_count()).unwrap_or(u32::MAX);
+
+        Ok(RuntimeStatus {
+            connections,
+            last_syncs,
+            pending_retries,
+            pending_writes,
+            outbox_size,
+            pending_actions,
+            total_unread,
+            timestamp: chrono::Utc::now().timestamp_millis(),
+        })
+    }
+
+    /// Spawns a background task emitting `runtime:status` at a fixed interval.
+    pub fn spawn_reporter(handle: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_secs(REPORT_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+                match Self::collect(&handle).await {
+                    Ok(status) => events::emit(&handle, &status),
+                    Err(e) => log::warn!("Failed to collect runtime status: {e}"),
+                }
+            }
+        });
+    }
+}
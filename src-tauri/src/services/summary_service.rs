@@ -0,0 +1,110 @@
+//! Daily digest of received notifications.
+//!
+//! When `daily_summary_enabled` is set, a local notification summarizing the
+//! last day's unread counts and busiest topics is shown once a day at
+//! `daily_summary_time`, and the same summary is emitted to the frontend.
+
+use chrono::{Local, NaiveDate, NaiveTime};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::config::summary::{CHECK_INTERVAL_SECS, TOP_TOPICS, WINDOW_SECS};
+use crate::db::Database;
+use crate::error::AppError;
+use crate::events;
+use crate::models::DailySummary;
+
+/// Service for building and showing the daily digest.
+pub struct SummaryService;
+
+impl SummaryService {
+    /// Builds a summary of notifications received in the last day.
+    pub fn build(db: &Database) -> Result<DailySummary, AppError> {
+        let since = chrono::Utc::now().timestamp_millis() - WINDOW_SECS * 1000;
+        Ok(DailySummary::new(since, db.get_topic_activity(since)?))
+    }
+
+    /// Shows the digest as a notification and emits it to the frontend.
+    ///
+    /// Nothing is shown if no notifications were received.
+    pub fn send(handle: &AppHandle) {
+        use tauri_plugin_notification::NotificationExt;
+
+        let db: tauri::State<Database> = handle.state();
+        let summary = match Self::build(&db) {
+            Ok(summary) => summary,
+            Err(e) => {
+                log::warn!("Failed to build daily summary: {e}");
+                return;
+            }
+        };
+
+        if summary.total_received == 0 {
+            return;
+        }
+
+        let title = format!(
+            "{} notifications in the last day, {} unread",
+            summary.total_received, summary.total_unread
+        );
+        let body = summary
+            .topics
+            .iter()
+            .take(TOP_TOPICS)
+            .map(|t| format!("{}: {}", t.name, t.received))
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        if let Err(e) = handle
+            .notification()
+            .builder()
+            .title(&title)
+            .body(&body)
+            .show()
+        {
+            log::warn!("Failed to show daily summary: {e}");
+        }
+
+        events::emit(handle, &summary);
+    }
+
+    /// Spawns a background task that shows the digest once a day at the configured time.
+    ///
+    /// If the app starts after today's time has passed, the first digest is shown tomorrow.
+    pub fn spawn_scheduler(handle: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            let mut last_sent: Option<NaiveDate> = None;
+            if let Some(at) = Self::scheduled_time(&handle) {
+                let now = Local::now();
+                if now.time() >= at {
+                    last_sent = Some(now.date_naive());
+                }
+            }
+
+            let mut interval = tokio::time::interval(Duration::from_secs(CHECK_INTERVAL_SECS));
+            loop {
+                interval.tick().await;
+
+                let Some(at) = Self::scheduled_time(&handle) else {
+                    continue;
+                };
+                let now = Local::now();
+                let today = now.date_naive();
+                if now.time() >= at && last_sent != Some(today) {
+                    last_sent = Some(today);
+                    Self::send(&handle);
+                }
+            }
+        });
+    }
+
+    /// Returns the configured digest time, or `None` if the digest is disabled.
+    fn scheduled_time(handle: &AppHandle) -> Option<NaiveTime> {
+        let db: tauri::State<Database> = handle.state();
+        if !db.get_daily_summary_enabled().unwrap_or(false) {
+            return None;
+        }
+        let time = db.get_daily_summary_time().ok()?;
+        NaiveTime::parse_from_str(&time, "%H:%M").ok()
+    }
+}
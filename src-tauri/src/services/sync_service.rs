@@ -3,11 +3,141 @@
 //! Handles syncing subscriptions from ntfy servers and fetching
 //! historical notifications for each subscription.
 
-use tauri::{AppHandle, Emitter, Manager};
-
+use serde::Serialize;
+use specta::Type;
+use std::sync::Mutex;
+use std::time::Instant;
+use tauri::{AppHandle, Manager};
+use tokio_util::sync::CancellationToken;
+
+use crate::config::sync::CLOCK_SKEW_WARN_SECS;
 use crate::db::Database;
-use crate::models::{normalize_url, CreateSubscription};
-use crate::services::{ConnectionManager, NtfyClient, TrayManager};
+use crate::error::AppError;
+use crate::events::{self, NotificationNew};
+use crate::models::{
+    normalize_url, CreateSubscription, Notification, NtfyMessage, RuleOutcome, Subscription,
+};
+use crate::services::{
+    log_redaction, ConnectionManager, FileMirrorService, LinkPreviewService, MetricsService,
+    NtfyClient, RuleService, TrayManager,
+};
+
+/// Cancellation handle shared by all in-flight sync operations.
+///
+/// Each sync grabs the current token; `cancel()` fires it and installs a fresh
+/// one so syncs started afterwards are unaffected.
+pub struct SyncCancellation {
+    token: Mutex<CancellationToken>,
+}
+
+impl SyncCancellation {
+    pub fn new() -> Self {
+        Self {
+            token: Mutex::new(CancellationToken::new()),
+        }
+    }
+
+    /// Returns the token that in-flight syncs should observe.
+    pub fn token(&self) -> CancellationToken {
+        match self.token.lock() {
+            Ok(token) => token.clone(),
+            Err(poisoned) => poisoned.into_inner().clone(),
+        }
+    }
+
+    /// Cancels all in-flight syncs.
+    pub fn cancel(&self) {
+        let mut token = match self.token.lock() {
+            Ok(token) => token,
+            Err(poisoned) => poisoned.into_inner(),
+        };
+        token.cancel();
+        *token = CancellationToken::new();
+        log::info!("Sync cancelled");
+    }
+}
+
+impl Default for SyncCancellation {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Payload of the `notifications:batch_inserted` event.
+///
+/// Emitted once per subscription after a sync stored new notifications.
+#[derive(Debug, Clone, Serialize, Type)]
+pub struct NotificationBatchInserted {
+    pub subscription_id: String,
+    /// Number of notifications inserted in this batch.
+    pub count: u32,
+}
+
+impl NotificationBatchInserted {
+    /// The event announcing `synced`, unless nothing was stored.
+    fn of(subscription_id: &str, synced: &[SyncedNotification]) -> Option<Self> {
+        (!synced.is_empty()).then(|| Self {
+            subscription_id: subscription_id.to_string(),
+            count: synced.len() as u32,
+        })
+    }
+}
+
+/// Payload of the `sync:completed` event summarizing a notification sync run.
+#[derive(Debug, Clone, Default, Serialize, Type)]
+pub struct SyncSummary {
+    /// Subscriptions that were synced successfully.
+    pub synced: u32,
+    /// Subscriptions whose sync failed.
+    pub failed: u32,
+    /// Total notifications inserted across all subscriptions.
+    pub inserted: u32,
+    /// Whether the run was cancelled before finishing.
+    pub cancelled: bool,
+}
+
+impl SyncSummary {
+    /// Records the outcome of syncing a single subscription.
+    pub fn record(&mut self, result: &Result<u32, AppError>) {
+        match result {
+            Ok(inserted) => {
+                self.synced += 1;
+                self.inserted += inserted;
+            }
+            Err(_) => self.failed += 1,
+        }
+    }
+
+    /// Emits this summary as the `sync:completed` event.
+    pub fn emit(&self, handle: &AppHandle) {
+        log::info!(
+            "Sync completed: {} synced, {} failed, {} inserted",
+            self.synced,
+            self.failed,
+            self.inserted
+        );
+        events::emit(handle, self);
+    }
+}
+
+/// Messages missed by a subscription, as fetched for a sync.
+struct MissedMessages {
+    messages: Vec<NtfyMessage>,
+    /// Poll start the messages were fetched since.
+    since: Option<i64>,
+    /// The server's time when polled (Unix seconds), or the local time if
+    /// the server didn't send it.
+    server_now: i64,
+}
+
+/// A notification stored by a sync, with what's needed to announce it.
+struct SyncedNotification {
+    notification: Notification,
+    ntfy_id: String,
+    /// Whether a keyword filter mutes it.
+    keyword_muted: bool,
+    rules: RuleOutcome,
+}
 
 /// Synchronization service for subscriptions and notifications.
 pub struct SyncService;
@@ -16,11 +146,12 @@ impl SyncService {
     /// Syncs subscriptions from all configured servers that have credentials.
     ///
     /// For each server with valid credentials, fetches the account's subscriptions
-    /// and creates any that don't exist locally. Also starts WebSocket connections
-    /// for newly created subscriptions.
+    /// and creates any that don't exist locally, skipping topics matched by the
+    /// blocklist. Also starts WebSocket connections for newly created subscriptions.
     pub async fn sync_subscriptions(handle: &AppHandle) {
         let db: tauri::State<Database> = handle.state();
         let conn_manager: tauri::State<ConnectionManager> = handle.state();
+        let cancel_token = handle.state::<SyncCancellation>().token();
 
         let settings = match db.get_settings() {
             Ok(s) => s,
@@ -30,7 +161,20 @@ impl SyncService {
             }
         };
 
+        let blocklist = match db.get_blocked_topics() {
+            Ok(b) => b,
+            Err(e) => {
+                log::error!("Failed to get topic blocklist: {e}");
+                return;
+            }
+        };
+
         for server in &settings.servers {
+            if cancel_token.is_cancelled() {
+                log::info!("Subscription sync cancelled");
+                return;
+            }
+
             let (username, password) = match server.credentials() {
                 Some(creds) => creds,
                 None => continue,
@@ -39,7 +183,7 @@ impl SyncService {
             log::info!("Syncing subscriptions from: {}", server.url);
 
             let client = match NtfyClient::new() {
-                Ok(c) => c,
+                Ok(c) => c.with_cancellation(cancel_token.clone()),
                 Err(e) => {
                     log::error!("Failed to create ntfy client: {e}");
                     continue;
@@ -60,7 +204,11 @@ impl SyncService {
                 server.url
             );
 
-            let existing = match db.get_all_subscriptions() {
+            // Trashed subscriptions count as existing so sync doesn't resurrect them
+            let existing = match db.get_all_subscriptions().and_then(|mut subs| {
+                subs.extend(db.get_deleted_subscriptions()?);
+                Ok(subs)
+            }) {
                 Ok(s) => s,
                 Err(e) => {
                     log::error!("Failed to get existing subscriptions: {e}");
@@ -85,7 +233,21 @@ impl SyncService {
                     continue;
                 }
 
-                log::info!("Creating subscription: {}", ntfy_sub.topic);
+                if blocklist
+                    .iter()
+                    .any(|b| b.matches(&server.url, &ntfy_sub.topic))
+                {
+                    log::info!(
+                        "Topic is blocklisted, skipping: {}",
+                        log_redaction::redact(&ntfy_sub.topic)
+                    );
+                    continue;
+                }
+
+                log::info!(
+                    "Creating subscription: {}",
+                    log_redaction::redact(&ntfy_sub.topic)
+                );
                 if let Ok(new_sub) = db.create_subscription(CreateSubscription {
                     topic: ntfy_sub.topic,
                     server_url: server.url.clone(),
@@ -104,9 +266,13 @@ impl SyncService {
     /// Syncs notifications for all subscriptions from their servers.
     ///
     /// Fetches messages newer than each subscription's last sync timestamp
-    /// and stores them in the database.
+    /// and stores them in the database. Subscriptions excluded from sync are
+    /// skipped; the rest are synced in order of importance, each emitting its
+    /// own `notifications:batch_inserted`, so the topics that matter populate
+    /// first. Emits `sync:completed` when done.
     pub async fn sync_notifications(handle: &AppHandle) {
         let db: tauri::State<Database> = handle.state();
+        let cancel_token = handle.state::<SyncCancellation>().token();
 
         let settings = match db.get_settings() {
             Ok(s) => s,
@@ -116,7 +282,7 @@ impl SyncService {
             }
         };
 
-        let subscriptions = match db.get_all_subscriptions() {
+        let mut subscriptions = match db.get_all_subscriptions() {
             Ok(s) => s,
             Err(e) => {
                 log::error!("Failed to get subscriptions for notification sync: {e}");
@@ -124,15 +290,28 @@ impl SyncService {
             }
         };
 
+        Self::sort_by_importance(&mut subscriptions);
+
         let client = match NtfyClient::new() {
-            Ok(c) => c,
+            Ok(c) => c.with_cancellation(cancel_token.clone()),
             Err(e) => {
                 log::error!("Failed to create ntfy client: {e}");
                 return;
             }
         };
 
-        for sub in subscriptions {
+        let mut summary = SyncSummary::default();
+
+        for sub in subscriptions
+            .into_iter()
+            .filter(|s| !s.detached && !s.local && !s.exclude_from_sync)
+        {
+            if cancel_token.is_cancelled() {
+                log::info!("Notification sync cancelled");
+                summary.cancelled = true;
+                break;
+            }
+
             // Find server credentials for this subscription
             let server = settings
                 .servers
@@ -143,11 +322,93 @@ impl SyncService {
                 None => (None, None),
             };
 
-            Self::sync_subscription_notifications(handle, &db, &client, &sub, username, password)
-                .await;
+            let result = Self::sync_subscription_notifications(
+                handle, &db, &client, &sub, username, password,
+            )
+            .await;
+            summary.record(&result);
         }
 
-        log::info!("Notification sync completed");
+        summary.emit(handle);
+    }
+
+    /// Orders subscriptions for syncing: pinned first, then unmuted, then
+    /// muted. The existing order is kept within each group.
+    pub fn sort_by_importance(subscriptions: &mut [Subscription]) {
+        subscriptions.sort_by_key(|sub| (!sub.pinned, sub.muted));
+    }
+
+    /// Returns a poll start to use instead of `since` if it lies in the
+    /// server's future, which happens when it was stored from a local clock
+    /// running ahead. The stored time is moved back by the current skew,
+    /// assuming the clock was off by the same amount back then.
+    fn corrected_since(since: i64, local_now: i64, server_now: i64) -> Option<i64> {
+        (since > server_now).then(|| (since - (local_now - server_now).max(0)).min(server_now))
+    }
+
+    /// Returns the poll start for a subscription last synced at `last_sync`
+    /// that only fetches messages from `window_hours` back. `None` polls
+    /// everything the server has.
+    fn windowed_since(last_sync: Option<i64>, window_hours: Option<u32>, now: i64) -> Option<i64> {
+        let window_start = window_hours.map(|hours| now - i64::from(hours) * 3600);
+        match (last_sync, window_start) {
+            (Some(last), Some(start)) => Some(last.max(start)),
+            (last, start) => last.or(start),
+        }
+    }
+
+    /// Polls a topic's messages since `last_sync`. If that lies in the
+    /// server's future, the topic is polled again from the corrected time.
+    async fn fetch_missed(
+        client: &NtfyClient,
+        server_url: &str,
+        topic: &str,
+        last_sync: Option<i64>,
+        username: Option<&str>,
+        password: Option<&str>,
+    ) -> Result<MissedMessages, AppError> {
+        let fetch = |since| client.get_messages(server_url, topic, since, username, password);
+        let polled = fetch(last_sync).await.inspect_err(|e| {
+            log::error!(
+                "Failed to fetch messages for {}/{}: {}",
+                server_url,
+                log_redaction::redact(topic),
+                e
+            );
+        })?;
+
+        // Sync times are kept in server time so a local clock running ahead
+        // cannot make the next poll skip messages
+        let local_now = chrono::Utc::now().timestamp();
+        let server_now = polled.server_time.unwrap_or(local_now);
+        if (local_now - server_now).abs() > CLOCK_SKEW_WARN_SECS {
+            log::warn!(
+                "Local clock differs from {} by {}s",
+                server_url,
+                local_now - server_now
+            );
+        }
+
+        let corrected =
+            last_sync.and_then(|last| Self::corrected_since(last, local_now, server_now));
+        let since = corrected.or(last_sync);
+        let messages = if let Some(corrected) = corrected {
+            log::warn!(
+                "Last sync of {}/{} is ahead of the server clock, polling again since {}",
+                server_url,
+                log_redaction::redact(topic),
+                corrected
+            );
+            fetch(Some(corrected)).await?.messages
+        } else {
+            polled.messages
+        };
+
+        Ok(MissedMessages {
+            messages,
+            since,
+            server_now,
+        })
     }
 
     /// Syncs notifications for a single subscription.
@@ -155,66 +416,146 @@ impl SyncService {
     /// If `username` and `password` are provided, they are used for authentication.
     /// Otherwise, credentials are looked up from the `servers` list.
     ///
-    /// Shows system notifications for each new message unless the subscription is muted.
+    /// Only messages within the subscription's sync window, if it has one, are
+    /// fetched.
+    ///
+    /// Shows system notifications for each new message unless the subscription is muted,
+    /// and emits `notifications:batch_inserted` if anything was stored.
+    ///
+    /// Returns the number of notifications inserted.
     pub async fn sync_subscription_notifications(
         handle: &AppHandle,
         db: &Database,
         client: &NtfyClient,
-        sub: &crate::models::Subscription,
+        sub: &Subscription,
         username: Option<&str>,
         password: Option<&str>,
-    ) {
+    ) -> Result<u32, AppError> {
+        let started = Instant::now();
         let last_sync = match db.get_subscription_with_last_sync(&sub.id) {
-            Ok(Some((_, last_sync))) => last_sync,
+            Ok(Some((current, last_sync))) => Self::windowed_since(
+                last_sync,
+                current.sync_window_hours,
+                chrono::Utc::now().timestamp(),
+            ),
             Ok(None) => {
                 log::warn!("Subscription {} not found", sub.id);
-                return;
+                return Err(AppError::NotFound(format!(
+                    "Subscription {} not found",
+                    sub.id
+                )));
             }
             Err(e) => {
                 log::error!("Failed to get last_sync for {}: {}", sub.id, e);
-                return;
+                return Err(e);
             }
         };
 
         log::info!(
             "Syncing notifications for {}/{} (since: {:?})",
             sub.server_url,
-            sub.topic,
+            log_redaction::redact(&sub.topic),
             last_sync
         );
 
-        let messages = match client
-            .get_messages(&sub.server_url, &sub.topic, last_sync, username, password)
-            .await
-        {
-            Ok(m) => m,
-            Err(e) => {
-                log::error!(
-                    "Failed to fetch messages for {}/{}: {}",
-                    sub.server_url,
-                    sub.topic,
-                    e
-                );
-                return;
-            }
-        };
-
-        if messages.is_empty() {
-            log::info!("No new messages for {}/{}", sub.server_url, sub.topic);
+        let missed = Self::fetch_missed(
+            client,
+            &sub.server_url,
+            &sub.topic,
+            last_sync,
+            username,
+            password,
+        )
+        .await?;
+
+        if missed.messages.is_empty() {
+            log::info!(
+                "No new messages for {}/{}",
+                sub.server_url,
+                log_redaction::redact(&sub.topic)
+            );
         } else {
             log::info!(
                 "Found {} new messages for {}/{}",
-                messages.len(),
+                missed.messages.len(),
                 sub.server_url,
-                sub.topic
+                log_redaction::redact(&sub.topic)
             );
         }
 
-        let mut max_timestamp: i64 = last_sync.unwrap_or(0);
+        let new_notifications = Self::store_missed(db, sub, missed);
 
+        if let (Some(mirror), false) = (&sub.mirror, new_notifications.is_empty()) {
+            let notifications: Vec<&Notification> =
+                new_notifications.iter().map(|n| &n.notification).collect();
+            FileMirrorService::append(mirror, &sub.topic, &notifications);
+        }
+
+        // Emit events and show system notifications for new messages. The
+        // toasts of a sync share the slots of live ones and hold theirs until
+        // it's done, so a burst after reconnecting shows only a few
+        let conn_manager: tauri::State<ConnectionManager> = handle.state();
+        let mut toast_permits = Vec::new();
+        let mut skipped_toasts = 0;
+        for synced in &new_notifications {
+            let notification = &synced.notification;
+            events::emit(handle, &NotificationNew(notification.clone()));
+            LinkPreviewService::spawn(handle, notification);
+            RuleService::spawn_commands(handle, synced.rules.commands.clone(), notification);
+
+            // Same as for messages received live
+            let suppressed = synced.rules.mute || synced.keyword_muted;
+            if !suppressed && (!sub.muted || notification.mentioned || synced.rules.force_toast) {
+                let Some(permit) = conn_manager.try_toast_permit() else {
+                    skipped_toasts += 1;
+                    continue;
+                };
+                toast_permits.push(permit);
+                ConnectionManager::show_notification_with_rules(
+                    handle,
+                    notification,
+                    &synced.ntfy_id,
+                    &synced.rules,
+                )
+                .await;
+            }
+        }
+        drop(toast_permits);
+        if skipped_toasts > 0 {
+            log::info!(
+                "Too many toasts being shown, skipped {skipped_toasts} for {}/{}",
+                sub.server_url,
+                log_redaction::redact(&sub.topic)
+            );
+        }
+
+        let inserted = new_notifications.len() as u32;
+        MetricsService::record_received(&sub.id, u64::from(inserted));
+
+        // Update tray icon and notify the UI once per batch
+        if let Some(batch) = NotificationBatchInserted::of(&sub.id, &new_notifications) {
+            let tray_manager: tauri::State<TrayManager> = handle.state();
+            tray_manager.refresh_from_db(handle);
+            events::emit(handle, &batch);
+        }
+
+        MetricsService::record_sync(started.elapsed());
+        Ok(inserted)
+    }
+
+    /// Stores the missed messages of a subscription that aren't stored yet
+    /// and moves its last sync past them.
+    ///
+    /// Returns the notifications stored, which still have to be announced.
+    fn store_missed(
+        db: &Database,
+        sub: &Subscription,
+        missed: MissedMessages,
+    ) -> Vec<SyncedNotification> {
+        let mut max_timestamp: i64 = missed.since.unwrap_or(0);
         let mut new_notifications = Vec::new();
 
-        for msg in messages {
+        for msg in missed.messages {
             if db
                 .notification_exists_by_ntfy_id(msg.ntfy_id())
                 .unwrap_or(false)
@@ -224,10 +565,19 @@ impl SyncService {
 
             let ntfy_id = msg.ntfy_id().to_string();
             let msg_time = msg.time;
-            let mut notification = msg.into_notification(sub.id.clone());
-
-            // Auto-mark as read for muted topics
-            if sub.muted {
+            let mut notification =
+                ConnectionManager::prepare_notification(db, msg, &sub.id, sub.muted);
+            if msg_time > max_timestamp {
+                max_timestamp = msg_time;
+            }
+            let keyword_filters = db
+                .get_keyword_filters_for(&notification)
+                .unwrap_or_default();
+            if keyword_filters.iter().any(|f| f.discard) {
+                continue;
+            }
+            let rules = RuleService::evaluate(db, &sub.topic, &notification);
+            if rules.mark_read {
                 notification.read = true;
             }
 
@@ -235,38 +585,165 @@ impl SyncService {
                 log::error!("Failed to insert notification: {e}");
             } else {
                 log::info!(
-                    "Inserted notification: {} - {}",
-                    notification.title,
-                    notification.message
+                    "Inserted notification {}: {} - {}",
+                    notification.id,
+                    log_redaction::redact(&notification.title),
+                    log_redaction::redact(&notification.message)
                 );
-                new_notifications.push(notification);
+                new_notifications.push(SyncedNotification {
+                    notification,
+                    ntfy_id,
+                    keyword_muted: !keyword_filters.is_empty(),
+                    rules,
+                });
             }
+        }
 
-            if msg_time > max_timestamp {
-                max_timestamp = msg_time;
-            }
+        let new_sync_time = std::cmp::max(max_timestamp + 1, missed.server_now);
+        if let Err(e) = db.update_subscription_last_sync(&sub.id, new_sync_time) {
+            log::error!("Failed to update last_sync for {}: {}", sub.id, e);
         }
 
-        // Emit events and show system notifications for new messages
-        for notification in &new_notifications {
-            if let Err(e) = handle.emit("notification:new", notification) {
-                log::error!("Failed to emit notification event: {e}");
-            }
+        new_notifications
+    }
+}
 
-            if !sub.muted {
-                ConnectionManager::show_notification(handle, notification).await;
-            }
-        }
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::MockNtfyServer;
+
+    #[test]
+    fn test_corrected_since() {
+        // Stored just now by a clock 100s ahead
+        assert_eq!(SyncService::corrected_since(1100, 1100, 1000), Some(1000));
+        // Stored a while ago by the same clock
+        assert_eq!(SyncService::corrected_since(1050, 1100, 1000), Some(950));
+        // Never past the server's time, even if the clock is now behind
+        assert_eq!(SyncService::corrected_since(1100, 900, 1000), Some(1000));
+        assert_eq!(SyncService::corrected_since(900, 1100, 1000), None);
+    }
 
-        // Update tray icon if there were new notifications
-        if !new_notifications.is_empty() {
-            let tray_manager: tauri::State<TrayManager> = handle.state();
-            tray_manager.refresh_from_db(handle).await;
-        }
+    #[test]
+    fn test_windowed_since() {
+        let now = 100_000;
+        assert_eq!(SyncService::windowed_since(None, None, now), None);
+        assert_eq!(SyncService::windowed_since(Some(5), None, now), Some(5));
+        // A window starting later than the last sync cuts the catch-up short
+        assert_eq!(
+            SyncService::windowed_since(Some(5), Some(1), now),
+            Some(96_400)
+        );
+        assert_eq!(
+            SyncService::windowed_since(Some(99_000), Some(1), now),
+            Some(99_000)
+        );
+        assert_eq!(
+            SyncService::windowed_since(None, Some(2), now),
+            Some(92_800)
+        );
+    }
 
-        let new_sync_time = std::cmp::max(max_timestamp + 1, chrono::Utc::now().timestamp());
-        if let Err(e) = db.update_subscription_last_sync(&sub.id, new_sync_time) {
-            log::error!("Failed to update last_sync for {}: {}", sub.id, e);
-        }
+    #[tokio::test]
+    async fn test_fetch_missed_from_mock_server() {
+        let server = MockNtfyServer::start().await;
+        assert!(server.is_ok());
+        let Ok(server) = server else { return };
+        let client = NtfyClient::new();
+        assert!(client.is_ok());
+        let Ok(client) = client else { return };
+
+        // The server's clock is an hour behind the one the last sync was
+        // stored from, so polling since then would skip this message
+        server.set_clock_offset(-3600);
+        server.publish("alerts", "m1", server.now() - 2, "Disk full");
+        let last_sync = chrono::Utc::now().timestamp() - 5;
+
+        let missed =
+            SyncService::fetch_missed(&client, server.url(), "alerts", Some(last_sync), None, None)
+                .await;
+        assert!(missed.is_ok());
+        let Ok(missed) = missed else { return };
+        let ids: Vec<&str> = missed.messages.iter().map(|m| m.id.as_str()).collect();
+        assert_eq!(ids, ["m1"]);
+        assert!(missed.since.is_some_and(|since| since < server.now()));
+        assert!((missed.server_now - server.now()).abs() <= 2);
+        let polls = server
+            .requests()
+            .iter()
+            .filter(|r| r.path.starts_with("/alerts/json"))
+            .count();
+        assert_eq!(polls, 2);
+    }
+
+    /// Polls all of a topic's messages and stores the missed ones.
+    async fn sync_all(
+        db: &Database,
+        client: &NtfyClient,
+        sub: &Subscription,
+    ) -> Vec<SyncedNotification> {
+        let missed =
+            SyncService::fetch_missed(client, &sub.server_url, &sub.topic, None, None, None).await;
+        assert!(missed.is_ok());
+        missed.map_or_else(
+            |_| Vec::new(),
+            |missed| SyncService::store_missed(db, sub, missed),
+        )
+    }
+
+    fn ntfy_ids(synced: &[SyncedNotification]) -> Vec<&str> {
+        synced.iter().map(|n| n.ntfy_id.as_str()).collect()
+    }
+
+    #[tokio::test]
+    async fn test_store_missed_from_mock_server() {
+        let server = MockNtfyServer::start().await;
+        assert!(server.is_ok());
+        let Ok(server) = server else { return };
+        let client = NtfyClient::new();
+        assert!(client.is_ok());
+        let Ok(client) = client else { return };
+        let db = Database::new(std::path::Path::new(":memory:"));
+        assert!(db.is_ok());
+        let Ok(db) = db else { return };
+        let created = db.create_subscriptions(&[CreateSubscription {
+            topic: "alerts".to_string(),
+            server_url: server.url().to_string(),
+            display_name: None,
+        }]);
+        let id = created
+            .ok()
+            .and_then(|ids| ids.into_iter().flatten().next());
+        let sub = id.and_then(|id| db.get_subscription_by_id(&id).ok().flatten());
+        assert!(sub.is_some());
+        let Some(sub) = sub else { return };
+        let stored = |db: &Database| {
+            db.get_notifications_by_subscription(&sub.id)
+                .map_or(0, |notifications| notifications.len())
+        };
+
+        server.publish("alerts", "m1", server.now() - 2, "Disk full");
+        server.publish("alerts", "m2", server.now() - 1, "Disk still full");
+        let synced = sync_all(&db, &client, &sub).await;
+        assert_eq!(ntfy_ids(&synced), ["m1", "m2"]);
+        let batch = NotificationBatchInserted::of(&sub.id, &synced);
+        assert_eq!(
+            batch.map(|b| (b.subscription_id, b.count)),
+            Some((sub.id.clone(), 2))
+        );
+        assert_eq!(stored(&db), 2);
+        let last_sync = db.get_subscription_with_last_sync(&sub.id);
+        assert!(matches!(last_sync, Ok(Some((_, Some(t)))) if t >= server.now() - 1));
+
+        // Polling everything again only stores the new message
+        server.publish("alerts", "m3", server.now(), "Disk cleaned up");
+        let synced = sync_all(&db, &client, &sub).await;
+        assert_eq!(ntfy_ids(&synced), ["m3"]);
+        assert_eq!(stored(&db), 3);
+
+        let synced = sync_all(&db, &client, &sub).await;
+        assert!(synced.is_empty());
+        assert!(NotificationBatchInserted::of(&sub.id, &synced).is_none());
+        assert_eq!(stored(&db), 3);
     }
 }
@@ -0,0 +1,59 @@
+//! Subscribing from a template.
+
+use rand::distributions::{Alphanumeric, DistString};
+
+use crate::config::templates::TOPIC_SUFFIX_LEN;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{ApplyTemplateParams, CreateSubscription, Subscription, SubscriptionTemplate};
+use crate::services::ConnectionManager;
+
+/// Service for creating subscriptions from templates.
+pub struct TemplateService;
+
+impl TemplateService {
+    /// Creates and connects a subscription from a template, sets its toast
+    /// floor and adds the template's tag priorities to the settings.
+    pub async fn apply(
+        db: &Database,
+        conn_manager: &ConnectionManager,
+        template_id: &str,
+        params: ApplyTemplateParams,
+    ) -> Result<Subscription, AppError> {
+        let template = SubscriptionTemplate::find(template_id)
+            .ok_or_else(|| AppError::NotFound(format!("Template {template_id} not found")))?;
+
+        let topic = params
+            .topic
+            .filter(|t| !t.trim().is_empty())
+            .unwrap_or_else(|| {
+                let suffix = Alphanumeric
+                    .sample_string(&mut rand::thread_rng(), TOPIC_SUFFIX_LEN)
+                    .to_lowercase();
+                format!("{}-{suffix}", template.suggested_topic)
+            });
+        let display_name = params
+            .display_name
+            .filter(|n| !n.trim().is_empty())
+            .unwrap_or_else(|| template.name.clone());
+
+        let sub = db.create_subscription(CreateSubscription {
+            topic,
+            server_url: params.server_url,
+            display_name: Some(display_name),
+        })?;
+        if template.min_toast_priority.is_some() {
+            db.set_subscription_min_toast_priority(&sub.id, template.min_toast_priority)?;
+        }
+        if !template.tag_priorities.is_empty() {
+            let merged = template.merge_tag_priorities(&db.get_tag_priorities()?);
+            db.set_setting("tag_priorities", &serde_json::to_string(&merged)?)?;
+        }
+
+        let sub = db
+            .get_subscription_by_id(&sub.id)?
+            .ok_or_else(|| AppError::NotFound(format!("Subscription {} not found", sub.id)))?;
+        conn_manager.connect(&sub).await?;
+        Ok(sub)
+    }
+}
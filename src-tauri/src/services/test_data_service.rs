@@ -0,0 +1,87 @@
+//! Synthetic notifications for local test topics.
+//!
+//! Generated messages are delivered through the same path as messages from a
+//! server, so channels, sounds, display settings and auto-delete can be tried
+//! out without publishing anything.
+
+use tauri::{AppHandle, Manager};
+
+use crate::config::local_topics::{MAX_TEST_NOTIFICATIONS, TEST_NOTIFICATION_SPACING_SECS};
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::{NtfyMessage, Priority};
+use crate::services::{ConnectionManager, TrayManager};
+
+/// Service for filling local subscriptions with test notifications.
+pub struct TestDataService;
+
+impl TestDataService {
+    /// Generates `count` notifications for a local subscription.
+    ///
+    /// Priorities cycle through `priority_mix` (default priority if empty).
+    /// Timestamps are spaced an hour apart, ending now, so retention settings
+    /// have old messages to act on. Returns the number generated.
+    pub fn generate(
+        handle: &AppHandle,
+        subscription_id: &str,
+        count: u32,
+        priority_mix: &[Priority],
+    ) -> Result<u32, AppError> {
+        let subscription = {
+            let db: tauri::State<Database> = handle.state();
+            db.get_subscription_by_id(subscription_id)?.ok_or_else(|| {
+                AppError::NotFound(format!("Subscription {subscription_id} not found"))
+            })?
+        };
+
+        if !subscription.local {
+            return Err(AppError::Validation(
+                "Test notifications can only be generated for local subscriptions".to_string(),
+            ));
+        }
+
+        let count = count.min(MAX_TEST_NOTIFICATIONS);
+        let now = chrono::Utc::now().timestamp();
+
+        // Oldest first, so the newest message is delivered last
+        for i in (0..count).rev() {
+            let priority = if priority_mix.is_empty() {
+                Priority::Default
+            } else {
+                priority_mix[i as usize % priority_mix.len()]
+            };
+            let number = count - i;
+
+            let msg = NtfyMessage {
+                id: format!("test-{}", uuid::Uuid::new_v4()),
+                time: now - i64::from(i) * TEST_NOTIFICATION_SPACING_SECS,
+                event: "message".to_string(),
+                topic: subscription.topic.clone(),
+                message: Some(format!(
+                    "Synthetic message {number} of {count} (priority {})",
+                    priority as u8
+                )),
+                title: Some(format!("Test notification {number}")),
+                priority: Some(priority as i8),
+                tags: Some(vec!["test".to_string()]),
+                click: None,
+                actions: None,
+                attachment: None,
+                content_type: None,
+            };
+
+            ConnectionManager::handle_notification(
+                handle,
+                subscription_id,
+                msg,
+                subscription.muted,
+            );
+        }
+
+        let tray_manager: tauri::State<TrayManager> = handle.state();
+        tray_manager.refresh_from_db(handle);
+
+        log::info!("Generated {count} test notifications for {subscription_id}");
+        Ok(count)
+    }
+}
@@ -0,0 +1,83 @@
+//! Tracks shown toasts so they can be cleared from the Action Center.
+//!
+//! Each toast shown through `WinRT` is recorded under its notification ID with
+//! the tag and group it was shown with. When the notification is read in the
+//! app, the toast is removed via the `ToastNotificationHistory` API so handled
+//! alerts don't linger. On other platforms nothing is recorded.
+
+use std::collections::HashMap;
+use std::sync::Mutex;
+use tauri::AppHandle;
+
+/// Tag and group a toast was shown with.
+struct ShownToast {
+    tag: String,
+    group: String,
+}
+
+/// Toasts currently shown, keyed by notification ID.
+#[derive(Default)]
+pub struct ToastHistory {
+    shown: Mutex<HashMap<String, ShownToast>>,
+}
+
+impl ToastHistory {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Records a toast shown for a notification.
+    #[cfg_attr(not(windows), allow(dead_code))]
+    pub fn record(&self, notification_id: &str, tag: &str, group: &str) {
+        self.lock_shown().insert(
+            notification_id.to_string(),
+            ShownToast {
+                tag: tag.to_string(),
+                group: group.to_string(),
+            },
+        );
+    }
+
+    /// Removes the toast of a notification, if one was shown.
+    pub fn dismiss(&self, handle: &AppHandle, notification_id: &str) {
+        let Some(toast) = self.lock_shown().remove(notification_id) else {
+            return;
+        };
+
+        #[cfg(windows)]
+        {
+            let aumid = handle.config().identifier.as_str();
+            if let Err(e) = super::windows_toast::remove(aumid, &toast.tag, &toast.group) {
+                log::warn!("Failed to remove toast: {e}");
+            }
+        }
+        #[cfg(not(windows))]
+        let _ = (handle, toast.tag, toast.group);
+    }
+
+    /// Removes all toasts of a subscription.
+    ///
+    /// The whole group is cleared, which also covers toasts shown before the
+    /// app was restarted.
+    pub fn dismiss_subscription(&self, handle: &AppHandle, subscription_id: &str) {
+        self.lock_shown()
+            .retain(|_, toast| toast.group != subscription_id);
+
+        #[cfg(windows)]
+        {
+            let aumid = handle.config().identifier.as_str();
+            if let Err(e) = super::windows_toast::remove_group(aumid, subscription_id) {
+                log::warn!("Failed to remove toasts: {e}");
+            }
+        }
+        #[cfg(not(windows))]
+        let _ = handle;
+    }
+
+    fn lock_shown(&self) -> std::sync::MutexGuard<'_, HashMap<String, ShownToast>> {
+        match self.shown.lock() {
+            Ok(shown) => shown,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
@@ -0,0 +1,113 @@
+//! Trash management for soft-deleted notifications and subscriptions.
+//!
+//! Deleting a notification or subscription only marks it with `deleted_at`,
+//! keeping it restorable. Purging makes the deletion permanent and is also
+//! when notifications are removed from the ntfy server (unless
+//! `delete_local_only` is set).
+
+use tauri::{AppHandle, Manager};
+
+use crate::config::trash::RETENTION_SECS;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::services::NtfyClient;
+
+/// Service for purging the trash.
+pub struct TrashService;
+
+impl TrashService {
+    /// Permanently deletes everything in the trash.
+    pub async fn empty(handle: &AppHandle) -> Result<(), AppError> {
+        Self::purge(handle, None).await
+    }
+
+    /// Permanently deletes items that have been in the trash longer than the retention period.
+    pub async fn purge_expired(handle: &AppHandle) {
+        let cutoff = chrono::Utc::now().timestamp_millis() - RETENTION_SECS * 1000;
+        if let Err(e) = Self::purge(handle, Some(cutoff)).await {
+            log::warn!("Failed to purge expired trash: {e}");
+        }
+    }
+
+    /// Permanently deletes trashed items, optionally only those deleted before `deleted_before`.
+    async fn purge(handle: &AppHandle, deleted_before: Option<i64>) -> Result<(), AppError> {
+        let db: tauri::State<Database> = handle.state();
+
+        let trashed = db.get_deleted_notification_meta(deleted_before)?;
+
+        if !trashed.is_empty() && !db.get_delete_local_only()? {
+            match NtfyClient::new() {
+                Ok(client) => {
+                    for (_, ntfy_id, subscription_id) in &trashed {
+                        if let Some(ntfy_id) = ntfy_id {
+                            Self::delete_remote(&db, &client, ntfy_id, subscription_id).await;
+                        }
+                    }
+                }
+                Err(e) => {
+                    log::warn!("Failed to create HTTP client for remote delete: {e}");
+                }
+            }
+        }
+
+        for (id, _, _) in &trashed {
+            db.delete_notification(id)?;
+        }
+
+        let purged_subscriptions = db.purge_deleted_subscriptions(deleted_before)?;
+
+        log::info!(
+            "Purged {} notifications and {} subscriptions from trash",
+            trashed.len(),
+            purged_subscriptions
+        );
+
+        Ok(())
+    }
+
+    /// Deletes a message from its ntfy server (best-effort).
+    async fn delete_remote(
+        db: &Database,
+        client: &NtfyClient,
+        ntfy_id: &str,
+        subscription_id: &str,
+    ) {
+        let subscription = match db.get_subscription_by_id(subscription_id) {
+            Ok(Some(s)) if !s.local => s,
+            Ok(_) => return,
+            Err(e) => {
+                log::warn!("Failed to look up subscription {subscription_id}: {e}");
+                return;
+            }
+        };
+
+        // Find credentials for this server
+        let servers = match db.get_servers_with_credentials() {
+            Ok(s) => s,
+            Err(e) => {
+                log::warn!("Failed to load servers for remote delete: {e}");
+                return;
+            }
+        };
+        let server = servers
+            .iter()
+            .find(|s| s.url_matches(&subscription.server_url));
+
+        let (username, password) = server
+            .and_then(|s| s.credentials())
+            .map_or((None, None), |(u, p)| (Some(u), Some(p)));
+
+        if let Err(e) = client
+            .delete_message(
+                &subscription.server_url,
+                &subscription.topic,
+                ntfy_id,
+                username,
+                password,
+            )
+            .await
+        {
+            log::warn!("Failed to delete message remotely: {e}");
+        }
+    }
+}
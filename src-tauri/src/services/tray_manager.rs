@@ -1,13 +1,47 @@
 //! System tray icon management.
 //!
 //! Handles dynamic tray icon updates to show unread notification status.
-//! Loads custom icons from the application's icons directory.
+//! Icons are embedded in the binary, in the variant selected by the
+//! `tray_icon_style` setting. PNGs with the same file names placed in
+//! `<app data>/icons` override the embedded ones, and the `tray_icon_path` /
+//! `tray_unread_icon_path` settings override both with any PNG or ICO file.
 
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
 use std::sync::Arc;
+use std::time::Duration;
 use tauri::{image::Image, tray::TrayIcon, AppHandle, Manager};
 use tokio::sync::RwLock;
 
+use crate::config::tray::{MAX_CUSTOM_ICON_PX, REFRESH_DEBOUNCE_MS};
 use crate::db::Database;
+use crate::error::AppError;
+use crate::models::TrayIconStyle;
+
+/// Tray icons compiled into the binary, by file name.
+const EMBEDDED_ICONS: &[(&str, &[u8])] = &[
+    ("tray.png", include_bytes!("../../icons/tray.png")),
+    (
+        "tray-unread.png",
+        include_bytes!("../../icons/tray-unread.png"),
+    ),
+    (
+        "tray-mono-light.png",
+        include_bytes!("../../icons/tray-mono-light.png"),
+    ),
+    (
+        "tray-mono-light-unread.png",
+        include_bytes!("../../icons/tray-mono-light-unread.png"),
+    ),
+    (
+        "tray-mono-dark.png",
+        include_bytes!("../../icons/tray-mono-dark.png"),
+    ),
+    (
+        "tray-mono-dark-unread.png",
+        include_bytes!("../../icons/tray-mono-dark-unread.png"),
+    ),
+];
 
 /// Internal state for tray icon management.
 #[derive(Default)]
@@ -21,16 +55,20 @@ struct TrayState {
 /// Manages system tray icon state and appearance.
 ///
 /// Supports two icon states: normal and unread (notification badge).
-/// Icon updates are debounced to prevent flickering.
+/// Refreshes from the database are debounced, so bursts of messages don't
+/// query the unread count or flicker the icon for each one.
 #[derive(Clone)]
 pub struct TrayManager {
     state: Arc<RwLock<TrayState>>,
+    /// Whether a debounced refresh is waiting to run.
+    refresh_pending: Arc<AtomicBool>,
 }
 
 impl TrayManager {
     pub fn new() -> Self {
         Self {
             state: Arc::new(RwLock::new(TrayState::default())),
+            refresh_pending: Arc::new(AtomicBool::new(false)),
         }
     }
 
@@ -40,19 +78,25 @@ impl TrayManager {
         state.tray_icon = Some(tray);
     }
 
-    /// Load icon assets from the icons directory
+    /// Load icon assets for the configured style
     pub async fn load_icons(&self, app_handle: &AppHandle) -> Result<(), String> {
-        // Get icons directory relative to executable
-        let icons_dir = Self::get_icons_dir(app_handle)?;
+        let override_dir = Self::get_override_dir(app_handle);
+        let override_dir = override_dir.as_deref();
+        let (normal_file, unread_file) = Self::icon_files(Self::resolve_style(app_handle));
 
-        // Try to load tray.png, fall back to 32x32.png
-        let normal_icon = Self::load_icon_from_dir(&icons_dir, "tray.png")
-            .or_else(|_| Self::load_icon_from_dir(&icons_dir, "32x32.png"))
-            .map_err(|e| format!("Failed to load normal icon: {e}"))?;
+        let db: tauri::State<Database> = app_handle.state();
+
+        // Custom icon files win; if they fail to load, the defaults are used
+        let normal_icon = match Self::load_custom_icon(db.get_tray_icon_path()) {
+            Some(icon) => icon,
+            None => Self::load_icon(override_dir, normal_file)
+                .map_err(|e| format!("Failed to load normal icon: {e}"))?,
+        };
 
-        // Try to load tray-unread.png, fall back to normal icon if not found
-        let unread_icon = Self::load_icon_from_dir(&icons_dir, "tray-unread.png")
-            .unwrap_or_else(|_| normal_icon.clone());
+        // Fall back to normal icon if the unread one can't be loaded
+        let unread_icon = Self::load_custom_icon(db.get_tray_unread_icon_path())
+            .or_else(|| Self::load_icon(override_dir, unread_file).ok())
+            .unwrap_or_else(|| normal_icon.clone());
 
         let mut state = self.state.write().await;
         state.icon_normal = Some(normal_icon);
@@ -61,60 +105,112 @@ impl TrayManager {
         Ok(())
     }
 
-    fn get_icons_dir(app_handle: &AppHandle) -> Result<std::path::PathBuf, String> {
-        // Try multiple locations for icons directory
+    /// Reload icon assets (e.g. after the style changed) and reapply the current icon
+    pub async fn reload_icons(&self, app_handle: &AppHandle) {
+        if let Err(e) = self.load_icons(app_handle).await {
+            log::warn!("Failed to reload tray icons: {e}");
+            return;
+        }
+        self.initial_refresh(app_handle).await;
+    }
 
-        // 1. Resource directory (production)
-        if let Ok(resource_dir) = app_handle.path().resource_dir() {
-            let icons_path = resource_dir.join("icons");
-            if icons_path.exists() {
-                log::info!("Found icons at: {}", icons_path.display());
-                return Ok(icons_path);
+    /// Resolve the configured style, picking a monochrome variant for `Auto`
+    /// from the OS theme (as reported for the main window)
+    fn resolve_style(app_handle: &AppHandle) -> TrayIconStyle {
+        let db: tauri::State<Database> = app_handle.state();
+        match db.get_tray_icon_style().unwrap_or_default() {
+            TrayIconStyle::Auto => {
+                let theme = app_handle
+                    .get_webview_window("main")
+                    .and_then(|window| window.theme().ok());
+                if theme == Some(tauri::Theme::Dark) {
+                    TrayIconStyle::MonochromeLight
+                } else {
+                    TrayIconStyle::MonochromeDark
+                }
             }
+            style => style,
         }
+    }
 
-        // 2. Relative to executable
-        if let Ok(exe_path) = std::env::current_exe() {
-            if let Some(exe_dir) = exe_path.parent() {
-                let icons_path = exe_dir.join("icons");
-                if icons_path.exists() {
-                    log::info!("Found icons at: {}", icons_path.display());
-                    return Ok(icons_path);
-                }
+    /// Icon file names (normal, unread) for a resolved style
+    const fn icon_files(style: TrayIconStyle) -> (&'static str, &'static str) {
+        match style {
+            TrayIconStyle::MonochromeLight => ("tray-mono-light.png", "tray-mono-light-unread.png"),
+            TrayIconStyle::MonochromeDark => ("tray-mono-dark.png", "tray-mono-dark-unread.png"),
+            TrayIconStyle::Color | TrayIconStyle::Auto => ("tray.png", "tray-unread.png"),
+        }
+    }
 
-                // 3. Development: src-tauri/icons (exe is in target/debug)
-                // Go up from target/debug to src-tauri
-                let dev_icons = exe_dir
-                    .parent() // target
-                    .and_then(|p| p.parent()) // src-tauri
-                    .map(|p| p.join("icons"));
-
-                if let Some(icons_path) = dev_icons {
-                    if icons_path.exists() {
-                        log::info!("Found icons at (dev): {}", icons_path.display());
-                        return Ok(icons_path);
-                    }
+    /// Directory where users can drop PNGs named like the embedded icons to
+    /// replace them (`<app data>/icons`), if it exists
+    fn get_override_dir(app_handle: &AppHandle) -> Option<PathBuf> {
+        let icons_path = app_handle.path().app_data_dir().ok()?.join("icons");
+        icons_path.is_dir().then_some(icons_path)
+    }
+
+    /// Load an icon by file name, preferring a user override over the embedded copy
+    fn load_icon(override_dir: Option<&Path>, filename: &str) -> Result<Image<'static>, String> {
+        if let Some(icon_path) = override_dir
+            .map(|dir| dir.join(filename))
+            .filter(|path| path.is_file())
+        {
+            match image::open(&icon_path) {
+                Ok(img) => {
+                    log::info!("Using tray icon override: {}", icon_path.display());
+                    return Ok(Self::to_tauri_image(&img));
+                }
+                Err(e) => {
+                    log::warn!("Ignoring tray icon override {}: {e}", icon_path.display());
                 }
             }
         }
 
-        Err("Icons directory not found".to_string())
+        let bytes = EMBEDDED_ICONS
+            .iter()
+            .find(|(name, _)| *name == filename)
+            .map(|(_, bytes)| *bytes)
+            .ok_or_else(|| format!("No embedded icon {filename}"))?;
+        let img = image::load_from_memory(bytes)
+            .map_err(|e| format!("Failed to decode embedded icon {filename}: {e}"))?;
+
+        Ok(Self::to_tauri_image(&img))
     }
 
-    fn load_icon_from_dir(
-        icons_dir: &std::path::Path,
-        filename: &str,
-    ) -> Result<Image<'static>, String> {
-        let icon_path = icons_dir.join(filename);
+    /// Load and validate a user-supplied PNG or ICO icon file
+    pub fn load_icon_file(path: &Path) -> Result<Image<'static>, String> {
+        let extension = path
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(str::to_ascii_lowercase);
+        if !matches!(extension.as_deref(), Some("png" | "ico")) {
+            return Err(format!("{} is not a PNG or ICO file", path.display()));
+        }
+
+        let img = image::open(path)
+            .map_err(|e| format!("Failed to open image {}: {e}", path.display()))?;
+        if img.width() > MAX_CUSTOM_ICON_PX || img.height() > MAX_CUSTOM_ICON_PX {
+            return Err(format!(
+                "{} is larger than {MAX_CUSTOM_ICON_PX}x{MAX_CUSTOM_ICON_PX} pixels",
+                path.display()
+            ));
+        }
+
+        Ok(Self::to_tauri_image(&img))
+    }
 
-        let img = image::open(&icon_path)
-            .map_err(|e| format!("Failed to open image {}: {e}", icon_path.display()))?;
+    /// Load a custom icon from a path setting, logging and ignoring failures
+    fn load_custom_icon(path: Result<Option<String>, AppError>) -> Option<Image<'static>> {
+        let path = path.ok().flatten()?;
+        Self::load_icon_file(Path::new(&path))
+            .inspect_err(|e| log::warn!("Ignoring custom tray icon: {e}"))
+            .ok()
+    }
 
+    fn to_tauri_image(img: &image::DynamicImage) -> Image<'static> {
         let rgba = img.to_rgba8();
         let (width, height) = rgba.dimensions();
-        let raw_data = rgba.into_raw();
-
-        Ok(Image::new_owned(raw_data, width, height))
+        Image::new_owned(rgba.into_raw(), width, height)
     }
 
     /// Update tray icon based on unread count
@@ -160,28 +256,38 @@ impl TrayManager {
         }
     }
 
-    /// Refresh tray icon based on current unread count from database
-    pub async fn refresh_from_db(&self, app_handle: &AppHandle) {
-        let db: tauri::State<Database> = app_handle.state();
-        let has_unread = db
-            .get_total_unread_count()
-            .map(|count| count > 0)
-            .unwrap_or(false);
+    /// Schedules a refresh of the tray icon from the unread count in the
+    /// database, after `REFRESH_DEBOUNCE_MS`. Calls made before it runs are
+    /// coalesced into it.
+    pub fn refresh_from_db(&self, app_handle: &AppHandle) {
+        if self.refresh_pending.swap(true, Ordering::AcqRel) {
+            return;
+        }
 
-        self.update_icon(has_unread).await;
+        let tray_manager = self.clone();
+        let app_handle = app_handle.clone();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(REFRESH_DEBOUNCE_MS)).await;
+            // Cleared before counting, so changes made during the query schedule another refresh
+            tray_manager.refresh_pending.store(false, Ordering::Release);
+            tray_manager
+                .update_icon(Self::has_unread(&app_handle))
+                .await;
+        });
     }
 
     /// Initial refresh - forces icon update even if state matches
     pub async fn initial_refresh(&self, app_handle: &AppHandle) {
-        let db: tauri::State<Database> = app_handle.state();
-        let has_unread = db
-            .get_total_unread_count()
-            .map(|count| count > 0)
-            .unwrap_or(false);
+        let has_unread = Self::has_unread(app_handle);
 
         log::info!("Initial tray refresh, has_unread: {has_unread}");
         self.force_update_icon(has_unread).await;
     }
+
+    fn has_unread(app_handle: &AppHandle) -> bool {
+        let db: tauri::State<Database> = app_handle.state();
+        db.get_total_unread_count().is_ok_and(|count| count > 0)
+    }
 }
 
 impl Default for TrayManager {
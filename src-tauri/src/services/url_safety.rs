@@ -0,0 +1,112 @@
+//! Safety checks for links opened from notifications.
+//!
+//! Click and view actions come from whoever can publish to a topic, so a link
+//! is only opened after it passes these checks:
+//! - the scheme must be in `ALLOWED_SCHEMES` (no `file:` or `javascript:`)
+//! - the host must not match the URL blocklist
+//! - links from untrusted subscriptions need the user's consent, asked for
+//!   through the `OpenUrl` capability of `PermissionService`
+
+use tauri::{AppHandle, Manager};
+use url::Url;
+
+use crate::config::url_safety::ALLOWED_SCHEMES;
+use crate::db::Database;
+use crate::error::AppError;
+use crate::models::Capability;
+use crate::services::PermissionService;
+
+/// Service for checking and opening notification links.
+pub struct UrlSafety;
+
+impl UrlSafety {
+    /// Parses a link, rejecting schemes that may not be opened.
+    pub fn parse(url: &str) -> Result<Url, AppError> {
+        let parsed = Url::parse(url.trim())
+            .map_err(|e| AppError::InvalidUrl(format!("Invalid link: {e}")))?;
+
+        if !ALLOWED_SCHEMES.contains(&parsed.scheme()) {
+            return Err(AppError::InvalidUrl(format!(
+                "Links with the {}: scheme cannot be opened",
+                parsed.scheme()
+            )));
+        }
+
+        Ok(parsed)
+    }
+
+    /// Parses a link and checks it against the URL blocklist.
+    pub fn check(db: &Database, url: &str) -> Result<Url, AppError> {
+        let parsed = Self::parse(url)?;
+
+        if let Some(entry) = db
+            .get_blocked_urls()?
+            .into_iter()
+            .find(|entry| entry.matches(&parsed))
+        {
+            return Err(AppError::InvalidUrl(format!(
+                "Links to {} are blocked",
+                entry.host_pattern
+            )));
+        }
+
+        Ok(parsed)
+    }
+
+    /// Opens a link from a notification of `subscription_id` in the default browser.
+    ///
+    /// Returns `false` if the user declined to open a link from an untrusted
+    /// subscription, and an error if the link failed the checks.
+    pub async fn open(
+        handle: &AppHandle,
+        subscription_id: &str,
+        url: &str,
+    ) -> Result<bool, AppError> {
+        let parsed = Self::check(&handle.state::<Database>(), url)?;
+
+        if !PermissionService::request(
+            handle,
+            subscription_id,
+            Capability::OpenUrl,
+            parsed.as_str(),
+        )
+        .await
+        {
+            return Ok(false);
+        }
+
+        // The frontend opens links through the same plugin; its replacement
+        // (tauri-plugin-opener) isn't a dependency yet
+        #[allow(deprecated)]
+        {
+            use tauri_plugin_shell::ShellExt;
+            handle
+                .shell()
+                .open(parsed.as_str(), None)
+                .map_err(|e| AppError::Connection(format!("Failed to open link: {e}")))?;
+        }
+
+        Ok(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_allows_web_and_mail_links() {
+        assert!(UrlSafety::parse("https://example.com/run/1").is_ok());
+        assert!(UrlSafety::parse(" http://example.com ").is_ok());
+        assert!(UrlSafety::parse("mailto:ops@example.com").is_ok());
+    }
+
+    #[test]
+    fn test_rejects_unsafe_schemes() {
+        assert!(UrlSafety::parse("file:///etc/passwd").is_err());
+        assert!(UrlSafety::parse("javascript:alert(1)").is_err());
+        assert!(UrlSafety::parse("JavaScript:alert(1)").is_err());
+        assert!(UrlSafety::parse("ms-settings:privacy").is_err());
+        assert!(UrlSafety::parse("not a url").is_err());
+    }
+}
@@ -0,0 +1,326 @@
+//! Windows toast notifications with Action Center grouping.
+//!
+//! Toasts are built from XML and shown through the `WinRT` APIs directly so each
+//! one carries a tag and a group:
+//! - tag: the ntfy message ID, so a toast can be replaced or removed later
+//! - group: the subscription ID, so all toasts of a topic can be managed together
+//!
+//! With grouping enabled, toasts also get a header per subscription, which makes
+//! the Action Center collapse them by topic.
+
+// The XML builder is platform-independent; only showing and removing need `WinRT`.
+#![cfg_attr(not(windows), allow(dead_code))]
+
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::models::NotificationSound;
+
+/// Sound played with a toast.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ToastSound {
+    Default,
+    /// Louder sound used for high priority messages.
+    Sms,
+    Im,
+    Mail,
+    Reminder,
+    /// Looping alarm, for escalations.
+    Alarm,
+}
+
+impl ToastSound {
+    /// Returns the toast sound for a subscription's sound setting, `None`
+    /// for silent.
+    pub const fn from_setting(sound: NotificationSound) -> Option<Self> {
+        match sound {
+            NotificationSound::Silent => None,
+            NotificationSound::Default => Some(Self::Default),
+            NotificationSound::Sms => Some(Self::Sms),
+            NotificationSound::Im => Some(Self::Im),
+            NotificationSound::Mail => Some(Self::Mail),
+            NotificationSound::Reminder => Some(Self::Reminder),
+            NotificationSound::Alarm => Some(Self::Alarm),
+        }
+    }
+}
+
+/// Where an image is placed in a toast.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ImagePlacement {
+    /// Above the text, for landscape images.
+    Hero,
+    /// Below the text, for portrait images (avoids cropping).
+    Inline,
+}
+
+/// Prefix of the activation arguments of buttons run by the app; the
+/// action's ID follows it.
+pub const ACTION_ARG_PREFIX: &str = "action:";
+
+/// A button of a toast.
+#[derive(Debug, Clone)]
+pub enum ToastButton<'a> {
+    /// Opens `url` via protocol activation.
+    Link { label: &'a str, url: &'a str },
+    /// Passes `arguments` to the app, which handles them while it's running.
+    App { label: &'a str, arguments: String },
+}
+
+/// Header grouping toasts of one subscription in the Action Center.
+#[derive(Debug, Clone)]
+pub struct ToastHeader<'a> {
+    pub id: &'a str,
+    pub title: &'a str,
+}
+
+/// Content of a toast notification.
+#[derive(Debug, Clone, Default)]
+pub struct ToastContent<'a> {
+    pub title: &'a str,
+    pub body: &'a str,
+    pub header: Option<ToastHeader<'a>>,
+    /// Keep the toast on screen longer (used for high priority).
+    pub long_duration: bool,
+    /// Show as an alarm, which ignores Focus Assist.
+    pub alarm: bool,
+    /// `None` shows the toast silently.
+    pub sound: Option<ToastSound>,
+    pub buttons: Vec<ToastButton<'a>>,
+    pub image: Option<(&'a Path, ImagePlacement)>,
+}
+
+/// Escapes text for use in XML content and attribute values.
+fn escape_xml(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+/// Builds the toast XML document for the given content.
+pub fn build_xml(content: &ToastContent<'_>) -> String {
+    let mut xml = String::from("<toast");
+    if content.long_duration {
+        xml.push_str(r#" duration="long""#);
+    }
+    if content.alarm {
+        xml.push_str(r#" scenario="alarm""#);
+    }
+    xml.push('>');
+
+    if let Some(ref header) = content.header {
+        let _ = write!(
+            xml,
+            r#"<header id="{}" title="{}" arguments=""/>"#,
+            escape_xml(header.id),
+            escape_xml(header.title)
+        );
+    }
+
+    xml.push_str(r#"<visual><binding template="ToastGeneric">"#);
+    let _ = write!(
+        xml,
+        "<text>{}</text><text>{}</text>",
+        escape_xml(content.title),
+        escape_xml(content.body)
+    );
+    if let Some((path, placement)) = content.image {
+        let placement = match placement {
+            ImagePlacement::Hero => r#" placement="hero""#,
+            ImagePlacement::Inline => "",
+        };
+        let _ = write!(
+            xml,
+            r#"<image{placement} src="file:///{}"/>"#,
+            escape_xml(&path.display().to_string())
+        );
+    }
+    xml.push_str("</binding></visual>");
+
+    match content.sound {
+        Some(ToastSound::Default) => {
+            xml.push_str(r#"<audio src="ms-winsoundevent:Notification.Default"/>"#);
+        }
+        Some(ToastSound::Sms) => {
+            xml.push_str(r#"<audio src="ms-winsoundevent:Notification.SMS"/>"#);
+        }
+        Some(ToastSound::Im) => {
+            xml.push_str(r#"<audio src="ms-winsoundevent:Notification.IM"/>"#);
+        }
+        Some(ToastSound::Mail) => {
+            xml.push_str(r#"<audio src="ms-winsoundevent:Notification.Mail"/>"#);
+        }
+        Some(ToastSound::Reminder) => {
+            xml.push_str(r#"<audio src="ms-winsoundevent:Notification.Reminder"/>"#);
+        }
+        Some(ToastSound::Alarm) => {
+            xml.push_str(
+                r#"<audio src="ms-winsoundevent:Notification.Looping.Alarm" loop="true"/>"#,
+            );
+        }
+        None => xml.push_str(r#"<audio silent="true"/>"#),
+    }
+
+    if !content.buttons.is_empty() {
+        xml.push_str("<actions>");
+        for button in &content.buttons {
+            let (label, activation, arguments) = match button {
+                ToastButton::Link { label, url } => (label, "protocol", *url),
+                ToastButton::App { label, arguments } => (label, "foreground", arguments.as_str()),
+            };
+            let _ = write!(
+                xml,
+                r#"<action content="{}" activationType="{activation}" arguments="{}"/>"#,
+                escape_xml(label),
+                escape_xml(arguments)
+            );
+        }
+        xml.push_str("</actions>");
+    }
+
+    xml.push_str("</toast>");
+    xml
+}
+
+/// Shows a toast tagged with `tag` in `group`, replacing any toast with the same pair.
+///
+/// `on_activated` is called with the activation arguments when the toast or
+/// one of its app buttons is clicked while the app is running. Clicking the
+/// toast itself passes empty arguments.
+#[cfg(windows)]
+pub fn show<F>(
+    aumid: &str,
+    content: &ToastContent<'_>,
+    tag: &str,
+    group: &str,
+    on_activated: F,
+) -> windows::core::Result<()>
+where
+    F: Fn(String) + Send + 'static,
+{
+    use windows::core::{IInspectable, Interface, HSTRING};
+    use windows::Data::Xml::Dom::XmlDocument;
+    use windows::Foundation::TypedEventHandler;
+    use windows::UI::Notifications::{
+        ToastActivatedEventArgs, ToastNotification, ToastNotificationManager,
+    };
+
+    let doc = XmlDocument::new()?;
+    doc.LoadXml(&HSTRING::from(build_xml(content)))?;
+
+    let toast = ToastNotification::CreateToastNotification(&doc)?;
+    toast.SetTag(&HSTRING::from(tag))?;
+    toast.SetGroup(&HSTRING::from(group))?;
+
+    let handler = TypedEventHandler::<ToastNotification, IInspectable>::new(move |_, args| {
+        if let Some(args) = args.as_ref() {
+            let arguments = args.cast::<ToastActivatedEventArgs>()?.Arguments()?;
+            on_activated(arguments.to_string());
+        }
+        Ok(())
+    });
+    toast.Activated(&handler)?;
+
+    ToastNotificationManager::CreateToastNotifierWithId(&HSTRING::from(aumid))?.Show(&toast)
+}
+
+/// Removes the toast tagged with `tag` in `group` from the Action Center.
+#[cfg(windows)]
+pub fn remove(aumid: &str, tag: &str, group: &str) -> windows::core::Result<()> {
+    use windows::core::HSTRING;
+    use windows::UI::Notifications::ToastNotificationManager;
+
+    ToastNotificationManager::History()?.RemoveGroupedTagWithId(
+        &HSTRING::from(tag),
+        &HSTRING::from(group),
+        &HSTRING::from(aumid),
+    )
+}
+
+/// Removes all toasts in `group` from the Action Center.
+#[cfg(windows)]
+pub fn remove_group(aumid: &str, group: &str) -> windows::core::Result<()> {
+    use windows::core::HSTRING;
+    use windows::UI::Notifications::ToastNotificationManager;
+
+    ToastNotificationManager::History()?
+        .RemoveGroupWithId(&HSTRING::from(group), &HSTRING::from(aumid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_escapes_text() {
+        let xml = build_xml(&ToastContent {
+            title: "a < b & c",
+            body: r#"say "hi""#,
+            ..Default::default()
+        });
+        assert!(xml.contains("<text>a &lt; b &amp; c</text>"));
+        assert!(xml.contains("<text>say &quot;hi&quot;</text>"));
+    }
+
+    #[test]
+    fn test_silent_without_sound() {
+        let xml = build_xml(&ToastContent::default());
+        assert!(xml.contains(r#"<audio silent="true"/>"#));
+        assert!(!xml.contains("<actions>"));
+    }
+
+    #[test]
+    fn test_sound_from_setting() {
+        assert_eq!(ToastSound::from_setting(NotificationSound::Silent), None);
+        let xml = build_xml(&ToastContent {
+            sound: ToastSound::from_setting(NotificationSound::Mail),
+            ..Default::default()
+        });
+        assert!(xml.contains("Notification.Mail"));
+
+        let xml = build_xml(&ToastContent {
+            sound: ToastSound::from_setting(NotificationSound::Alarm),
+            ..Default::default()
+        });
+        assert!(xml.contains(r#"Notification.Looping.Alarm" loop="true""#));
+    }
+
+    #[test]
+    fn test_header_and_buttons() {
+        let xml = build_xml(&ToastContent {
+            header: Some(ToastHeader {
+                id: "sub-1",
+                title: "alerts",
+            }),
+            long_duration: true,
+            sound: Some(ToastSound::Sms),
+            buttons: vec![
+                ToastButton::Link {
+                    label: "Open",
+                    url: "https://example.com/?a=1&b=2",
+                },
+                ToastButton::App {
+                    label: "Close door",
+                    arguments: format!("{ACTION_ARG_PREFIX}a1"),
+                },
+            ],
+            ..Default::default()
+        });
+        assert!(xml.starts_with(r#"<toast duration="long">"#));
+        assert!(xml.contains(r#"<header id="sub-1" title="alerts" arguments=""/>"#));
+        assert!(xml.contains("Notification.SMS"));
+        assert!(xml
+            .contains(r#"activationType="protocol" arguments="https://example.com/?a=1&amp;b=2""#));
+        assert!(xml
+            .contains(r#"content="Close door" activationType="foreground" arguments="action:a1""#));
+    }
+}
@@ -0,0 +1,148 @@
+//! Retries notification writes that failed on arrival.
+//!
+//! Real-time notifications are written to the database as they arrive. When
+//! that write fails, typically because the database is busy, the notification
+//! is queued and written again after a backoff instead of being lost. The
+//! queue is bounded and only lives in memory; anything still queued on exit is
+//! picked up again by the next history sync. A notification is announced to
+//! the frontend, mirrored and has its rule commands run once it's written;
+//! no toast is shown for it, as it's late by then.
+
+use std::collections::VecDeque;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Manager};
+
+use crate::config::write_retry::{CAPACITY, RETRY_BACKOFF_MS};
+use crate::db::Database;
+use crate::models::Notification;
+use crate::services::{ConnectionManager, TrayManager};
+
+/// A notification waiting to be written again.
+struct PendingWrite {
+    notification: Notification,
+    ntfy_id: String,
+    /// Commands of the rules it matched, run once it's written.
+    commands: Vec<String>,
+    /// Number of retries made so far.
+    retries: usize,
+    retry_at: Instant,
+}
+
+/// Bounded queue of notification writes to retry.
+#[derive(Default)]
+pub struct WriteRetryQueue {
+    pending: Mutex<VecDeque<PendingWrite>>,
+}
+
+impl WriteRetryQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queues a notification whose write failed.
+    pub fn push(&self, notification: Notification, ntfy_id: String, commands: Vec<String>) {
+        let mut pending = self.lock_pending();
+        if pending.len() >= CAPACITY {
+            if let Some(dropped) = pending.pop_front() {
+                log::error!(
+                    "Write retry queue is full, dropping notification {}",
+                    dropped.ntfy_id
+                );
+            }
+        }
+        pending.push_back(PendingWrite {
+            notification,
+            ntfy_id,
+            commands,
+            retries: 0,
+            retry_at: Instant::now() + Duration::from_millis(RETRY_BACKOFF_MS[0]),
+        });
+    }
+
+    /// Spawns a background task that retries queued writes once their
+    /// backoff has passed.
+    pub fn spawn_worker(handle: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(Duration::from_millis(RETRY_BACKOFF_MS[0]));
+            loop {
+                interval.tick().await;
+
+                let service: tauri::State<Self> = handle.state();
+                let due = service.take_due();
+                if due.is_empty() {
+                    continue;
+                }
+
+                let db: tauri::State<Database> = handle.state();
+                let mut written = false;
+                for mut write in due {
+                    // The history sync may have stored it in the meantime
+                    if db
+                        .notification_exists_by_ntfy_id(&write.ntfy_id)
+                        .unwrap_or(false)
+                    {
+                        continue;
+                    }
+
+                    match db.insert_notification_with_ntfy_id(&write.notification, &write.ntfy_id) {
+                        Ok(()) => {
+                            log::info!("Saved notification {} on retry", write.ntfy_id);
+                            ConnectionManager::announce_stored(
+                                &handle,
+                                &write.notification,
+                                write.commands,
+                            );
+                            written = true;
+                        }
+                        Err(e) => {
+                            write.retries += 1;
+                            let Some(delay) = RETRY_BACKOFF_MS.get(write.retries) else {
+                                log::error!(
+                                    "Giving up on saving notification {}: {e}",
+                                    write.ntfy_id
+                                );
+                                continue;
+                            };
+                            log::warn!("Retry of notification {} failed: {e}", write.ntfy_id);
+                            write.retry_at = Instant::now() + Duration::from_millis(*delay);
+                            service.lock_pending().push_back(write);
+                        }
+                    }
+                }
+
+                if written {
+                    let tray_manager: tauri::State<TrayManager> = handle.state();
+                    tray_manager.refresh_from_db(&handle);
+                }
+            }
+        });
+    }
+
+    /// Number of writes waiting to be retried.
+    pub fn len(&self) -> usize {
+        self.lock_pending().len()
+    }
+
+    /// Whether no write is waiting to be retried.
+    pub fn is_empty(&self) -> bool {
+        self.lock_pending().is_empty()
+    }
+
+    /// Removes and returns the writes whose backoff has passed.
+    fn take_due(&self) -> Vec<PendingWrite> {
+        let now = Instant::now();
+        let mut pending = self.lock_pending();
+        let (due, waiting): (Vec<_>, Vec<_>) =
+            pending.drain(..).partition(|write| write.retry_at <= now);
+        *pending = waiting.into();
+        due
+    }
+
+    fn lock_pending(&self) -> std::sync::MutexGuard<'_, VecDeque<PendingWrite>> {
+        match self.pending.lock() {
+            Ok(pending) => pending,
+            Err(poisoned) => poisoned.into_inner(),
+        }
+    }
+}
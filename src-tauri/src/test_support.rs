@@ -0,0 +1,296 @@
+//! A mock ntfy server for tests exercising the network code end to end.
+//!
+//! It speaks just enough of ntfy's protocol: polling a topic's cached
+//! messages as newline-delimited JSON, publishing JSON to the server root and
+//! subscribing over WebSocket. Session tokens aren't issued, so clients fall
+//! back to basic auth. Requests are recorded so tests can check what was
+//! sent, and the server's clock can be offset to simulate clock skew.
+
+use std::sync::{Arc, Mutex, MutexGuard};
+use std::time::Duration;
+
+use futures_util::{SinkExt, StreamExt};
+use serde_json::json;
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tokio::sync::mpsc;
+use tokio_tungstenite::tungstenite::Message;
+
+/// Largest request head read; longer requests are dropped.
+const MAX_HEAD_BYTES: usize = 16 * 1024;
+
+/// A request received by the mock server.
+#[derive(Debug, Clone)]
+pub struct RecordedRequest {
+    pub method: String,
+    /// Path including the query string.
+    pub path: String,
+    pub authorization: Option<String>,
+}
+
+#[derive(Default)]
+struct MockState {
+    /// Cached messages as (topic, JSON), oldest first.
+    messages: Vec<(String, serde_json::Value)>,
+    requests: Vec<RecordedRequest>,
+    /// Open WebSocket subscriptions by topic. Dropping a sender closes its
+    /// connection.
+    subscribers: Vec<(String, mpsc::UnboundedSender<String>)>,
+    websocket_connections: usize,
+    clock_offset_secs: i64,
+}
+
+impl MockState {
+    fn now(&self) -> i64 {
+        chrono::Utc::now().timestamp() + self.clock_offset_secs
+    }
+}
+
+type SharedState = Arc<Mutex<MockState>>;
+
+fn lock(state: &SharedState) -> MutexGuard<'_, MockState> {
+    match state.lock() {
+        Ok(state) => state,
+        Err(poisoned) => poisoned.into_inner(),
+    }
+}
+
+/// A local ntfy server, stopped when dropped.
+pub struct MockNtfyServer {
+    url: String,
+    state: SharedState,
+    task: tokio::task::JoinHandle<()>,
+}
+
+impl Drop for MockNtfyServer {
+    fn drop(&mut self) {
+        self.task.abort();
+    }
+}
+
+impl MockNtfyServer {
+    /// Starts a server on a free local port.
+    pub async fn start() -> std::io::Result<Self> {
+        let listener = TcpListener::bind("127.0.0.1:0").await?;
+        let url = format!("http://{}", listener.local_addr()?);
+        let state = SharedState::default();
+
+        let task = tokio::spawn({
+            let state = Arc::clone(&state);
+            async move {
+                while let Ok((stream, _)) = listener.accept().await {
+                    tokio::spawn(handle_connection(stream, Arc::clone(&state)));
+                }
+            }
+        });
+
+        Ok(Self { url, state, task })
+    }
+
+    /// Base URL of the server, e.g. `http://127.0.0.1:1234`.
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// WebSocket URL of a topic.
+    pub fn ws_url(&self, topic: &str) -> String {
+        format!("{}/{topic}/ws", self.url.replacen("http", "ws", 1))
+    }
+
+    /// The server's current time (Unix seconds).
+    pub fn now(&self) -> i64 {
+        lock(&self.state).now()
+    }
+
+    /// Makes the server's clock run `secs` ahead of the local one, or behind
+    /// if negative.
+    pub fn set_clock_offset(&self, secs: i64) {
+        lock(&self.state).clock_offset_secs = secs;
+    }
+
+    /// Caches a message sent at server time `time` and sends it to the
+    /// topic's open WebSocket connections.
+    pub fn publish(&self, topic: &str, id: &str, time: i64, message: &str) {
+        let message = json!({
+            "id": id,
+            "time": time,
+            "event": "message",
+            "topic": topic,
+            "message": message,
+        });
+        store(&mut lock(&self.state), topic, message);
+    }
+
+    /// Requests received so far, oldest first.
+    pub fn requests(&self) -> Vec<RecordedRequest> {
+        lock(&self.state).requests.clone()
+    }
+
+    /// Number of WebSocket connections accepted so far.
+    pub fn websocket_connections(&self) -> usize {
+        lock(&self.state).websocket_connections
+    }
+
+    /// Drops all open WebSocket connections without a close frame, like a
+    /// lost network connection.
+    pub fn drop_connections(&self) {
+        lock(&self.state).subscribers.clear();
+    }
+}
+
+fn store(state: &mut MockState, topic: &str, message: serde_json::Value) {
+    let text = message.to_string();
+    state
+        .subscribers
+        .retain(|(t, tx)| t != topic || tx.send(text.clone()).is_ok());
+    state.messages.push((topic.to_string(), message));
+}
+
+async fn handle_connection(mut stream: TcpStream, state: SharedState) {
+    // The head is only peeked at, so a WebSocket handshake can read it again
+    let mut buf = vec![0; MAX_HEAD_BYTES];
+    let head_len = loop {
+        let Ok(n) = stream.peek(&mut buf).await else {
+            return;
+        };
+        if n == 0 || n == buf.len() {
+            return;
+        }
+        if let Some(end) = buf[..n].windows(4).position(|w| w == b"\r\n\r\n") {
+            break end + 4;
+        }
+        tokio::time::sleep(Duration::from_millis(1)).await;
+    };
+
+    let head = String::from_utf8_lossy(&buf[..head_len]).to_string();
+    let mut lines = head.lines();
+    let mut request_line = lines.next().unwrap_or_default().split(' ');
+    let method = request_line.next().unwrap_or_default().to_string();
+    let path = request_line.next().unwrap_or_default().to_string();
+    let header = |name: &str| {
+        head.lines()
+            .filter_map(|line| line.split_once(':'))
+            .find(|(key, _)| key.trim().eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.trim().to_string())
+    };
+    let is_upgrade = header("upgrade").is_some_and(|u| u.eq_ignore_ascii_case("websocket"));
+    let content_length: usize = header("content-length")
+        .and_then(|len| len.parse().ok())
+        .unwrap_or(0);
+    lock(&state).requests.push(RecordedRequest {
+        method: method.clone(),
+        path: path.clone(),
+        authorization: header("authorization"),
+    });
+
+    if is_upgrade {
+        let topic = path
+            .trim_start_matches('/')
+            .split('/')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        serve_websocket(stream, topic, state).await;
+        return;
+    }
+
+    let mut body = vec![0; content_length];
+    if stream.read_exact(&mut buf[..head_len]).await.is_err()
+        || stream.read_exact(&mut body).await.is_err()
+    {
+        return;
+    }
+    let (status, body) = respond(&method, &path, &body, &state);
+
+    let date = chrono::DateTime::from_timestamp(lock(&state).now(), 0)
+        .unwrap_or_default()
+        .format("%a, %d %b %Y %H:%M:%S GMT");
+    let response = format!(
+        "HTTP/1.1 {status}\r\nDate: {date}\r\nContent-Type: application/json\r\n\
+         Content-Length: {}\r\nConnection: close\r\n\r\n{body}",
+        body.len()
+    );
+    let _ = stream.write_all(response.as_bytes()).await;
+    let _ = stream.shutdown().await;
+}
+
+/// Returns the status line and body answering a plain HTTP request.
+fn respond(method: &str, path: &str, body: &[u8], state: &SharedState) -> (&'static str, String) {
+    let (route, query) = path.split_once('?').unwrap_or((path, ""));
+    let not_found = ("404 Not Found", r#"{"error":"not found"}"#.to_string());
+
+    match method {
+        "GET" if route.ends_with("/json") && !route.starts_with("/v1/") => {
+            let topic = route.trim_start_matches('/').trim_end_matches("/json");
+            let since = query
+                .split('&')
+                .find_map(|param| param.strip_prefix("since="))
+                .and_then(|since| since.parse::<i64>().ok());
+            let state = lock(state);
+            let lines: Vec<String> = state
+                .messages
+                .iter()
+                .filter(|(t, message)| {
+                    t == topic && since.map_or(true, |s| message["time"].as_i64() >= Some(s))
+                })
+                .map(|(_, message)| message.to_string())
+                .collect();
+            ("200 OK", lines.join("\n"))
+        }
+        "POST" | "PUT" if route == "/" => {
+            let Ok(mut message) = serde_json::from_slice::<serde_json::Value>(body) else {
+                return ("400 Bad Request", r#"{"error":"invalid json"}"#.to_string());
+            };
+            let topic = message["topic"].as_str().unwrap_or_default().to_string();
+            let mut state = lock(state);
+            message["id"] = uuid::Uuid::new_v4().to_string().into();
+            message["time"] = state.now().into();
+            message["event"] = "message".into();
+            store(&mut state, &topic, message.clone());
+            ("200 OK", message.to_string())
+        }
+        _ => not_found,
+    }
+}
+
+/// Sends an `open` event, then the topic's new messages until the client
+/// disconnects or the connection is dropped.
+async fn serve_websocket(stream: TcpStream, topic: String, state: SharedState) {
+    let Ok(mut ws) = tokio_tungstenite::accept_async(stream).await else {
+        return;
+    };
+
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let open = {
+        let mut state = lock(&state);
+        state.websocket_connections += 1;
+        state.subscribers.push((topic.clone(), tx));
+        json!({
+            "id": uuid::Uuid::new_v4().to_string(),
+            "time": state.now(),
+            "event": "open",
+            "topic": topic,
+        })
+    };
+    if ws.send(Message::Text(open.to_string())).await.is_err() {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            message = rx.recv() => {
+                let Some(text) = message else {
+                    return;
+                };
+                if ws.send(Message::Text(text)).await.is_err() {
+                    return;
+                }
+            }
+            incoming = ws.next() => {
+                if !matches!(incoming, Some(Ok(_))) {
+                    return;
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,39 @@
+import { useCallback, useState } from "react";
+import { useTauriEvent } from "@/hooks/useTauriEvent";
+
+interface NotificationAnnouncement {
+	notificationId: string;
+	text: string;
+}
+
+/**
+ * Visually hidden live region announcing notifications to screen readers.
+ *
+ * The backend only emits `notification:announce` when screen reader
+ * announcements are enabled in settings.
+ */
+export function ScreenReaderAnnouncer() {
+	const [announcement, setAnnouncement] =
+		useState<NotificationAnnouncement | null>(null);
+
+	useTauriEvent<NotificationAnnouncement>(
+		"notification:announce",
+		useCallback((payload: NotificationAnnouncement) => {
+			setAnnouncement(payload);
+		}, []),
+	);
+
+	return (
+		<div
+			role="status"
+			aria-live="assertive"
+			aria-atomic="true"
+			className="sr-only"
+		>
+			{/* Keyed so identical texts are still announced as new content */}
+			{announcement && (
+				<span key={announcement.notificationId}>{announcement.text}</span>
+			)}
+		</div>
+	);
+}
@@ -137,9 +137,15 @@ export const NotificationCard = memo(function NotificationCard({
 		<>
 			{notification.message && (
 				<div className="mt-2 text-sm text-muted-foreground selectable">
-					<Suspense fallback={<span>{notification.message}</span>}>
-						<MarkdownContent content={notification.message} />
-					</Suspense>
+					{notification.contentKind === "markdown" ? (
+						<Suspense fallback={<span>{notification.message}</span>}>
+							<MarkdownContent content={notification.message} />
+						</Suspense>
+					) : (
+						<p className="whitespace-pre-wrap break-words">
+							{notification.message}
+						</p>
+					)}
 				</div>
 			)}
 			<NotificationTags tags={notification.tags} />
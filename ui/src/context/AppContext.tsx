@@ -227,20 +227,10 @@ export function AppProvider({ children }: { children: ReactNode }) {
 		"notification:new",
 		useCallback(
 			(notification: Notification) => {
-				// Auto-expand new notifications in compact view if enabled
-				if (settings.compactView && settings.expandNewMessages) {
-					notification.isExpanded = true;
-					notificationsApi.setExpanded(notification.id, true).catch((err) => {
-						console.error("[Background] Failed to persist expand state:", err);
-					});
-				}
+				// Expanded state for new messages is already set by the backend
 				notifications.addNotification(notification);
 			},
-			[
-				settings.compactView,
-				settings.expandNewMessages,
-				notifications.addNotification,
-			],
+			[notifications.addNotification],
 		),
 	);
 
@@ -254,7 +244,7 @@ export function AppProvider({ children }: { children: ReactNode }) {
 		}, []),
 	);
 
-	// Listen for navigate:subscription event (from tray icon click)
+	// Listen for navigate:subscription event (from a toast click)
 	useTauriEvent<string>(
 		"navigate:subscription",
 		useCallback((subscriptionId: string) => {
@@ -52,17 +52,40 @@ export const mockNotifications: Notification[] = [
 		actions: [
 			{
 				id: "act-1",
+				action: "view",
 				label: "Open Dashboard",
 				url: "https://dashboard.example.com",
 				method: null,
+				headers: {},
+				body: null,
+				intent: null,
+				extras: {},
 				clear: false,
 			},
-			{ id: "act-2", label: "Dismiss", url: null, method: null, clear: true },
+			{
+				id: "act-2",
+				action: "view",
+				label: "Dismiss",
+				url: null,
+				method: null,
+				headers: {},
+				body: null,
+				intent: null,
+				extras: {},
+				clear: true,
+			},
 		],
 		attachments: [],
 		read: false,
 		isExpanded: false,
 		isFavorite: false,
+		deletedAt: null,
+		parsed: null,
+		linkPreview: null,
+		acknowledgedBy: null,
+		acknowledgedAt: null,
+		note: null,
+		contentKind: "plain",
 	},
 	{
 		id: "notif-2",
@@ -76,9 +99,14 @@ export const mockNotifications: Notification[] = [
 		actions: [
 			{
 				id: "act-3",
+				action: "view",
 				label: "View Logs",
 				url: "https://logs.example.com/nginx",
 				method: null,
+				headers: {},
+				body: null,
+				intent: null,
+				extras: {},
 				clear: false,
 			},
 		],
@@ -86,6 +114,13 @@ export const mockNotifications: Notification[] = [
 		read: false,
 		isExpanded: false,
 		isFavorite: false,
+		deletedAt: null,
+		parsed: null,
+		linkPreview: null,
+		acknowledgedBy: null,
+		acknowledgedAt: null,
+		note: null,
+		contentKind: "plain",
 	},
 	{
 		id: "notif-3",
@@ -101,6 +136,13 @@ export const mockNotifications: Notification[] = [
 		read: true,
 		isExpanded: false,
 		isFavorite: false,
+		deletedAt: null,
+		parsed: null,
+		linkPreview: null,
+		acknowledgedBy: null,
+		acknowledgedAt: null,
+		note: null,
+		contentKind: "plain",
 	},
 	{
 		id: "notif-4",
@@ -114,16 +156,26 @@ export const mockNotifications: Notification[] = [
 		actions: [
 			{
 				id: "act-4",
+				action: "view",
 				label: "View Security Log",
 				url: "https://security.example.com/logs",
 				method: null,
+				headers: {},
+				body: null,
+				intent: null,
+				extras: {},
 				clear: false,
 			},
 			{
 				id: "act-5",
+				action: "http",
 				label: "Unblock IP",
 				url: null,
 				method: "POST",
+				headers: {},
+				body: null,
+				intent: null,
+				extras: {},
 				clear: false,
 			},
 		],
@@ -139,6 +191,13 @@ export const mockNotifications: Notification[] = [
 		read: false,
 		isExpanded: false,
 		isFavorite: false,
+		deletedAt: null,
+		parsed: null,
+		linkPreview: null,
+		acknowledgedBy: null,
+		acknowledgedAt: null,
+		note: null,
+		contentKind: "plain",
 	},
 	{
 		id: "notif-5",
@@ -152,9 +211,14 @@ export const mockNotifications: Notification[] = [
 		actions: [
 			{
 				id: "act-6",
+				action: "view",
 				label: "Review Rules",
 				url: "https://firewall.example.com",
 				method: null,
+				headers: {},
+				body: null,
+				intent: null,
+				extras: {},
 				clear: false,
 			},
 		],
@@ -162,6 +226,13 @@ export const mockNotifications: Notification[] = [
 		read: false,
 		isExpanded: false,
 		isFavorite: false,
+		deletedAt: null,
+		parsed: null,
+		linkPreview: null,
+		acknowledgedBy: null,
+		acknowledgedAt: null,
+		note: null,
+		contentKind: "plain",
 	},
 	{
 		id: "notif-6",
@@ -175,9 +246,14 @@ export const mockNotifications: Notification[] = [
 		actions: [
 			{
 				id: "act-7",
+				action: "view",
 				label: "Renew Now",
 				url: "https://certs.example.com/renew",
 				method: null,
+				headers: {},
+				body: null,
+				intent: null,
+				extras: {},
 				clear: false,
 			},
 		],
@@ -185,6 +261,13 @@ export const mockNotifications: Notification[] = [
 		read: false,
 		isExpanded: false,
 		isFavorite: false,
+		deletedAt: null,
+		parsed: null,
+		linkPreview: null,
+		acknowledgedBy: null,
+		acknowledgedAt: null,
+		note: null,
+		contentKind: "plain",
 	},
 	{
 		id: "notif-7",
@@ -198,9 +281,14 @@ export const mockNotifications: Notification[] = [
 		actions: [
 			{
 				id: "act-8",
+				action: "view",
 				label: "View Devices",
 				url: "https://network.example.com/devices",
 				method: null,
+				headers: {},
+				body: null,
+				intent: null,
+				extras: {},
 				clear: false,
 			},
 		],
@@ -208,6 +296,13 @@ export const mockNotifications: Notification[] = [
 		read: true,
 		isExpanded: false,
 		isFavorite: false,
+		deletedAt: null,
+		parsed: null,
+		linkPreview: null,
+		acknowledgedBy: null,
+		acknowledgedAt: null,
+		note: null,
+		contentKind: "plain",
 	},
 	{
 		id: "notif-8",
@@ -223,6 +318,13 @@ export const mockNotifications: Notification[] = [
 		read: true,
 		isExpanded: false,
 		isFavorite: false,
+		deletedAt: null,
+		parsed: null,
+		linkPreview: null,
+		acknowledgedBy: null,
+		acknowledgedAt: null,
+		note: null,
+		contentKind: "plain",
 	},
 	{
 		id: "notif-9",
@@ -236,9 +338,14 @@ export const mockNotifications: Notification[] = [
 		actions: [
 			{
 				id: "act-9",
+				action: "view",
 				label: "View Run",
 				url: "https://github.com/user/repo/actions/runs/123",
 				method: null,
+				headers: {},
+				body: null,
+				intent: null,
+				extras: {},
 				clear: false,
 			},
 		],
@@ -246,6 +353,13 @@ export const mockNotifications: Notification[] = [
 		read: true,
 		isExpanded: false,
 		isFavorite: false,
+		deletedAt: null,
+		parsed: null,
+		linkPreview: null,
+		acknowledgedBy: null,
+		acknowledgedAt: null,
+		note: null,
+		contentKind: "plain",
 	},
 	{
 		id: "notif-10",
@@ -269,6 +383,13 @@ export const mockNotifications: Notification[] = [
 		read: false,
 		isExpanded: false,
 		isFavorite: false,
+		deletedAt: null,
+		parsed: null,
+		linkPreview: null,
+		acknowledgedBy: null,
+		acknowledgedAt: null,
+		note: null,
+		contentKind: "plain",
 	},
 	{
 		id: "notif-11",
@@ -284,6 +405,13 @@ export const mockNotifications: Notification[] = [
 		read: false,
 		isExpanded: false,
 		isFavorite: false,
+		deletedAt: null,
+		parsed: null,
+		linkPreview: null,
+		acknowledgedBy: null,
+		acknowledgedAt: null,
+		note: null,
+		contentKind: "plain",
 	},
 	{
 		id: "notif-12",
@@ -297,9 +425,14 @@ export const mockNotifications: Notification[] = [
 		actions: [
 			{
 				id: "act-10",
+				action: "view",
 				label: "Open Grafana",
 				url: "https://grafana.example.com",
 				method: null,
+				headers: {},
+				body: null,
+				intent: null,
+				extras: {},
 				clear: false,
 			},
 		],
@@ -315,6 +448,13 @@ export const mockNotifications: Notification[] = [
 		read: false,
 		isExpanded: false,
 		isFavorite: false,
+		deletedAt: null,
+		parsed: null,
+		linkPreview: null,
+		acknowledgedBy: null,
+		acknowledgedAt: null,
+		note: null,
+		contentKind: "plain",
 	},
 	{
 		id: "notif-13",
@@ -328,16 +468,26 @@ export const mockNotifications: Notification[] = [
 		actions: [
 			{
 				id: "act-11",
+				action: "view",
 				label: "View Preview",
 				url: "https://preview-127.example.com",
 				method: null,
+				headers: {},
+				body: null,
+				intent: null,
+				extras: {},
 				clear: false,
 			},
 			{
 				id: "act-12",
+				action: "view",
 				label: "View PR",
 				url: "https://github.com/user/repo/pull/127",
 				method: null,
+				headers: {},
+				body: null,
+				intent: null,
+				extras: {},
 				clear: false,
 			},
 		],
@@ -353,6 +503,13 @@ export const mockNotifications: Notification[] = [
 		read: false,
 		isExpanded: false,
 		isFavorite: false,
+		deletedAt: null,
+		parsed: null,
+		linkPreview: null,
+		acknowledgedBy: null,
+		acknowledgedAt: null,
+		note: null,
+		contentKind: "plain",
 	},
 ];
 
@@ -1,6 +1,7 @@
 import { createRootRoute, HeadContent, Scripts } from "@tanstack/react-router";
 import { Toaster } from "sonner";
 import { AppErrorBoundary } from "@/components/common/ErrorBoundary";
+import { ScreenReaderAnnouncer } from "@/components/common/ScreenReaderAnnouncer";
 import { ThemeProvider } from "@/components/common/ThemeProvider";
 import { AppProvider } from "@/context/AppContext";
 import appCss from "../styles.css?url";
@@ -41,6 +42,7 @@ function RootDocument({ children }: { children: React.ReactNode }) {
 					<ThemeProvider>
 						<AppProvider>{children}</AppProvider>
 						<Toaster position="top-right" richColors />
+						<ScreenReaderAnnouncer />
 					</ThemeProvider>
 				</AppErrorBoundary>
 				<Scripts />
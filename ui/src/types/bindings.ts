@@ -310,7 +310,7 @@ async getAppVersionDisplay() : Promise<string> {
  * type AppError = { Database: string } | { WebSocket: string } | ...
  * ```
  */
-export type AppError = { Database: string } | { WebSocket: string } | { Serialization: string } | { NotFound: string } | { InvalidUrl: string } | { Connection: string } | { Credential: string } | { Updater: string }
+export type AppError = { Database: string } | { WebSocket: string } | { Serialization: string } | { NotFound: string } | { InvalidUrl: string } | { Validation: string } | { Connection: string } | { Credential: string } | { Updater: string } | { Cancelled: string } | { PolicyLocked: string }
 /**
  * Application-wide settings.
  */
@@ -375,10 +375,35 @@ favoritesEnabled?: boolean }
  * A file attachment on a notification.
  */
 export type Attachment = { id: string; name: string; type: string; url: string; size: number | null }
+/**
+ * Format of a notification's message.
+ */
+export type ContentKind = "plain" | 
+/**
+ * Declared as `text/markdown` by the publisher.
+ */
+"markdown"
 /**
  * Data required to create a new subscription.
  */
 export type CreateSubscription = { topic: string; serverUrl: string; displayName: string | null }
+/**
+ * Preview metadata of a link found in a notification's message.
+ */
+export type LinkPreview = { 
+/**
+ * The previewed URL as it appears in the message.
+ */
+url: string; title: string | null; siteName: string | null; description: string | null; 
+/**
+ * Remote thumbnail URL from `og:image`.
+ */
+imageUrl: string | null; 
+/**
+ * Local path of the thumbnail in the image cache, if it was downloaded.
+ * Cached images are cleaned up after a while, so the file may be gone.
+ */
+imagePath: string | null }
 /**
  * A notification stored in the local database.
  */
@@ -398,11 +423,59 @@ isExpanded: boolean;
 /**
  * Whether the notification is marked as a favorite.
  */
-isFavorite: boolean }
+isFavorite: boolean; 
+/**
+ * When the notification was moved to the trash (milliseconds), if it was.
+ */
+deletedAt: number | null; 
+/**
+ * Fields extracted from a known producer's payload, if recognized.
+ */
+parsed: ParsedMessage | null; 
+/**
+ * Preview of the first bare URL in the message, once fetched.
+ */
+linkPreview: LinkPreview | null; 
+/**
+ * Who acknowledged the notification, if anyone did.
+ */
+acknowledgedBy: string | null; 
+/**
+ * When the notification was acknowledged (milliseconds).
+ */
+acknowledgedAt: number | null; 
+/**
+ * The user's own note about the notification (kept locally).
+ */
+note: string | null; 
+/**
+ * Whether the message is plain text or markdown.
+ */
+contentKind: ContentKind }
 /**
  * An action button attached to a notification.
  */
-export type NotificationAction = { id: string; label: string; url: string | null; method: string | null; clear: boolean }
+export type NotificationAction = { id: string; 
+/**
+ * Action type: `view`, `http` or `broadcast`.
+ */
+action: string; label: string; url: string | null; method: string | null; 
+/**
+ * Request headers of an `http` action.
+ */
+headers: Partial<{ [key in string]: string }>; 
+/**
+ * Request body of an `http` action.
+ */
+body: string | null; 
+/**
+ * Android intent of a `broadcast` action.
+ */
+intent: string | null; 
+/**
+ * Intent extras of a `broadcast` action.
+ */
+extras: Partial<{ [key in string]: string }>; clear: boolean }
 /**
  * Notification display method.
  */
@@ -415,6 +488,26 @@ export type NotificationDisplayMethod =
  * Windows-specific enhanced notifications with action buttons and force display.
  */
 "windows_enhanced"
+/**
+ * Fields extracted from a structured payload by a message parser.
+ */
+export type ParsedMessage = { 
+/**
+ * Producer the payload was recognized as (e.g. `grafana`).
+ */
+source: string; 
+/**
+ * Producer-specific state, e.g. `firing`, `down` or `failure`.
+ */
+status: string | null; 
+/**
+ * Host, instance or repository the message is about.
+ */
+host: string | null; 
+/**
+ * Link to the alert, monitor or run.
+ */
+link: string | null }
 /**
  * Configuration for a single ntfy server.
  */